@@ -4,9 +4,11 @@ use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, Runtime};
 
 use crate::app_state::AppState;
+use crate::color_management::OutputColorSpace;
+use crate::processing_recipes::ProcessingRecipe;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -77,6 +79,8 @@ pub struct MyLens {
 pub enum PasteMode {
     Merge,
     Replace,
+    /// Shifts each image's own values by the copied offsets from the defaults.
+    Relative,
 }
 
 pub fn all_available_adjustments() -> HashSet<String> {
@@ -204,6 +208,8 @@ pub struct CopyPasteSettings {
     pub known_adjustments: HashSet<String>,
     #[serde(default)]
     pub auto_sync: bool,
+    #[serde(default)]
+    pub relative_masks: bool,
 }
 
 impl Default for CopyPasteSettings {
@@ -213,10 +219,21 @@ impl Default for CopyPasteSettings {
             included_adjustments: default_included_adjustments(),
             known_adjustments: all_available_adjustments(),
             auto_sync: false,
+            relative_masks: false,
         }
     }
 }
 
+// Older settings files stored some enum fields as free-form strings or null.
+fn lenient_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned + Default,
+{
+    let value = Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).unwrap_or_default())
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportPreset {
@@ -229,6 +246,8 @@ pub struct ExportPreset {
     pub resize_value: u32,
     pub dont_enlarge: bool,
     pub keep_metadata: bool,
+    #[serde(default)]
+    pub preserve_timestamps: Option<bool>,
     pub strip_gps: bool,
     pub filename_template: String,
     pub enable_watermark: bool,
@@ -238,13 +257,107 @@ pub struct ExportPreset {
     pub watermark_spacing: u32,
     pub watermark_opacity: u32,
     #[serde(default)]
+    pub watermark_type: Option<String>,
+    #[serde(default)]
+    pub watermark_text: Option<String>,
+    #[serde(default)]
+    pub watermark_font_family: Option<String>,
+    #[serde(default)]
+    pub watermark_font_size: Option<f32>,
+    #[serde(default)]
+    pub watermark_color: Option<String>,
+    #[serde(default)]
     pub export_masks: Option<bool>,
     #[serde(default)]
     pub preserve_folders: Option<bool>,
+    #[serde(default, deserialize_with = "lenient_default")]
+    pub output_color_space: OutputColorSpace,
+    #[serde(default)]
+    pub enable_output_sharpening: Option<bool>,
+    #[serde(default)]
+    pub output_sharpening_target: Option<String>,
+    #[serde(default)]
+    pub output_sharpening_amount: Option<u32>,
+    #[serde(default)]
+    pub enable_max_file_size: Option<bool>,
+    #[serde(default)]
+    pub max_file_size_kb: Option<u32>,
+    #[serde(default)]
+    pub enable_hdr_gain_map: Option<bool>,
+    #[serde(default)]
+    pub hdr_headroom_stops: Option<f32>,
     #[serde(default)]
     pub last_export_path: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRecipeVariant {
+    pub preset: ExportPreset,
+    #[serde(default)]
+    pub sub_folder: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRecipe {
+    pub id: String,
+    pub name: String,
+    pub variants: Vec<ExportRecipeVariant>,
+}
+
+/// A watched input folder whose new images are edited and exported automatically.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HotFolderSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub input_folder: String,
+    #[serde(default)]
+    pub output_folder: String,
+    /// Name or ID of a library preset, or a path to a preset file.
+    #[serde(default)]
+    pub preset: Option<String>,
+    #[serde(default)]
+    pub auto_adjust: bool,
+    /// Name or ID of an entry in `export_presets`.
+    #[serde(default)]
+    pub export_preset: String,
+    /// Originals and their sidecars are moved here once exported.
+    #[serde(default)]
+    pub archive_folder: Option<String>,
+}
+
+/// A program used for "Edit in". `{file}` in the arguments is replaced with the image path; when
+/// no argument contains it, the path is passed last.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalEditor {
+    pub id: String,
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+pub fn default_external_editors() -> Vec<ExternalEditor> {
+    let editor = |id: &str, name: &str| {
+        // macOS apps are opened by name, elsewhere the lowercase binary is expected on the PATH.
+        #[cfg(target_os = "macos")]
+        let (command, args) = ("open".to_string(), vec!["-a", name, "{file}"]);
+        #[cfg(not(target_os = "macos"))]
+        let (command, args) = (name.to_lowercase(), vec!["{file}"]);
+        ExternalEditor {
+            id: id.to_string(),
+            name: name.to_string(),
+            command,
+            args: args.into_iter().map(str::to_string).collect(),
+        }
+    };
+    vec![editor("gimp", "GIMP"), editor("krita", "Krita")]
+}
+
 pub fn default_export_presets() -> Vec<ExportPreset> {
     vec![
         ExportPreset {
@@ -257,6 +370,7 @@ pub fn default_export_presets() -> Vec<ExportPreset> {
             resize_value: 2048,
             dont_enlarge: true,
             keep_metadata: true,
+            preserve_timestamps: Some(false),
             strip_gps: false,
             filename_template: "{original_filename}".to_string(),
             enable_watermark: false,
@@ -265,8 +379,21 @@ pub fn default_export_presets() -> Vec<ExportPreset> {
             watermark_scale: 10,
             watermark_spacing: 5,
             watermark_opacity: 75,
+            watermark_type: Some("image".to_string()),
+            watermark_text: None,
+            watermark_font_family: None,
+            watermark_font_size: Some(3.0),
+            watermark_color: Some("#ffffff".to_string()),
             export_masks: Some(false),
             preserve_folders: Some(false),
+            output_color_space: OutputColorSpace::Srgb,
+            enable_output_sharpening: Some(false),
+            output_sharpening_target: Some("screen".to_string()),
+            output_sharpening_amount: Some(50),
+            enable_max_file_size: Some(false),
+            max_file_size_kb: Some(2048),
+            enable_hdr_gain_map: Some(false),
+            hdr_headroom_stops: Some(3.0),
             last_export_path: None,
         },
         ExportPreset {
@@ -279,6 +406,7 @@ pub fn default_export_presets() -> Vec<ExportPreset> {
             resize_value: 2048,
             dont_enlarge: true,
             keep_metadata: false,
+            preserve_timestamps: Some(false),
             strip_gps: true,
             filename_template: "{original_filename}_web".to_string(),
             enable_watermark: false,
@@ -287,8 +415,21 @@ pub fn default_export_presets() -> Vec<ExportPreset> {
             watermark_scale: 10,
             watermark_spacing: 5,
             watermark_opacity: 75,
+            watermark_type: Some("image".to_string()),
+            watermark_text: None,
+            watermark_font_family: None,
+            watermark_font_size: Some(3.0),
+            watermark_color: Some("#ffffff".to_string()),
             export_masks: Some(false),
             preserve_folders: Some(false),
+            output_color_space: OutputColorSpace::Srgb,
+            enable_output_sharpening: Some(false),
+            output_sharpening_target: Some("screen".to_string()),
+            output_sharpening_amount: Some(50),
+            enable_max_file_size: Some(false),
+            max_file_size_kb: Some(2048),
+            enable_hdr_gain_map: Some(false),
+            hdr_headroom_stops: Some(3.0),
             last_export_path: None,
         },
     ]
@@ -362,6 +503,10 @@ pub fn default_linear_raw_mode() -> String {
     "auto".to_string()
 }
 
+pub fn default_raw_highlight_mode() -> String {
+    "compress".to_string()
+}
+
 pub fn default_tagging_shortcuts_option() -> Option<Vec<String>> {
     Some(vec![
         "portrait".to_string(),
@@ -442,6 +587,10 @@ pub struct AppSettings {
     pub copy_paste_settings: CopyPasteSettings,
     #[serde(default)]
     pub raw_highlight_compression: Option<f32>,
+    /// `compress` desaturates clipped highlights, `reconstruct` rebuilds them from neighbouring
+    /// photosites before demosaicing.
+    #[serde(default = "default_raw_highlight_mode")]
+    pub raw_highlight_mode: String,
     #[serde(default)]
     pub processing_backend: Option<String>,
     #[serde(default)]
@@ -453,6 +602,10 @@ pub struct AppSettings {
     #[serde(default = "default_export_presets")]
     pub export_presets: Vec<ExportPreset>,
     #[serde(default)]
+    pub export_recipes: Vec<ExportRecipe>,
+    #[serde(default)]
+    pub processing_recipes: Vec<ProcessingRecipe>,
+    #[serde(default)]
     pub my_lenses: Option<Vec<MyLens>>,
     #[serde(default)]
     pub enable_folder_image_counts: Option<bool>,
@@ -499,6 +652,8 @@ pub struct AppSettings {
     #[serde(default)]
     pub apply_preprocessing_to_non_raws: Option<bool>,
     #[serde(default)]
+    pub raw_defect_detection: Option<bool>,
+    #[serde(default)]
     pub exif_overlay: Option<String>,
     #[serde(default)]
     pub language: Option<String>,
@@ -521,6 +676,20 @@ pub struct AppSettings {
     #[serde(default)]
     pub always_decode_raw_thumbnails: Option<bool>,
     #[serde(default)]
+    pub scene_referred_merges: Option<bool>,
+    #[serde(default)]
+    pub merge_exr_precision: Option<String>,
+    #[serde(default)]
+    pub merge_dng_output: Option<bool>,
+    #[serde(default)]
+    pub automation_server_enabled: Option<bool>,
+    #[serde(default)]
+    pub automation_server_port: Option<u16>,
+    #[serde(default)]
+    pub hot_folder: Option<HotFolderSettings>,
+    #[serde(default = "default_external_editors")]
+    pub external_editors: Vec<ExternalEditor>,
+    #[serde(default)]
     pub workspace: WorkspaceState,
 }
 
@@ -562,11 +731,14 @@ impl Default for AppSettings {
             open_tree_sections: default_open_tree_sections(),
             copy_paste_settings: CopyPasteSettings::default(),
             raw_highlight_compression: Some(2.5),
+            raw_highlight_mode: default_raw_highlight_mode(),
             processing_backend: Some("auto".to_string()),
             linux_gpu_optimization: Some(false),
             linux_gpu_optimization_migrated_v1: Some(true),
             library_view_mode: Some("flat".to_string()),
             export_presets: default_export_presets(),
+            export_recipes: Vec::new(),
+            processing_recipes: Vec::new(),
             my_lenses: Some(Vec::new()),
             #[cfg(target_os = "android")]
             high_res_zoom_multiplier: Some(0.75),
@@ -603,6 +775,7 @@ impl Default for AppSettings {
             raw_preprocessing_color_nr: Some(0.5),
             raw_preprocessing_sharpening: Some(0.35),
             apply_preprocessing_to_non_raws: Some(false),
+            raw_defect_detection: Some(false),
             exif_overlay: Some("off".to_string()),
             language: Some("en".to_string()),
             folder_tree_sort: Some(FolderTreeSort::default()),
@@ -613,12 +786,19 @@ impl Default for AppSettings {
             group_associated_files: Some(false),
             group_preferred_type: Some("raw".to_string()),
             always_decode_raw_thumbnails: Some(false),
+            scene_referred_merges: Some(false),
+            merge_exr_precision: Some("half".to_string()),
+            merge_dng_output: Some(false),
+            automation_server_enabled: Some(false),
+            automation_server_port: Some(crate::automation_server::DEFAULT_AUTOMATION_PORT),
+            hot_folder: None,
+            external_editors: default_external_editors(),
             workspace: WorkspaceState::default(),
         }
     }
 }
 
-pub fn get_settings_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+pub fn get_settings_path<R: Runtime>(app_handle: &AppHandle<R>) -> Result<PathBuf, String> {
     let settings_dir = app_handle
         .path()
         .app_data_dir()
@@ -637,7 +817,7 @@ pub fn is_tethering_supported() -> bool {
 }
 
 #[tauri::command]
-pub fn load_settings(app_handle: AppHandle) -> Result<AppSettings, String> {
+pub fn load_settings<R: Runtime>(app_handle: AppHandle<R>) -> Result<AppSettings, String> {
     let path = get_settings_path(&app_handle)?;
 
     let mut settings: AppSettings = if path.exists() {
@@ -8,17 +8,19 @@ use image::{DynamicImage, GrayImage};
 use serde::{Deserialize, Serialize};
 use sysinfo::Disks;
 use tokio::sync::Mutex as TokioMutex;
-use tokio::task::JoinHandle;
 use wgpu::{Texture, TextureView};
 
 use crate::ai_processing::AiState;
 use crate::cache_utils::DecodedImageCache;
 use crate::camera_tethering::CameraSession;
+use crate::defect_pixels::DefectMapCache;
 use crate::gpu_processing::GpuProcessor;
 use crate::image_processing::GpuContext;
+use crate::job_queue::{JobQueueState, TaskRegistry};
 use crate::launch_request::ExternalEditSession;
 use crate::lens_correction::LensDatabase;
 use crate::lut_processing::Lut;
+use crate::raw_processing::RawDevelopOptions;
 
 #[derive(Serialize, Deserialize)]
 pub struct WindowState {
@@ -35,6 +37,7 @@ pub struct LoadedImage {
     pub path: String,
     pub image: Arc<DynamicImage>,
     pub is_raw: bool,
+    pub develop_options: RawDevelopOptions,
 }
 
 #[derive(Clone)]
@@ -146,12 +149,12 @@ pub struct AppState {
     pub gpu_processor: Mutex<Option<GpuProcessorState>>,
     pub ai_state: Mutex<Option<AiState>>,
     pub ai_init_lock: TokioMutex<()>,
-    pub export_task_token: Arc<Mutex<Option<Arc<AtomicBool>>>>,
+    pub task_registry: Arc<TaskRegistry>,
+    pub job_queue: JobQueueState,
     pub hdr_result: Arc<Mutex<Option<DynamicImage>>>,
     pub panorama_result: Arc<Mutex<Option<DynamicImage>>>,
     pub focus_stack_result: Arc<Mutex<Option<DynamicImage>>>,
     pub denoise_result: Arc<Mutex<Option<DynamicImage>>>,
-    pub indexing_task_handle: Mutex<Option<JoinHandle<()>>>,
     pub lut_cache: Mutex<HashMap<String, Arc<Lut>>>,
     pub initial_file_path: Mutex<Option<String>>,
     pub pending_edit_session: Mutex<Option<ExternalEditSession>>,
@@ -164,6 +167,7 @@ pub struct AppState {
     pub geometry_cache: Mutex<HashMap<u64, DynamicImage>>,
     pub thumbnail_geometry_cache: Mutex<HashMap<String, (u64, DynamicImage, f32)>>,
     pub lens_db: Mutex<Option<Arc<LensDatabase>>>,
+    pub defect_maps: DefectMapCache,
     pub load_image_generation: Arc<AtomicUsize>,
     pub full_warped_cache: Mutex<Option<(u64, Arc<DynamicImage>)>>,
     pub full_transformed_cache: Mutex<Option<TransformedImageCache>>,
@@ -174,3 +178,50 @@ pub struct AppState {
     pub disks_cache_refreshing: AtomicBool,
     pub camera_session: Mutex<CameraSession>,
 }
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            window_setup_complete: AtomicBool::new(false),
+            gpu_crash_flag_path: Mutex::new(None),
+            original_image: Mutex::new(None),
+            cached_preview: Mutex::new(None),
+            gpu_context: Mutex::new(None),
+            gpu_image_cache: Mutex::new(None),
+            gpu_processor: Mutex::new(None),
+            ai_state: Mutex::new(None),
+            ai_init_lock: TokioMutex::new(()),
+            task_registry: Arc::new(TaskRegistry::default()),
+            job_queue: JobQueueState::default(),
+            hdr_result: Arc::new(Mutex::new(None)),
+            panorama_result: Arc::new(Mutex::new(None)),
+            focus_stack_result: Arc::new(Mutex::new(None)),
+            denoise_result: Arc::new(Mutex::new(None)),
+            lut_cache: Mutex::new(HashMap::new()),
+            initial_file_path: Mutex::new(None),
+            pending_edit_session: Mutex::new(None),
+            thumbnail_cancellation_token: Arc::new(AtomicBool::new(false)),
+            thumbnail_progress: Mutex::new(ThumbnailProgressTracker {
+                total: 0,
+                completed: 0,
+            }),
+            preview_worker_tx: Mutex::new(None),
+            analytics_worker_tx: Mutex::new(None),
+            mask_cache: Mutex::new(HashMap::new()),
+            patch_cache: Mutex::new(HashMap::new()),
+            geometry_cache: Mutex::new(HashMap::new()),
+            thumbnail_geometry_cache: Mutex::new(HashMap::new()),
+            lens_db: Mutex::new(None),
+            defect_maps: DefectMapCache::default(),
+            load_image_generation: Arc::new(AtomicUsize::new(0)),
+            full_warped_cache: Mutex::new(None),
+            full_transformed_cache: Mutex::new(None),
+            decoded_image_cache: Mutex::new(DecodedImageCache::new(5)),
+            thumbnail_manager: ThumbnailManager::new(),
+            metadata_manager: MetadataManager::new(),
+            disks_cache: Mutex::new(None),
+            disks_cache_refreshing: AtomicBool::new(false),
+            camera_session: Mutex::new(CameraSession::new()),
+        }
+    }
+}
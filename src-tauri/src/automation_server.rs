@@ -0,0 +1,604 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::net::Ipv4Addr;
+use std::path::Path;
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use tauri::{AppHandle, Listener, Manager, Runtime};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+
+use crate::AppState;
+use crate::export_manifest::ExportSyncOptions;
+use crate::export_processing::{self, ExportSettings};
+use crate::relative_adjustments::RelativeAdjustments;
+use crate::{file_management, image_loader, tagging};
+
+pub const DEFAULT_AUTOMATION_PORT: u16 = 7878;
+
+/// File in the app data dir holding the token every request must send as `Authorization: Bearer <token>`.
+const TOKEN_FILE_NAME: &str = "automation_token";
+
+/// App events that are streamed to `GET /events` subscribers as JSON-RPC notifications.
+const FORWARDED_EVENTS: &[&str] = &[
+    "batch-export-progress",
+    "export-complete",
+    "export-error",
+    "export-cancelling",
+    "export-cancelled",
+    "thumbnail-progress",
+    "thumbnail-generation-complete",
+    "job-progress",
+    "job-queue-updated",
+];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+const UNAUTHORIZED: i64 = -32001;
+
+const MAX_HEAD_BYTES: u64 = 16 * 1024;
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+type Handler =
+    Arc<dyn Fn(String, Value) -> BoxFuture<'static, Result<Value, RpcError>> + Send + Sync>;
+
+struct Server {
+    token: Arc<str>,
+    handler: Handler,
+    events: broadcast::Sender<String>,
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl From<String> for RpcError {
+    fn from(message: String) -> Self {
+        Self {
+            code: SERVER_ERROR,
+            message,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LoadImageParams {
+    path: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApplyAdjustmentsParams {
+    paths: Vec<String>,
+    adjustments: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApplyRelativeAdjustmentsParams {
+    paths: Vec<String>,
+    relative: RelativeAdjustments,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportImagesParams {
+    paths: Vec<String>,
+    output_folder_or_file: String,
+    #[serde(default)]
+    is_explicit_file_path: bool,
+    #[serde(default)]
+    base_origin_folders: Vec<String>,
+    export_settings: ExportSettings,
+    output_format: String,
+    #[serde(default)]
+    sync_options: Option<ExportSyncOptions>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetRatingParams {
+    paths: Vec<String>,
+    rating: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddTagParams {
+    paths: Vec<String>,
+    tag: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListImagesParams {
+    path: String,
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError {
+        code: INVALID_PARAMS,
+        message: e.to_string(),
+    })
+}
+
+fn to_result<T: serde::Serialize>(value: T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::from(e.to_string()))
+}
+
+async fn run_blocking<T, F>(task: F) -> Result<T, RpcError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| RpcError::from(e.to_string()))?
+        .map_err(RpcError::from)
+}
+
+async fn list_images<R: Runtime>(
+    params: Value,
+    app_handle: AppHandle<R>,
+) -> Result<Value, RpcError> {
+    let params: ListImagesParams = parse_params(params)?;
+    let images =
+        run_blocking(move || file_management::list_images_in_dir(params.path, app_handle)).await?;
+    to_result(images)
+}
+
+async fn dispatch(method: &str, params: Value, app_handle: &AppHandle) -> Result<Value, RpcError> {
+    match method {
+        "load_image" => {
+            let params: LoadImageParams = parse_params(params)?;
+            let state = app_handle.state::<AppState>();
+            to_result(image_loader::load_image(params.path, state, app_handle.clone()).await?)
+        }
+        "apply_adjustments_to_paths" => {
+            let params: ApplyAdjustmentsParams = parse_params(params)?;
+            file_management::apply_adjustments_to_paths(
+                params.paths,
+                params.adjustments,
+                app_handle.clone(),
+            )
+            .await?;
+            Ok(Value::Null)
+        }
+        "apply_relative_adjustments_to_paths" => {
+            let params: ApplyRelativeAdjustmentsParams = parse_params(params)?;
+            file_management::apply_relative_adjustments_to_paths(
+                params.paths,
+                params.relative,
+                app_handle.clone(),
+            )
+            .await?;
+            Ok(Value::Null)
+        }
+        "export_images" => {
+            let params: ExportImagesParams = parse_params(params)?;
+            let state = app_handle.state::<AppState>();
+            // Completion is reported through the forwarded export events.
+            export_processing::export_images(
+                params.paths,
+                params.output_folder_or_file,
+                params.is_explicit_file_path,
+                params.base_origin_folders,
+                params.export_settings,
+                params.output_format,
+                None,
+                None,
+                params.sync_options,
+                state,
+                app_handle.clone(),
+            )
+            .await?;
+            Ok(Value::Null)
+        }
+        "set_rating_for_paths" => {
+            let params: SetRatingParams = parse_params(params)?;
+            let app_handle = app_handle.clone();
+            run_blocking(move || {
+                file_management::set_rating_for_paths(params.paths, params.rating, app_handle)
+            })
+            .await?;
+            Ok(Value::Null)
+        }
+        "add_tag_for_paths" => {
+            let params: AddTagParams = parse_params(params)?;
+            let app_handle = app_handle.clone();
+            run_blocking(move || tagging::add_tag_for_paths(params.paths, params.tag, app_handle))
+                .await?;
+            Ok(Value::Null)
+        }
+        "list_images_in_dir" => list_images(params, app_handle.clone()).await,
+        _ => Err(RpcError {
+            code: METHOD_NOT_FOUND,
+            message: format!("Method not found: {}", method),
+        }),
+    }
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+    .to_string()
+}
+
+fn response(id: Value, result: Result<Value, RpcError>) -> String {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string(),
+        Err(e) => error_response(id, e.code, &e.message),
+    }
+}
+
+fn generate_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+fn write_token_file(path: &Path, token: &str) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // `mode` only applies to newly created files, so tighten a token file left by an older run too.
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    let mut file = options.open(path)?;
+    file.write_all(token.as_bytes())
+}
+
+fn token_matches(provided: Option<&str>, expected: &str) -> bool {
+    let Some(provided) = provided else {
+        return false;
+    };
+    if provided.len() != expected.len() {
+        return false;
+    }
+    provided
+        .bytes()
+        .zip(expected.bytes())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+async fn read_request(stream: &mut BufReader<TcpStream>) -> Option<HttpRequest> {
+    let mut head = (&mut *stream).take(MAX_HEAD_BYTES);
+    let mut line = String::new();
+    head.read_line(&mut line).await.ok()?;
+    let mut request_line = line.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+
+    let mut authorization = None;
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        if head.read_line(&mut line).await.ok()? == 0 {
+            return None;
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header.split_once(':')?;
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse().ok()?;
+        }
+    }
+
+    if content_length > MAX_BODY_BYTES {
+        return None;
+    }
+    let mut body = vec![0; content_length];
+    stream.read_exact(&mut body).await.ok()?;
+
+    Some(HttpRequest {
+        method,
+        path,
+        authorization,
+        body,
+    })
+}
+
+async fn write_response(
+    stream: &mut BufReader<TcpStream>,
+    status: &str,
+    extra_headers: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    let content_type = if body.is_empty() {
+        ""
+    } else {
+        "Content-Type: application/json\r\n"
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\n{}{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        extra_headers,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await
+}
+
+/// Runs a single JSON-RPC request. Notifications (no `id`) produce no response body.
+async fn handle_rpc(body: &[u8], handler: &Handler) -> Option<String> {
+    let Ok(message) = serde_json::from_slice::<Value>(body) else {
+        return Some(error_response(Value::Null, PARSE_ERROR, "Parse error"));
+    };
+    let id = message.get("id").cloned().unwrap_or(Value::Null);
+    let request = match serde_json::from_value::<RpcRequest>(message) {
+        Ok(request) if request.jsonrpc == "2.0" => request,
+        _ => return Some(error_response(id, INVALID_REQUEST, "Invalid request")),
+    };
+
+    let result = handler(request.method, request.params).await;
+    request.id.map(|id| response(id, result))
+}
+
+async fn stream_events(stream: &mut BufReader<TcpStream>, events: &broadcast::Sender<String>) {
+    let mut receiver = events.subscribe();
+    let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n";
+    if stream.write_all(head.as_bytes()).await.is_err() || stream.flush().await.is_err() {
+        return;
+    }
+
+    loop {
+        let notification = match receiver.recv().await {
+            Ok(notification) => notification,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        let frame = format!("data: {}\n\n", notification);
+        if stream.write_all(frame.as_bytes()).await.is_err() || stream.flush().await.is_err() {
+            return;
+        }
+    }
+}
+
+async fn handle_connection(stream: TcpStream, server: Arc<Server>) {
+    let mut stream = BufReader::new(stream);
+    let Some(request) = read_request(&mut stream).await else {
+        let _ = write_response(&mut stream, "400 Bad Request", "", "").await;
+        return;
+    };
+
+    let token = request
+        .authorization
+        .as_deref()
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if !token_matches(token, &server.token) {
+        let body = error_response(Value::Null, UNAUTHORIZED, "Unauthorized");
+        let _ = write_response(
+            &mut stream,
+            "401 Unauthorized",
+            "WWW-Authenticate: Bearer\r\n",
+            &body,
+        )
+        .await;
+        return;
+    }
+
+    let _ = match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/") => match handle_rpc(&request.body, &server.handler).await {
+            Some(body) => write_response(&mut stream, "200 OK", "", &body).await,
+            None => write_response(&mut stream, "204 No Content", "", "").await,
+        },
+        ("GET", "/events") => {
+            stream_events(&mut stream, &server.events).await;
+            Ok(())
+        }
+        (_, "/") => {
+            write_response(&mut stream, "405 Method Not Allowed", "Allow: POST\r\n", "").await
+        }
+        (_, "/events") => {
+            write_response(&mut stream, "405 Method Not Allowed", "Allow: GET\r\n", "").await
+        }
+        _ => write_response(&mut stream, "404 Not Found", "", "").await,
+    };
+}
+
+async fn serve(listener: TcpListener, server: Arc<Server>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tauri::async_runtime::spawn(handle_connection(stream, server.clone()));
+            }
+            Err(e) => log::warn!("Automation server failed to accept a connection: {}", e),
+        }
+    }
+}
+
+pub fn start_automation_server(app_handle: AppHandle, port: u16) {
+    let token_path = match app_handle.path().app_data_dir() {
+        Ok(dir) => dir.join(TOKEN_FILE_NAME),
+        Err(e) => {
+            log::error!("Failed to resolve the automation token path: {}", e);
+            return;
+        }
+    };
+    let token: Arc<str> = generate_token().into();
+    if let Err(e) = token_path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| write_token_file(&token_path, &token))
+    {
+        log::error!(
+            "Failed to write automation token to {}: {}",
+            token_path.display(),
+            e
+        );
+        return;
+    }
+
+    let (events, _) = broadcast::channel(256);
+    for &event in FORWARDED_EVENTS {
+        let events = events.clone();
+        app_handle.listen_any(event, move |e| {
+            let params = serde_json::from_str::<Value>(e.payload()).unwrap_or(Value::Null);
+            let notification = json!({ "jsonrpc": "2.0", "method": event, "params": params });
+            let _ = events.send(notification.to_string());
+        });
+    }
+
+    let dispatch_handle = app_handle.clone();
+    let handler: Handler = Arc::new(move |method, params| {
+        let app_handle = dispatch_handle.clone();
+        Box::pin(async move { dispatch(&method, params, &app_handle).await })
+    });
+    let server = Arc::new(Server {
+        token,
+        handler,
+        events,
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("Failed to start automation server on port {}: {}", port, e);
+                return;
+            }
+        };
+        log::info!(
+            "Automation server listening on http://127.0.0.1:{} (token in {})",
+            port,
+            token_path.display()
+        );
+        serve(listener, server).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::net::SocketAddr;
+
+    async fn post(addr: SocketAddr, token: Option<&str>, body: &Value) -> (String, Value) {
+        let body = body.to_string();
+        let authorization = token.map_or(String::new(), |t| {
+            format!("Authorization: Bearer {}\r\n", t)
+        });
+        let request = format!(
+            "POST / HTTP/1.1\r\nHost: {}\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            addr,
+            authorization,
+            body.len(),
+            body
+        );
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.lines().next().unwrap().to_string();
+        (status, serde_json::from_str(body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn lists_images_over_http_with_token() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("IMG_0001.jpg"), b"").unwrap();
+        fs::write(dir.path().join("notes.txt"), b"").unwrap();
+
+        let app = tauri::test::mock_app();
+        app.manage(AppState::default());
+        let app_handle = app.handle().clone();
+        let handler: Handler = Arc::new(move |method, params| {
+            let app_handle = app_handle.clone();
+            Box::pin(async move {
+                match method.as_str() {
+                    "list_images_in_dir" => list_images(params, app_handle).await,
+                    _ => Err(RpcError {
+                        code: METHOD_NOT_FOUND,
+                        message: format!("Method not found: {}", method),
+                    }),
+                }
+            })
+        });
+
+        let token: Arc<str> = generate_token().into();
+        let server = Arc::new(Server {
+            token: token.clone(),
+            handler,
+            events: broadcast::channel(1).0,
+        });
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, server));
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "list_images_in_dir",
+            "params": { "path": dir.path().to_string_lossy() },
+        });
+
+        let (status, body) = post(addr, None, &request).await;
+        assert_eq!(status, "HTTP/1.1 401 Unauthorized");
+        assert_eq!(body["error"]["code"], UNAUTHORIZED);
+
+        let (status, body) = post(addr, Some("wrong"), &request).await;
+        assert_eq!(status, "HTTP/1.1 401 Unauthorized");
+        assert_eq!(body["error"]["code"], UNAUTHORIZED);
+
+        let (status, body) = post(addr, Some(&token), &request).await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(body["id"], 1);
+        let images = body["result"].as_array().unwrap();
+        assert_eq!(images.len(), 1);
+        assert!(
+            images[0]["path"]
+                .as_str()
+                .unwrap()
+                .ends_with("IMG_0001.jpg")
+        );
+    }
+}
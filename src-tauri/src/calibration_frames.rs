@@ -0,0 +1,335 @@
+use anyhow::{Result, anyhow};
+use rawler::{
+    decoders::RawDecodeParams,
+    rawimage::{RawImage, RawImageData, RawPhotometricInterpretation},
+    rawsource::RawSource,
+};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::file_management::read_file_mapped;
+use crate::formats::is_raw_file;
+use crate::raw_processing::black_level_at;
+
+const MAGIC: &[u8; 4] = b"RRCF";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 21;
+
+/// Flat-field gains below this are treated as dead photosites and left alone.
+const MIN_FLAT_GAIN: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FrameKind {
+    Dark,
+    Flat,
+}
+
+impl FrameKind {
+    fn file_name(self) -> &'static str {
+        match self {
+            FrameKind::Dark => "master_dark.rrcal",
+            FrameKind::Flat => "master_flat.rrcal",
+        }
+    }
+}
+
+/// A master calibration frame at sensor resolution. Darks hold raw sensor values, flats hold
+/// the gain of each photosite relative to the mean of its colour.
+pub struct MasterFrame {
+    pub kind: FrameKind,
+    pub width: usize,
+    pub height: usize,
+    pub frame_count: u32,
+    pub data: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MasterFrameInfo {
+    pub path: String,
+    pub kind: FrameKind,
+    pub width: usize,
+    pub height: usize,
+    pub frame_count: u32,
+}
+
+impl MasterFrame {
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut writer = BufWriter::new(fs::File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&[match self.kind {
+            FrameKind::Dark => 0,
+            FrameKind::Flat => 1,
+        }])?;
+        writer.write_all(&(self.width as u32).to_le_bytes())?;
+        writer.write_all(&(self.height as u32).to_le_bytes())?;
+        writer.write_all(&self.frame_count.to_le_bytes())?;
+        for value in &self.data {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path)?;
+        if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+            return Err(anyhow!("Not a master calibration frame"));
+        }
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap_or_default())
+        };
+        if read_u32(4) != VERSION {
+            return Err(anyhow!("Unsupported calibration frame version"));
+        }
+        let kind = match bytes[8] {
+            0 => FrameKind::Dark,
+            1 => FrameKind::Flat,
+            _ => return Err(anyhow!("Unknown calibration frame kind")),
+        };
+        let width = read_u32(9) as usize;
+        let height = read_u32(13) as usize;
+        let frame_count = read_u32(17);
+        let body = &bytes[HEADER_LEN..];
+        if body.len() != width * height * 4 {
+            return Err(anyhow!("Calibration frame is truncated"));
+        }
+        let data = body
+            .par_chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        Ok(Self {
+            kind,
+            width,
+            height,
+            frame_count,
+            data,
+        })
+    }
+
+    fn matches(&self, raw_image: &RawImage) -> bool {
+        self.width == raw_image.width && self.height == raw_image.height
+    }
+}
+
+/// The dark frame and flat field referenced by an image's adjustments.
+#[derive(Default)]
+pub struct CalibrationFrames {
+    pub dark: Option<MasterFrame>,
+    pub flat: Option<MasterFrame>,
+}
+
+impl CalibrationFrames {
+    pub fn load(dark_path: Option<&str>, flat_path: Option<&str>) -> Self {
+        let load = |path: Option<&str>, kind: FrameKind| {
+            let path = path?;
+            match MasterFrame::load(Path::new(path)) {
+                Ok(frame) if frame.kind == kind => Some(frame),
+                Ok(_) => {
+                    log::warn!("'{}' is not a master {:?} frame", path, kind);
+                    None
+                }
+                Err(e) => {
+                    log::warn!("Failed to load calibration frame '{}': {}", path, e);
+                    None
+                }
+            }
+        };
+        Self {
+            dark: load(dark_path, FrameKind::Dark),
+            flat: load(flat_path, FrameKind::Flat),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dark.is_none() && self.flat.is_none()
+    }
+
+    /// Subtracts the dark frame and divides by the flat field on the undemosaiced sensor data.
+    /// The black level is kept, so the rest of the develop is unchanged.
+    pub fn apply(&self, raw_image: &mut RawImage) {
+        if self.is_empty() || raw_image.cpp != 1 {
+            return;
+        }
+        let usable = |frame: &Option<MasterFrame>| match frame {
+            Some(frame) if frame.matches(raw_image) => Some(frame.data.as_slice()),
+            Some(frame) => {
+                log::warn!(
+                    "Skipping {:?} frame of {}x{} for a {}x{} raw",
+                    frame.kind,
+                    frame.width,
+                    frame.height,
+                    raw_image.width,
+                    raw_image.height
+                );
+                None
+            }
+            None => None,
+        };
+        let dark = usable(&self.dark);
+        let flat = usable(&self.flat);
+        if dark.is_none() && flat.is_none() {
+            return;
+        }
+
+        let width = raw_image.width;
+        let black = &raw_image.blacklevel;
+        let RawImageData::Integer(data) = &mut raw_image.data else {
+            return;
+        };
+        data.par_iter_mut().enumerate().for_each(|(i, value)| {
+            let black = black_level_at(black, i / width, i % width);
+            let mut corrected = *value as f32;
+            if let Some(dark) = dark {
+                corrected = corrected - dark[i] + black;
+            }
+            if let Some(flat) = flat
+                && flat[i] > MIN_FLAT_GAIN
+            {
+                corrected = black + (corrected - black) / flat[i];
+            }
+            *value = corrected.round().clamp(0.0, u16::MAX as f32) as u16;
+        });
+    }
+}
+
+fn decode_sensor_data(path: &Path) -> Result<RawImage> {
+    let mmap = read_file_mapped(path).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    let source = RawSource::new_from_slice(&mmap);
+    let decoder = rawler::get_decoder(&source)?;
+    let raw_image = decoder.raw_image(&source, &RawDecodeParams::default(), false)?;
+    if raw_image.cpp != 1 || !matches!(raw_image.data, RawImageData::Integer(_)) {
+        return Err(anyhow!(
+            "{} is not a single-channel raw and cannot be used for calibration",
+            path.display()
+        ));
+    }
+    Ok(raw_image)
+}
+
+/// Averages calibration shots into a master frame. Flats are turned into per-photosite gains by
+/// removing the black level and dividing by the mean of each CFA colour.
+pub fn build_master_frame(paths: &[PathBuf], kind: FrameKind) -> Result<MasterFrame> {
+    let mut first: Option<RawImage> = None;
+    let mut sum: Vec<f32> = Vec::new();
+    let mut frame_count = 0u32;
+
+    for path in paths {
+        let raw_image = match decode_sensor_data(path) {
+            Ok(raw_image) => raw_image,
+            Err(e) => {
+                log::warn!("Skipping calibration frame: {}", e);
+                continue;
+            }
+        };
+        if let Some(first) = &first
+            && (raw_image.width != first.width || raw_image.height != first.height)
+        {
+            log::warn!("Skipping {:?}: its size differs from the first frame", path);
+            continue;
+        }
+        if let RawImageData::Integer(data) = &raw_image.data {
+            if sum.is_empty() {
+                sum = vec![0.0; data.len()];
+            }
+            sum.par_iter_mut()
+                .zip(data.par_iter())
+                .for_each(|(s, &v)| *s += v as f32);
+            frame_count += 1;
+        }
+        if first.is_none() {
+            first = Some(raw_image);
+        }
+    }
+    let Some(first) = first else {
+        return Err(anyhow!("None of the calibration frames could be read"));
+    };
+    let (width, height) = (first.width, first.height);
+
+    let mut data: Vec<f32> = sum
+        .into_par_iter()
+        .map(|s| s / frame_count as f32)
+        .collect();
+
+    if kind == FrameKind::Flat {
+        let color_at = |row: usize, col: usize| match &first.photometric {
+            RawPhotometricInterpretation::Cfa(config) => config.cfa.color_at(row, col).min(3),
+            _ => 0,
+        };
+        let black = &first.blacklevel;
+        data.par_iter_mut().enumerate().for_each(|(i, v)| {
+            *v = (*v - black_level_at(black, i / width, i % width)).max(0.0);
+        });
+
+        let mut totals = [(0.0f64, 0u64); 4];
+        for (i, &v) in data.iter().enumerate() {
+            let total = &mut totals[color_at(i / width, i % width)];
+            total.0 += v as f64;
+            total.1 += 1;
+        }
+        let means = totals.map(|(sum, count)| {
+            if count > 0 {
+                (sum / count as f64) as f32
+            } else {
+                0.0
+            }
+        });
+        data.par_iter_mut().enumerate().for_each(|(i, v)| {
+            let mean = means[color_at(i / width, i % width)];
+            *v = if mean > 0.0 { *v / mean } else { 1.0 };
+        });
+    }
+
+    Ok(MasterFrame {
+        kind,
+        width,
+        height,
+        frame_count,
+        data,
+    })
+}
+
+/// Builds a master dark or flat from every raw in `folder` and saves it there.
+#[tauri::command]
+pub async fn build_master_calibration_frame(
+    folder: String,
+    kind: FrameKind,
+) -> Result<MasterFrameInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let folder = PathBuf::from(folder);
+        let mut paths: Vec<PathBuf> = fs::read_dir(&folder)
+            .map_err(|e| e.to_string())?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file() && is_raw_file(path))
+            .collect();
+        paths.sort();
+        if paths.is_empty() {
+            return Err(format!("No raw files found in {}", folder.display()));
+        }
+
+        let master = build_master_frame(&paths, kind).map_err(|e| e.to_string())?;
+        let output_path = folder.join(kind.file_name());
+        master.save(&output_path).map_err(|e| e.to_string())?;
+        log::info!(
+            "Built master {:?} frame from {} shots at {:?}",
+            kind,
+            master.frame_count,
+            output_path
+        );
+
+        Ok(MasterFrameInfo {
+            path: output_path.to_string_lossy().into_owned(),
+            kind,
+            width: master.width,
+            height: master.height,
+            frame_count: master.frame_count,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,1342 @@
+use glam::{Mat3, Vec2, Vec3};
+use image::DynamicImage;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::image_processing::{
+    GlobalAdjustments, PRIMARIES_REC2020, PRIMARIES_SRGB, WP_D65, calculate_agx_matrices_glam,
+    mat3_to_gpu_mat3, primaries_to_xyz_matrix, xy_to_xyz,
+};
+
+const WP_D50: Vec2 = Vec2::new(0.3457, 0.3585);
+const PCS_D50_XYZ: [f32; 3] = [0.9642, 1.0, 0.8249];
+
+const PRIMARIES_DISPLAY_P3: [Vec2; 3] = [
+    Vec2::new(0.680, 0.320),
+    Vec2::new(0.265, 0.690),
+    Vec2::new(0.150, 0.060),
+];
+const PRIMARIES_ADOBE_RGB: [Vec2; 3] = [
+    Vec2::new(0.64, 0.33),
+    Vec2::new(0.21, 0.71),
+    Vec2::new(0.15, 0.06),
+];
+const PRIMARIES_PROPHOTO: [Vec2; 3] = [
+    Vec2::new(0.7347, 0.2653),
+    Vec2::new(0.1596, 0.8404),
+    Vec2::new(0.0366, 0.0001),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputColorSpace {
+    #[default]
+    Srgb,
+    DisplayP3,
+    AdobeRgb,
+    Rec2020,
+    ProPhoto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TransferFunction {
+    Srgb,
+    Rec709,
+    Gamma(f32),
+}
+
+impl TransferFunction {
+    // ICC parametricCurveType: function type and parameters describing encoded -> linear.
+    fn icc_parametric_curve(self) -> (u16, Vec<f32>) {
+        match self {
+            TransferFunction::Srgb => (
+                3,
+                vec![2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.04045],
+            ),
+            TransferFunction::Rec709 => (
+                3,
+                vec![1.0 / 0.45, 1.0 / 1.099, 0.099 / 1.099, 1.0 / 4.5, 0.081],
+            ),
+            TransferFunction::Gamma(gamma) => (0, vec![gamma]),
+        }
+    }
+}
+
+impl OutputColorSpace {
+    pub fn is_srgb(self) -> bool {
+        self == OutputColorSpace::Srgb
+    }
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            OutputColorSpace::Srgb => "sRGB",
+            OutputColorSpace::DisplayP3 => "Display P3",
+            OutputColorSpace::AdobeRgb => "Adobe RGB (1998)",
+            OutputColorSpace::Rec2020 => "Rec. ITU-R BT.2020",
+            OutputColorSpace::ProPhoto => "ProPhoto RGB",
+        }
+    }
+
+    fn primaries(self) -> &'static [Vec2; 3] {
+        match self {
+            OutputColorSpace::Srgb => &PRIMARIES_SRGB,
+            OutputColorSpace::DisplayP3 => &PRIMARIES_DISPLAY_P3,
+            OutputColorSpace::AdobeRgb => &PRIMARIES_ADOBE_RGB,
+            OutputColorSpace::Rec2020 => &PRIMARIES_REC2020,
+            OutputColorSpace::ProPhoto => &PRIMARIES_PROPHOTO,
+        }
+    }
+
+    fn white_point(self) -> Vec2 {
+        match self {
+            OutputColorSpace::ProPhoto => WP_D50,
+            _ => WP_D65,
+        }
+    }
+
+    fn transfer(self) -> TransferFunction {
+        match self {
+            OutputColorSpace::Srgb | OutputColorSpace::DisplayP3 => TransferFunction::Srgb,
+            OutputColorSpace::AdobeRgb => TransferFunction::Gamma(563.0 / 256.0),
+            OutputColorSpace::Rec2020 => TransferFunction::Rec709,
+            OutputColorSpace::ProPhoto => TransferFunction::Gamma(1.8),
+        }
+    }
+
+    /// CICP (ITU-T H.273) colour primaries and transfer characteristics, where one exists.
+    pub fn cicp(self) -> Option<(u16, u16)> {
+        match self {
+            OutputColorSpace::Srgb => Some((1, 13)),
+            OutputColorSpace::DisplayP3 => Some((12, 13)),
+            OutputColorSpace::Rec2020 => Some((9, 1)),
+            OutputColorSpace::AdobeRgb | OutputColorSpace::ProPhoto => None,
+        }
+    }
+
+    pub fn rgb_to_xyz(self) -> Mat3 {
+        primaries_to_xyz_matrix(self.primaries(), self.white_point())
+    }
+
+    fn linear_srgb_to_self(self) -> Mat3 {
+        let srgb_to_xyz = primaries_to_xyz_matrix(&PRIMARIES_SRGB, WP_D65);
+        let adapt = bradford_adaptation(WP_D65, self.white_point());
+        self.rgb_to_xyz().inverse() * adapt * srgb_to_xyz
+    }
+}
+
+fn bradford_adaptation(source_white: Vec2, target_white: Vec2) -> Mat3 {
+    if source_white == target_white {
+        return Mat3::IDENTITY;
+    }
+    let bradford = Mat3::from_cols(
+        Vec3::new(0.8951, -0.7502, 0.0389),
+        Vec3::new(0.2664, 1.7135, -0.0685),
+        Vec3::new(-0.1614, 0.0367, 1.0296),
+    );
+    let source_cone = bradford * xy_to_xyz(source_white);
+    let target_cone = bradford * xy_to_xyz(target_white);
+    bradford.inverse() * Mat3::from_diagonal(target_cone / source_cone) * bradford
+}
+
+/// Makes the GPU render into `color_space` instead of sRGB. The scene-linear image is moved into
+/// the export primaries before tonemapping, AgX is re-based so in-gamut colours render as they
+/// do in sRGB, and the result is encoded with the space's transfer curve before the final clamp.
+pub fn configure_render(adjustments: &mut GlobalAdjustments, color_space: OutputColorSpace) {
+    if color_space.is_srgb() {
+        return;
+    }
+    let (transfer, gamma) = match color_space.transfer() {
+        TransferFunction::Srgb => (1, 0.0),
+        TransferFunction::Rec709 => (2, 0.0),
+        TransferFunction::Gamma(gamma) => (3, gamma),
+    };
+    let to_output = color_space.linear_srgb_to_self();
+    let (pipe_to_rendering, rendering_to_pipe) = calculate_agx_matrices_glam();
+
+    adjustments.output_transfer = transfer;
+    adjustments.output_gamma = gamma;
+    adjustments.output_gamut_matrix = mat3_to_gpu_mat3(to_output);
+    adjustments.agx_pipe_to_rendering_matrix =
+        mat3_to_gpu_mat3(pipe_to_rendering * to_output.inverse());
+    adjustments.agx_rendering_to_pipe_matrix = mat3_to_gpu_mat3(to_output * rendering_to_pipe);
+}
+
+fn push_s15_fixed16(buf: &mut Vec<u8>, value: f32) {
+    buf.extend_from_slice(&((value as f64 * 65536.0).round() as i32).to_be_bytes());
+}
+
+fn pad_to_four(buf: &mut Vec<u8>) {
+    while !buf.len().is_multiple_of(4) {
+        buf.push(0);
+    }
+}
+
+fn icc_mluc_tag(text: &str) -> Vec<u8> {
+    let utf16: Vec<u8> = text.encode_utf16().flat_map(|c| c.to_be_bytes()).collect();
+    let mut tag = Vec::with_capacity(28 + utf16.len());
+    tag.extend_from_slice(b"mluc");
+    tag.extend_from_slice(&[0; 4]);
+    tag.extend_from_slice(&1u32.to_be_bytes());
+    tag.extend_from_slice(&12u32.to_be_bytes());
+    tag.extend_from_slice(b"enUS");
+    tag.extend_from_slice(&(utf16.len() as u32).to_be_bytes());
+    tag.extend_from_slice(&28u32.to_be_bytes());
+    tag.extend_from_slice(&utf16);
+    tag
+}
+
+fn icc_xyz_tag(xyz: [f32; 3]) -> Vec<u8> {
+    let mut tag = Vec::with_capacity(20);
+    tag.extend_from_slice(b"XYZ ");
+    tag.extend_from_slice(&[0; 4]);
+    for v in xyz {
+        push_s15_fixed16(&mut tag, v);
+    }
+    tag
+}
+
+fn icc_sf32_tag(matrix: Mat3) -> Vec<u8> {
+    let mut tag = Vec::with_capacity(44);
+    tag.extend_from_slice(b"sf32");
+    tag.extend_from_slice(&[0; 4]);
+    let rows = matrix.transpose();
+    for row in [rows.x_axis, rows.y_axis, rows.z_axis] {
+        for v in row.to_array() {
+            push_s15_fixed16(&mut tag, v);
+        }
+    }
+    tag
+}
+
+fn icc_para_tag(transfer: TransferFunction) -> Vec<u8> {
+    let (function_type, params) = transfer.icc_parametric_curve();
+    let mut tag = Vec::with_capacity(12 + params.len() * 4);
+    tag.extend_from_slice(b"para");
+    tag.extend_from_slice(&[0; 4]);
+    tag.extend_from_slice(&function_type.to_be_bytes());
+    tag.extend_from_slice(&[0; 2]);
+    for p in params {
+        push_s15_fixed16(&mut tag, p);
+    }
+    tag
+}
+
+/// Builds a compact ICC v4 matrix/TRC display profile for the given output space.
+pub fn build_icc_profile(color_space: OutputColorSpace) -> Vec<u8> {
+    let adapt = bradford_adaptation(color_space.white_point(), WP_D50);
+    let colorants = adapt * color_space.rgb_to_xyz();
+    let trc = icc_para_tag(color_space.transfer());
+
+    let tags: Vec<([u8; 4], Vec<u8>)> = vec![
+        (*b"desc", icc_mluc_tag(color_space.display_name())),
+        (*b"cprt", icc_mluc_tag("No copyright, use freely")),
+        (*b"wtpt", icc_xyz_tag(PCS_D50_XYZ)),
+        (*b"chad", icc_sf32_tag(adapt)),
+        (*b"rXYZ", icc_xyz_tag(colorants.x_axis.to_array())),
+        (*b"gXYZ", icc_xyz_tag(colorants.y_axis.to_array())),
+        (*b"bXYZ", icc_xyz_tag(colorants.z_axis.to_array())),
+        (*b"rTRC", trc.clone()),
+        (*b"gTRC", trc.clone()),
+        (*b"bTRC", trc),
+    ];
+
+    let table_len = 4 + tags.len() * 12;
+    let mut data = Vec::new();
+    let mut table = Vec::with_capacity(table_len);
+    table.extend_from_slice(&(tags.len() as u32).to_be_bytes());
+
+    let mut trc_offset: Option<(u32, u32)> = None;
+    for (signature, body) in &tags {
+        let is_trc = matches!(signature, b"rTRC" | b"gTRC" | b"bTRC");
+        let (offset, size) = match (is_trc, trc_offset) {
+            (true, Some(shared)) => shared,
+            _ => {
+                pad_to_four(&mut data);
+                let offset = (128 + table_len + data.len()) as u32;
+                data.extend_from_slice(body);
+                (offset, body.len() as u32)
+            }
+        };
+        if is_trc {
+            trc_offset = Some((offset, size));
+        }
+        table.extend_from_slice(signature);
+        table.extend_from_slice(&offset.to_be_bytes());
+        table.extend_from_slice(&size.to_be_bytes());
+    }
+    pad_to_four(&mut data);
+
+    let total_len = 128 + table.len() + data.len();
+    let mut profile = Vec::with_capacity(total_len);
+    profile.extend_from_slice(&(total_len as u32).to_be_bytes());
+    profile.extend_from_slice(&[0; 4]);
+    profile.extend_from_slice(&0x0430_0000u32.to_be_bytes());
+    profile.extend_from_slice(b"mntr");
+    profile.extend_from_slice(b"RGB ");
+    profile.extend_from_slice(b"XYZ ");
+    for v in [2024u16, 1, 1, 0, 0, 0] {
+        profile.extend_from_slice(&v.to_be_bytes());
+    }
+    profile.extend_from_slice(b"acsp");
+    profile.extend_from_slice(&[0; 24]);
+    profile.extend_from_slice(&0u32.to_be_bytes());
+    for v in PCS_D50_XYZ {
+        push_s15_fixed16(&mut profile, v);
+    }
+    profile.resize(128, 0);
+    profile.extend_from_slice(&table);
+    profile.extend_from_slice(&data);
+    profile
+}
+
+/// `colr` box payload carrying CICP (`nclx`) when the space has code points, otherwise the
+/// full ICC profile (`prof`). AVIF from the AV1 encoder uses a BT.601 YCbCr matrix.
+pub fn avif_colr_payload(color_space: OutputColorSpace) -> Vec<u8> {
+    match color_space.cicp() {
+        Some((primaries, transfer)) => {
+            let mut payload = Vec::with_capacity(11);
+            payload.extend_from_slice(b"nclx");
+            payload.extend_from_slice(&primaries.to_be_bytes());
+            payload.extend_from_slice(&transfer.to_be_bytes());
+            payload.extend_from_slice(&6u16.to_be_bytes());
+            payload.push(1 << 7);
+            payload
+        }
+        None => {
+            let mut payload = b"prof".to_vec();
+            payload.extend_from_slice(&build_icc_profile(color_space));
+            payload
+        }
+    }
+}
+
+fn read_u16_be(buf: &[u8], pos: usize) -> Result<u16, String> {
+    buf.get(pos..pos + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or_else(|| "Truncated ISOBMFF data".to_string())
+}
+
+fn read_u32_be(buf: &[u8], pos: usize) -> Result<u32, String> {
+    buf.get(pos..pos + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| "Truncated ISOBMFF data".to_string())
+}
+
+fn read_sized_uint(buf: &[u8], pos: usize, size: usize) -> Result<u64, String> {
+    match size {
+        0 => Ok(0),
+        4 => read_u32_be(buf, pos).map(u64::from),
+        8 => Ok(((read_u32_be(buf, pos)? as u64) << 32) | read_u32_be(buf, pos + 4)? as u64),
+        _ => Err(format!("Unsupported iloc field size {}", size)),
+    }
+}
+
+fn write_sized_uint(buf: &mut [u8], pos: usize, size: usize, value: u64) {
+    match size {
+        4 => buf[pos..pos + 4].copy_from_slice(&(value as u32).to_be_bytes()),
+        8 => buf[pos..pos + 8].copy_from_slice(&value.to_be_bytes()),
+        _ => {}
+    }
+}
+
+struct IsoBox {
+    kind: [u8; 4],
+    start: usize,
+    header_len: usize,
+    end: usize,
+}
+
+fn parse_boxes(buf: &[u8], mut pos: usize, end: usize) -> Result<Vec<IsoBox>, String> {
+    let mut boxes = Vec::new();
+    while pos + 8 <= end {
+        let size32 = read_u32_be(buf, pos)? as usize;
+        let kind: [u8; 4] = buf[pos + 4..pos + 8].try_into().unwrap();
+        let (size, header_len) = match size32 {
+            0 => (end - pos, 8),
+            1 => (read_sized_uint(buf, pos + 8, 8)? as usize, 16),
+            s => (s, 8),
+        };
+        if size < header_len || pos + size > end {
+            return Err("Malformed ISOBMFF box".to_string());
+        }
+        boxes.push(IsoBox {
+            kind,
+            start: pos,
+            header_len,
+            end: pos + size,
+        });
+        pos += size;
+    }
+    Ok(boxes)
+}
+
+fn wrap_box(kind: &[u8; 4], prefix: &[u8], children: &[u8]) -> Vec<u8> {
+    let len = 8 + prefix.len() + children.len();
+    let mut out = Vec::with_capacity(len);
+    out.extend_from_slice(&(len as u32).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(prefix);
+    out.extend_from_slice(children);
+    out
+}
+
+fn find_box<'a>(boxes: &'a [IsoBox], kind: &[u8; 4]) -> Result<&'a IsoBox, String> {
+    boxes
+        .iter()
+        .find(|b| &b.kind == kind)
+        .ok_or_else(|| format!("AVIF is missing '{}' box", String::from_utf8_lossy(kind)))
+}
+
+fn add_ipma_association(ipma: &[u8], item_id: u32, property_index: u16) -> Result<Vec<u8>, String> {
+    let version = ipma[0];
+    let flags = read_u32_be(ipma, 0)? & 0x00ff_ffff;
+    let wide_index = flags & 1 == 1;
+    if !wide_index && property_index > 0x7f {
+        return Err("Too many AVIF item properties".to_string());
+    }
+
+    let entry_count = read_u32_be(ipma, 4)?;
+    let mut out = ipma[..8].to_vec();
+    let mut pos = 8;
+    let mut associated = false;
+    for _ in 0..entry_count {
+        let id = if version < 1 {
+            let id = read_u16_be(ipma, pos)? as u32;
+            out.extend_from_slice(&ipma[pos..pos + 2]);
+            pos += 2;
+            id
+        } else {
+            let id = read_u32_be(ipma, pos)?;
+            out.extend_from_slice(&ipma[pos..pos + 4]);
+            pos += 4;
+            id
+        };
+        let count = *ipma.get(pos).ok_or("Truncated ipma box")?;
+        let assoc_len = count as usize * if wide_index { 2 } else { 1 };
+        let assoc = ipma
+            .get(pos + 1..pos + 1 + assoc_len)
+            .ok_or("Truncated ipma box")?;
+        pos += 1 + assoc_len;
+
+        if id == item_id {
+            out.push(
+                count
+                    .checked_add(1)
+                    .ok_or("Too many AVIF item properties")?,
+            );
+            out.extend_from_slice(assoc);
+            if wide_index {
+                out.extend_from_slice(&property_index.to_be_bytes());
+            } else {
+                out.push(property_index as u8);
+            }
+            associated = true;
+        } else {
+            out.push(count);
+            out.extend_from_slice(assoc);
+        }
+    }
+    if !associated {
+        return Err("AVIF primary item has no property associations".to_string());
+    }
+    Ok(out)
+}
+
+fn shift_iloc_offsets(iloc: &mut [u8], threshold: u64, delta: u64) -> Result<(), String> {
+    let version = iloc[0];
+    let sizes = read_u16_be(iloc, 4)?;
+    let offset_size = (sizes >> 12) as usize;
+    let length_size = ((sizes >> 8) & 0xf) as usize;
+    let base_offset_size = ((sizes >> 4) & 0xf) as usize;
+    let index_size = if version >= 1 {
+        (sizes & 0xf) as usize
+    } else {
+        0
+    };
+
+    let mut pos = 6;
+    let item_count = if version < 2 {
+        let c = read_u16_be(iloc, pos)? as u32;
+        pos += 2;
+        c
+    } else {
+        let c = read_u32_be(iloc, pos)?;
+        pos += 4;
+        c
+    };
+
+    for _ in 0..item_count {
+        pos += if version < 2 { 2 } else { 4 };
+        let construction_method = if version >= 1 {
+            let m = read_u16_be(iloc, pos)? & 0xf;
+            pos += 2;
+            m
+        } else {
+            0
+        };
+        pos += 2;
+        let base_offset = read_sized_uint(iloc, pos, base_offset_size)?;
+        let base_pos = pos;
+        pos += base_offset_size;
+        let extent_count = read_u16_be(iloc, pos)?;
+        pos += 2;
+
+        let file_offsets = construction_method == 0;
+        if file_offsets && base_offset_size > 0 && base_offset >= threshold {
+            write_sized_uint(iloc, base_pos, base_offset_size, base_offset + delta);
+        }
+        for _ in 0..extent_count {
+            pos += index_size;
+            let extent_offset = read_sized_uint(iloc, pos, offset_size)?;
+            if file_offsets && base_offset_size == 0 && extent_offset >= threshold {
+                if offset_size == 0 {
+                    return Err("Cannot relocate AVIF item without offsets".to_string());
+                }
+                write_sized_uint(iloc, pos, offset_size, extent_offset + delta);
+            }
+            pos += offset_size + length_size;
+        }
+    }
+    Ok(())
+}
+
+/// Appends a `colr` property to the primary item of an AVIF file and relocates the item data
+/// that follows the grown `meta` box.
+pub fn embed_avif_colr(avif: &[u8], colr_payload: &[u8]) -> Result<Vec<u8>, String> {
+    let top = parse_boxes(avif, 0, avif.len())?;
+    let meta = find_box(&top, b"meta")?;
+    let meta_body = meta.start + meta.header_len + 4;
+    let children = parse_boxes(avif, meta_body, meta.end)?;
+
+    let pitm = find_box(&children, b"pitm")?;
+    let pitm_body = pitm.start + pitm.header_len;
+    let primary_item = if avif[pitm_body] == 0 {
+        read_u16_be(avif, pitm_body + 4)? as u32
+    } else {
+        read_u32_be(avif, pitm_body + 4)?
+    };
+
+    let iprp = find_box(&children, b"iprp")?;
+    let iprp_children = parse_boxes(avif, iprp.start + iprp.header_len, iprp.end)?;
+    let ipco = find_box(&iprp_children, b"ipco")?;
+    let ipco_children = parse_boxes(avif, ipco.start + ipco.header_len, ipco.end)?;
+    let property_index = (ipco_children.len() + 1) as u16;
+
+    let mut new_ipco_body = avif[ipco.start + ipco.header_len..ipco.end].to_vec();
+    new_ipco_body.extend_from_slice(&wrap_box(b"colr", &[], colr_payload));
+
+    let mut new_iprp_body = Vec::new();
+    for child in &iprp_children {
+        match &child.kind {
+            b"ipco" => new_iprp_body.extend_from_slice(&wrap_box(b"ipco", &[], &new_ipco_body)),
+            b"ipma" => {
+                let body = &avif[child.start + child.header_len..child.end];
+                let patched = add_ipma_association(body, primary_item, property_index)?;
+                new_iprp_body.extend_from_slice(&wrap_box(b"ipma", &[], &patched));
+            }
+            _ => new_iprp_body.extend_from_slice(&avif[child.start..child.end]),
+        }
+    }
+    let new_iprp = wrap_box(b"iprp", &[], &new_iprp_body);
+    let delta = (new_iprp.len() - (iprp.end - iprp.start)) as u64;
+
+    let mut new_meta_body = Vec::new();
+    for child in &children {
+        match &child.kind {
+            b"iprp" => new_meta_body.extend_from_slice(&new_iprp),
+            b"iloc" => {
+                let mut iloc = avif[child.start..child.end].to_vec();
+                shift_iloc_offsets(&mut iloc[child.header_len..], meta.end as u64, delta)?;
+                new_meta_body.extend_from_slice(&iloc);
+            }
+            _ => new_meta_body.extend_from_slice(&avif[child.start..child.end]),
+        }
+    }
+    let full_box_header = &avif[meta.start + meta.header_len..meta_body];
+
+    let mut out = Vec::with_capacity(avif.len() + delta as usize);
+    out.extend_from_slice(&avif[..meta.start]);
+    out.extend_from_slice(&wrap_box(b"meta", full_box_header, &new_meta_body));
+    out.extend_from_slice(&avif[meta.end..]);
+    Ok(out)
+}
+
+/// Inserts an `ICCP` chunk into a WebP file, promoting simple lossy/lossless files to the
+/// extended (`VP8X`) layout.
+pub fn embed_webp_icc(
+    webp: &[u8],
+    icc_profile: &[u8],
+    width: u32,
+    height: u32,
+    has_alpha: bool,
+) -> Result<Vec<u8>, String> {
+    if webp.len() < 20 || &webp[0..4] != b"RIFF" || &webp[8..12] != b"WEBP" {
+        return Err("Invalid WebP data".to_string());
+    }
+
+    let mut iccp_chunk = Vec::with_capacity(icc_profile.len() + 9);
+    iccp_chunk.extend_from_slice(b"ICCP");
+    iccp_chunk.extend_from_slice(&(icc_profile.len() as u32).to_le_bytes());
+    iccp_chunk.extend_from_slice(icc_profile);
+    if icc_profile.len() % 2 == 1 {
+        iccp_chunk.push(0);
+    }
+
+    let mut body = Vec::with_capacity(webp.len() + iccp_chunk.len() + 18);
+    if &webp[12..16] == b"VP8X" {
+        let vp8x_len = 8 + u32::from_le_bytes(webp[16..20].try_into().unwrap()) as usize;
+        let mut vp8x = webp
+            .get(12..12 + vp8x_len)
+            .ok_or("Truncated VP8X chunk")?
+            .to_vec();
+        vp8x[8] |= 0x20;
+        body.extend_from_slice(&vp8x);
+        body.extend_from_slice(&iccp_chunk);
+        body.extend_from_slice(&webp[12 + vp8x_len..]);
+    } else {
+        let mut flags = 0x20u8;
+        if has_alpha {
+            flags |= 0x10;
+        }
+        body.extend_from_slice(b"VP8X");
+        body.extend_from_slice(&10u32.to_le_bytes());
+        body.extend_from_slice(&[flags, 0, 0, 0]);
+        body.extend_from_slice(&(width - 1).to_le_bytes()[..3]);
+        body.extend_from_slice(&(height - 1).to_le_bytes()[..3]);
+        body.extend_from_slice(&iccp_chunk);
+        body.extend_from_slice(&webp[12..]);
+    }
+
+    let mut out = Vec::with_capacity(body.len() + 12);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&((body.len() + 4) as u32).to_le_bytes());
+    out.extend_from_slice(b"WEBP");
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+impl TransferFunction {
+    fn to_linear(self, value: f32) -> f32 {
+        match self {
+            TransferFunction::Srgb if value <= 0.04045 => value / 12.92,
+            TransferFunction::Srgb => ((value + 0.055) / 1.055).powf(2.4),
+            TransferFunction::Rec709 if value < 0.081 => value / 4.5,
+            TransferFunction::Rec709 => ((value + 0.099) / 1.099).powf(1.0 / 0.45),
+            TransferFunction::Gamma(gamma) => value.max(0.0).powf(gamma),
+        }
+    }
+}
+
+/// Re-encodes pixels rendered in `color_space` as sRGB, clipping colours outside of it.
+pub fn convert_to_srgb(image: &DynamicImage, color_space: OutputColorSpace) -> DynamicImage {
+    if color_space.is_srgb() {
+        return image.clone();
+    }
+    let transfer = color_space.transfer();
+    let to_srgb = color_space.linear_srgb_to_self().inverse();
+    let encode = |linear: f32| {
+        let linear = linear.clamp(0.0, 1.0);
+        if linear <= 0.0031308 {
+            linear * 12.92
+        } else {
+            1.055 * linear.powf(1.0 / 2.4) - 0.055
+        }
+    };
+    let convert = |pixel: &mut [f32]| {
+        let linear = Vec3::new(
+            transfer.to_linear(pixel[0]),
+            transfer.to_linear(pixel[1]),
+            transfer.to_linear(pixel[2]),
+        );
+        let srgb = to_srgb * linear;
+        pixel[0] = encode(srgb.x);
+        pixel[1] = encode(srgb.y);
+        pixel[2] = encode(srgb.z);
+    };
+
+    if image.color().has_alpha() {
+        let mut rgba = image.to_rgba32f();
+        rgba.par_chunks_mut(4).for_each(convert);
+        DynamicImage::ImageRgba32F(rgba)
+    } else {
+        let mut rgb = image.to_rgb32f();
+        rgb.par_chunks_mut(3).for_each(convert);
+        DynamicImage::ImageRgb32F(rgb)
+    }
+}
+
+/// LSB-first bit reader over a JPEG XL codestream.
+struct JxlBitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl JxlBitReader<'_> {
+    fn read(&mut self, bits: usize) -> Result<u64, String> {
+        let mut value = 0u64;
+        for i in 0..bits {
+            let byte = self
+                .data
+                .get((self.pos + i) / 8)
+                .ok_or("Truncated JPEG XL header")?;
+            value |= (((byte >> ((self.pos + i) % 8)) & 1) as u64) << i;
+        }
+        self.pos += bits;
+        Ok(value)
+    }
+
+    fn read_bool(&mut self) -> Result<bool, String> {
+        Ok(self.read(1)? == 1)
+    }
+
+    /// `U32` field: a 2-bit selector, then the selected distribution as `(bits, offset)`.
+    fn read_u32(&mut self, distributions: [(usize, u32); 4]) -> Result<u32, String> {
+        let (bits, offset) = distributions[self.read(2)? as usize];
+        Ok(offset + self.read(bits)? as u32)
+    }
+
+    fn read_u64(&mut self) -> Result<u64, String> {
+        match self.read(2)? {
+            0 => Ok(0),
+            1 => Ok(1 + self.read(4)?),
+            2 => Ok(17 + self.read(8)?),
+            _ => {
+                let mut value = self.read(12)?;
+                let mut shift = 12;
+                while self.read_bool()? {
+                    if shift == 60 {
+                        value |= self.read(4)? << 60;
+                        break;
+                    }
+                    value |= self.read(8)? << shift;
+                    shift += 8;
+                }
+                Ok(value)
+            }
+        }
+    }
+
+    fn read_enum(&mut self) -> Result<u32, String> {
+        self.read_u32(JXL_ENUM)
+    }
+
+    fn skip(&mut self, bits: usize) {
+        self.pos += bits;
+    }
+}
+
+#[derive(Default)]
+struct JxlBitWriter {
+    data: Vec<u8>,
+    pos: usize,
+}
+
+impl JxlBitWriter {
+    fn write(&mut self, value: u64, bits: usize) {
+        for i in 0..bits {
+            if self.pos.is_multiple_of(8) {
+                self.data.push(0);
+            }
+            let bit = ((value >> i) & 1) as u8;
+            *self.data.last_mut().unwrap() |= bit << (self.pos % 8);
+            self.pos += 1;
+        }
+    }
+
+    fn write_u32(&mut self, value: u32, distributions: [(usize, u32); 4]) {
+        let (selector, (bits, offset)) = distributions
+            .into_iter()
+            .enumerate()
+            .find(|&(_, (bits, offset))| {
+                value >= offset && u64::from(value - offset) < (1u64 << bits)
+            })
+            .expect("value outside of the U32 distributions");
+        self.write(selector as u64, 2);
+        self.write(u64::from(value - offset), bits);
+    }
+
+    fn write_enum(&mut self, value: u32) {
+        self.write_u32(value, JXL_ENUM);
+    }
+
+    fn copy(&mut self, data: &[u8], range: std::ops::Range<usize>) {
+        let mut reader = JxlBitReader {
+            data,
+            pos: range.start,
+        };
+        let mut remaining = range.len();
+        while remaining > 0 {
+            let bits = remaining.min(32);
+            self.write(reader.read(bits).unwrap(), bits);
+            remaining -= bits;
+        }
+    }
+}
+
+const JXL_ENUM: [(usize, u32); 4] = [(0, 0), (0, 1), (4, 2), (6, 18)];
+const JXL_DIMENSION: [(usize, u32); 4] = [(9, 1), (13, 1), (18, 1), (30, 1)];
+const JXL_CUSTOM_XY: [(usize, u32); 4] = [(19, 0), (19, 524288), (20, 1048576), (21, 2097152)];
+
+/// Bit positions of the fields `embed_jxl_colour_encoding` rewrites.
+struct JxlHeaderLayout {
+    metadata_all_default: bool,
+    metadata_start: usize,
+    /// Empty when the image metadata is all default.
+    colour_encoding: std::ops::Range<usize>,
+    /// End of the image metadata and transform data, before the padding to the first frame.
+    metadata_end: usize,
+}
+
+fn skip_jxl_bit_depth(reader: &mut JxlBitReader) -> Result<(), String> {
+    if reader.read_bool()? {
+        reader.read_u32([(0, 32), (0, 16), (0, 24), (6, 1)])?;
+        reader.skip(4);
+    } else {
+        reader.read_u32([(0, 8), (0, 10), (0, 12), (6, 1)])?;
+    }
+    Ok(())
+}
+
+fn skip_jxl_size(reader: &mut JxlBitReader) -> Result<(), String> {
+    let div8 = reader.read_bool()?;
+    let read_dimension = |reader: &mut JxlBitReader| -> Result<(), String> {
+        if div8 {
+            reader.skip(5);
+        } else {
+            reader.read_u32(JXL_DIMENSION)?;
+        }
+        Ok(())
+    };
+    read_dimension(reader)?;
+    if reader.read(3)? == 0 {
+        read_dimension(reader)?;
+    }
+    Ok(())
+}
+
+fn skip_jxl_colour_encoding(reader: &mut JxlBitReader) -> Result<(), String> {
+    if reader.read_bool()? {
+        return Ok(());
+    }
+    if reader.read_bool()? {
+        return Err("JPEG XL files with an ICC profile are not supported".to_string());
+    }
+    const XYB: u32 = 2;
+    const GREY: u32 = 1;
+    let colour_space = reader.read_enum()?;
+    let skip_xy = |reader: &mut JxlBitReader| -> Result<(), String> {
+        reader.read_u32(JXL_CUSTOM_XY)?;
+        reader.read_u32(JXL_CUSTOM_XY)?;
+        Ok(())
+    };
+    if colour_space != XYB && reader.read_enum()? == 2 {
+        skip_xy(reader)?;
+    }
+    if colour_space != XYB && colour_space != GREY && reader.read_enum()? == 2 {
+        for _ in 0..3 {
+            skip_xy(reader)?;
+        }
+    }
+    if colour_space != XYB {
+        if reader.read_bool()? {
+            reader.skip(24);
+        } else {
+            reader.read_enum()?;
+        }
+    }
+    reader.read_enum()?;
+    Ok(())
+}
+
+fn read_jxl_header_layout(jxl: &[u8]) -> Result<JxlHeaderLayout, String> {
+    if !jxl.starts_with(&[0xff, 0x0a]) {
+        return Err("Not a bare JPEG XL codestream".to_string());
+    }
+    let mut reader = JxlBitReader { data: jxl, pos: 16 };
+    skip_jxl_size(&mut reader)?;
+
+    let metadata_start = reader.pos;
+    let metadata_all_default = reader.read_bool()?;
+    let mut xyb_encoded = true;
+    let mut colour_encoding = reader.pos..reader.pos;
+    if !metadata_all_default {
+        let extra_fields = reader.read_bool()?;
+        if extra_fields {
+            reader.skip(3);
+            if reader.read_bool()? {
+                skip_jxl_size(&mut reader)?;
+            }
+            if reader.read_bool()? {
+                let div8 = [(0, 16), (0, 32), (5, 1), (9, 33)];
+                let div8_flag = reader.read_bool()?;
+                let full = [(6, 1), (8, 65), (10, 321), (12, 1345)];
+                let dimension = if div8_flag { div8 } else { full };
+                reader.read_u32(dimension)?;
+                if reader.read(3)? == 0 {
+                    reader.read_u32(dimension)?;
+                }
+            }
+            if reader.read_bool()? {
+                reader.read_u32([(0, 100), (0, 1000), (10, 1), (30, 1)])?;
+                reader.read_u32([(0, 1), (0, 1001), (8, 1), (10, 1)])?;
+                reader.read_u32([(0, 0), (3, 0), (16, 0), (32, 0)])?;
+                reader.skip(1);
+            }
+        }
+        skip_jxl_bit_depth(&mut reader)?;
+        reader.skip(1);
+        let extra_channels = reader.read_u32([(0, 0), (0, 1), (4, 2), (12, 1)])?;
+        for _ in 0..extra_channels {
+            if reader.read_bool()? {
+                continue;
+            }
+            let kind = reader.read_enum()?;
+            skip_jxl_bit_depth(&mut reader)?;
+            reader.read_u32([(0, 0), (0, 3), (0, 4), (3, 1)])?;
+            let name_len = reader.read_u32([(0, 0), (4, 0), (5, 16), (10, 48)])?;
+            reader.skip(8 * name_len as usize);
+            match kind {
+                0 => reader.skip(1),
+                2 => reader.skip(4 * 16),
+                5 => {
+                    reader.read_u32([(0, 1), (2, 0), (4, 3), (8, 19)])?;
+                }
+                _ => {}
+            }
+        }
+        xyb_encoded = reader.read_bool()?;
+        let start = reader.pos;
+        skip_jxl_colour_encoding(&mut reader)?;
+        colour_encoding = start..reader.pos;
+        if extra_fields && !reader.read_bool()? {
+            reader.skip(3 * 16 + 1);
+        }
+        let extensions = reader.read_u64()?;
+        let mut extension_bits = 0;
+        for _ in 0..extensions.count_ones() {
+            extension_bits += reader.read_u64()?;
+        }
+        reader.skip(extension_bits as usize);
+    }
+
+    if !reader.read_bool()? {
+        if xyb_encoded && !reader.read_bool()? {
+            reader.skip(16 * 16);
+        }
+        let weights = reader.read(3)?;
+        for (flag, count) in [(1, 15), (2, 55), (4, 210)] {
+            if weights & flag != 0 {
+                reader.skip(count * 16);
+            }
+        }
+    }
+    if reader.pos > jxl.len() * 8 {
+        return Err("Truncated JPEG XL header".to_string());
+    }
+
+    Ok(JxlHeaderLayout {
+        metadata_all_default,
+        metadata_start,
+        colour_encoding,
+        metadata_end: reader.pos,
+    })
+}
+
+fn write_jxl_custom_xy(writer: &mut JxlBitWriter, xy: Vec2) {
+    for value in [xy.x, xy.y] {
+        let value = (value as f64 * 1e6).round() as i64;
+        let packed = if value >= 0 {
+            2 * value
+        } else {
+            -2 * value - 1
+        };
+        writer.write_u32(packed as u32, JXL_CUSTOM_XY);
+    }
+}
+
+/// JPEG XL `ColourEncoding` bundle describing the space, using the enumerated primaries and
+/// transfer curves where they exist and custom chromaticities and gammas otherwise.
+fn write_jxl_colour_encoding(writer: &mut JxlBitWriter, color_space: OutputColorSpace) {
+    const CUSTOM: u32 = 2;
+    writer.write(0, 1);
+    writer.write(0, 1);
+    writer.write_enum(0);
+
+    if color_space.white_point() == WP_D65 {
+        writer.write_enum(1);
+    } else {
+        writer.write_enum(CUSTOM);
+        write_jxl_custom_xy(writer, color_space.white_point());
+    }
+
+    match color_space {
+        OutputColorSpace::Srgb => writer.write_enum(1),
+        OutputColorSpace::Rec2020 => writer.write_enum(9),
+        OutputColorSpace::DisplayP3 => writer.write_enum(11),
+        OutputColorSpace::AdobeRgb | OutputColorSpace::ProPhoto => {
+            writer.write_enum(CUSTOM);
+            for &primary in color_space.primaries() {
+                write_jxl_custom_xy(writer, primary);
+            }
+        }
+    }
+
+    match color_space.transfer() {
+        TransferFunction::Srgb => {
+            writer.write(0, 1);
+            writer.write_enum(13);
+        }
+        TransferFunction::Rec709 => {
+            writer.write(0, 1);
+            writer.write_enum(1);
+        }
+        TransferFunction::Gamma(gamma) => {
+            writer.write(1, 1);
+            writer.write((1e7 / gamma as f64).round() as u64, 24);
+        }
+    }
+    // Relative colorimetric, the default intent.
+    writer.write_enum(1);
+}
+
+/// Replaces the colour encoding in a JPEG XL codestream header. Frames start on a byte
+/// boundary after the header, so everything past it is copied unchanged. Lossy files store
+/// absolute XYB, so the encoding tells decoders which space to output in.
+pub fn embed_jxl_colour_encoding(
+    jxl: &[u8],
+    color_space: OutputColorSpace,
+) -> Result<Vec<u8>, String> {
+    let layout = read_jxl_header_layout(jxl)?;
+    let mut writer = JxlBitWriter::default();
+
+    if layout.metadata_all_default {
+        // Spell out the defaults: 8-bit samples, no extra channels, XYB, no extensions.
+        writer.copy(jxl, 0..layout.metadata_start);
+        writer.write(0, 1);
+        writer.write(0, 1);
+        writer.write(0, 1);
+        writer.write(0, 2);
+        writer.write(1, 1);
+        writer.write(0, 2);
+        writer.write(1, 1);
+        write_jxl_colour_encoding(&mut writer, color_space);
+        writer.write(0, 2);
+        writer.copy(jxl, layout.metadata_start + 1..layout.metadata_end);
+    } else {
+        writer.copy(jxl, 0..layout.colour_encoding.start);
+        write_jxl_colour_encoding(&mut writer, color_space);
+        writer.copy(jxl, layout.colour_encoding.end..layout.metadata_end);
+    }
+
+    let mut out = writer.data;
+    out.extend_from_slice(&jxl[layout.metadata_end.div_ceil(8)..]);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_SPACES: [OutputColorSpace; 5] = [
+        OutputColorSpace::Srgb,
+        OutputColorSpace::DisplayP3,
+        OutputColorSpace::AdobeRgb,
+        OutputColorSpace::Rec2020,
+        OutputColorSpace::ProPhoto,
+    ];
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!((a - b).abs().max_element() < 1e-3, "{:?} != {:?}", a, b);
+    }
+
+    fn wrap_full_box(kind: &[u8; 4], version: u8, body: &[u8]) -> Vec<u8> {
+        wrap_box(kind, &[version, 0, 0, 0], body)
+    }
+
+    fn synthetic_avif(payload: &[u8]) -> Vec<u8> {
+        let ftyp = wrap_box(b"ftyp", b"avif\0\0\0\0avifmif1", &[]);
+        let pitm = wrap_full_box(b"pitm", 0, &1u16.to_be_bytes());
+        let ispe = wrap_full_box(b"ispe", 0, &[0, 0, 0, 1, 0, 0, 0, 1]);
+        let ipco = wrap_box(b"ipco", &[], &ispe);
+        let ipma = wrap_full_box(b"ipma", 0, &[0, 0, 0, 1, 0, 1, 1, 0x81]);
+        let iprp = wrap_box(b"iprp", &[], &[ipco, ipma].concat());
+
+        let build = |data_offset: u32| {
+            let mut iloc_body = vec![0x44, 0x00, 0, 1, 0, 1, 0, 0, 0, 1];
+            iloc_body.extend_from_slice(&data_offset.to_be_bytes());
+            iloc_body.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+            let iloc = wrap_full_box(b"iloc", 0, &iloc_body);
+            let meta = wrap_full_box(b"meta", 0, &[pitm.clone(), iloc, iprp.clone()].concat());
+            [ftyp.clone(), meta, wrap_box(b"mdat", &[], payload)].concat()
+        };
+        let probe = build(0);
+        build((probe.len() - payload.len()) as u32)
+    }
+
+    fn avif_item_data(avif: &[u8]) -> &[u8] {
+        let top = parse_boxes(avif, 0, avif.len()).unwrap();
+        let meta = find_box(&top, b"meta").unwrap();
+        let children = parse_boxes(avif, meta.start + meta.header_len + 4, meta.end).unwrap();
+        let iloc = find_box(&children, b"iloc").unwrap();
+        let extent = iloc.start + iloc.header_len + 4 + 10;
+        let offset = read_u32_be(avif, extent).unwrap() as usize;
+        let length = read_u32_be(avif, extent + 4).unwrap() as usize;
+        &avif[offset..offset + length]
+    }
+
+    #[test]
+    fn white_maps_to_white_in_every_space() {
+        for space in ALL_SPACES {
+            assert_close(space.linear_srgb_to_self() * Vec3::ONE, Vec3::ONE);
+        }
+    }
+
+    #[test]
+    fn srgb_primaries_stay_inside_wider_gamuts() {
+        for space in [
+            OutputColorSpace::DisplayP3,
+            OutputColorSpace::AdobeRgb,
+            OutputColorSpace::Rec2020,
+            OutputColorSpace::ProPhoto,
+        ] {
+            let m = space.linear_srgb_to_self();
+            for primary in [Vec3::X, Vec3::Y, Vec3::Z] {
+                let converted = m * primary;
+                assert!(
+                    converted.min_element() > -1e-4,
+                    "{:?}: {:?}",
+                    space,
+                    converted
+                );
+                assert!(
+                    converted.max_element() < 1.0 + 1e-4,
+                    "{:?}: {:?}",
+                    space,
+                    converted
+                );
+            }
+        }
+        assert_close(
+            OutputColorSpace::Srgb.linear_srgb_to_self() * Vec3::X,
+            Vec3::X,
+        );
+    }
+
+    #[test]
+    fn configure_render_leaves_srgb_untouched() {
+        let mut adjustments = GlobalAdjustments::default();
+        configure_render(&mut adjustments, OutputColorSpace::Srgb);
+        assert_eq!(adjustments.output_transfer, 0);
+
+        configure_render(&mut adjustments, OutputColorSpace::Rec2020);
+        assert_eq!(adjustments.output_transfer, 2);
+
+        let mut adjustments = GlobalAdjustments::default();
+        configure_render(&mut adjustments, OutputColorSpace::AdobeRgb);
+        assert_eq!(adjustments.output_transfer, 3);
+        assert!((adjustments.output_gamma - 563.0 / 256.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn icc_profile_has_valid_header_and_tag_table() {
+        for space in ALL_SPACES {
+            let icc = build_icc_profile(space);
+            assert_eq!(read_u32_be(&icc, 0).unwrap() as usize, icc.len());
+            assert_eq!(&icc[12..16], b"mntr");
+            assert_eq!(&icc[16..20], b"RGB ");
+            assert_eq!(&icc[36..40], b"acsp");
+
+            let tag_count = read_u32_be(&icc, 128).unwrap() as usize;
+            assert_eq!(tag_count, 10);
+            for i in 0..tag_count {
+                let entry = 132 + i * 12;
+                let offset = read_u32_be(&icc, entry + 4).unwrap() as usize;
+                let size = read_u32_be(&icc, entry + 8).unwrap() as usize;
+                assert_eq!(offset % 4, 0);
+                assert!(offset + size <= icc.len());
+            }
+        }
+    }
+
+    #[test]
+    fn avif_colr_uses_nclx_when_cicp_exists() {
+        let p3 = avif_colr_payload(OutputColorSpace::DisplayP3);
+        assert_eq!(p3, [b'n', b'c', b'l', b'x', 0, 12, 0, 13, 0, 6, 0x80]);
+
+        let prophoto = avif_colr_payload(OutputColorSpace::ProPhoto);
+        assert_eq!(&prophoto[..4], b"prof");
+        assert_eq!(
+            &prophoto[4..],
+            build_icc_profile(OutputColorSpace::ProPhoto).as_slice()
+        );
+    }
+
+    #[test]
+    fn embed_avif_colr_relocates_item_data() {
+        let payload = b"av1 payload bytes";
+        let avif = synthetic_avif(payload);
+        assert_eq!(avif_item_data(&avif), payload);
+
+        let colr = avif_colr_payload(OutputColorSpace::Rec2020);
+        let tagged = embed_avif_colr(&avif, &colr).unwrap();
+        assert_eq!(avif_item_data(&tagged), payload);
+
+        let top = parse_boxes(&tagged, 0, tagged.len()).unwrap();
+        let meta = find_box(&top, b"meta").unwrap();
+        let children = parse_boxes(&tagged, meta.start + meta.header_len + 4, meta.end).unwrap();
+        let iprp = find_box(&children, b"iprp").unwrap();
+        let iprp_children = parse_boxes(&tagged, iprp.start + iprp.header_len, iprp.end).unwrap();
+        let ipco = find_box(&iprp_children, b"ipco").unwrap();
+        let properties = parse_boxes(&tagged, ipco.start + ipco.header_len, ipco.end).unwrap();
+        assert_eq!(&properties[1].kind, b"colr");
+
+        let ipma = find_box(&iprp_children, b"ipma").unwrap();
+        let ipma_body = &tagged[ipma.start + ipma.header_len..ipma.end];
+        assert_eq!(&ipma_body[10..], &[2, 0x81, 2]);
+    }
+
+    #[test]
+    fn embed_webp_icc_promotes_simple_files_to_vp8x() {
+        let vp8l = [0x2f, 0, 0, 0, 0];
+        let mut webp = b"RIFF".to_vec();
+        webp.extend_from_slice(&(4 + 8 + vp8l.len() as u32 + 1).to_le_bytes());
+        webp.extend_from_slice(b"WEBPVP8L");
+        webp.extend_from_slice(&(vp8l.len() as u32).to_le_bytes());
+        webp.extend_from_slice(&vp8l);
+        webp.push(0);
+
+        let icc = build_icc_profile(OutputColorSpace::DisplayP3);
+        let tagged = embed_webp_icc(&webp, &icc, 1, 1, true).unwrap();
+        assert_eq!(&tagged[12..16], b"VP8X");
+        assert_eq!(tagged[20], 0x30);
+        assert_eq!(&tagged[30..34], b"ICCP");
+        assert_eq!(
+            u32::from_le_bytes(tagged[4..8].try_into().unwrap()) as usize,
+            tagged.len() - 8
+        );
+        assert_eq!(&tagged[38..38 + icc.len()], icc.as_slice());
+    }
+
+    /// A 64x64 codestream header followed by two bytes of frame data.
+    fn synthetic_jxl(metadata: impl FnOnce(&mut JxlBitWriter)) -> Vec<u8> {
+        let mut writer = JxlBitWriter::default();
+        writer.write(0x0aff, 16);
+        writer.write(1, 1);
+        writer.write(7, 5);
+        writer.write(1, 3);
+        metadata(&mut writer);
+        let mut jxl = writer.data;
+        jxl.extend_from_slice(&[0xde, 0xad]);
+        jxl
+    }
+
+    /// Reads the colour encoding back as (white point, primaries, gamma, transfer function).
+    fn read_colour_encoding(jxl: &[u8]) -> (u32, u32, Option<u64>, Option<u32>) {
+        let layout = read_jxl_header_layout(jxl).unwrap();
+        assert!(!layout.metadata_all_default);
+        let mut reader = JxlBitReader {
+            data: jxl,
+            pos: layout.colour_encoding.start,
+        };
+        assert_eq!(reader.read(2).unwrap(), 0);
+        assert_eq!(reader.read_enum().unwrap(), 0);
+        let white_point = reader.read_enum().unwrap();
+        if white_point == 2 {
+            reader.read_u32(JXL_CUSTOM_XY).unwrap();
+            reader.read_u32(JXL_CUSTOM_XY).unwrap();
+        }
+        let primaries = reader.read_enum().unwrap();
+        if primaries == 2 {
+            for _ in 0..6 {
+                reader.read_u32(JXL_CUSTOM_XY).unwrap();
+            }
+        }
+        let (gamma, transfer) = if reader.read_bool().unwrap() {
+            (Some(reader.read(24).unwrap()), None)
+        } else {
+            (None, Some(reader.read_enum().unwrap()))
+        };
+        assert_eq!(reader.read_enum().unwrap(), 1);
+        assert_eq!(reader.pos, layout.colour_encoding.end);
+        (white_point, primaries, gamma, transfer)
+    }
+
+    #[test]
+    fn embed_jxl_colour_encoding_spells_out_default_metadata() {
+        let jxl = synthetic_jxl(|writer| {
+            writer.write(1, 1);
+            writer.write(1, 1);
+        });
+        let tagged = embed_jxl_colour_encoding(&jxl, OutputColorSpace::DisplayP3).unwrap();
+
+        assert_eq!(read_colour_encoding(&tagged), (1, 11, None, Some(13)));
+        assert_eq!(&tagged[tagged.len() - 2..], &[0xde, 0xad]);
+        let layout = read_jxl_header_layout(&tagged).unwrap();
+        assert_eq!(layout.metadata_end.div_ceil(8), tagged.len() - 2);
+    }
+
+    #[test]
+    fn embed_jxl_colour_encoding_keeps_the_other_metadata() {
+        let jxl = synthetic_jxl(|writer| {
+            writer.write(0, 1);
+            writer.write(0, 1);
+            writer.write(0, 1);
+            writer.write_u32(16, [(0, 8), (0, 10), (0, 12), (6, 1)]);
+            writer.write(1, 1);
+            writer.write_u32(1, [(0, 0), (0, 1), (4, 2), (12, 1)]);
+            writer.write(1, 1);
+            writer.write(0, 1);
+            writer.write(1, 1);
+            writer.write(0, 2);
+            writer.write(1, 1);
+        });
+        let original = read_jxl_header_layout(&jxl).unwrap();
+        let tagged = embed_jxl_colour_encoding(&jxl, OutputColorSpace::ProPhoto).unwrap();
+
+        assert_eq!(read_colour_encoding(&tagged), (2, 2, Some(5555556), None));
+        assert_eq!(&tagged[tagged.len() - 2..], &[0xde, 0xad]);
+        let mut before = JxlBitReader { data: &jxl, pos: 0 };
+        let mut after = JxlBitReader {
+            data: &tagged,
+            pos: 0,
+        };
+        for _ in 0..original.colour_encoding.start {
+            assert_eq!(before.read(1).unwrap(), after.read(1).unwrap());
+        }
+
+        let rec2020 = embed_jxl_colour_encoding(&jxl, OutputColorSpace::Rec2020).unwrap();
+        assert_eq!(read_colour_encoding(&rec2020), (1, 9, None, Some(1)));
+        let adobe = embed_jxl_colour_encoding(&jxl, OutputColorSpace::AdobeRgb).unwrap();
+        assert_eq!(read_colour_encoding(&adobe), (1, 2, Some(4547069), None));
+    }
+
+    #[test]
+    fn convert_to_srgb_undoes_the_output_encoding() {
+        let srgb_encode = |linear: f32| {
+            if linear <= 0.0031308 {
+                linear * 12.92
+            } else {
+                1.055 * linear.powf(1.0 / 2.4) - 0.055
+            }
+        };
+        let color = Vec3::new(0.8, 0.3, 0.1);
+        let spaces: [(OutputColorSpace, &dyn Fn(f32) -> f32); 2] = [
+            (OutputColorSpace::DisplayP3, &srgb_encode),
+            (OutputColorSpace::AdobeRgb, &|linear: f32| {
+                linear.powf(256.0 / 563.0)
+            }),
+        ];
+        for (space, encode) in spaces {
+            let rendered = (space.linear_srgb_to_self() * color).to_array().map(encode);
+            let image = DynamicImage::ImageRgb32F(
+                image::ImageBuffer::from_raw(1, 1, rendered.to_vec()).unwrap(),
+            );
+
+            let srgb = convert_to_srgb(&image, space).to_rgb32f();
+            assert_close(
+                Vec3::from_slice(srgb.as_raw()),
+                Vec3::from_array(color.to_array().map(srgb_encode)),
+            );
+        }
+    }
+}
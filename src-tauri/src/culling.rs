@@ -126,6 +126,7 @@ fn analyze_image(
     path: &str,
     hasher: &image_hasher::Hasher,
     settings: &crate::app_settings::AppSettings,
+    app_handle: &AppHandle,
 ) -> Result<ImageAnalysisData, String> {
     const ANALYSIS_DIM: u32 = 720; // FIXME: How should we calculate good focus if it's downscaled?!?
 
@@ -135,8 +136,15 @@ fn analyze_image(
 
     let file_bytes = std::fs::read(path).map_err(|e| e.to_string())?;
 
-    let img = image_loader::load_base_image_from_bytes(&file_bytes, path, true, settings, None)
-        .map_err(|e| e.to_string())?;
+    let img = image_loader::load_base_image_from_bytes(
+        &file_bytes,
+        path,
+        true,
+        settings,
+        app_handle,
+        None,
+    )
+    .map_err(|e| e.to_string())?;
 
     let (width, height) = img.dimensions();
     let thumbnail = img.thumbnail(ANALYSIS_DIM, ANALYSIS_DIM);
@@ -213,7 +221,8 @@ pub async fn cull_images(
                 },
             );
 
-            analyze_image(path, &hasher, &app_settings).map_err(|e| (path.to_string(), e))
+            analyze_image(path, &hasher, &app_settings, &app_handle)
+                .map_err(|e| (path.to_string(), e))
         })
         .collect();
 
@@ -0,0 +1,864 @@
+#[cfg(target_os = "android")]
+use crate::android_integration::{
+    is_android_content_uri, read_android_content_uri, resolve_android_content_uri_name,
+};
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use std::fs::{create_dir_all, read_dir};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+type Matrix = [[f64; 3]; 3];
+
+const TAG_UNIQUE_CAMERA_MODEL: u16 = 50708;
+const TAG_COLOR_MATRIX_1: u16 = 50721;
+const TAG_COLOR_MATRIX_2: u16 = 50722;
+const TAG_CALIBRATION_ILLUMINANT_1: u16 = 50778;
+const TAG_CALIBRATION_ILLUMINANT_2: u16 = 50779;
+const TAG_PROFILE_NAME: u16 = 50936;
+const TAG_HUE_SAT_MAP_DIMS: u16 = 50937;
+const TAG_HUE_SAT_MAP_DATA_1: u16 = 50938;
+const TAG_HUE_SAT_MAP_DATA_2: u16 = 50939;
+const TAG_TONE_CURVE: u16 = 50940;
+const TAG_FORWARD_MATRIX_1: u16 = 50964;
+const TAG_FORWARD_MATRIX_2: u16 = 50965;
+const TAG_LOOK_TABLE_DIMS: u16 = 50981;
+const TAG_LOOK_TABLE_DATA: u16 = 50982;
+const TAG_HUE_SAT_MAP_ENCODING: u16 = 51108;
+const TAG_LOOK_TABLE_ENCODING: u16 = 51109;
+
+const D50_XY: [f64; 2] = [0.3457, 0.3585];
+
+const BRADFORD: Matrix = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+const XYZ_D50_TO_PROPHOTO: Matrix = [
+    [1.3459433, -0.2556075, -0.0511118],
+    [-0.5445989, 1.5081673, 0.0205351],
+    [0.0, 0.0, 1.2118128],
+];
+
+const PROPHOTO_TO_XYZ_D50: Matrix = [
+    [0.7976749, 0.1351917, 0.0313534],
+    [0.2880402, 0.7118741, 0.0000857],
+    [0.0, 0.0, 0.8252100],
+];
+
+/// Bradford-adapted, so D50 white lands on sRGB white.
+const XYZ_D50_TO_LINEAR_SRGB: Matrix = [
+    [3.1338561, -1.6168667, -0.4906146],
+    [-0.9787684, 1.9161415, 0.0334540],
+    [0.0719453, -0.2289914, 1.4052427],
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraProfileEntry {
+    pub name: String,
+    pub path: String,
+    pub camera_model: String,
+}
+
+/// A HueSatMap or LookTable: hue shift in degrees, saturation scale and value scale, indexed by
+/// value, then hue, then saturation.
+#[derive(Debug, Clone)]
+pub struct HueSatMap {
+    hue_divisions: usize,
+    sat_divisions: usize,
+    val_divisions: usize,
+    data: Vec<[f32; 3]>,
+    srgb_encoded: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ToneCurve {
+    samples: Vec<f32>,
+}
+
+/// A DNG Camera Profile. Single-illuminant profiles only fill the first slot of each pair.
+#[derive(Debug, Clone)]
+pub struct DcpProfile {
+    pub name: String,
+    pub camera_model: String,
+    illuminants: [u16; 2],
+    color_matrices: [Option<Matrix>; 2],
+    forward_matrices: [Option<Matrix>; 2],
+    hue_sat_maps: [Option<HueSatMap>; 2],
+    look_table: Option<HueSatMap>,
+    pub tone_curve: Option<ToneCurve>,
+}
+
+/// A profile resolved for one image's white balance, mapping white-balanced camera RGB to
+/// linear sRGB.
+pub struct ProfileTransform<'a> {
+    camera_to_prophoto: [[f32; 3]; 3],
+    hue_sat_map: Option<HueSatMap>,
+    look_table: Option<&'a HueSatMap>,
+    tone_curve: Option<&'a ToneCurve>,
+    prophoto_to_srgb: [[f32; 3]; 3],
+}
+
+struct TiffReader<'a> {
+    bytes: &'a [u8],
+    little_endian: bool,
+}
+
+struct TiffEntry {
+    tag: u16,
+    kind: u16,
+    count: usize,
+    offset: usize,
+}
+
+impl<'a> TiffReader<'a> {
+    fn u16_at(&self, offset: usize) -> Option<u16> {
+        let b: [u8; 2] = self.bytes.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u16::from_le_bytes(b)
+        } else {
+            u16::from_be_bytes(b)
+        })
+    }
+
+    fn u32_at(&self, offset: usize) -> Option<u32> {
+        let b: [u8; 4] = self.bytes.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(b)
+        } else {
+            u32::from_be_bytes(b)
+        })
+    }
+
+    fn u64_at(&self, offset: usize) -> Option<u64> {
+        let b: [u8; 8] = self.bytes.get(offset..offset + 8)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u64::from_le_bytes(b)
+        } else {
+            u64::from_be_bytes(b)
+        })
+    }
+
+    fn entries(&self) -> Result<Vec<TiffEntry>> {
+        let ifd = self
+            .u32_at(4)
+            .ok_or_else(|| anyhow!("Truncated profile header"))? as usize;
+        let count = self
+            .u16_at(ifd)
+            .ok_or_else(|| anyhow!("Invalid profile directory"))? as usize;
+
+        let mut entries = Vec::with_capacity(count);
+        for i in 0..count {
+            let base = ifd + 2 + i * 12;
+            let (Some(tag), Some(kind), Some(count)) = (
+                self.u16_at(base),
+                self.u16_at(base + 2),
+                self.u32_at(base + 4),
+            ) else {
+                return Err(anyhow!("Truncated profile directory"));
+            };
+            let size = match kind {
+                1 | 2 | 6 | 7 => 1,
+                3 | 8 => 2,
+                4 | 9 | 11 => 4,
+                5 | 10 | 12 => 8,
+                _ => continue,
+            } * count as usize;
+            let offset = if size <= 4 {
+                base + 8
+            } else {
+                self.u32_at(base + 8).unwrap_or(0) as usize
+            };
+            if offset + size > self.bytes.len() {
+                return Err(anyhow!("Profile tag {} points outside the file", tag));
+            }
+            entries.push(TiffEntry {
+                tag,
+                kind,
+                count: count as usize,
+                offset,
+            });
+        }
+        Ok(entries)
+    }
+
+    fn numbers(&self, entry: &TiffEntry) -> Vec<f64> {
+        (0..entry.count)
+            .filter_map(|i| {
+                let o = entry.offset;
+                match entry.kind {
+                    1 | 7 => self.bytes.get(o + i).map(|&v| v as f64),
+                    6 => self.bytes.get(o + i).map(|&v| v as i8 as f64),
+                    3 => self.u16_at(o + i * 2).map(|v| v as f64),
+                    8 => self.u16_at(o + i * 2).map(|v| v as i16 as f64),
+                    4 => self.u32_at(o + i * 4).map(|v| v as f64),
+                    9 => self.u32_at(o + i * 4).map(|v| v as i32 as f64),
+                    11 => self.u32_at(o + i * 4).map(|v| f32::from_bits(v) as f64),
+                    5 => {
+                        let n = self.u32_at(o + i * 8)? as f64;
+                        let d = self.u32_at(o + i * 8 + 4)? as f64;
+                        Some(if d == 0.0 { 0.0 } else { n / d })
+                    }
+                    10 => {
+                        let n = self.u32_at(o + i * 8)? as i32 as f64;
+                        let d = self.u32_at(o + i * 8 + 4)? as i32 as f64;
+                        Some(if d == 0.0 { 0.0 } else { n / d })
+                    }
+                    12 => self.u64_at(o + i * 8).map(f64::from_bits),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    fn text(&self, entry: &TiffEntry) -> String {
+        let raw = &self.bytes[entry.offset..entry.offset + entry.count];
+        let end = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+        String::from_utf8_lossy(&raw[..end]).trim().to_string()
+    }
+}
+
+fn to_matrix(values: &[f64]) -> Option<Matrix> {
+    if values.len() != 9 {
+        return None;
+    }
+    Some([
+        [values[0], values[1], values[2]],
+        [values[3], values[4], values[5]],
+        [values[6], values[7], values[8]],
+    ])
+}
+
+fn parse_hue_sat_map(dims: &[f64], data: &[f64], encoding: Option<f64>) -> Option<HueSatMap> {
+    let hue_divisions = *dims.first()? as usize;
+    let sat_divisions = *dims.get(1)? as usize;
+    let val_divisions = dims.get(2).map_or(1, |&v| v as usize).max(1);
+    if hue_divisions == 0 || sat_divisions < 2 {
+        return None;
+    }
+    if data.len() != hue_divisions * sat_divisions * val_divisions * 3 {
+        return None;
+    }
+    Some(HueSatMap {
+        hue_divisions,
+        sat_divisions,
+        val_divisions,
+        data: data
+            .chunks_exact(3)
+            .map(|c| [c[0] as f32, c[1] as f32, c[2] as f32])
+            .collect(),
+        srgb_encoded: encoding == Some(1.0),
+    })
+}
+
+pub fn parse_dcp(bytes: &[u8]) -> Result<DcpProfile> {
+    let little_endian = match bytes.get(..2) {
+        Some(b"II") => true,
+        Some(b"MM") => false,
+        _ => return Err(anyhow!("Not a DNG camera profile")),
+    };
+    let reader = TiffReader {
+        bytes,
+        little_endian,
+    };
+    if reader.u16_at(2) != Some(0x4352) {
+        return Err(anyhow!("Not a DNG camera profile"));
+    }
+
+    let entries = reader.entries()?;
+    let find = |tag: u16| entries.iter().find(|e| e.tag == tag);
+    let numbers = |tag: u16| find(tag).map(|e| reader.numbers(e));
+    let matrix = |tag: u16| numbers(tag).and_then(|v| to_matrix(&v));
+    let illuminant = |tag: u16| numbers(tag).and_then(|v| v.first().copied()).unwrap_or(0.0) as u16;
+
+    let color_matrix_1 = matrix(TAG_COLOR_MATRIX_1)
+        .ok_or_else(|| anyhow!("Profile has no three-colour ColorMatrix1"))?;
+
+    let hue_sat_maps = match numbers(TAG_HUE_SAT_MAP_DIMS) {
+        Some(dims) => {
+            let encoding = numbers(TAG_HUE_SAT_MAP_ENCODING).and_then(|v| v.first().copied());
+            [TAG_HUE_SAT_MAP_DATA_1, TAG_HUE_SAT_MAP_DATA_2]
+                .map(|tag| numbers(tag).and_then(|data| parse_hue_sat_map(&dims, &data, encoding)))
+        }
+        None => [None, None],
+    };
+
+    let look_table = numbers(TAG_LOOK_TABLE_DIMS).and_then(|dims| {
+        let encoding = numbers(TAG_LOOK_TABLE_ENCODING).and_then(|v| v.first().copied());
+        parse_hue_sat_map(&dims, &numbers(TAG_LOOK_TABLE_DATA)?, encoding)
+    });
+
+    let tone_curve = numbers(TAG_TONE_CURVE)
+        .filter(|points| points.len() >= 4 && points.len() % 2 == 0)
+        .map(|points| ToneCurve::from_points(&points));
+
+    Ok(DcpProfile {
+        name: find(TAG_PROFILE_NAME)
+            .map(|e| reader.text(e))
+            .unwrap_or_default(),
+        camera_model: find(TAG_UNIQUE_CAMERA_MODEL)
+            .map(|e| reader.text(e))
+            .unwrap_or_default(),
+        illuminants: [
+            illuminant(TAG_CALIBRATION_ILLUMINANT_1),
+            illuminant(TAG_CALIBRATION_ILLUMINANT_2),
+        ],
+        color_matrices: [Some(color_matrix_1), matrix(TAG_COLOR_MATRIX_2)],
+        forward_matrices: [matrix(TAG_FORWARD_MATRIX_1), matrix(TAG_FORWARD_MATRIX_2)],
+        hue_sat_maps,
+        look_table,
+        tone_curve,
+    })
+}
+
+/// Loads a profile, leaving out its tone curve unless asked for, as the curve targets display
+/// output and usually fights the tonemapper.
+pub fn load_dcp(path: &str, with_tone_curve: bool) -> Result<DcpProfile> {
+    let bytes = std::fs::read(path)?;
+    let mut profile = parse_dcp(&bytes)?;
+    if !with_tone_curve {
+        profile.tone_curve = None;
+    }
+    Ok(profile)
+}
+
+impl ToneCurve {
+    const SAMPLES: usize = 4096;
+
+    fn from_points(points: &[f64]) -> Self {
+        let points: Vec<(f64, f64)> = points.chunks_exact(2).map(|p| (p[0], p[1])).collect();
+        let samples = (0..Self::SAMPLES)
+            .map(|i| {
+                let x = i as f64 / (Self::SAMPLES - 1) as f64;
+                let upper = points
+                    .iter()
+                    .position(|&(px, _)| px >= x)
+                    .unwrap_or(points.len() - 1)
+                    .max(1);
+                let (x0, y0) = points[upper - 1];
+                let (x1, y1) = points[upper];
+                let t = if x1 > x0 {
+                    ((x - x0) / (x1 - x0)).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                (y0 + (y1 - y0) * t) as f32
+            })
+            .collect();
+        Self { samples }
+    }
+
+    /// Values above 1 continue with unit slope so highlight headroom survives.
+    fn eval(&self, x: f32) -> f32 {
+        let last = self.samples.len() - 1;
+        if x >= 1.0 {
+            return self.samples[last] + (x - 1.0);
+        }
+        let pos = x.max(0.0) * last as f32;
+        let i = (pos as usize).min(last - 1);
+        let t = pos - i as f32;
+        self.samples[i] + (self.samples[i + 1] - self.samples[i]) * t
+    }
+
+    /// Applies the curve to the largest and smallest channels and interpolates the middle one,
+    /// like Adobe's RGB tone, so hues do not shift.
+    fn apply(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let mut order = [0, 1, 2];
+        order.sort_by(|&a, &b| rgb[b].total_cmp(&rgb[a]));
+        let (max, mid, min) = (rgb[order[0]], rgb[order[1]], rgb[order[2]]);
+        let max_out = self.eval(max);
+        let min_out = self.eval(min);
+        let mid_out = if max > min {
+            min_out + (max_out - min_out) * (mid - min) / (max - min)
+        } else {
+            max_out
+        };
+        let mut out = [0.0; 3];
+        out[order[0]] = max_out;
+        out[order[1]] = mid_out;
+        out[order[2]] = min_out;
+        out
+    }
+}
+
+fn rgb_to_hsv([r, g, b]: [f32; 3]) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let gap = max - min;
+    if gap <= 0.0 || max <= 0.0 {
+        return (0.0, 0.0, max);
+    }
+    let hue = if r == max {
+        let h = (g - b) / gap;
+        if h < 0.0 { h + 6.0 } else { h }
+    } else if g == max {
+        2.0 + (b - r) / gap
+    } else {
+        4.0 + (r - g) / gap
+    };
+    (hue, gap / max, max)
+}
+
+fn hsv_to_rgb(hue: f32, sat: f32, val: f32) -> [f32; 3] {
+    if sat <= 0.0 {
+        return [val; 3];
+    }
+    let hue = hue.rem_euclid(6.0);
+    let sector = hue.floor();
+    let f = hue - sector;
+    let p = val * (1.0 - sat);
+    let q = val * (1.0 - sat * f);
+    let t = val * (1.0 - sat * (1.0 - f));
+    match sector as u8 {
+        0 => [val, t, p],
+        1 => [q, val, p],
+        2 => [p, val, t],
+        3 => [p, q, val],
+        4 => [t, p, val],
+        _ => [val, p, q],
+    }
+}
+
+fn srgb_encode(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+impl HueSatMap {
+    fn blend(a: &HueSatMap, b: &HueSatMap, weight: f32) -> Option<HueSatMap> {
+        if a.data.len() != b.data.len() || a.hue_divisions != b.hue_divisions {
+            return None;
+        }
+        let data = a
+            .data
+            .iter()
+            .zip(&b.data)
+            .map(|(x, y)| [0, 1, 2].map(|c| x[c] * weight + y[c] * (1.0 - weight)))
+            .collect();
+        Some(HueSatMap { data, ..a.clone() })
+    }
+
+    fn entry(&self, val: usize, hue: usize, sat: usize) -> [f32; 3] {
+        self.data[(val * self.hue_divisions + hue) * self.sat_divisions + sat]
+    }
+
+    fn apply(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let (hue, sat, val) = rgb_to_hsv(rgb);
+
+        let hue_scaled = hue * self.hue_divisions as f32 / 6.0;
+        let h0 = hue_scaled.floor();
+        let h_frac = hue_scaled - h0;
+        let h0 = h0 as usize % self.hue_divisions;
+        let h1 = (h0 + 1) % self.hue_divisions;
+
+        let sat_scaled = sat.clamp(0.0, 1.0) * (self.sat_divisions - 1) as f32;
+        let s0 = (sat_scaled.floor() as usize).min(self.sat_divisions - 2);
+        let s_frac = sat_scaled - s0 as f32;
+
+        let (v0, v1, v_frac) = if self.val_divisions > 1 {
+            let v = val.clamp(0.0, 1.0);
+            let v = if self.srgb_encoded { srgb_encode(v) } else { v };
+            let v_scaled = v * (self.val_divisions - 1) as f32;
+            let v0 = (v_scaled.floor() as usize).min(self.val_divisions - 2);
+            (v0, v0 + 1, v_scaled - v0 as f32)
+        } else {
+            (0, 0, 0.0)
+        };
+
+        let lerp = |a: [f32; 3], b: [f32; 3], t: f32| [0, 1, 2].map(|c| a[c] + (b[c] - a[c]) * t);
+        let plane = |v: usize| {
+            let low = lerp(self.entry(v, h0, s0), self.entry(v, h0, s0 + 1), s_frac);
+            let high = lerp(self.entry(v, h1, s0), self.entry(v, h1, s0 + 1), s_frac);
+            lerp(low, high, h_frac)
+        };
+        let [hue_shift, sat_scale, val_scale] = lerp(plane(v0), plane(v1), v_frac);
+
+        hsv_to_rgb(
+            hue + hue_shift * 6.0 / 360.0,
+            (sat * sat_scale).clamp(0.0, 1.0),
+            val * val_scale,
+        )
+    }
+}
+
+fn mat_mul(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut out = [[0.0; 3]; 3];
+    for (i, row) in out.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    out
+}
+
+fn mat_vec(m: &Matrix, v: [f64; 3]) -> [f64; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+fn diagonal(v: [f64; 3]) -> Matrix {
+    [[v[0], 0.0, 0.0], [0.0, v[1], 0.0], [0.0, 0.0, v[2]]]
+}
+
+fn invert(m: &Matrix) -> Option<Matrix> {
+    let cofactor =
+        |r0: usize, r1: usize, c0: usize, c1: usize| m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0];
+    let det = m[0][0] * cofactor(1, 2, 1, 2) - m[0][1] * cofactor(1, 2, 0, 2)
+        + m[0][2] * cofactor(1, 2, 0, 1);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    Some([
+        [
+            cofactor(1, 2, 1, 2) / det,
+            -cofactor(0, 2, 1, 2) / det,
+            cofactor(0, 1, 1, 2) / det,
+        ],
+        [
+            -cofactor(1, 2, 0, 2) / det,
+            cofactor(0, 2, 0, 2) / det,
+            -cofactor(0, 1, 0, 2) / det,
+        ],
+        [
+            cofactor(1, 2, 0, 1) / det,
+            -cofactor(0, 2, 0, 1) / det,
+            cofactor(0, 1, 0, 1) / det,
+        ],
+    ])
+}
+
+fn blend_matrices(a: &Matrix, b: &Matrix, weight: f64) -> Matrix {
+    let mut out = *a;
+    for (row, other) in out.iter_mut().zip(b) {
+        for (value, other) in row.iter_mut().zip(other) {
+            *value = *value * weight + other * (1.0 - weight);
+        }
+    }
+    out
+}
+
+fn pick_matrix(pair: &[Option<Matrix>; 2], weight: f64) -> Option<Matrix> {
+    match pair {
+        [Some(a), Some(b)] => Some(blend_matrices(a, b, weight)),
+        [Some(a), None] => Some(*a),
+        _ => None,
+    }
+}
+
+fn to_f32(m: &Matrix) -> [[f32; 3]; 3] {
+    m.map(|row| row.map(|v| v as f32))
+}
+
+fn xy_to_xyz([x, y]: [f64; 2]) -> [f64; 3] {
+    [x / y, 1.0, (1.0 - x - y) / y]
+}
+
+fn bradford_adaptation(from: [f64; 2], to: [f64; 2]) -> Option<Matrix> {
+    let source = mat_vec(&BRADFORD, xy_to_xyz(from));
+    let target = mat_vec(&BRADFORD, xy_to_xyz(to));
+    let scale = diagonal([0, 1, 2].map(|c| target[c] / source[c]));
+    Some(mat_mul(&invert(&BRADFORD)?, &mat_mul(&scale, &BRADFORD)))
+}
+
+/// McCamy's approximation, which is plenty for weighting between two calibration illuminants.
+fn correlated_temperature([x, y]: [f64; 2]) -> f64 {
+    let n = (x - 0.3320) / (y - 0.1858);
+    (-449.0 * n.powi(3) + 3525.0 * n.powi(2) - 6823.3 * n + 5520.33).clamp(2000.0, 50000.0)
+}
+
+/// Colour temperatures of the EXIF LightSource values used as calibration illuminants.
+fn illuminant_temperature(illuminant: u16) -> Option<f64> {
+    match illuminant {
+        17 => Some(2856.0),
+        3 => Some(2850.0),
+        24 => Some(3200.0),
+        15 => Some(3450.0),
+        2 | 14 => Some(4150.0),
+        18 => Some(4874.0),
+        13 => Some(5000.0),
+        23 => Some(5003.0),
+        1 | 4 | 9 => Some(5500.0),
+        20 => Some(5503.0),
+        12 => Some(6430.0),
+        10 => Some(6500.0),
+        21 => Some(6504.0),
+        19 => Some(6774.0),
+        11 => Some(7500.0),
+        22 => Some(7504.0),
+        _ => None,
+    }
+}
+
+impl DcpProfile {
+    /// How much of the first illuminant's calibration applies to a white point, interpolated in
+    /// inverse temperature as the DNG specification describes.
+    fn illuminant_weight(&self, white_xy: [f64; 2]) -> f64 {
+        if self.color_matrices[1].is_none() {
+            return 1.0;
+        }
+        let (Some(t1), Some(t2)) = (
+            illuminant_temperature(self.illuminants[0]),
+            illuminant_temperature(self.illuminants[1]),
+        ) else {
+            return 1.0;
+        };
+        if (t1 - t2).abs() < 1.0 {
+            return 1.0;
+        }
+        let t = correlated_temperature(white_xy);
+        ((1.0 / t - 1.0 / t2) / (1.0 / t1 - 1.0 / t2)).clamp(0.0, 1.0)
+    }
+
+    /// Finds the white point of a camera neutral by iterating between it and the illuminant
+    /// weight, since each depends on the other.
+    fn find_white(&self, neutral: [f64; 3]) -> Option<([f64; 2], f64)> {
+        let mut xy = D50_XY;
+        let mut weight = self.illuminant_weight(xy);
+        for _ in 0..30 {
+            let camera_to_xyz = invert(&pick_matrix(&self.color_matrices, weight)?)?;
+            let xyz = mat_vec(&camera_to_xyz, neutral);
+            let sum = xyz[0] + xyz[1] + xyz[2];
+            if sum <= 0.0 || xyz[1] <= 0.0 {
+                break;
+            }
+            let next = [xyz[0] / sum, xyz[1] / sum];
+            let converged = (next[0] - xy[0]).abs() + (next[1] - xy[1]).abs() < 1e-7;
+            xy = next;
+            weight = self.illuminant_weight(xy);
+            if converged {
+                break;
+            }
+        }
+        Some((xy, weight))
+    }
+
+    /// Resolves the profile for an image. `camera_neutral` is the camera RGB of a neutral
+    /// surface, i.e. the inverse of the white balance multipliers.
+    pub fn transform(&self, camera_neutral: [f32; 3]) -> Option<ProfileTransform<'_>> {
+        let neutral = camera_neutral.map(f64::from);
+        let (white_xy, weight) = self.find_white(neutral)?;
+
+        let camera_to_xyz = match pick_matrix(&self.forward_matrices, weight) {
+            Some(forward) => forward,
+            None => {
+                let color_matrix = pick_matrix(&self.color_matrices, weight)?;
+                let to_xyz = mat_mul(&invert(&color_matrix)?, &diagonal(neutral));
+                let adapted = mat_mul(&bradford_adaptation(white_xy, D50_XY)?, &to_xyz);
+                let white_y: f64 = adapted[1].iter().sum();
+                if white_y <= 0.0 {
+                    return None;
+                }
+                adapted.map(|row| row.map(|v| v / white_y))
+            }
+        };
+
+        let hue_sat_map = match &self.hue_sat_maps {
+            [Some(a), Some(b)] => HueSatMap::blend(a, b, weight as f32),
+            [Some(a), None] => Some(a.clone()),
+            _ => None,
+        };
+
+        Some(ProfileTransform {
+            camera_to_prophoto: to_f32(&mat_mul(&XYZ_D50_TO_PROPHOTO, &camera_to_xyz)),
+            hue_sat_map,
+            look_table: self.look_table.as_ref(),
+            tone_curve: self.tone_curve.as_ref(),
+            prophoto_to_srgb: to_f32(&mat_mul(&XYZ_D50_TO_LINEAR_SRGB, &PROPHOTO_TO_XYZ_D50)),
+        })
+    }
+}
+
+fn apply_matrix(m: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+impl ProfileTransform<'_> {
+    pub fn apply(&self, camera_rgb: [f32; 3]) -> [f32; 3] {
+        let mut rgb = apply_matrix(&self.camera_to_prophoto, camera_rgb).map(|c| c.max(0.0));
+        if let Some(map) = &self.hue_sat_map {
+            rgb = map.apply(rgb);
+        }
+        if let Some(table) = self.look_table {
+            rgb = table.apply(rgb);
+        }
+        if let Some(curve) = self.tone_curve {
+            rgb = curve.apply(rgb);
+        }
+        apply_matrix(&self.prophoto_to_srgb, rgb)
+    }
+}
+
+fn normalize_model(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Profiles name cameras like "Sony ILCE-7M3", while EXIF splits that into "SONY" and
+/// "ILCE-7M3", and some makers repeat the brand in the model.
+pub fn profile_matches_camera(profile_model: &str, make: &str, model: &str) -> bool {
+    let profile = normalize_model(profile_model);
+    if profile.is_empty() {
+        return true;
+    }
+    let model = normalize_model(model);
+    if model.is_empty() {
+        return false;
+    }
+    let brand = normalize_model(make.split_whitespace().next().unwrap_or(""));
+    profile == model || profile == format!("{}{}", brand, model)
+}
+
+pub fn get_camera_profiles_dir(app_data_dir: &Path) -> Result<PathBuf> {
+    let dir = app_data_dir.join("camera_profiles");
+    if !dir.exists() {
+        create_dir_all(&dir)?;
+    }
+    Ok(dir)
+}
+
+fn camera_profiles_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    get_camera_profiles_dir(&data_dir).map_err(|e| e.to_string())
+}
+
+pub fn list_camera_profiles_in_dir(dir: &Path) -> Result<Vec<CameraProfileEntry>> {
+    let mut entries = Vec::new();
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+        let is_dcp = path
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("dcp"));
+        if !is_dcp {
+            continue;
+        }
+        let profile = match std::fs::read(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| parse_dcp(&bytes))
+        {
+            Ok(profile) => profile,
+            Err(e) => {
+                log::warn!("Skipping unreadable camera profile {:?}: {}", path, e);
+                continue;
+            }
+        };
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Profile")
+            .to_string();
+        entries.push(CameraProfileEntry {
+            name: if profile.name.is_empty() {
+                stem
+            } else {
+                profile.name
+            },
+            path: path.to_string_lossy().into_owned(),
+            camera_model: profile.camera_model,
+        });
+    }
+    entries.sort_by_key(|e| e.name.to_lowercase());
+    Ok(entries)
+}
+
+fn read_import_source(source: &str) -> Result<(Vec<u8>, String)> {
+    #[cfg(target_os = "android")]
+    if is_android_content_uri(source) {
+        let name = resolve_android_content_uri_name(source).map_err(|e| anyhow!(e))?;
+        let bytes = read_android_content_uri(source).map_err(|e| anyhow!(e))?;
+        return Ok((bytes, name));
+    }
+
+    let name = Path::new(source)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("profile.dcp")
+        .to_string();
+    Ok((std::fs::read(source)?, name))
+}
+
+pub fn import_camera_profiles_to_dir(dir: &Path, source_paths: &[String]) {
+    for source in source_paths {
+        let (bytes, name) = match read_import_source(source) {
+            Ok(result) => result,
+            Err(e) => {
+                log::error!("Failed to read camera profile '{}': {}", source, e);
+                continue;
+            }
+        };
+        if let Err(e) = parse_dcp(&bytes) {
+            log::warn!("Skipping invalid camera profile '{}': {}", source, e);
+            continue;
+        }
+
+        let stem = Path::new(&name)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Profile");
+        let mut destination = dir.join(format!("{}.dcp", stem));
+        let mut suffix = 1;
+        while destination.exists() && suffix < 1000 {
+            destination = dir.join(format!("{} ({}).dcp", stem, suffix));
+            suffix += 1;
+        }
+        if let Err(e) = std::fs::write(&destination, &bytes) {
+            log::error!("Failed to import camera profile '{}': {}", source, e);
+        }
+    }
+}
+
+/// Lists imported profiles. With a camera given, only the profiles made for it are returned.
+#[tauri::command]
+pub fn list_camera_profiles(
+    app_handle: AppHandle,
+    make: Option<String>,
+    model: Option<String>,
+) -> Result<Vec<CameraProfileEntry>, String> {
+    let dir = camera_profiles_dir(&app_handle)?;
+    let mut entries = list_camera_profiles_in_dir(&dir).map_err(|e| e.to_string())?;
+    if let Some(model) = model {
+        let make = make.unwrap_or_default();
+        entries.retain(|entry| profile_matches_camera(&entry.camera_model, &make, &model));
+    }
+    Ok(entries)
+}
+
+#[tauri::command]
+pub fn import_camera_profiles(
+    app_handle: AppHandle,
+    source_paths: Vec<String>,
+    make: Option<String>,
+    model: Option<String>,
+) -> Result<Vec<CameraProfileEntry>, String> {
+    let dir = camera_profiles_dir(&app_handle)?;
+    import_camera_profiles_to_dir(&dir, &source_paths);
+    list_camera_profiles(app_handle, make, model)
+}
+
+#[tauri::command]
+pub fn remove_camera_profile(
+    app_handle: AppHandle,
+    path: String,
+    make: Option<String>,
+    model: Option<String>,
+) -> Result<Vec<CameraProfileEntry>, String> {
+    let dir = camera_profiles_dir(&app_handle)?;
+    let target = Path::new(&path);
+    if !target.starts_with(&dir) {
+        return Err(
+            "Access denied: Cannot remove files outside the camera profile directory".to_string(),
+        );
+    }
+    std::fs::remove_file(target).map_err(|e| e.to_string())?;
+    list_camera_profiles(app_handle, make, model)
+}
@@ -0,0 +1,581 @@
+use anyhow::Result;
+use rawler::{
+    decoders::RawDecodeParams,
+    rawimage::{RawImage, RawImageData, RawPhotometricInterpretation},
+    rawsource::RawSource,
+};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+use crate::demosaic::CfaPattern;
+use crate::file_management::read_file_mapped;
+use crate::raw_processing::black_level_at;
+
+/// A photosite is hot when it is this many times brighter than everything around it.
+const HOT_RATIO: f32 = 4.0;
+/// A photosite is dead when it is below this fraction of its darkest same-colour neighbour.
+const DEAD_RATIO: f32 = 0.25;
+/// Margins as a fraction of the sensor range, so noise in the shadows is never flagged.
+const HOT_MARGIN: f32 = 0.01;
+const DEAD_MIN_SIGNAL: f32 = 0.05;
+const SEARCH_RADIUS: isize = 2;
+const MIN_NEIGHBOURS: usize = 4;
+
+/// Identifies a camera body. Defect maps are per sensor, so raws without a serial number
+/// have no key.
+pub fn camera_key(make: &str, model: &str, serial: Option<&str>) -> Option<String> {
+    let serial = serial.map(str::trim).filter(|s| !s.is_empty())?;
+    Some(format!("{} {} {}", make.trim(), model.trim(), serial))
+}
+
+fn defect_maps_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    Ok(data_dir.join("defect_maps"))
+}
+
+fn map_path(dir: &Path, camera_key: &str) -> PathBuf {
+    let file_name: String = camera_key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    dir.join(format!("{}.json", file_name))
+}
+
+/// Known defective photosites of one camera, as `[row, col]` pairs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefectMap {
+    pub camera_key: String,
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<(u32, u32)>,
+}
+
+impl DefectMap {
+    pub fn load(dir: &Path, camera_key: &str) -> Option<Self> {
+        let path = map_path(dir, camera_key);
+        let contents = fs::read_to_string(&path).ok()?;
+        match serde_json::from_str(&contents) {
+            Ok(map) => Some(map),
+            Err(e) => {
+                log::warn!("Ignoring unreadable defect map {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        fs::write(
+            map_path(dir, &self.camera_key),
+            serde_json::to_string(self)?,
+        )?;
+        Ok(())
+    }
+
+    fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.pixels
+            .iter()
+            .map(|&(row, col)| (row as usize, col as usize))
+            .filter(|&(row, col)| row < self.height && col < self.width)
+            .map(|(row, col)| row * self.width + col)
+    }
+}
+
+/// Defect maps read so far, keyed by camera. `None` records a camera without a map.
+#[derive(Default)]
+pub struct DefectMapCache {
+    maps: Mutex<HashMap<String, Option<Arc<DefectMap>>>>,
+}
+
+impl DefectMapCache {
+    fn invalidate(&self, camera_key: &str) {
+        self.maps.lock().unwrap().remove(camera_key);
+    }
+}
+
+/// The defect maps in the app data dir, each read from disk once per camera.
+pub struct DefectMaps<'a> {
+    dir: PathBuf,
+    cache: &'a DefectMapCache,
+}
+
+impl<'a> DefectMaps<'a> {
+    pub fn new(app_handle: &'a AppHandle) -> Option<Self> {
+        Some(Self {
+            dir: defect_maps_dir(app_handle).ok()?,
+            cache: &app_handle.state::<AppState>().inner().defect_maps,
+        })
+    }
+
+    pub fn get(&self, camera_key: &str) -> Option<Arc<DefectMap>> {
+        self.cache
+            .maps
+            .lock()
+            .unwrap()
+            .entry(camera_key.to_string())
+            .or_insert_with(|| DefectMap::load(&self.dir, camera_key).map(Arc::new))
+            .clone()
+    }
+}
+
+/// Offsets of the same-colour photosites around each position of the CFA pattern.
+fn neighbour_offsets(
+    pattern: &CfaPattern,
+    pattern_size: (usize, usize),
+) -> Vec<Vec<(isize, isize)>> {
+    let (pattern_width, pattern_height) = pattern_size;
+    let mut offsets = Vec::with_capacity(pattern_width * pattern_height);
+    for row in 0..pattern_height {
+        for col in 0..pattern_width {
+            let color = pattern.color_at(row, col);
+            let mut same = Vec::new();
+            for dy in -SEARCH_RADIUS..=SEARCH_RADIUS {
+                for dx in -SEARCH_RADIUS..=SEARCH_RADIUS {
+                    if (dy, dx) == (0, 0) {
+                        continue;
+                    }
+                    let r = (row as isize + dy).rem_euclid(pattern_height as isize) as usize;
+                    let c = (col as isize + dx).rem_euclid(pattern_width as isize) as usize;
+                    if pattern.color_at(r, c) == color {
+                        same.push((dy, dx));
+                    }
+                }
+            }
+            offsets.push(same);
+        }
+    }
+    offsets
+}
+
+struct SensorView<'a> {
+    data: &'a [u16],
+    width: usize,
+    height: usize,
+    pattern_size: (usize, usize),
+    offsets: Vec<Vec<(isize, isize)>>,
+}
+
+impl<'a> SensorView<'a> {
+    fn new(raw_image: &'a RawImage) -> Option<Self> {
+        if raw_image.cpp != 1 {
+            return None;
+        }
+        let RawPhotometricInterpretation::Cfa(config) = &raw_image.photometric else {
+            return None;
+        };
+        let RawImageData::Integer(data) = &raw_image.data else {
+            return None;
+        };
+        let pattern_size = (config.cfa.width, config.cfa.height);
+        if pattern_size.0 == 0 || pattern_size.1 == 0 {
+            return None;
+        }
+        let pattern = CfaPattern::new(pattern_size.0, pattern_size.1, |row, col| {
+            config.cfa.color_at(row, col)
+        });
+        Some(Self::from_cfa(
+            data,
+            raw_image.width,
+            raw_image.height,
+            &pattern,
+            pattern_size,
+        ))
+    }
+
+    fn from_cfa(
+        data: &'a [u16],
+        width: usize,
+        height: usize,
+        pattern: &CfaPattern,
+        pattern_size: (usize, usize),
+    ) -> Self {
+        Self {
+            data,
+            width,
+            height,
+            pattern_size,
+            offsets: neighbour_offsets(pattern, pattern_size),
+        }
+    }
+
+    fn neighbours(&self, row: usize, col: usize) -> impl Iterator<Item = usize> + '_ {
+        let phase = (row % self.pattern_size.1) * self.pattern_size.0 + col % self.pattern_size.0;
+        self.offsets[phase].iter().filter_map(move |&(dy, dx)| {
+            let r = row.checked_add_signed(dy).filter(|&r| r < self.height)?;
+            let c = col.checked_add_signed(dx).filter(|&c| c < self.width)?;
+            Some(r * self.width + c)
+        })
+    }
+
+    fn adjacent(&self, row: usize, col: usize) -> impl Iterator<Item = usize> + '_ {
+        (-1isize..=1)
+            .flat_map(|dy| (-1isize..=1).map(move |dx| (dy, dx)))
+            .filter(|&offset| offset != (0, 0))
+            .filter_map(move |(dy, dx)| {
+                let r = row.checked_add_signed(dy).filter(|&r| r < self.height)?;
+                let c = col.checked_add_signed(dx).filter(|&c| c < self.width)?;
+                Some(r * self.width + c)
+            })
+    }
+
+    fn find_defects(
+        &self,
+        black_at: impl Fn(usize, usize) -> f32 + Sync,
+        white: f32,
+    ) -> Vec<usize> {
+        let signal = |i: usize| {
+            let (row, col) = (i / self.width, i % self.width);
+            (self.data[i] as f32 - black_at(row, col)).max(0.0)
+        };
+
+        (0..self.height)
+            .into_par_iter()
+            .flat_map_iter(|row| {
+                let signal = &signal;
+                let black_at = &black_at;
+                (0..self.width).filter_map(move |col| {
+                    let i = row * self.width + col;
+                    let range = (white - black_at(row, col)).max(1.0);
+                    let value = signal(i);
+
+                    let mut count = 0;
+                    let mut same_max = 0.0f32;
+                    let mut same_min = f32::MAX;
+                    for n in self.neighbours(row, col) {
+                        let v = signal(n);
+                        same_max = same_max.max(v);
+                        same_min = same_min.min(v);
+                        count += 1;
+                    }
+                    if count < MIN_NEIGHBOURS {
+                        return None;
+                    }
+
+                    if value > HOT_RATIO * same_max + HOT_MARGIN * range {
+                        let adjacent_max = self.adjacent(row, col).map(signal).fold(0.0, f32::max);
+                        if value > HOT_RATIO * adjacent_max + HOT_MARGIN * range {
+                            return Some(i);
+                        }
+                    }
+                    if same_min > DEAD_MIN_SIGNAL * range && value < DEAD_RATIO * same_min {
+                        return Some(i);
+                    }
+                    None
+                })
+            })
+            .collect()
+    }
+
+    /// The median of each defect's same-colour neighbours that are not defects.
+    fn replacements(&self, defects: &HashSet<usize>) -> Vec<(usize, u16)> {
+        defects
+            .par_iter()
+            .filter(|&&i| i < self.data.len())
+            .filter_map(|&i| {
+                let (row, col) = (i / self.width, i % self.width);
+                let mut values: Vec<u16> = self
+                    .neighbours(row, col)
+                    .filter(|n| !defects.contains(n))
+                    .map(|n| self.data[n])
+                    .collect();
+                if values.is_empty() {
+                    return None;
+                }
+                values.sort_unstable();
+                Some((i, values[values.len() / 2]))
+            })
+            .collect()
+    }
+}
+
+/// Finds photosites that are far brighter than every neighbour, or far darker than all of
+/// their same-colour neighbours in a well-exposed area.
+pub fn detect_defects(raw_image: &RawImage) -> Vec<usize> {
+    let Some(view) = SensorView::new(raw_image) else {
+        return Vec::new();
+    };
+    let black = &raw_image.blacklevel;
+    let white = raw_image
+        .whitelevel
+        .0
+        .first()
+        .cloned()
+        .unwrap_or(u16::MAX as u32) as f32;
+    view.find_defects(|row, col| black_level_at(black, row, col), white)
+}
+
+/// Replaces each defect with the median of its same-colour neighbours that are not defects.
+fn interpolate_defects(raw_image: &mut RawImage, defects: &HashSet<usize>) {
+    let replacements = match SensorView::new(raw_image) {
+        Some(view) => view.replacements(defects),
+        None => return,
+    };
+    if let RawImageData::Integer(data) = &mut raw_image.data {
+        for (i, value) in replacements {
+            data[i] = value;
+        }
+    }
+}
+
+/// Repairs the photosites in the camera's defect map, plus any found in this frame when
+/// `detect` is set, on the undemosaiced sensor data.
+pub fn correct_defects(raw_image: &mut RawImage, map: Option<&DefectMap>, detect: bool) {
+    let mut defects: HashSet<usize> = HashSet::new();
+    if let Some(map) = map {
+        if map.width == raw_image.width && map.height == raw_image.height {
+            defects.extend(map.indices());
+        } else {
+            log::warn!(
+                "Skipping defect map of {}x{} for a {}x{} raw",
+                map.width,
+                map.height,
+                raw_image.width,
+                raw_image.height
+            );
+        }
+    }
+    if detect {
+        defects.extend(detect_defects(raw_image));
+    }
+    if !defects.is_empty() {
+        interpolate_defects(raw_image, &defects);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefectMapInfo {
+    pub camera_key: String,
+    pub pixel_count: usize,
+}
+
+/// Reads the camera key of the raw at `path`, and its sensor data when `with_image` is set.
+fn read_raw(path: &str, with_image: bool) -> Result<(String, Option<RawImage>), String> {
+    let mmap = read_file_mapped(Path::new(path)).map_err(|e| e.to_string())?;
+    let source = RawSource::new_from_slice(&mmap);
+    let decoder = rawler::get_decoder(&source).map_err(|e| e.to_string())?;
+    let metadata = decoder
+        .raw_metadata(&source, &RawDecodeParams::default())
+        .map_err(|e| e.to_string())?;
+    let key = camera_key(
+        &metadata.make,
+        &metadata.model,
+        metadata.exif.serial_number.as_deref(),
+    )
+    .ok_or_else(|| "This raw file does not record a camera serial number".to_string())?;
+    let raw_image = if with_image {
+        Some(
+            decoder
+                .raw_image(&source, &RawDecodeParams::default(), false)
+                .map_err(|e| e.to_string())?,
+        )
+    } else {
+        None
+    };
+    Ok((key, raw_image))
+}
+
+#[tauri::command]
+pub async fn get_defect_map_info(
+    path: String,
+    app_handle: AppHandle,
+) -> Result<DefectMapInfo, String> {
+    let dir = defect_maps_dir(&app_handle)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let (key, _) = read_raw(&path, false)?;
+        let pixel_count = DefectMap::load(&dir, &key).map_or(0, |map| map.pixels.len());
+        Ok(DefectMapInfo {
+            camera_key: key,
+            pixel_count,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Detects defects in `path` and adds them to the map of the camera that shot it. Best run on
+/// a long exposure with the lens cap on, where every bright photosite is a defect.
+#[tauri::command]
+pub async fn add_defects_to_map(
+    path: String,
+    app_handle: AppHandle,
+) -> Result<DefectMapInfo, String> {
+    let dir = defect_maps_dir(&app_handle)?;
+    let info = tauri::async_runtime::spawn_blocking(move || {
+        let (key, raw_image) = read_raw(&path, true)?;
+        let Some(raw_image) = raw_image else {
+            return Err("Could not read the sensor data".to_string());
+        };
+        let width = raw_image.width;
+        let found = detect_defects(&raw_image);
+
+        let mut map = DefectMap::load(&dir, &key)
+            .filter(|map| map.width == width && map.height == raw_image.height)
+            .unwrap_or_else(|| DefectMap {
+                camera_key: key.clone(),
+                width,
+                height: raw_image.height,
+                pixels: Vec::new(),
+            });
+        let mut known: HashSet<usize> = map.indices().collect();
+        for i in found {
+            if known.insert(i) {
+                map.pixels.push(((i / width) as u32, (i % width) as u32));
+            }
+        }
+        map.save(&dir).map_err(|e| e.to_string())?;
+        log::info!(
+            "Defect map for '{}' now holds {} photosites",
+            key,
+            map.pixels.len()
+        );
+        Ok::<_, String>(DefectMapInfo {
+            camera_key: key,
+            pixel_count: map.pixels.len(),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let state = app_handle.state::<AppState>();
+    state.defect_maps.invalidate(&info.camera_key);
+    crate::cache_utils::clear_image_caches(state);
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn clear_defect_map(
+    path: String,
+    app_handle: AppHandle,
+) -> Result<DefectMapInfo, String> {
+    let info = get_defect_map_info(path, app_handle.clone()).await?;
+    let map_path = map_path(&defect_maps_dir(&app_handle)?, &info.camera_key);
+    if map_path.exists() {
+        fs::remove_file(map_path).map_err(|e| e.to_string())?;
+    }
+    let state = app_handle.state::<AppState>();
+    state.defect_maps.invalidate(&info.camera_key);
+    crate::cache_utils::clear_image_caches(state);
+    Ok(DefectMapInfo {
+        pixel_count: 0,
+        ..info
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: usize = 64;
+    const HEIGHT: usize = 48;
+    const BLACK: f32 = 512.0;
+    const WHITE: f32 = 16383.0;
+
+    fn bayer() -> CfaPattern {
+        CfaPattern::new(2, 2, |row, col| [[0, 1], [1, 2]][row][col])
+    }
+
+    /// A frame at `level` with a little deterministic noise on every photosite.
+    fn noisy_frame(level: u16) -> Vec<u16> {
+        let mut seed = 1u32;
+        (0..WIDTH * HEIGHT)
+            .map(|_| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                level + (seed >> 24) as u16 % 40
+            })
+            .collect()
+    }
+
+    fn find(data: &[u16]) -> Vec<usize> {
+        let pattern = bayer();
+        let view = SensorView::from_cfa(data, WIDTH, HEIGHT, &pattern, (2, 2));
+        let mut found = view.find_defects(|_, _| BLACK, WHITE);
+        found.sort_unstable();
+        found
+    }
+
+    #[test]
+    fn camera_key_requires_a_serial() {
+        assert_eq!(
+            camera_key(" Canon ", "EOS R5", Some(" 1234 ")).as_deref(),
+            Some("Canon EOS R5 1234")
+        );
+        assert_eq!(camera_key("Canon", "EOS R5", Some("  ")), None);
+        assert_eq!(camera_key("Canon", "EOS R5", None), None);
+    }
+
+    #[test]
+    fn noise_is_not_a_defect() {
+        assert!(find(&noisy_frame(560)).is_empty());
+        assert!(find(&noisy_frame(6000)).is_empty());
+    }
+
+    #[test]
+    fn finds_hot_and_dead_photosites() {
+        let hot = 10 * WIDTH + 10;
+        let dead = 20 * WIDTH + 41;
+
+        let mut dark = noisy_frame(560);
+        dark[hot] = 9000;
+        assert_eq!(find(&dark), vec![hot]);
+
+        let mut bright = noisy_frame(6000);
+        bright[dead] = 600;
+        assert_eq!(find(&bright), vec![dead]);
+    }
+
+    #[test]
+    fn dark_photosites_in_shadows_are_not_dead() {
+        let mut data = noisy_frame(560);
+        data[20 * WIDTH + 41] = 512;
+        assert!(find(&data).is_empty());
+    }
+
+    #[test]
+    fn bright_details_wider_than_a_photosite_are_kept() {
+        let mut data = noisy_frame(560);
+        for row in 30..33 {
+            for col in 12..15 {
+                data[row * WIDTH + col] = 8000;
+            }
+        }
+        assert!(find(&data).is_empty());
+    }
+
+    #[test]
+    fn replaces_defects_with_the_median_of_healthy_neighbours() {
+        let mut data = vec![1000u16; WIDTH * HEIGHT];
+        let hot = 10 * WIDTH + 10;
+        let neighbour = 10 * WIDTH + 12;
+        data[hot] = 16000;
+        data[neighbour] = 16000;
+        let pattern = bayer();
+        let view = SensorView::from_cfa(&data, WIDTH, HEIGHT, &pattern, (2, 2));
+
+        let defects: HashSet<usize> = [hot, neighbour].into_iter().collect();
+        let mut replacements = view.replacements(&defects);
+        replacements.sort_unstable();
+        assert_eq!(replacements, vec![(hot, 1000), (neighbour, 1000)]);
+    }
+
+    #[test]
+    fn map_indices_skip_photosites_outside_the_sensor() {
+        let map = DefectMap {
+            camera_key: "Canon EOS R5 1234".to_string(),
+            width: WIDTH,
+            height: HEIGHT,
+            pixels: vec![(1, 2), (HEIGHT as u32, 0), (0, WIDTH as u32)],
+        };
+        assert_eq!(map.indices().collect::<Vec<_>>(), vec![WIDTH + 2]);
+    }
+}
@@ -0,0 +1,962 @@
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The `demosaic` adjustment. RCD and Markesteijn are the high-quality choices for Bayer and
+/// X-Trans sensors; choosing one for the other sensor type uses the matching one instead.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum DemosaicMethod {
+    /// rawler's built-in demosaic.
+    #[default]
+    Default,
+    Rcd,
+    Markesteijn,
+    Bilinear,
+}
+
+impl DemosaicMethod {
+    pub fn from_adjustments(adjustments: &Value) -> Self {
+        adjustments
+            .get("demosaic")
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DemosaicMethod::Default => "default",
+            DemosaicMethod::Rcd => "rcd",
+            DemosaicMethod::Markesteijn => "markesteijn",
+            DemosaicMethod::Bilinear => "bilinear",
+        }
+    }
+}
+
+/// A repeating colour filter pattern. Colours are 0 (red), 1 (green) and 2 (blue).
+pub struct CfaPattern {
+    width: usize,
+    height: usize,
+    colors: Vec<usize>,
+}
+
+impl CfaPattern {
+    pub fn new(width: usize, height: usize, color_at: impl Fn(usize, usize) -> usize) -> Self {
+        let colors = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|(row, col)| color_at(row, col))
+            .collect();
+        Self {
+            width,
+            height,
+            colors,
+        }
+    }
+
+    pub fn is_rgb(&self) -> bool {
+        self.width > 0 && self.height > 0 && self.colors.iter().all(|&c| c < 3)
+    }
+
+    pub fn color_at(&self, row: usize, col: usize) -> usize {
+        self.colors[(row % self.height) * self.width + col % self.width]
+    }
+
+    fn is_bayer(&self) -> bool {
+        self.width == 2 && self.height == 2
+    }
+
+    fn is_xtrans(&self) -> bool {
+        self.width == 6 && self.height == 6
+    }
+
+    #[inline]
+    fn color(&self, row: isize, col: isize) -> usize {
+        let row = row.rem_euclid(self.height as isize) as usize;
+        let col = col.rem_euclid(self.width as isize) as usize;
+        self.colors[row * self.width + col]
+    }
+}
+
+/// A single-channel mosaic with values normalized to 0..1.
+pub struct Mosaic<'a> {
+    pub data: &'a [f32],
+    pub width: usize,
+    pub height: usize,
+    pub pattern: &'a CfaPattern,
+}
+
+impl Mosaic<'_> {
+    #[inline]
+    fn at(&self, row: isize, col: isize) -> f32 {
+        self.data[row as usize * self.width + col as usize]
+    }
+
+    #[inline]
+    fn color(&self, row: isize, col: isize) -> usize {
+        self.pattern.color(row, col)
+    }
+}
+
+/// Returns one RGB triple per photosite, or `None` when `method` is rawler's own or does not
+/// apply to this pattern.
+pub fn demosaic(mosaic: &Mosaic, method: DemosaicMethod) -> Option<Vec<[f32; 3]>> {
+    if !mosaic.pattern.is_rgb() || mosaic.width < 16 || mosaic.height < 16 {
+        return None;
+    }
+    let is_bayer = mosaic.pattern.is_bayer();
+    let is_xtrans = mosaic.pattern.is_xtrans();
+    match method {
+        DemosaicMethod::Default => None,
+        DemosaicMethod::Bilinear => Some(bilinear(mosaic)),
+        DemosaicMethod::Rcd | DemosaicMethod::Markesteijn if is_bayer => Some(rcd(mosaic)),
+        DemosaicMethod::Rcd | DemosaicMethod::Markesteijn if is_xtrans => {
+            Some(markesteijn(mosaic, 3))
+        }
+        _ => Some(bilinear(mosaic)),
+    }
+}
+
+/// Averages the nearest photosites of each missing colour. Also fills the borders that the
+/// other algorithms leave out.
+fn bilinear_at(mosaic: &Mosaic, row: usize, col: usize) -> [f32; 3] {
+    let own = mosaic.color(row as isize, col as isize);
+    let mut rgb = [0.0; 3];
+    rgb[own] = mosaic.at(row as isize, col as isize);
+
+    for radius in 1..=2isize {
+        let mut sum = [0.0f32; 3];
+        let mut count = [0u32; 3];
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let (r, c) = (row as isize + dy, col as isize + dx);
+                if r < 0 || c < 0 || r >= mosaic.height as isize || c >= mosaic.width as isize {
+                    continue;
+                }
+                let color = mosaic.color(r, c);
+                if color != own {
+                    sum[color] += mosaic.at(r, c);
+                    count[color] += 1;
+                }
+            }
+        }
+        let missing = (0..3).filter(|&c| c != own);
+        if missing.clone().all(|c| count[c] > 0) || radius == 2 {
+            for c in missing {
+                if count[c] > 0 {
+                    rgb[c] = sum[c] / count[c] as f32;
+                }
+            }
+            break;
+        }
+    }
+    rgb
+}
+
+fn bilinear(mosaic: &Mosaic) -> Vec<[f32; 3]> {
+    let mut out = vec![[0.0; 3]; mosaic.width * mosaic.height];
+    out.par_chunks_mut(mosaic.width)
+        .enumerate()
+        .for_each(|(row, line)| {
+            for (col, px) in line.iter_mut().enumerate() {
+                *px = bilinear_at(mosaic, row, col);
+            }
+        });
+    out
+}
+
+fn fill_border(mosaic: &Mosaic, out: &mut [[f32; 3]], border: usize) {
+    let (width, height) = (mosaic.width, mosaic.height);
+    out.par_chunks_mut(width)
+        .enumerate()
+        .for_each(|(row, line)| {
+            let full_row = row < border || row >= height - border;
+            for (col, px) in line.iter_mut().enumerate() {
+                if full_row || col < border || col >= width - border {
+                    *px = bilinear_at(mosaic, row, col);
+                }
+            }
+        });
+}
+
+/// Copies each tile's interior into `out`. Tiles are processed in parallel one band at a time,
+/// so only a band's worth of tile buffers is alive at once.
+fn run_tiles<F>(mosaic: &Mosaic, out: &mut [[f32; 3]], tile: usize, border: usize, process: F)
+where
+    F: Fn(usize, usize, usize, usize) -> Vec<[f32; 3]> + Sync,
+{
+    let (width, height) = (mosaic.width, mosaic.height);
+    let step = tile - 2 * border;
+    let lefts: Vec<usize> = (0..width).step_by(step).collect();
+
+    for top in (0..height).step_by(step) {
+        let tile_top = top.saturating_sub(border);
+        let rows = (top + step + border).min(height) - tile_top;
+
+        let blocks: Vec<(usize, usize, usize, Vec<[f32; 3]>)> = lefts
+            .par_iter()
+            .map(|&left| {
+                let tile_left = left.saturating_sub(border);
+                let cols = (left + step + border).min(width) - tile_left;
+                let block = process(tile_top, tile_left, rows, cols);
+                (left, tile_left, cols, block)
+            })
+            .collect();
+
+        for (left, tile_left, cols, block) in blocks {
+            let col_end = (left + step).min(width);
+            for row in top..(top + step).min(height) {
+                let src = (row - tile_top) * cols + (left - tile_left);
+                let dst = row * width;
+                out[dst + left..dst + col_end].copy_from_slice(&block[src..src + (col_end - left)]);
+            }
+        }
+    }
+}
+
+const RCD_TILE: usize = 256;
+const RCD_BORDER: usize = 9;
+
+/// Ratio Corrected Demosaicing (Luis Sanz Rodríguez), after the RawTherapee/darktable version.
+fn rcd(mosaic: &Mosaic) -> Vec<[f32; 3]> {
+    let mut out = vec![[0.0; 3]; mosaic.width * mosaic.height];
+    run_tiles(
+        mosaic,
+        &mut out,
+        RCD_TILE,
+        RCD_BORDER,
+        |top, left, rows, cols| rcd_tile(mosaic, top, left, rows, cols),
+    );
+    fill_border(mosaic, &mut out, RCD_BORDER);
+    out
+}
+
+fn rcd_tile(mosaic: &Mosaic, top: usize, left: usize, rows: usize, cols: usize) -> Vec<[f32; 3]> {
+    const EPS: f32 = 1e-5;
+    const EPSSQ: f32 = 1e-10;
+    let size = rows * cols;
+    let (w1, w2, w3, w4) = (cols, 2 * cols, 3 * cols, 4 * cols);
+    let color = |r: usize, c: usize| mosaic.color((top + r) as isize, (left + c) as isize);
+
+    let mut cfa = vec![0.0f32; size];
+    let mut rgb = [vec![0.0f32; size], vec![0.0f32; size], vec![0.0f32; size]];
+    for r in 0..rows {
+        for c in 0..cols {
+            let v = mosaic.at((top + r) as isize, (left + c) as isize);
+            cfa[r * cols + c] = v;
+            rgb[color(r, c)][r * cols + c] = v;
+        }
+    }
+    if rows < 9 || cols < 9 {
+        return (0..size)
+            .map(|i| bilinear_at(mosaic, top + i / cols, left + i % cols))
+            .collect();
+    }
+
+    // Vertical and horizontal discrimination from squared high-pass filters.
+    let mut buffer_v = vec![0.0f32; size];
+    let mut buffer_h = vec![0.0f32; size];
+    for r in 3..rows - 3 {
+        for c in 3..cols - 3 {
+            let i = r * cols + c;
+            let v = (cfa[i - w3] - cfa[i - w1] - cfa[i + w1] + cfa[i + w3])
+                - 3.0 * (cfa[i - w2] + cfa[i + w2])
+                + 6.0 * cfa[i];
+            let h = (cfa[i - 3] - cfa[i - 1] - cfa[i + 1] + cfa[i + 3])
+                - 3.0 * (cfa[i - 2] + cfa[i + 2])
+                + 6.0 * cfa[i];
+            buffer_v[i] = v * v;
+            buffer_h[i] = h * h;
+        }
+    }
+    let mut vh_dir = vec![0.0f32; size];
+    for r in 4..rows - 4 {
+        for c in 4..cols - 4 {
+            let i = r * cols + c;
+            let v_stat = (buffer_v[i - w1] + buffer_v[i] + buffer_v[i + w1]).max(EPSSQ);
+            let h_stat = (buffer_h[i - 1] + buffer_h[i] + buffer_h[i + 1]).max(EPSSQ);
+            vh_dir[i] = v_stat / (v_stat + h_stat);
+        }
+    }
+    drop(buffer_v);
+    drop(buffer_h);
+
+    // Low-pass filter of the red and blue sites.
+    let mut lpf = vec![0.0f32; size];
+    for r in 2..rows - 2 {
+        for c in 2..cols - 2 {
+            if color(r, c) == 1 {
+                continue;
+            }
+            let i = r * cols + c;
+            lpf[i] = cfa[i]
+                + 0.5 * (cfa[i - w1] + cfa[i + w1] + cfa[i - 1] + cfa[i + 1])
+                + 0.25 * (cfa[i - w1 - 1] + cfa[i - w1 + 1] + cfa[i + w1 - 1] + cfa[i + w1 + 1]);
+        }
+    }
+
+    let refine = |dir: &[f32], i: usize| {
+        let central = dir[i];
+        let neighbourhood =
+            0.25 * (dir[i - w1 - 1] + dir[i - w1 + 1] + dir[i + w1 - 1] + dir[i + w1 + 1]);
+        if (0.5 - central).abs() < (0.5 - neighbourhood).abs() {
+            neighbourhood
+        } else {
+            central
+        }
+    };
+
+    // Green at red and blue sites.
+    for r in 4..rows - 4 {
+        for c in 4..cols - 4 {
+            if color(r, c) == 1 {
+                continue;
+            }
+            let i = r * cols + c;
+            let n_grad = EPS
+                + (cfa[i - w1] - cfa[i + w1]).abs()
+                + (cfa[i] - cfa[i - w2]).abs()
+                + (cfa[i - w1] - cfa[i - w3]).abs()
+                + (cfa[i - w2] - cfa[i - w4]).abs();
+            let s_grad = EPS
+                + (cfa[i - w1] - cfa[i + w1]).abs()
+                + (cfa[i] - cfa[i + w2]).abs()
+                + (cfa[i + w1] - cfa[i + w3]).abs()
+                + (cfa[i + w2] - cfa[i + w4]).abs();
+            let w_grad = EPS
+                + (cfa[i - 1] - cfa[i + 1]).abs()
+                + (cfa[i] - cfa[i - 2]).abs()
+                + (cfa[i - 1] - cfa[i - 3]).abs()
+                + (cfa[i - 2] - cfa[i - 4]).abs();
+            let e_grad = EPS
+                + (cfa[i - 1] - cfa[i + 1]).abs()
+                + (cfa[i] - cfa[i + 2]).abs()
+                + (cfa[i + 1] - cfa[i + 3]).abs()
+                + (cfa[i + 2] - cfa[i + 4]).abs();
+
+            let lpfi = lpf[i];
+            let n_est = cfa[i - w1] * 2.0 * lpfi / (EPS + lpfi + lpf[i - w2]);
+            let s_est = cfa[i + w1] * 2.0 * lpfi / (EPS + lpfi + lpf[i + w2]);
+            let w_est = cfa[i - 1] * 2.0 * lpfi / (EPS + lpfi + lpf[i - 2]);
+            let e_est = cfa[i + 1] * 2.0 * lpfi / (EPS + lpfi + lpf[i + 2]);
+
+            let v_est = (s_grad * n_est + n_grad * s_est) / (n_grad + s_grad);
+            let h_est = (w_grad * e_est + e_grad * w_est) / (e_grad + w_grad);
+
+            let vh_disc = refine(&vh_dir, i);
+            rgb[1][i] = (vh_disc * h_est + (1.0 - vh_disc) * v_est).max(0.0);
+        }
+    }
+    drop(lpf);
+
+    // Diagonal discrimination for red at blue sites and vice versa.
+    let mut p_hpf = vec![0.0f32; size];
+    let mut q_hpf = vec![0.0f32; size];
+    for r in 3..rows - 3 {
+        for c in 3..cols - 3 {
+            let i = r * cols + c;
+            let p = (cfa[i - w3 - 3] - cfa[i - w1 - 1] - cfa[i + w1 + 1] + cfa[i + w3 + 3])
+                - 3.0 * (cfa[i - w2 - 2] + cfa[i + w2 + 2])
+                + 6.0 * cfa[i];
+            let q = (cfa[i - w3 + 3] - cfa[i - w1 + 1] - cfa[i + w1 - 1] + cfa[i + w3 - 3])
+                - 3.0 * (cfa[i - w2 + 2] + cfa[i + w2 - 2])
+                + 6.0 * cfa[i];
+            p_hpf[i] = p * p;
+            q_hpf[i] = q * q;
+        }
+    }
+    let mut pq_dir = vec![0.0f32; size];
+    for r in 4..rows - 4 {
+        for c in 4..cols - 4 {
+            let i = r * cols + c;
+            let p_stat = (p_hpf[i - w1 - 1] + p_hpf[i] + p_hpf[i + w1 + 1]).max(EPSSQ);
+            let q_stat = (q_hpf[i - w1 + 1] + q_hpf[i] + q_hpf[i + w1 - 1]).max(EPSSQ);
+            pq_dir[i] = p_stat / (p_stat + q_stat);
+        }
+    }
+    drop(p_hpf);
+    drop(q_hpf);
+
+    for r in 4..rows - 4 {
+        for c in 4..cols - 4 {
+            let own = color(r, c);
+            if own == 1 {
+                continue;
+            }
+            let i = r * cols + c;
+            let ch = 2 - own;
+            let (g, x) = (&rgb[1], &rgb[ch]);
+            let pq_disc = refine(&pq_dir, i);
+
+            let nw_grad = EPS
+                + (x[i - w1 - 1] - x[i + w1 + 1]).abs()
+                + (x[i - w1 - 1] - x[i - w3 - 3]).abs()
+                + (g[i] - g[i - w2 - 2]).abs();
+            let ne_grad = EPS
+                + (x[i - w1 + 1] - x[i + w1 - 1]).abs()
+                + (x[i - w1 + 1] - x[i - w3 + 3]).abs()
+                + (g[i] - g[i - w2 + 2]).abs();
+            let sw_grad = EPS
+                + (x[i - w1 + 1] - x[i + w1 - 1]).abs()
+                + (x[i + w1 - 1] - x[i + w3 - 3]).abs()
+                + (g[i] - g[i + w2 - 2]).abs();
+            let se_grad = EPS
+                + (x[i - w1 - 1] - x[i + w1 + 1]).abs()
+                + (x[i + w1 + 1] - x[i + w3 + 3]).abs()
+                + (g[i] - g[i + w2 + 2]).abs();
+
+            let nw_est = x[i - w1 - 1] - g[i - w1 - 1];
+            let ne_est = x[i - w1 + 1] - g[i - w1 + 1];
+            let sw_est = x[i + w1 - 1] - g[i + w1 - 1];
+            let se_est = x[i + w1 + 1] - g[i + w1 + 1];
+
+            let p_est = (nw_grad * se_est + se_grad * nw_est) / (nw_grad + se_grad);
+            let q_est = (ne_grad * sw_est + sw_grad * ne_est) / (ne_grad + sw_grad);
+
+            let value = g[i] + pq_disc * q_est + (1.0 - pq_disc) * p_est;
+            rgb[ch][i] = value.max(0.0);
+        }
+    }
+
+    // Red and blue at green sites.
+    for r in 4..rows - 4 {
+        for c in 4..cols - 4 {
+            if color(r, c) != 1 {
+                continue;
+            }
+            let i = r * cols + c;
+            let vh_disc = refine(&vh_dir, i);
+            for ch in [0, 2] {
+                let (g, x) = (&rgb[1], &rgb[ch]);
+                let n_grad = EPS
+                    + (g[i] - g[i - w2]).abs()
+                    + (x[i - w1] - x[i + w1]).abs()
+                    + (x[i - w1] - x[i - w3]).abs();
+                let s_grad = EPS
+                    + (g[i] - g[i + w2]).abs()
+                    + (x[i + w1] - x[i - w1]).abs()
+                    + (x[i + w1] - x[i + w3]).abs();
+                let w_grad = EPS
+                    + (g[i] - g[i - 2]).abs()
+                    + (x[i - 1] - x[i + 1]).abs()
+                    + (x[i - 1] - x[i - 3]).abs();
+                let e_grad = EPS
+                    + (g[i] - g[i + 2]).abs()
+                    + (x[i + 1] - x[i - 1]).abs()
+                    + (x[i + 1] - x[i + 3]).abs();
+
+                let n_est = x[i - w1] - g[i - w1];
+                let s_est = x[i + w1] - g[i + w1];
+                let w_est = x[i - 1] - g[i - 1];
+                let e_est = x[i + 1] - g[i + 1];
+
+                let v_est = (n_grad * s_est + s_grad * n_est) / (n_grad + s_grad);
+                let h_est = (e_grad * w_est + w_grad * e_est) / (e_grad + w_grad);
+
+                let value = g[i] + vh_disc * h_est + (1.0 - vh_disc) * v_est;
+                rgb[ch][i] = value.max(0.0);
+            }
+        }
+    }
+
+    (0..size)
+        .map(|i| [rgb[0][i], rgb[1][i], rgb[2][i]])
+        .collect()
+}
+
+const XTRANS_TILE: usize = 256;
+
+/// Green neighbourhoods around each position of the 3x3 green structure of X-Trans, as
+/// (row, column) offsets, plus the position of the solitary green photosite.
+struct XTransHex {
+    hex: [[[(isize, isize); 8]; 3]; 3],
+    sgrow: isize,
+    sgcol: isize,
+}
+
+impl XTransHex {
+    fn new(pattern: &CfaPattern) -> Self {
+        const ORTH: [isize; 12] = [1, 0, 0, 1, -1, 0, 0, -1, 1, 0, 0, 1];
+        const PATT: [[isize; 16]; 2] = [
+            [0, 1, 0, -1, 2, 0, -1, 0, 1, 1, 1, -1, 0, 0, 0, 0],
+            [0, 1, 0, -2, 1, 0, -2, 0, 1, 1, -2, -2, 1, -1, -1, 1],
+        ];
+        let mut hex = [[[(0, 0); 8]; 3]; 3];
+        let (mut sgrow, mut sgcol) = (0, 0);
+
+        for row in 0..3isize {
+            for col in 0..3isize {
+                let g = (pattern.color(row, col) == 1) as usize;
+                let mut ng = 0;
+                for d in (0..10).step_by(2) {
+                    if pattern.color(row + ORTH[d], col + ORTH[d + 2]) == 1 {
+                        ng = 0;
+                    } else {
+                        ng += 1;
+                    }
+                    if ng == 4 {
+                        sgrow = row;
+                        sgcol = col;
+                    }
+                    if ng == g + 1 {
+                        for c in 0..8 {
+                            let v = ORTH[d] * PATT[g][c * 2] + ORTH[d + 1] * PATT[g][c * 2 + 1];
+                            let h = ORTH[d + 2] * PATT[g][c * 2] + ORTH[d + 3] * PATT[g][c * 2 + 1];
+                            hex[row as usize][col as usize][c ^ ((g * 2) & d)] = (v, h);
+                        }
+                    }
+                }
+            }
+        }
+        Self { hex, sgrow, sgcol }
+    }
+
+    fn at(&self, row: isize, col: isize) -> &[(isize, isize); 8] {
+        &self.hex[row.rem_euclid(3) as usize][col.rem_euclid(3) as usize]
+    }
+}
+
+fn cielab(rgb: [f32; 3]) -> [f32; 3] {
+    const M: [[f32; 3]; 3] = [
+        [
+            0.412_453 / 0.950_456,
+            0.357_580 / 0.950_456,
+            0.180_423 / 0.950_456,
+        ],
+        [0.212_671, 0.715_160, 0.072_169],
+        [
+            0.019_334 / 1.088_754,
+            0.119_193 / 1.088_754,
+            0.950_227 / 1.088_754,
+        ],
+    ];
+    let f = |t: f32| {
+        if t > 0.008_856 {
+            t.cbrt()
+        } else {
+            7.787 * t + 16.0 / 116.0
+        }
+    };
+    let [x, y, z] = M.map(|m| f(m[0] * rgb[0] + m[1] * rgb[1] + m[2] * rgb[2]));
+    [116.0 * y - 16.0, 500.0 * (x - y), 200.0 * (y - z)]
+}
+
+/// Frank Markesteijn's X-Trans interpolation, ported from dcraw's `xtrans_interpolate`.
+/// Three passes refine the green estimates and average the four extra directions in.
+fn markesteijn(mosaic: &Mosaic, passes: usize) -> Vec<[f32; 3]> {
+    let (width, height) = (mosaic.width as isize, mosaic.height as isize);
+    let hex = XTransHex::new(mosaic.pattern);
+    let mut out = vec![[0.0; 3]; mosaic.width * mosaic.height];
+
+    let lefts: Vec<isize> = (3..(width - 19).max(4)).step_by(XTRANS_TILE - 16).collect();
+    for top in (3..(height - 19).max(4)).step_by(XTRANS_TILE - 16) {
+        let blocks: Vec<_> = lefts
+            .par_iter()
+            .map(|&left| markesteijn_tile(mosaic, &hex, passes, top, left))
+            .collect();
+        for (top, left, rows, cols, block) in blocks {
+            for row in 0..rows {
+                let dst = (top + row) as usize * mosaic.width + left as usize;
+                out[dst..dst + cols as usize]
+                    .copy_from_slice(&block[(row * cols) as usize..((row + 1) * cols) as usize]);
+            }
+        }
+    }
+
+    fill_border(mosaic, &mut out, 8);
+    out
+}
+
+/// Processes the tile at (`top`, `left`) and returns the interior it is responsible for as
+/// (top, left, rows, cols, pixels).
+fn markesteijn_tile(
+    mosaic: &Mosaic,
+    hex: &XTransHex,
+    passes: usize,
+    top: isize,
+    left: isize,
+) -> (isize, isize, isize, isize, Vec<[f32; 3]>) {
+    let ts = XTRANS_TILE as isize;
+    let tsu = XTRANS_TILE;
+    let (width, height) = (mosaic.width as isize, mosaic.height as isize);
+    let ndir = if passes > 1 { 8 } else { 4 };
+    let (sgrow, sgcol) = (hex.sgrow, hex.sgcol);
+    let plane = tsu * tsu;
+
+    // The colour of a photosite, and the green range around red and blue photosites that
+    // limits the green estimates there.
+    let fcol = |row: isize, col: isize| mosaic.color(row, col);
+    let green_range = |row: isize, col: isize| {
+        let mut min = f32::MAX;
+        let mut max = f32::MIN;
+        for &(dy, dx) in &hex.at(row, col)[..6] {
+            let v = mosaic.at(row + dy, col + dx);
+            min = min.min(v);
+            max = max.max(v);
+        }
+        (min, max)
+    };
+    // dcraw's `image[][]`: the photosite's own colour, with the green range stored in the green
+    // channel of red and blue photosites.
+    let pix = |row: isize, col: isize, ch: usize| -> f32 {
+        let own = fcol(row, col);
+        if own == ch {
+            mosaic.at(row, col)
+        } else if ch == 1 {
+            green_range(row, col).0
+        } else {
+            0.0
+        }
+    };
+
+    let mrow = (top + ts).min(height - 3);
+    let mcol = (left + ts).min(width - 3);
+    let mut rgb = vec![[0.0f32; 3]; plane * ndir];
+    let idx = |d: usize, row: isize, col: isize| d * plane + (row * ts + col) as usize;
+
+    for row in top..mrow {
+        for col in left..mcol {
+            let px = [pix(row, col, 0), pix(row, col, 1), pix(row, col, 2)];
+            for d in 0..4 {
+                rgb[idx(d, row - top, col - left)] = px;
+            }
+        }
+    }
+
+    // Green horizontally, vertically and along both diagonals.
+    for row in top..mrow {
+        for col in left..mcol {
+            let f = fcol(row, col);
+            if f == 1 {
+                continue;
+            }
+            let h = hex.at(row, col);
+            let g = |k: isize, o: (isize, isize)| mosaic.at(row + k * o.0, col + k * o.1);
+            let own = |k: isize, o: (isize, isize)| pix(row + k * o.0, col + k * o.1, f);
+            let mut color = [0.0f32; 4];
+            color[0] = 174.0 * (g(1, h[1]) + g(1, h[0])) - 46.0 * (g(2, h[1]) + g(2, h[0]));
+            color[1] =
+                223.0 * g(1, h[3]) + 33.0 * g(1, h[2]) + 92.0 * (own(0, h[0]) - own(-1, h[2]));
+            for c in 0..2 {
+                color[2 + c] = 164.0 * g(1, h[4 + c])
+                    + 92.0 * g(-2, h[4 + c])
+                    + 33.0 * (2.0 * own(0, h[0]) - own(3, h[4 + c]) - own(-3, h[4 + c]));
+            }
+            let (min, max) = green_range(row, col);
+            let flip = ((row - sgrow) % 3 == 0) as usize;
+            for (c, value) in color.iter().enumerate() {
+                rgb[idx(c ^ flip, row - top, col - left)][1] = (value / 256.0).clamp(min, max);
+            }
+        }
+    }
+
+    let mut base = 0;
+    for pass in 0..passes {
+        if pass == 1 {
+            rgb.copy_within(0..4 * plane, 4 * plane);
+            base = 4;
+        }
+        let at = |d: usize, row: isize, col: isize| idx(base + d, row - top, col - left);
+
+        // Recalculate green from interpolated values of closer photosites.
+        if pass > 0 {
+            for row in top + 2..mrow - 2 {
+                for col in left + 2..mcol - 2 {
+                    let f = fcol(row, col);
+                    if f == 1 {
+                        continue;
+                    }
+                    let h = hex.at(row, col);
+                    let (min, max) = green_range(row, col);
+                    let flip = ((row - sgrow) % 3 == 0) as usize;
+                    for (d, &(dy, dx)) in h.iter().enumerate().take(6).skip(3) {
+                        let i = at((d - 2) ^ flip, row, col) as isize;
+                        let o = dy * ts + dx;
+                        let px = |offset: isize| rgb[(i + offset) as usize];
+                        let val = px(-2 * o)[1] + 2.0 * px(o)[1] - px(-2 * o)[f] - 2.0 * px(o)[f]
+                            + 3.0 * px(0)[f];
+                        rgb[i as usize][1] = (val / 3.0).clamp(min, max);
+                    }
+                }
+            }
+        }
+
+        // Red and blue for solitary green photosites.
+        let first_row = (top - sgrow + 4) / 3 * 3 + sgrow;
+        let first_col = (left - sgcol + 4) / 3 * 3 + sgcol;
+        for row in (first_row..mrow - 2).step_by(3) {
+            for col in (first_col..mcol - 2).step_by(3) {
+                let mut rix = at(0, row, col) as isize;
+                let mut h = fcol(row, col + 1);
+                let mut diff = [0.0f32; 6];
+                let mut color = [[0.0f32; 6]; 3];
+                let mut i = 1isize;
+                for d in 0..6 {
+                    for c in 0..2 {
+                        let o = i << c;
+                        let px = |offset: isize| rgb[(rix + offset) as usize];
+                        let g = 2.0 * px(0)[1] - px(o)[1] - px(-o)[1];
+                        color[h][d] = g + px(o)[h] + px(-o)[h];
+                        if d > 1 {
+                            let e = px(o)[1] - px(-o)[1] - px(o)[h] + px(-o)[h];
+                            diff[d] += e * e + g * g;
+                        }
+                        h ^= 2;
+                    }
+                    if d > 1 && (d & 1) == 1 && diff[d - 1] < diff[d] {
+                        for c in [0, 2] {
+                            color[c][d] = color[c][d - 1];
+                        }
+                    }
+                    if d < 2 || (d & 1) == 1 {
+                        for c in [0, 2] {
+                            rgb[rix as usize][c] = (color[c][d] / 2.0).clamp(0.0, 1.0);
+                        }
+                        rix += plane as isize;
+                    }
+                    i ^= ts ^ 1;
+                    h ^= 2;
+                }
+            }
+        }
+
+        // Red for blue photosites and vice versa.
+        for row in top + 3..mrow - 3 {
+            for col in left + 3..mcol - 3 {
+                let f = 2 - fcol(row, col) as isize;
+                if f == 1 {
+                    continue;
+                }
+                let f = f as usize;
+                let mut rix = at(0, row, col) as isize;
+                let c = if (row - sgrow) % 3 != 0 { ts } else { 1 };
+                let h = 3 * (c ^ ts ^ 1);
+                for d in 0..4isize {
+                    let px = |offset: isize| rgb[(rix + offset) as usize];
+                    let g0 = px(0)[1];
+                    let i = if d > 1
+                        || ((d ^ c) & 1) == 1
+                        || ((g0 - px(c)[1]).abs() + (g0 - px(-c)[1]).abs())
+                            < 2.0 * ((g0 - px(h)[1]).abs() + (g0 - px(-h)[1]).abs())
+                    {
+                        c
+                    } else {
+                        h
+                    };
+                    let value = (px(i)[f] + px(-i)[f] + 2.0 * g0 - px(i)[1] - px(-i)[1]) / 2.0;
+                    rgb[rix as usize][f] = value.clamp(0.0, 1.0);
+                    rix += plane as isize;
+                }
+            }
+        }
+
+        // Red and blue for 2x2 blocks of green.
+        for row in top + 2..mrow - 2 {
+            if (row - sgrow) % 3 == 0 {
+                continue;
+            }
+            for col in left + 2..mcol - 2 {
+                if (col - sgcol) % 3 == 0 {
+                    continue;
+                }
+                let mut rix = at(0, row, col) as isize;
+                let h = hex.at(row, col);
+                for d in (0..ndir).step_by(2) {
+                    let o1 = h[d].0 * ts + h[d].1;
+                    let o2 = h[d + 1].0 * ts + h[d + 1].1;
+                    let px = |offset: isize| rgb[(rix + offset) as usize];
+                    let values = if o1 + o2 != 0 {
+                        let g = 3.0 * px(0)[1] - 2.0 * px(o1)[1] - px(o2)[1];
+                        [0, 2].map(|c| (g + 2.0 * px(o1)[c] + px(o2)[c]) / 3.0)
+                    } else {
+                        let g = 2.0 * px(0)[1] - px(o1)[1] - px(o2)[1];
+                        [0, 2].map(|c| (g + px(o1)[c] + px(o2)[c]) / 2.0)
+                    };
+                    rgb[rix as usize][0] = values[0].clamp(0.0, 1.0);
+                    rgb[rix as usize][2] = values[1].clamp(0.0, 1.0);
+                    rix += plane as isize;
+                }
+            }
+        }
+    }
+
+    let rows = mrow - top;
+    let cols = mcol - left;
+
+    // Convert to CIELab and differentiate in all directions.
+    let dir = [1, ts, ts + 1, ts - 1];
+    let mut lab = vec![[0.0f32; 3]; plane];
+    let mut drv = vec![0.0f32; plane * ndir];
+    for d in 0..ndir {
+        for row in 2..rows - 2 {
+            for col in 2..cols - 2 {
+                lab[(row * ts + col) as usize] = cielab(rgb[idx(d, row, col)]);
+            }
+        }
+        let f = dir[d & 3];
+        for row in 3..rows - 3 {
+            for col in 3..cols - 3 {
+                let i = row * ts + col;
+                let (l0, lp, lm) = (
+                    lab[i as usize],
+                    lab[(i + f) as usize],
+                    lab[(i - f) as usize],
+                );
+                let g = 2.0 * l0[0] - lp[0] - lm[0];
+                let a = 2.0 * l0[1] - lp[1] - lm[1] + g * 500.0 / 232.0;
+                let b = 2.0 * l0[2] - lp[2] - lm[2] - g * 500.0 / 580.0;
+                drv[d * plane + i as usize] = g * g + a * a + b * b;
+            }
+        }
+    }
+    drop(lab);
+
+    // Build homogeneity maps from the derivatives.
+    let mut homo = vec![0u8; plane * ndir];
+    for row in 4..rows - 4 {
+        for col in 4..cols - 4 {
+            let i = (row * ts + col) as usize;
+            let tr = (0..ndir)
+                .map(|d| drv[d * plane + i])
+                .fold(f32::MAX, f32::min)
+                * 8.0;
+            for d in 0..ndir {
+                for v in -1..=1isize {
+                    for h in -1..=1isize {
+                        let j = ((row + v) * ts + col + h) as usize;
+                        if drv[d * plane + j] <= tr {
+                            homo[d * plane + i] += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    drop(drv);
+
+    // Average the most homogeneous directions for the final result.
+    let mut out_rows = rows;
+    let mut out_cols = cols;
+    if height - top < ts + 4 {
+        out_rows = height - top + 2;
+    }
+    if width - left < ts + 4 {
+        out_cols = width - left + 2;
+    }
+    let row_start = top.min(8);
+    let col_start = left.min(8);
+    let row_end = (out_rows - 8).min(rows - 4);
+    let col_end = (out_cols - 8).min(cols - 4);
+    let (block_rows, block_cols) = ((row_end - row_start).max(0), (col_end - col_start).max(0));
+    let mut block = vec![[0.0f32; 3]; (block_rows * block_cols) as usize];
+
+    for row in row_start..row_end {
+        for col in col_start..col_end {
+            let mut hm = [0u32; 8];
+            for (d, value) in hm.iter_mut().enumerate().take(ndir) {
+                for v in -2..=2isize {
+                    for h in -2..=2isize {
+                        let j = ((row + v) * ts + col + h) as usize;
+                        *value += homo[d * plane + j] as u32;
+                    }
+                }
+            }
+            for d in 0..ndir - 4 {
+                if hm[d] < hm[d + 4] {
+                    hm[d] = 0;
+                } else if hm[d] > hm[d + 4] {
+                    hm[d + 4] = 0;
+                }
+            }
+            let mut max = hm[..ndir].iter().copied().max().unwrap_or(0);
+            max -= max >> 3;
+            let mut avg = [0.0f32; 3];
+            let mut count = 0.0;
+            for d in 0..ndir {
+                if hm[d] >= max {
+                    let px = rgb[idx(d, row, col)];
+                    for c in 0..3 {
+                        avg[c] += px[c];
+                    }
+                    count += 1.0;
+                }
+            }
+            block[((row - row_start) * block_cols + col - col_start) as usize] =
+                avg.map(|v| v / count);
+        }
+    }
+
+    (
+        top + row_start,
+        left + col_start,
+        block_rows,
+        block_cols,
+        block,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XTRANS: [&str; 6] = ["GBGGRG", "RGRBGB", "GBGGRG", "GRGGBG", "BGBRGR", "GRGGBG"];
+
+    fn bayer() -> CfaPattern {
+        CfaPattern::new(2, 2, |row, col| [[0, 1], [1, 2]][row][col])
+    }
+
+    fn xtrans() -> CfaPattern {
+        CfaPattern::new(6, 6, |row, col| match XTRANS[row].as_bytes()[col] {
+            b'R' => 0,
+            b'G' => 1,
+            _ => 2,
+        })
+    }
+
+    fn ramp(row: usize, col: usize, width: usize, height: usize) -> f32 {
+        0.2 + 0.5 * col as f32 / width as f32 + 0.2 * row as f32 / height as f32
+    }
+
+    /// Demosaics a gray ramp spanning several tiles in each direction, so a tile copied to the
+    /// wrong place shows up as a jump.
+    fn assert_reconstructs_ramp(pattern: &CfaPattern, method: DemosaicMethod) {
+        let (width, height) = (601, 523);
+        let data: Vec<f32> = (0..width * height)
+            .map(|i| ramp(i / width, i % width, width, height))
+            .collect();
+        let mosaic = Mosaic {
+            data: &data,
+            width,
+            height,
+            pattern,
+        };
+        let out = demosaic(&mosaic, method).unwrap();
+
+        assert_eq!(out.len(), width * height);
+        for row in 10..height - 10 {
+            for col in 10..width - 10 {
+                let expected = ramp(row, col, width, height);
+                for value in out[row * width + col] {
+                    assert!(
+                        (value - expected).abs() < 0.01,
+                        "{:?} at ({}, {}): {} instead of {}",
+                        method,
+                        row,
+                        col,
+                        value,
+                        expected
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rcd_handles_frames_larger_than_a_tile() {
+        assert_reconstructs_ramp(&bayer(), DemosaicMethod::Rcd);
+    }
+
+    #[test]
+    fn markesteijn_handles_frames_larger_than_a_tile() {
+        assert_reconstructs_ramp(&xtrans(), DemosaicMethod::Markesteijn);
+    }
+}
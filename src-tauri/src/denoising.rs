@@ -87,106 +87,66 @@ pub async fn apply_denoising(
     .map_err(|e| format!("Denoising task failed: {}", e))
 }
 
-#[tauri::command]
-pub async fn batch_denoise_images(
-    paths: Vec<String>,
+/// Denoises one image and saves the result to `output_dir` (next to the source by default),
+/// copying its sidecar. Returns the path of the written file.
+pub(crate) fn denoise_and_save(
+    path_str: &str,
     intensity: f32,
-    method: String,
-    app_handle: tauri::AppHandle,
-    state: tauri::State<'_, AppState>,
-) -> Result<Vec<String>, String> {
-    let mut ai_session = None;
-    if method == "ai" {
-        let session = crate::ai_processing::get_or_init_denoise_model(
-            &app_handle,
-            &state.ai_state,
-            &state.ai_init_lock,
-        )
-        .await
-        .map_err(|e| e.to_string())?;
-        ai_session = Some(session);
-    }
+    method: &str,
+    app_handle: &AppHandle,
+    ai_session: Option<Arc<Mutex<ort::session::Session>>>,
+    output_dir: Option<&Path>,
+) -> Result<String, String> {
+    let (source_path, source_sidecar_path) = crate::file_management::parse_virtual_path(path_str);
+    let real_path = source_path.to_string_lossy().to_string();
+
+    let (image, _) = denoise_image(
+        real_path.clone(),
+        intensity,
+        method.to_string(),
+        app_handle.clone(),
+        ai_session,
+    )
+    .map_err(|e| format!("Failed to denoise {}: {}", real_path, e))?;
 
-    tokio::task::spawn_blocking(move || {
-        let mut results = Vec::new();
-
-        for (i, path_str) in paths.iter().enumerate() {
-            let _ = app_handle.emit(
-                "denoise-batch-progress",
-                serde_json::json!({
-                    "current": i + 1,
-                    "total": paths.len(),
-                    "path": path_str
-                }),
-            );
-
-            let (source_path, source_sidecar_path) =
-                crate::file_management::parse_virtual_path(path_str);
-            let real_path = source_path.to_string_lossy().to_string();
-
-            match crate::denoising::denoise_image(
-                real_path.clone(),
-                intensity,
-                method.clone(),
-                app_handle.clone(),
-                ai_session.clone(),
-            ) {
-                Ok((image, _)) => {
-                    let is_raw = crate::formats::is_raw_file(&real_path);
-                    let parent_dir = source_path.parent().unwrap_or(std::path::Path::new(""));
-                    let stem = source_path
-                        .file_stem()
-                        .unwrap_or_default()
-                        .to_string_lossy();
-
-                    let (output_filename, image_to_save) = if is_raw {
-                        (
-                            format!("{}_Denoised.tiff", stem),
-                            DynamicImage::ImageRgb16(image.to_rgb16()),
-                        )
-                    } else {
-                        (
-                            format!("{}_Denoised.png", stem),
-                            DynamicImage::ImageRgb8(image.to_rgb8()),
-                        )
-                    };
-
-                    let output_path = parent_dir.join(output_filename);
-                    if let Err(e) = image_to_save.save(&output_path) {
-                        let _ = app_handle.emit(
-                            "denoise-error",
-                            format!("Failed to save {}: {}", real_path, e),
-                        );
-                        continue;
-                    }
+    let is_raw = crate::formats::is_raw_file(&real_path);
+    let parent_dir = output_dir
+        .or_else(|| source_path.parent())
+        .unwrap_or(Path::new(""));
+    let stem = source_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
 
-                    let _ = crate::exif_processing::write_rrexif_sidecar(&real_path, &output_path);
+    let (output_filename, image_to_save) = if is_raw {
+        (
+            format!("{}_Denoised.tiff", stem),
+            DynamicImage::ImageRgb16(image.to_rgb16()),
+        )
+    } else {
+        (
+            format!("{}_Denoised.png", stem),
+            DynamicImage::ImageRgb8(image.to_rgb8()),
+        )
+    };
 
-                    if source_sidecar_path.exists()
-                        && let Some(output_path_str) = output_path.to_str()
-                    {
-                        let (_, dest_sidecar_path) =
-                            crate::file_management::parse_virtual_path(output_path_str);
-                        if let Err(e) = std::fs::copy(&source_sidecar_path, &dest_sidecar_path) {
-                            log::warn!("Failed to copy sidecar file for denoised image: {}", e);
-                        }
-                    }
+    let output_path = parent_dir.join(output_filename);
+    image_to_save
+        .save(&output_path)
+        .map_err(|e| format!("Failed to save {}: {}", real_path, e))?;
 
-                    results.push(output_path.to_string_lossy().to_string());
-                }
-                Err(e) => {
-                    let _ = app_handle.emit(
-                        "denoise-error",
-                        format!("Failed to denoise {}: {}", real_path, e),
-                    );
-                }
-            }
+    let _ = crate::exif_processing::write_rrexif_sidecar(&real_path, &output_path);
+
+    if source_sidecar_path.exists()
+        && let Some(output_path_str) = output_path.to_str()
+    {
+        let (_, dest_sidecar_path) = crate::file_management::parse_virtual_path(output_path_str);
+        if let Err(e) = std::fs::copy(&source_sidecar_path, &dest_sidecar_path) {
+            log::warn!("Failed to copy sidecar file for denoised image: {}", e);
         }
+    }
 
-        Ok(results)
-    })
-    .await
-    .map_err(|e| format!("Batch denoising task failed: {}", e))?
+    Ok(output_path.to_string_lossy().to_string())
 }
 
 #[tauri::command]
@@ -312,8 +272,9 @@ fn denoise_image(
     let _ = app_handle.emit("denoise-progress", "Loading image...");
 
     let file_bytes = fs::read(path).map_err(|e| e.to_string())?;
-    let dynamic_img = load_base_image_from_bytes(&file_bytes, &path_str, false, &settings, None)
-        .map_err(|e| e.to_string())?;
+    let dynamic_img =
+        load_base_image_from_bytes(&file_bytes, &path_str, false, &settings, &app_handle, None)
+            .map_err(|e| e.to_string())?;
 
     let rgb_img_for_denoiser = dynamic_img.to_rgb32f();
 
@@ -0,0 +1,512 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use image::{DynamicImage, GenericImageView, imageops};
+use rayon::prelude::*;
+
+use crate::exif_processing::{
+    get_creation_date_from_path, parse_sidecar_rational, read_exif_data_from_bytes,
+    read_rrexif_sidecar,
+};
+use crate::image_processing::{PRIMARIES_SRGB, WP_D65, primaries_to_xyz_matrix};
+
+const TIFF_BYTE: u16 = 1;
+const TIFF_ASCII: u16 = 2;
+const TIFF_SHORT: u16 = 3;
+const TIFF_LONG: u16 = 4;
+const TIFF_RATIONAL: u16 = 5;
+const TIFF_UNDEFINED: u16 = 7;
+const TIFF_SRATIONAL: u16 = 10;
+
+const PHOTOMETRIC_RGB: u16 = 2;
+const PHOTOMETRIC_LINEAR_RAW: u16 = 34892;
+const ILLUMINANT_D65: u16 = 21;
+
+const THUMBNAIL_LONG_EDGE: u32 = 256;
+const MAX_HIGHLIGHT_HEADROOM: f32 = 16.0;
+
+enum TagValue {
+    Byte(Vec<u8>),
+    Ascii(String),
+    Short(Vec<u16>),
+    Long(Vec<u32>),
+    Rational(Vec<(u32, u32)>),
+    SRational(Vec<(i32, i32)>),
+    Undefined(Vec<u8>),
+}
+
+impl TagValue {
+    fn field_type(&self) -> u16 {
+        match self {
+            TagValue::Byte(_) => TIFF_BYTE,
+            TagValue::Ascii(_) => TIFF_ASCII,
+            TagValue::Short(_) => TIFF_SHORT,
+            TagValue::Long(_) => TIFF_LONG,
+            TagValue::Rational(_) => TIFF_RATIONAL,
+            TagValue::SRational(_) => TIFF_SRATIONAL,
+            TagValue::Undefined(_) => TIFF_UNDEFINED,
+        }
+    }
+
+    fn count(&self) -> u32 {
+        match self {
+            TagValue::Byte(v) | TagValue::Undefined(v) => v.len() as u32,
+            TagValue::Ascii(s) => s.len() as u32 + 1,
+            TagValue::Short(v) => v.len() as u32,
+            TagValue::Long(v) => v.len() as u32,
+            TagValue::Rational(v) => v.len() as u32,
+            TagValue::SRational(v) => v.len() as u32,
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            TagValue::Byte(v) | TagValue::Undefined(v) => v.clone(),
+            TagValue::Ascii(s) => {
+                let mut bytes = s.as_bytes().to_vec();
+                bytes.push(0);
+                bytes
+            }
+            TagValue::Short(v) => v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            TagValue::Long(v) => v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            TagValue::Rational(v) => v
+                .iter()
+                .flat_map(|(n, d)| [n.to_le_bytes(), d.to_le_bytes()].concat())
+                .collect(),
+            TagValue::SRational(v) => v
+                .iter()
+                .flat_map(|(n, d)| [n.to_le_bytes(), d.to_le_bytes()].concat())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Ifd {
+    entries: Vec<(u16, TagValue)>,
+}
+
+impl Ifd {
+    fn set(&mut self, tag: u16, value: TagValue) {
+        self.entries.retain(|(t, _)| *t != tag);
+        self.entries.push((tag, value));
+    }
+
+    fn set_ascii(&mut self, tag: u16, value: &Option<String>) {
+        if let Some(v) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            self.set(tag, TagValue::Ascii(v.to_string()));
+        }
+    }
+
+    fn write(mut self, out: &mut Vec<u8>) -> u32 {
+        if out.len() % 2 == 1 {
+            out.push(0);
+        }
+        let ifd_offset = out.len();
+        self.entries.sort_by_key(|(tag, _)| *tag);
+
+        let mut data_offset = ifd_offset + 2 + self.entries.len() * 12 + 4;
+        let mut data = Vec::new();
+        out.extend_from_slice(&(self.entries.len() as u16).to_le_bytes());
+        for (tag, value) in &self.entries {
+            let bytes = value.to_bytes();
+            out.extend_from_slice(&tag.to_le_bytes());
+            out.extend_from_slice(&value.field_type().to_le_bytes());
+            out.extend_from_slice(&value.count().to_le_bytes());
+            if bytes.len() <= 4 {
+                let mut inline = [0u8; 4];
+                inline[..bytes.len()].copy_from_slice(&bytes);
+                out.extend_from_slice(&inline);
+            } else {
+                out.extend_from_slice(&(data_offset as u32).to_le_bytes());
+                data_offset += bytes.len() + (bytes.len() & 1);
+                data.extend_from_slice(&bytes);
+                if bytes.len() % 2 == 1 {
+                    data.push(0);
+                }
+            }
+        }
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&data);
+        ifd_offset as u32
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DngMetadata {
+    pub make: Option<String>,
+    pub model: Option<String>,
+    pub lens_make: Option<String>,
+    pub lens_model: Option<String>,
+    pub artist: Option<String>,
+    pub copyright: Option<String>,
+    pub date_time_original: Option<String>,
+    pub exposure_time: Option<(u32, u32)>,
+    pub f_number: Option<(u32, u32)>,
+    pub focal_length: Option<(u32, u32)>,
+    pub iso: Option<u16>,
+    pub original_file_name: Option<String>,
+}
+
+impl DngMetadata {
+    pub fn from_exif_map(map: &HashMap<String, String>) -> Self {
+        let text = |key: &str| {
+            map.get(key)
+                .map(|v| v.replace('"', "").trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let rational = |key: &str| {
+            map.get(key)
+                .and_then(|v| parse_sidecar_rational(v))
+                .filter(|r| r.denominator != 0)
+                .map(|r| (r.nominator, r.denominator))
+        };
+        let iso = map
+            .get("PhotographicSensitivity")
+            .or_else(|| map.get("ISOSpeed"))
+            .and_then(|v| v.replace('"', "").trim().parse::<u32>().ok())
+            .map(|v| v.min(u16::MAX as u32) as u16);
+
+        Self {
+            make: text("Make"),
+            model: text("Model"),
+            lens_make: text("LensMake"),
+            lens_model: text("LensModel"),
+            artist: text("Artist"),
+            copyright: text("Copyright"),
+            date_time_original: None,
+            exposure_time: rational("ExposureTime"),
+            f_number: rational("FNumber"),
+            focal_length: rational("FocalLength"),
+            iso,
+            original_file_name: None,
+        }
+    }
+
+    pub fn from_source(source_path_str: &str) -> Self {
+        let source_path = Path::new(source_path_str);
+        let exif_map = read_rrexif_sidecar(source_path).unwrap_or_else(|| {
+            std::fs::read(source_path)
+                .map(|bytes| read_exif_data_from_bytes(source_path_str, &bytes))
+                .unwrap_or_default()
+        });
+
+        let mut metadata = Self::from_exif_map(&exif_map);
+        if source_path.exists() {
+            metadata.date_time_original = Some(
+                get_creation_date_from_path(source_path)
+                    .format("%Y:%m:%d %H:%M:%S")
+                    .to_string(),
+            );
+        }
+        metadata.original_file_name = source_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+        metadata
+    }
+
+    fn unique_camera_model(&self) -> String {
+        match (&self.make, &self.model) {
+            (Some(make), Some(model)) if model.starts_with(make.as_str()) => model.clone(),
+            (Some(make), Some(model)) => format!("{} {}", make, model),
+            (None, Some(model)) => model.clone(),
+            (Some(make), None) => make.clone(),
+            (None, None) => "RapidRAW Linear".to_string(),
+        }
+    }
+}
+
+fn to_srational(value: f32) -> (i32, i32) {
+    ((value * 10000.0).round() as i32, 10000)
+}
+
+#[inline]
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[inline]
+fn linear_to_srgb(value: f32) -> f32 {
+    let v = value.clamp(0.0, 1.0);
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn build_thumbnail(linear: &image::Rgb32FImage) -> (u32, u32, Vec<u8>) {
+    let (width, height) = linear.dimensions();
+    let scale = THUMBNAIL_LONG_EDGE as f32 / width.max(height) as f32;
+    let thumbnail = if scale < 1.0 {
+        imageops::thumbnail(
+            linear,
+            ((width as f32 * scale).round() as u32).max(1),
+            ((height as f32 * scale).round() as u32).max(1),
+        )
+    } else {
+        linear.clone()
+    };
+    let pixels = thumbnail
+        .as_raw()
+        .iter()
+        .map(|v| (linear_to_srgb(*v) * 255.0).round() as u8)
+        .collect();
+    (thumbnail.width(), thumbnail.height(), pixels)
+}
+
+/// Writes a linear (already demosaiced) DNG. The samples are stored as 16-bit linear sRGB
+/// primaries; values above 1.0 are kept by lowering the white level instead of clipping.
+/// When `source_is_linear` is false the input is treated as display-referred sRGB and decoded.
+pub fn encode_linear_dng(
+    image: &DynamicImage,
+    source_is_linear: bool,
+    metadata: &DngMetadata,
+) -> Result<Vec<u8>, String> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return Err("Cannot write an empty DNG".to_string());
+    }
+    if width as u64 * height as u64 * 6 + (1 << 20) > u32::MAX as u64 {
+        return Err("Image is too large for a DNG file".to_string());
+    }
+
+    let mut linear = image.to_rgb32f();
+    if !source_is_linear {
+        linear
+            .par_iter_mut()
+            .for_each(|v| *v = srgb_to_linear(v.clamp(0.0, 1.0)));
+    }
+
+    let peak = linear
+        .as_raw()
+        .par_iter()
+        .copied()
+        .filter(|v| v.is_finite())
+        .reduce(|| 0.0f32, f32::max);
+    let headroom = peak.clamp(1.0, MAX_HIGHLIGHT_HEADROOM);
+    let white_level = (u16::MAX as f32 / headroom).round().max(1.0);
+
+    let raw_bytes: Vec<u8> = linear
+        .as_raw()
+        .par_iter()
+        .flat_map_iter(|v| {
+            let sample = (v.max(0.0) * white_level).round().min(u16::MAX as f32) as u16;
+            sample.to_le_bytes()
+        })
+        .collect();
+
+    let (thumb_w, thumb_h, thumb_bytes) = build_thumbnail(&linear);
+    drop(linear);
+
+    let mut out = Vec::with_capacity(raw_bytes.len() + thumb_bytes.len() + 4096);
+    out.extend_from_slice(b"II");
+    out.extend_from_slice(&42u16.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+
+    let thumb_offset = out.len() as u32;
+    out.extend_from_slice(&thumb_bytes);
+    if out.len() % 2 == 1 {
+        out.push(0);
+    }
+    let raw_offset = out.len() as u32;
+    out.extend_from_slice(&raw_bytes);
+
+    let mut exif_ifd = Ifd::default();
+    exif_ifd.set(36864, TagValue::Undefined(b"0231".to_vec()));
+    if let Some(v) = metadata.exposure_time {
+        exif_ifd.set(33434, TagValue::Rational(vec![v]));
+    }
+    if let Some(v) = metadata.f_number {
+        exif_ifd.set(33437, TagValue::Rational(vec![v]));
+    }
+    if let Some(v) = metadata.iso {
+        exif_ifd.set(34855, TagValue::Short(vec![v]));
+    }
+    exif_ifd.set_ascii(36867, &metadata.date_time_original);
+    if let Some(v) = metadata.focal_length {
+        exif_ifd.set(37386, TagValue::Rational(vec![v]));
+    }
+    exif_ifd.set_ascii(42035, &metadata.lens_make);
+    exif_ifd.set_ascii(42036, &metadata.lens_model);
+    let exif_offset = exif_ifd.write(&mut out);
+
+    let mut raw_ifd = Ifd::default();
+    raw_ifd.set(254, TagValue::Long(vec![0]));
+    raw_ifd.set(256, TagValue::Long(vec![width]));
+    raw_ifd.set(257, TagValue::Long(vec![height]));
+    raw_ifd.set(258, TagValue::Short(vec![16, 16, 16]));
+    raw_ifd.set(259, TagValue::Short(vec![1]));
+    raw_ifd.set(262, TagValue::Short(vec![PHOTOMETRIC_LINEAR_RAW]));
+    raw_ifd.set(273, TagValue::Long(vec![raw_offset]));
+    raw_ifd.set(277, TagValue::Short(vec![3]));
+    raw_ifd.set(278, TagValue::Long(vec![height]));
+    raw_ifd.set(279, TagValue::Long(vec![raw_bytes.len() as u32]));
+    raw_ifd.set(284, TagValue::Short(vec![1]));
+    raw_ifd.set(50717, TagValue::Long(vec![white_level as u32; 3]));
+    let raw_ifd_offset = raw_ifd.write(&mut out);
+
+    let xyz_to_camera = primaries_to_xyz_matrix(&PRIMARIES_SRGB, WP_D65).inverse();
+    let color_matrix: Vec<(i32, i32)> = (0..3)
+        .flat_map(|row| {
+            [
+                xyz_to_camera.x_axis[row],
+                xyz_to_camera.y_axis[row],
+                xyz_to_camera.z_axis[row],
+            ]
+        })
+        .map(to_srational)
+        .collect();
+
+    let mut main_ifd = Ifd::default();
+    main_ifd.set(254, TagValue::Long(vec![1]));
+    main_ifd.set(256, TagValue::Long(vec![thumb_w]));
+    main_ifd.set(257, TagValue::Long(vec![thumb_h]));
+    main_ifd.set(258, TagValue::Short(vec![8, 8, 8]));
+    main_ifd.set(259, TagValue::Short(vec![1]));
+    main_ifd.set(262, TagValue::Short(vec![PHOTOMETRIC_RGB]));
+    main_ifd.set_ascii(271, &metadata.make);
+    main_ifd.set_ascii(272, &metadata.model);
+    main_ifd.set(273, TagValue::Long(vec![thumb_offset]));
+    main_ifd.set(274, TagValue::Short(vec![1]));
+    main_ifd.set(277, TagValue::Short(vec![3]));
+    main_ifd.set(278, TagValue::Long(vec![thumb_h]));
+    main_ifd.set(279, TagValue::Long(vec![thumb_bytes.len() as u32]));
+    main_ifd.set(284, TagValue::Short(vec![1]));
+    main_ifd.set(305, TagValue::Ascii("RapidRAW".to_string()));
+    main_ifd.set_ascii(306, &metadata.date_time_original);
+    main_ifd.set_ascii(315, &metadata.artist);
+    main_ifd.set_ascii(33432, &metadata.copyright);
+    main_ifd.set(330, TagValue::Long(vec![raw_ifd_offset]));
+    main_ifd.set(34665, TagValue::Long(vec![exif_offset]));
+    main_ifd.set(50706, TagValue::Byte(vec![1, 4, 0, 0]));
+    main_ifd.set(50707, TagValue::Byte(vec![1, 1, 0, 0]));
+    main_ifd.set(50708, TagValue::Ascii(metadata.unique_camera_model()));
+    main_ifd.set(50721, TagValue::SRational(color_matrix));
+    main_ifd.set(50728, TagValue::Rational(vec![(1, 1), (1, 1), (1, 1)]));
+    main_ifd.set(50778, TagValue::Short(vec![ILLUMINANT_D65]));
+    main_ifd.set_ascii(50827, &metadata.original_file_name);
+
+    let main_offset = main_ifd.write(&mut out);
+    out[4..8].copy_from_slice(&main_offset.to_le_bytes());
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, Rgb32FImage};
+
+    struct Entry {
+        field_type: u16,
+        count: u32,
+        value: u32,
+    }
+
+    fn u16_at(buf: &[u8], pos: usize) -> u16 {
+        u16::from_le_bytes(buf[pos..pos + 2].try_into().unwrap())
+    }
+
+    fn u32_at(buf: &[u8], pos: usize) -> u32 {
+        u32::from_le_bytes(buf[pos..pos + 4].try_into().unwrap())
+    }
+
+    fn read_ifd(buf: &[u8], offset: u32) -> Vec<(u16, Entry)> {
+        let offset = offset as usize;
+        let count = u16_at(buf, offset) as usize;
+        (0..count)
+            .map(|i| {
+                let pos = offset + 2 + i * 12;
+                let entry = Entry {
+                    field_type: u16_at(buf, pos + 2),
+                    count: u32_at(buf, pos + 4),
+                    value: u32_at(buf, pos + 8),
+                };
+                (u16_at(buf, pos), entry)
+            })
+            .collect()
+    }
+
+    fn tag(ifd: &[(u16, Entry)], tag: u16) -> &Entry {
+        &ifd.iter().find(|(t, _)| *t == tag).unwrap().1
+    }
+
+    fn sample(buf: &[u8], raw_ifd: &[(u16, Entry)], index: usize) -> u16 {
+        u16_at(buf, tag(raw_ifd, 273).value as usize + index * 2)
+    }
+
+    fn solid_image(width: u32, height: u32, value: f32) -> DynamicImage {
+        DynamicImage::ImageRgb32F(Rgb32FImage::from_pixel(width, height, Rgb([value; 3])))
+    }
+
+    #[test]
+    fn writes_thumbnail_ifd_with_linear_raw_sub_ifd() {
+        let metadata = DngMetadata {
+            make: Some("Canon".to_string()),
+            model: Some("EOS R5".to_string()),
+            iso: Some(400),
+            ..Default::default()
+        };
+        let dng = encode_linear_dng(&solid_image(600, 300, 0.5), true, &metadata).unwrap();
+        assert_eq!(&dng[0..4], b"II*\0");
+
+        let main_ifd = read_ifd(&dng, u32_at(&dng, 4));
+        let tags: Vec<u16> = main_ifd.iter().map(|(t, _)| *t).collect();
+        assert!(tags.windows(2).all(|w| w[0] < w[1]), "tags must be sorted");
+        assert_eq!(tag(&main_ifd, 254).value, 1);
+        assert_eq!(tag(&main_ifd, 256).value, THUMBNAIL_LONG_EDGE);
+        assert_eq!(tag(&main_ifd, 257).value, THUMBNAIL_LONG_EDGE / 2);
+        assert_eq!(
+            tag(&main_ifd, 50706).value,
+            u32::from_le_bytes([1, 4, 0, 0])
+        );
+        assert_eq!(tag(&main_ifd, 50721).field_type, TIFF_SRATIONAL);
+        assert_eq!(tag(&main_ifd, 50721).count, 9);
+
+        let model = tag(&main_ifd, 50708);
+        let model_bytes = &dng[model.value as usize..][..model.count as usize];
+        assert_eq!(model_bytes, b"Canon EOS R5\0");
+
+        let raw_ifd = read_ifd(&dng, tag(&main_ifd, 330).value);
+        assert_eq!(tag(&raw_ifd, 254).value, 0);
+        assert_eq!(tag(&raw_ifd, 256).value, 600);
+        assert_eq!(tag(&raw_ifd, 257).value, 300);
+        assert_eq!(tag(&raw_ifd, 262).value, PHOTOMETRIC_LINEAR_RAW as u32);
+        let strip_end = tag(&raw_ifd, 273).value as usize + tag(&raw_ifd, 279).value as usize;
+        assert_eq!(tag(&raw_ifd, 279).value, 600 * 300 * 3 * 2);
+        assert!(strip_end <= dng.len());
+
+        let exif_ifd = read_ifd(&dng, tag(&main_ifd, 34665).value);
+        assert_eq!(tag(&exif_ifd, 34855).value, 400);
+    }
+
+    #[test]
+    fn keeps_highlights_above_white_by_lowering_the_white_level() {
+        let dng =
+            encode_linear_dng(&solid_image(4, 4, 2.0), true, &DngMetadata::default()).unwrap();
+        let main_ifd = read_ifd(&dng, u32_at(&dng, 4));
+        let raw_ifd = read_ifd(&dng, tag(&main_ifd, 330).value);
+
+        let white_level = tag(&raw_ifd, 50717);
+        assert_eq!(white_level.count, 3);
+        let white = u32_at(&dng, white_level.value as usize);
+        assert_eq!(white, (u16::MAX as f32 / 2.0).round() as u32);
+        assert_eq!(sample(&dng, &raw_ifd, 0), u16::MAX);
+    }
+
+    #[test]
+    fn decodes_display_referred_input() {
+        let dng =
+            encode_linear_dng(&solid_image(2, 2, 0.5), false, &DngMetadata::default()).unwrap();
+        let main_ifd = read_ifd(&dng, u32_at(&dng, 4));
+        let raw_ifd = read_ifd(&dng, tag(&main_ifd, 330).value);
+
+        let expected = (srgb_to_linear(0.5) * u16::MAX as f32).round() as u16;
+        assert_eq!(sample(&dng, &raw_ifd, 0), expected);
+    }
+}
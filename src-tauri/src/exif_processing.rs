@@ -3,6 +3,7 @@ use std::fs;
 use std::io::{BufReader, Cursor};
 use std::path::{Path, PathBuf};
 
+use crate::color_management::OutputColorSpace;
 use crate::formats::is_raw_file;
 use crate::image_processing::ImageMetadata;
 use chrono::{DateTime, NaiveDateTime, Utc};
@@ -874,12 +875,41 @@ fn apply_sidecar_field_overrides(metadata: &mut Metadata, map: &HashMap<String,
     }
 }
 
+pub(crate) fn parse_sidecar_rational(s: &str) -> Option<uR64> {
+    let cleaned_string = s
+        .replace("f/", "")
+        .replace(" s", "")
+        .replace(" mm", "")
+        .replace("\"", "");
+
+    let val = cleaned_string.trim();
+
+    if val.contains('/') {
+        let parts: Vec<&str> = val.split('/').collect();
+        if parts.len() == 2
+            && let (Ok(n), Ok(d)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>())
+        {
+            return Some(uR64 {
+                nominator: n,
+                denominator: d,
+            });
+        }
+    } else if let Ok(f) = val.parse::<f32>() {
+        return Some(uR64 {
+            nominator: (f * 1000.0) as u32,
+            denominator: 1000,
+        });
+    }
+    None
+}
+
 pub fn write_image_with_metadata(
     image_bytes: &mut Vec<u8>,
     original_path_str: &str,
     output_format: &str,
     keep_metadata: bool,
     strip_gps: bool,
+    color_space: OutputColorSpace,
 ) -> Result<(), String> {
     // FIXME: temporary solution until I find a way to write metadata to TIFF
     if !keep_metadata || output_format.to_lowercase() == "tiff" {
@@ -922,33 +952,6 @@ pub fn write_image_with_metadata(
 
         let clean_s = |s: &String| s.replace('"', "").trim().to_string();
 
-        let parse_ur64 = |s: &str| -> Option<uR64> {
-            let cleaned_string = s
-                .replace("f/", "")
-                .replace(" s", "")
-                .replace(" mm", "")
-                .replace("\"", "");
-
-            let val = cleaned_string.trim();
-
-            if val.contains('/') {
-                let parts: Vec<&str> = val.split('/').collect();
-                if parts.len() == 2
-                    && let (Ok(n), Ok(d)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>())
-                {
-                    return Some(uR64 {
-                        nominator: n,
-                        denominator: d,
-                    });
-                }
-            } else if let Ok(f) = val.parse::<f32>() {
-                return Some(uR64 {
-                    nominator: (f * 1000.0) as u32,
-                    denominator: 1000,
-                });
-            }
-            None
-        };
         if let Some(val) = map.get("Make") {
             metadata.set_tag(ExifTag::Make(clean_s(val)));
         }
@@ -980,17 +983,17 @@ pub fn write_image_with_metadata(
             metadata.set_tag(ExifTag::CreateDate(clean_s(val)));
         }
         if let Some(val) = map.get("FNumber")
-            && let Some(ur) = parse_ur64(val)
+            && let Some(ur) = parse_sidecar_rational(val)
         {
             metadata.set_tag(ExifTag::FNumber(vec![ur]));
         }
         if let Some(val) = map.get("ExposureTime")
-            && let Some(ur) = parse_ur64(val)
+            && let Some(ur) = parse_sidecar_rational(val)
         {
             metadata.set_tag(ExifTag::ExposureTime(vec![ur]));
         }
         if let Some(val) = map.get("FocalLength")
-            && let Some(ur) = parse_ur64(val)
+            && let Some(ur) = parse_sidecar_rational(val)
         {
             metadata.set_tag(ExifTag::FocalLength(vec![ur]));
         }
@@ -1278,7 +1281,12 @@ pub fn write_image_with_metadata(
 
     metadata.set_tag(ExifTag::Software("RapidRAW".to_string()));
     metadata.set_tag(ExifTag::Orientation(vec![1u16]));
-    metadata.set_tag(ExifTag::ColorSpace(vec![1u16]));
+    let exif_color_space = if color_space.is_srgb() {
+        1u16
+    } else {
+        0xFFFFu16
+    };
+    metadata.set_tag(ExifTag::ColorSpace(vec![exif_color_space]));
 
     if let Ok(reader) =
         image::ImageReader::new(Cursor::new(image_bytes.as_slice())).with_guessed_format()
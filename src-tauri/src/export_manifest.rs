@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::file_management::parse_virtual_path;
+
+pub const MANIFEST_FILE_NAME: &str = ".rapidraw-export.json";
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSyncOptions {
+    #[serde(default)]
+    pub delete_removed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    // Stored as strings: u64 hashes do not survive a round trip through JS numbers.
+    pub job_hash: String,
+    pub settings_hash: String,
+    #[serde(default)]
+    pub source_modified: Option<u64>,
+    pub outputs: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifest {
+    pub version: u32,
+    #[serde(default)]
+    pub entries: HashMap<String, ManifestEntry>,
+}
+
+impl Default for ExportManifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            entries: HashMap::new(),
+        }
+    }
+}
+
+/// Hashes the settings together with the contents of the files they reference (e.g. watermark
+/// images), so replacing such a file in place also invalidates earlier exports.
+pub fn hash_export_settings<T: Serialize>(settings: &T, referenced_files: &[&Path]) -> String {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(settings)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    for path in referenced_files {
+        fs::read(path).ok().hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+pub fn source_modified_secs(source_path: &Path) -> Option<u64> {
+    fs::metadata(source_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+}
+
+impl ManifestEntry {
+    pub fn new(job_hash: u64, settings_hash: &str, source_path: &Path) -> Self {
+        Self {
+            job_hash: format!("{:016x}", job_hash),
+            settings_hash: settings_hash.to_string(),
+            source_modified: source_modified_secs(source_path),
+            outputs: Vec::new(),
+        }
+    }
+
+    pub fn with_outputs(self, outputs: &[PathBuf], output_root: &Path) -> Self {
+        let outputs = outputs
+            .iter()
+            .map(|p| {
+                p.strip_prefix(output_root)
+                    .unwrap_or(p)
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        Self { outputs, ..self }
+    }
+
+    /// True when the previous export is still valid: same edit, same settings, an untouched
+    /// source file and every recorded output still on disk.
+    pub fn is_up_to_date(&self, other: &ManifestEntry, output_root: &Path) -> bool {
+        self.job_hash == other.job_hash
+            && self.settings_hash == other.settings_hash
+            && self.source_modified == other.source_modified
+            && !self.outputs.is_empty()
+            && self
+                .outputs
+                .iter()
+                .all(|output| output_root.join(output).exists())
+    }
+}
+
+impl ExportManifest {
+    pub fn load(output_root: &Path) -> Self {
+        let path = output_root.join(MANIFEST_FILE_NAME);
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        match serde_json::from_str::<ExportManifest>(&content) {
+            Ok(manifest) if manifest.version == MANIFEST_VERSION => manifest,
+            Ok(_) => Self::default(),
+            Err(e) => {
+                log::warn!(
+                    "Ignoring unreadable export manifest {}: {}",
+                    path.display(),
+                    e
+                );
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, output_root: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        let path = output_root.join(MANIFEST_FILE_NAME);
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, json).map_err(|e| e.to_string())?;
+        fs::rename(&tmp_path, &path).map_err(|e| e.to_string())
+    }
+
+    /// Drops entries whose source file no longer exists, deleting their outputs from disk.
+    /// Returns the number of files removed.
+    pub fn prune_removed_sources(&mut self, output_root: &Path) -> usize {
+        let removed: Vec<String> = self
+            .entries
+            .keys()
+            .filter(|key| !parse_virtual_path(key).0.exists())
+            .cloned()
+            .collect();
+
+        let mut deleted = 0;
+        for key in removed {
+            let Some(entry) = self.entries.remove(&key) else {
+                continue;
+            };
+            for output in entry.outputs {
+                let is_inside_root = Path::new(&output)
+                    .components()
+                    .all(|c| matches!(c, std::path::Component::Normal(_)));
+                if !is_inside_root {
+                    continue;
+                }
+                let output_path = output_root.join(&output);
+                match fs::remove_file(&output_path) {
+                    Ok(()) => deleted += 1,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => log::warn!(
+                        "Failed to delete orphaned export {}: {}",
+                        output_path.display(),
+                        e
+                    ),
+                }
+            }
+        }
+        deleted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(job_hash: u64, source: &Path, outputs: &[&str]) -> ManifestEntry {
+        ManifestEntry {
+            outputs: outputs.iter().map(|output| output.to_string()).collect(),
+            ..ManifestEntry::new(job_hash, "a", source)
+        }
+    }
+
+    #[test]
+    fn settings_hash_follows_referenced_file_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let watermark = dir.path().join("logo.png");
+        fs::write(&watermark, b"first").unwrap();
+        let settings = serde_json::json!({ "watermark": watermark });
+
+        let before = hash_export_settings(&settings, &[watermark.as_path()]);
+        assert_eq!(
+            before,
+            hash_export_settings(&settings, &[watermark.as_path()])
+        );
+
+        fs::write(&watermark, b"second").unwrap();
+        assert_ne!(
+            before,
+            hash_export_settings(&settings, &[watermark.as_path()])
+        );
+    }
+
+    #[test]
+    fn is_up_to_date_requires_matching_hashes_and_existing_outputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("IMG_0001.CR3");
+        fs::write(&source, b"raw").unwrap();
+        let output_root = dir.path().join("out");
+        fs::create_dir(&output_root).unwrap();
+        fs::write(output_root.join("IMG_0001.jpg"), b"jpeg").unwrap();
+
+        let outputs = [output_root.join("IMG_0001.jpg")];
+        let previous = ManifestEntry::new(1, "a", &source).with_outputs(&outputs, &output_root);
+        assert_eq!(previous.outputs, vec!["IMG_0001.jpg".to_string()]);
+
+        let current = ManifestEntry::new(1, "a", &source);
+        assert!(previous.is_up_to_date(&current, &output_root));
+        assert!(!previous.is_up_to_date(&ManifestEntry::new(2, "a", &source), &output_root));
+        assert!(!previous.is_up_to_date(&ManifestEntry::new(1, "b", &source), &output_root));
+
+        let mut touched = current.clone();
+        touched.source_modified = touched.source_modified.map(|t| t + 1);
+        assert!(!previous.is_up_to_date(&touched, &output_root));
+
+        let without_outputs = ManifestEntry::new(1, "a", &source);
+        assert!(!without_outputs.is_up_to_date(&current, &output_root));
+
+        fs::remove_file(output_root.join("IMG_0001.jpg")).unwrap();
+        assert!(!previous.is_up_to_date(&current, &output_root));
+    }
+
+    #[test]
+    fn prune_removed_sources_deletes_only_outputs_of_missing_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_root = dir.path().join("out");
+        fs::create_dir(&output_root).unwrap();
+        let kept_source = dir.path().join("kept.CR3");
+        fs::write(&kept_source, b"raw").unwrap();
+        let removed_source = dir.path().join("removed.CR3");
+        let outside = dir.path().join("outside.jpg");
+        for name in ["kept.jpg", "removed.jpg", "removed_small.jpg"] {
+            fs::write(output_root.join(name), b"jpeg").unwrap();
+        }
+        fs::write(&outside, b"jpeg").unwrap();
+
+        let mut manifest = ExportManifest::default();
+        manifest.entries.insert(
+            kept_source.to_string_lossy().into_owned(),
+            entry(1, &kept_source, &["kept.jpg"]),
+        );
+        manifest.entries.insert(
+            format!("{}?vc=abc123", removed_source.to_string_lossy()),
+            entry(
+                2,
+                &removed_source,
+                &[
+                    "removed.jpg",
+                    "removed_small.jpg",
+                    "missing.jpg",
+                    "../outside.jpg",
+                ],
+            ),
+        );
+
+        assert_eq!(manifest.prune_removed_sources(&output_root), 2);
+        assert_eq!(manifest.entries.len(), 1);
+        assert!(manifest.entries.contains_key(kept_source.to_str().unwrap()));
+        assert!(output_root.join("kept.jpg").exists());
+        assert!(!output_root.join("removed.jpg").exists());
+        assert!(!output_root.join("removed_small.jpg").exists());
+        assert!(outside.exists());
+    }
+}
@@ -1,24 +1,40 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::Cursor;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Arc, Mutex};
 
+use image::codecs::avif::AvifEncoder;
 use image::codecs::jpeg::JpegEncoder;
-use image::{DynamicImage, GenericImageView, GrayImage, ImageBuffer, ImageFormat, Luma, imageops};
+use image::codecs::png::PngEncoder;
+use image::codecs::tiff::TiffEncoder;
+use image::{
+    DynamicImage, GenericImageView, GrayImage, ImageBuffer, ImageEncoder, ImageFormat, Luma,
+    imageops,
+};
 use jxl_encoder::{
     LosslessConfig, LossyConfig, PixelLayout,
     api::{calibrated_jxl_quality, quality_to_distance},
 };
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::Emitter;
 use tauri::Manager;
 
 use crate::AppState;
+use crate::app_settings::{AppSettings, ExportPreset, ExportRecipe};
+use crate::color_management::{
+    OutputColorSpace, avif_colr_payload, build_icc_profile, configure_render, convert_to_srgb,
+    embed_avif_colr, embed_jxl_colour_encoding, embed_webp_icc,
+};
+use crate::dng_writer::{DngMetadata, encode_linear_dng};
 use crate::exif_processing;
+use crate::export_manifest::{
+    ExportManifest, ExportSyncOptions, ManifestEntry, hash_export_settings,
+};
 use crate::file_management::{
     generate_filename_from_template, parse_virtual_path, read_file_mapped,
 };
@@ -31,10 +47,18 @@ use crate::image_processing::{
     get_all_adjustments_from_json, get_or_init_gpu_context, process_and_get_dynamic_image,
     resolve_tonemapper_override_from_handle,
 };
+use crate::job_queue::{CancellationRequest, EXPORT_CANCELLED, EXPORT_TASK, TaskRegistry};
 use crate::lut_processing::{
     convert_image_to_cube_lut, generate_identity_lut_image, get_or_load_lut,
 };
 use crate::mask_generation::{MaskDefinition, generate_mask_bitmap};
+use crate::text_watermark::{
+    TextAlign, TextWatermarkSettings, expand_watermark_tokens, render_text_watermark,
+};
+use crate::ultra_hdr::{
+    HDR_RENDER_TONEMAPPER, HdrGainMapSettings, assemble_ultra_hdr_jpeg, compute_gain_map,
+    encode_gain_map_jpeg,
+};
 
 use crate::cache_utils::{calculate_full_job_hash, calculate_transform_hash};
 use crate::{
@@ -74,6 +98,14 @@ pub struct ExportSettings {
     pub export_masks: bool,
     #[serde(default)]
     pub preserve_folders: bool,
+    #[serde(default)]
+    pub output_color_space: OutputColorSpace,
+    #[serde(default)]
+    pub output_sharpening: Option<OutputSharpening>,
+    #[serde(default)]
+    pub max_file_size_bytes: Option<u64>,
+    #[serde(default)]
+    pub hdr_gain_map: Option<HdrGainMapSettings>,
 }
 
 #[derive(Clone)]
@@ -83,6 +115,152 @@ pub(crate) enum ExportAdjustmentsMode {
         active_adjustments: Option<Value>,
     },
     GlobalOverride(Value),
+    /// Adjustments keyed by image path; images without an entry use their sidecar.
+    PerImage(HashMap<String, Value>),
+}
+
+/// An image that could not be exported, reported through the export completion channel.
+#[derive(Debug, Clone)]
+pub(crate) struct ExportFailure {
+    pub path: String,
+    pub error: String,
+}
+
+struct ExportedImage {
+    path: String,
+    skipped: bool,
+    manifest_entry: Option<ManifestEntry>,
+}
+
+/// One output of an export pass. Every variant shares the developed image and only differs in
+/// what happens after the edit pipeline.
+#[derive(Serialize, Debug, Clone)]
+pub(crate) struct ExportVariant {
+    pub output_format: String,
+    pub sub_folder: Option<String>,
+    pub export_settings: ExportSettings,
+}
+
+fn parse_setting<T: serde::de::DeserializeOwned>(value: &str) -> Option<T> {
+    serde_json::from_value(Value::String(value.to_string())).ok()
+}
+
+impl ExportVariant {
+    pub(crate) fn from_preset(preset: &ExportPreset, sub_folder: Option<String>) -> Self {
+        let output_format = match preset.file_format.as_str() {
+            "jpeg" => "jpg".to_string(),
+            other => other.to_string(),
+        };
+
+        let resize = preset.enable_resize.then(|| ResizeOptions {
+            mode: parse_setting(&preset.resize_mode).unwrap_or(ResizeMode::LongEdge),
+            value: preset.resize_value,
+            dont_enlarge: preset.dont_enlarge,
+        });
+
+        let watermark = if preset.enable_watermark {
+            let anchor = preset
+                .watermark_anchor
+                .as_deref()
+                .and_then(parse_setting)
+                .unwrap_or(WatermarkAnchor::BottomRight);
+            let watermark = WatermarkSettings {
+                path: String::new(),
+                anchor,
+                scale: preset.watermark_scale as f32,
+                spacing: preset.watermark_spacing as f32,
+                opacity: preset.watermark_opacity as f32,
+                text: None,
+            };
+            if preset.watermark_type.as_deref() == Some("text") {
+                preset
+                    .watermark_text
+                    .as_ref()
+                    .filter(|text| !text.trim().is_empty())
+                    .map(|text| WatermarkSettings {
+                        text: Some(TextWatermarkSettings {
+                            text: text.clone(),
+                            font_family: preset.watermark_font_family.clone(),
+                            font_size: preset.watermark_font_size.unwrap_or(3.0),
+                            color: preset
+                                .watermark_color
+                                .clone()
+                                .unwrap_or_else(|| "#ffffff".to_string()),
+                        }),
+                        ..watermark
+                    })
+            } else {
+                preset
+                    .watermark_path
+                    .clone()
+                    .filter(|path| !path.is_empty())
+                    .map(|path| WatermarkSettings { path, ..watermark })
+            }
+        } else {
+            None
+        };
+
+        let output_sharpening =
+            preset
+                .enable_output_sharpening
+                .unwrap_or(false)
+                .then(|| OutputSharpening {
+                    target: preset
+                        .output_sharpening_target
+                        .as_deref()
+                        .and_then(parse_setting)
+                        .unwrap_or(SharpeningTarget::Screen),
+                    amount: preset.output_sharpening_amount.unwrap_or(50) as f32,
+                });
+
+        let max_file_size_bytes = preset
+            .enable_max_file_size
+            .unwrap_or(false)
+            .then(|| preset.max_file_size_kb.unwrap_or(0) as u64 * 1024)
+            .filter(|bytes| *bytes > 0 && supports_quality_search(&output_format));
+
+        let hdr_gain_map = (preset.enable_hdr_gain_map.unwrap_or(false) && output_format == "jpg")
+            .then(|| HdrGainMapSettings {
+                headroom_stops: preset.hdr_headroom_stops.unwrap_or(3.0),
+            });
+
+        ExportVariant {
+            export_settings: ExportSettings {
+                jpeg_quality: preset.jpeg_quality,
+                resize,
+                keep_metadata: preset.keep_metadata,
+                preserve_timestamps: preset.preserve_timestamps.unwrap_or(false),
+                strip_gps: preset.strip_gps,
+                filename_template: Some(preset.filename_template.clone())
+                    .filter(|template| !template.trim().is_empty()),
+                watermark,
+                export_masks: preset.export_masks.unwrap_or(false),
+                preserve_folders: preset.preserve_folders.unwrap_or(false),
+                output_color_space: preset.output_color_space,
+                output_sharpening,
+                max_file_size_bytes,
+                hdr_gain_map,
+            },
+            output_format,
+            sub_folder,
+        }
+    }
+
+    fn sub_folder_path(&self) -> Option<PathBuf> {
+        let sub_folder = self.sub_folder.as_deref()?.trim();
+        let path: PathBuf = Path::new(sub_folder)
+            .components()
+            .filter(|c| matches!(c, std::path::Component::Normal(_)))
+            .collect();
+        (!path.as_os_str().is_empty()).then_some(path)
+    }
+
+    fn filename_template(&self) -> &str {
+        self.export_settings
+            .filename_template
+            .as_deref()
+            .unwrap_or("{original_filename}_edited")
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -102,41 +280,141 @@ pub enum WatermarkAnchor {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct WatermarkSettings {
+    #[serde(default)]
     pub path: String,
     pub anchor: WatermarkAnchor,
     pub scale: f32,
     pub spacing: f32,
     pub opacity: f32,
+    #[serde(default)]
+    pub text: Option<TextWatermarkSettings>,
 }
 
-fn apply_watermark(
-    base_image: &mut DynamicImage,
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum SharpeningTarget {
+    Screen,
+    MattePaper,
+    GlossyPaper,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputSharpening {
+    pub target: SharpeningTarget,
+    pub amount: f32,
+}
+
+impl SharpeningTarget {
+    // (blur sigma in output pixels, strength at 100% amount, noise threshold)
+    fn parameters(self) -> (f32, f32, f32) {
+        match self {
+            SharpeningTarget::Screen => (0.6, 0.9, 0.004),
+            SharpeningTarget::MattePaper => (1.3, 1.8, 0.008),
+            SharpeningTarget::GlossyPaper => (1.0, 1.3, 0.006),
+        }
+    }
+}
+
+fn apply_output_sharpening(image: DynamicImage, sharpening: &OutputSharpening) -> DynamicImage {
+    let amount = (sharpening.amount / 100.0).clamp(0.0, 2.0);
+    if amount <= 0.0 {
+        return image;
+    }
+    let (sigma, strength, threshold) = sharpening.target.parameters();
+    let strength = strength * amount;
+    let color = image.color();
+
+    let mut rgba = image.into_rgba32f();
+    let (w, h) = rgba.dimensions();
+
+    let luma: ImageBuffer<Luma<f32>, Vec<f32>> = ImageBuffer::from_fn(w, h, |x, y| {
+        let p = rgba.get_pixel(x, y);
+        Luma([0.2126 * p[0] + 0.7152 * p[1] + 0.0722 * p[2]])
+    });
+    let blurred = imageops::blur(&luma, sigma);
+
+    rgba.par_chunks_mut(4)
+        .zip(luma.as_raw().par_iter().zip(blurred.as_raw().par_iter()))
+        .for_each(|(p, (&y, &y_blur))| {
+            let detail = y - y_blur;
+            let magnitude = (detail.abs() - threshold).max(0.0);
+            let delta = (detail.signum() * magnitude * strength).clamp(-0.25, 0.25);
+            p[0] = (p[0] + delta).clamp(0.0, 1.0);
+            p[1] = (p[1] + delta).clamp(0.0, 1.0);
+            p[2] = (p[2] + delta).clamp(0.0, 1.0);
+        });
+
+    let sharpened = DynamicImage::ImageRgba32F(rgba);
+    let bytes_per_channel = color.bytes_per_pixel() / color.channel_count();
+    match (bytes_per_channel, color.has_alpha()) {
+        (1, true) => DynamicImage::ImageRgba8(sharpened.to_rgba8()),
+        (1, false) => DynamicImage::ImageRgb8(sharpened.to_rgb8()),
+        (2, true) => DynamicImage::ImageRgba16(sharpened.to_rgba16()),
+        (2, false) => DynamicImage::ImageRgb16(sharpened.to_rgb16()),
+        (_, true) => sharpened,
+        (_, false) => DynamicImage::ImageRgb32F(sharpened.to_rgb32f()),
+    }
+}
+
+fn render_image_watermark(
     watermark_settings: &WatermarkSettings,
-) -> Result<(), String> {
+    base_min_dim: f32,
+    opacity_factor: f32,
+) -> Result<Option<DynamicImage>, String> {
     let watermark_img = image::open(&watermark_settings.path)
         .map_err(|e| format!("Failed to open watermark image: {}", e))?;
 
-    let (base_w, base_h) = base_image.dimensions();
-    let base_min_dim = base_w.min(base_h) as f32;
-
     let watermark_scale_factor =
         (base_min_dim * (watermark_settings.scale / 100.0)) / watermark_img.width().max(1) as f32;
     let new_wm_w = (watermark_img.width() as f32 * watermark_scale_factor).round() as u32;
     let new_wm_h = (watermark_img.height() as f32 * watermark_scale_factor).round() as u32;
 
     if new_wm_w == 0 || new_wm_h == 0 {
-        return Ok(());
+        return Ok(None);
     }
 
     let scaled_watermark =
         watermark_img.resize_exact(new_wm_w, new_wm_h, image::imageops::FilterType::Lanczos3);
     let mut scaled_watermark_rgba = scaled_watermark.to_rgba8();
 
-    let opacity_factor = (watermark_settings.opacity / 100.0).clamp(0.0, 1.0);
     for pixel in scaled_watermark_rgba.pixels_mut() {
         pixel[3] = (pixel[3] as f32 * opacity_factor) as u8;
     }
-    let final_watermark = DynamicImage::ImageRgba8(scaled_watermark_rgba);
+    Ok(Some(DynamicImage::ImageRgba8(scaled_watermark_rgba)))
+}
+
+fn apply_watermark(
+    base_image: &mut DynamicImage,
+    watermark_settings: &WatermarkSettings,
+    source_path: &str,
+) -> Result<(), String> {
+    let (base_w, base_h) = base_image.dimensions();
+    let base_min_dim = base_w.min(base_h) as f32;
+    let opacity_factor = (watermark_settings.opacity / 100.0).clamp(0.0, 1.0);
+
+    let watermark = match &watermark_settings.text {
+        Some(text_settings) => {
+            let align = match watermark_settings.anchor {
+                WatermarkAnchor::TopLeft
+                | WatermarkAnchor::CenterLeft
+                | WatermarkAnchor::BottomLeft => TextAlign::Left,
+                WatermarkAnchor::TopCenter
+                | WatermarkAnchor::Center
+                | WatermarkAnchor::BottomCenter => TextAlign::Center,
+                WatermarkAnchor::TopRight
+                | WatermarkAnchor::CenterRight
+                | WatermarkAnchor::BottomRight => TextAlign::Right,
+            };
+            let text = expand_watermark_tokens(&text_settings.text, source_path);
+            render_text_watermark(&text, text_settings, base_min_dim, opacity_factor, align)?
+                .map(DynamicImage::ImageRgba8)
+        }
+        None => render_image_watermark(watermark_settings, base_min_dim, opacity_factor)?,
+    };
+    let Some(final_watermark) = watermark else {
+        return Ok(());
+    };
 
     let spacing_pixels = (base_min_dim * (watermark_settings.spacing / 100.0)) as i64;
     let (wm_w, wm_h) = final_watermark.dimensions();
@@ -269,6 +547,7 @@ fn relative_export_dir_for_preserved_folders(
 fn apply_export_resize_and_watermark(
     mut image: DynamicImage,
     export_settings: &ExportSettings,
+    source_path: &str,
 ) -> Result<DynamicImage, String> {
     if let Some(resize_opts) = &export_settings.resize {
         let (current_w, current_h) = image.dimensions();
@@ -279,128 +558,42 @@ fn apply_export_resize_and_watermark(
         }
     }
 
+    if let Some(sharpening) = &export_settings.output_sharpening {
+        image = apply_output_sharpening(image, sharpening);
+    }
+
     if let Some(watermark_settings) = &export_settings.watermark {
-        apply_watermark(&mut image, watermark_settings)?;
+        apply_watermark(&mut image, watermark_settings, source_path)?;
     }
     Ok(image)
 }
 
 fn ensure_export_not_cancelled(cancellation_token: &AtomicBool) -> Result<(), String> {
     if cancellation_token.load(Ordering::SeqCst) {
-        Err("Export cancelled".to_string())
+        Err(EXPORT_CANCELLED.to_string())
     } else {
         Ok(())
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum ExportCancellationRequest {
-    Requested,
-    AlreadyRequested,
-    NoActiveTask,
-}
-
 struct ExportTaskGuard {
-    task_token: Arc<Mutex<Option<Arc<AtomicBool>>>>,
+    registry: Arc<TaskRegistry>,
     cancellation_token: Arc<AtomicBool>,
-    app_handle: Option<tauri::AppHandle>,
-}
-
-impl ExportTaskGuard {
-    fn new(
-        task_token: Arc<Mutex<Option<Arc<AtomicBool>>>>,
-        cancellation_token: Arc<AtomicBool>,
-    ) -> Self {
-        Self {
-            task_token,
-            cancellation_token,
-            app_handle: None,
-        }
-    }
-
-    fn with_app_handle(
-        task_token: Arc<Mutex<Option<Arc<AtomicBool>>>>,
-        cancellation_token: Arc<AtomicBool>,
-        app_handle: tauri::AppHandle,
-    ) -> Self {
-        let mut guard = Self::new(task_token, cancellation_token);
-        guard.app_handle = Some(app_handle);
-        guard
-    }
-}
-
-fn register_export_task(
-    task_token: &Mutex<Option<Arc<AtomicBool>>>,
-) -> Result<Arc<AtomicBool>, String> {
-    let mut active_token = task_token.lock().unwrap();
-    if active_token.is_some() {
-        return Err("An export is already in progress.".to_string());
-    }
-
-    let cancellation_token = Arc::new(AtomicBool::new(false));
-    *active_token = Some(Arc::clone(&cancellation_token));
-    Ok(cancellation_token)
-}
-
-fn request_export_cancellation<F>(
-    task_token: &Mutex<Option<Arc<AtomicBool>>>,
-    on_requested: F,
-) -> ExportCancellationRequest
-where
-    F: FnOnce(),
-{
-    let active_token = task_token.lock().unwrap();
-    let Some(cancellation_token) = active_token.as_ref() else {
-        return ExportCancellationRequest::NoActiveTask;
-    };
-
-    if cancellation_token.swap(true, Ordering::SeqCst) {
-        ExportCancellationRequest::AlreadyRequested
-    } else {
-        on_requested();
-        ExportCancellationRequest::Requested
-    }
-}
-
-fn finish_export_task<F>(
-    task_token: &Mutex<Option<Arc<AtomicBool>>>,
-    cancellation_token: &Arc<AtomicBool>,
-    on_finish: F,
-) -> bool
-where
-    F: FnOnce(bool),
-{
-    let mut active_token = task_token.lock().unwrap();
-    let Some(current_token) = active_token.as_ref() else {
-        return false;
-    };
-    if !Arc::ptr_eq(current_token, cancellation_token) {
-        return false;
-    }
-
-    let cancelled = cancellation_token.load(Ordering::SeqCst);
-    *active_token = None;
-
-    on_finish(cancelled);
-    true
+    app_handle: tauri::AppHandle,
 }
 
 impl Drop for ExportTaskGuard {
     fn drop(&mut self) {
-        let app_handle = self.app_handle.clone();
-        let _ = finish_export_task(
-            &self.task_token,
-            &self.cancellation_token,
-            |cancelled| match (cancelled, app_handle) {
-                (true, Some(app_handle)) => {
+        let app_handle = &self.app_handle;
+        let _ = self
+            .registry
+            .finish(EXPORT_TASK, &self.cancellation_token, |cancelled| {
+                if cancelled {
                     let _ = app_handle.emit("export-cancelled", ());
-                }
-                (false, Some(app_handle)) => {
+                } else {
                     let _ = app_handle.emit("export-error", "Export task terminated unexpectedly");
                 }
-                _ => {}
-            },
-        );
+            });
     }
 }
 
@@ -412,8 +605,35 @@ fn process_image_for_export_pipeline(
     context: &GpuContext,
     state: &tauri::State<AppState>,
     is_raw: bool,
+    color_space: OutputColorSpace,
     debug_tag: &str,
     app_handle: &tauri::AppHandle,
+) -> Result<DynamicImage, String> {
+    let tm_override = resolve_tonemapper_override_from_handle(app_handle, is_raw);
+    render_export_image(
+        path,
+        base_image,
+        js_adjustments,
+        context,
+        state,
+        is_raw,
+        color_space,
+        debug_tag,
+        tm_override,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_export_image(
+    path: &str,
+    base_image: &DynamicImage,
+    js_adjustments: &Value,
+    context: &GpuContext,
+    state: &tauri::State<AppState>,
+    is_raw: bool,
+    color_space: OutputColorSpace,
+    debug_tag: &str,
+    tm_override: Option<u32>,
 ) -> Result<DynamicImage, String> {
     let (transformed_image, unscaled_crop_offset) =
         apply_all_transformations(Cow::Borrowed(base_image), js_adjustments);
@@ -439,9 +659,9 @@ fn process_image_for_export_pipeline(
         })
         .collect();
 
-    let tm_override = resolve_tonemapper_override_from_handle(app_handle, is_raw);
     let mut all_adjustments = get_all_adjustments_from_json(js_adjustments, is_raw, tm_override);
     all_adjustments.global.show_clipping = 0;
+    configure_render(&mut all_adjustments.global, color_space);
 
     let lut_path = js_adjustments["lutPath"].as_str();
     let lut = lut_path.and_then(|p| get_or_load_lut(state, p).ok());
@@ -463,6 +683,34 @@ fn process_image_for_export_pipeline(
     )
 }
 
+/// Renders an image with the given adjustments exactly like an export does, without the
+/// resize, watermark and encoding steps.
+pub(crate) fn develop_image(
+    path: &str,
+    adjustments: &Value,
+    app_handle: &tauri::AppHandle,
+) -> Result<DynamicImage, String> {
+    let state = app_handle.state::<AppState>();
+    let context = get_or_init_gpu_context(&state, app_handle)?;
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+
+    let mut js_adjustments = adjustments.clone();
+    hydrate_adjustments(&state, &mut js_adjustments);
+    let base_image =
+        load_export_base_image(false, &state, path, &js_adjustments, &settings, app_handle)?;
+    process_image_for_export_pipeline(
+        path,
+        &base_image,
+        &js_adjustments,
+        &context,
+        &state,
+        is_raw_file(path),
+        OutputColorSpace::Srgb,
+        "develop_image",
+        app_handle,
+    )
+}
+
 fn set_timestamps_from_exif(src: &Path, dst: &Path) {
     let capture_dt = exif_processing::get_creation_date_from_path(src);
     let ft = filetime::FileTime::from_unix_time(
@@ -474,27 +722,150 @@ fn set_timestamps_from_exif(src: &Path, dst: &Path) {
     }
 }
 
+fn supports_quality_search(extension: &str) -> bool {
+    matches!(extension, "jpg" | "jpeg" | "webp" | "avif" | "jxl")
+}
+
+/// AVIF has no quality slider and keeps `AvifEncoder::new`'s quality unless a file size target
+/// makes the quality search pick a lower one.
+const AVIF_DEFAULT_QUALITY: u8 = 80;
+
+fn export_quality(extension: &str, export_settings: &ExportSettings) -> u8 {
+    if extension.eq_ignore_ascii_case("avif") {
+        AVIF_DEFAULT_QUALITY
+    } else {
+        export_settings.jpeg_quality
+    }
+}
+
+fn encode_with_metadata(
+    image: &DynamicImage,
+    extension: &str,
+    quality: u8,
+    source_path_str: &str,
+    export_settings: &ExportSettings,
+    gain_map_jpeg: Option<&[u8]>,
+) -> Result<Vec<u8>, String> {
+    let mut image_bytes = encode_image_to_bytes(
+        image,
+        extension,
+        quality,
+        export_settings.output_color_space,
+    )?;
+
+    exif_processing::write_image_with_metadata(
+        &mut image_bytes,
+        source_path_str,
+        extension,
+        export_settings.keep_metadata,
+        export_settings.strip_gps,
+        export_settings.output_color_space,
+    )?;
+
+    match gain_map_jpeg {
+        Some(gain_map) => assemble_ultra_hdr_jpeg(&image_bytes, gain_map),
+        None => Ok(image_bytes),
+    }
+}
+
+fn encode_within_file_size(
+    image: &DynamicImage,
+    extension: &str,
+    source_path_str: &str,
+    export_settings: &ExportSettings,
+    gain_map_jpeg: Option<&[u8]>,
+    max_bytes: u64,
+) -> Result<(Vec<u8>, u8), String> {
+    let max_quality = export_quality(extension, export_settings).clamp(1, 100);
+    let encoded = encode_with_metadata(
+        image,
+        extension,
+        max_quality,
+        source_path_str,
+        export_settings,
+        gain_map_jpeg,
+    )?;
+    if encoded.len() as u64 <= max_bytes {
+        return Ok((encoded, max_quality));
+    }
+
+    let mut best: Option<(Vec<u8>, u8)> = None;
+    let mut smallest = (encoded, max_quality);
+    let (mut low, mut high) = (1u8, max_quality - 1);
+    while low <= high {
+        let quality = low + (high - low) / 2;
+        let candidate = encode_with_metadata(
+            image,
+            extension,
+            quality,
+            source_path_str,
+            export_settings,
+            gain_map_jpeg,
+        )?;
+        if candidate.len() as u64 <= max_bytes {
+            best = Some((candidate, quality));
+            low = quality + 1;
+        } else {
+            if candidate.len() < smallest.0.len() {
+                smallest = (candidate, quality);
+            }
+            if quality == 1 {
+                break;
+            }
+            high = quality - 1;
+        }
+    }
+
+    Ok(best.unwrap_or_else(|| {
+        log::warn!(
+            "'{}' does not fit in {} bytes even at quality {}; exporting {} bytes",
+            source_path_str,
+            max_bytes,
+            smallest.1,
+            smallest.0.len()
+        );
+        smallest
+    }))
+}
+
+/// Returns the quality that was picked when a maximum file size forced a search.
 fn save_image_with_metadata(
     image: &DynamicImage,
     output_path: &std::path::Path,
     source_path_str: &str,
     export_settings: &ExportSettings,
-) -> Result<(), String> {
+    gain_map_jpeg: Option<&[u8]>,
+) -> Result<Option<u8>, String> {
     let extension = output_path
         .extension()
         .and_then(|s| s.to_str())
         .unwrap_or("")
         .to_lowercase();
 
-    let mut image_bytes = encode_image_to_bytes(image, &extension, export_settings.jpeg_quality)?;
-
-    exif_processing::write_image_with_metadata(
-        &mut image_bytes,
-        source_path_str,
-        &extension,
-        export_settings.keep_metadata,
-        export_settings.strip_gps,
-    )?;
+    let (image_bytes, chosen_quality) = match export_settings.max_file_size_bytes {
+        Some(max_bytes) if supports_quality_search(&extension) => {
+            let (bytes, quality) = encode_within_file_size(
+                image,
+                &extension,
+                source_path_str,
+                export_settings,
+                gain_map_jpeg,
+                max_bytes,
+            )?;
+            (bytes, Some(quality))
+        }
+        _ => (
+            encode_with_metadata(
+                image,
+                &extension,
+                export_quality(&extension, export_settings),
+                source_path_str,
+                export_settings,
+                gain_map_jpeg,
+            )?,
+            None,
+        ),
+    };
 
     #[cfg(target_os = "android")]
     {
@@ -512,7 +883,7 @@ fn save_image_with_metadata(
     #[cfg(not(target_os = "android"))]
     fs::write(output_path, image_bytes).map_err(|e| e.to_string())?;
 
-    Ok(())
+    Ok(chosen_quality)
 }
 
 #[cfg(target_os = "android")]
@@ -525,33 +896,111 @@ pub fn mime_type_for_extension(extension: &str) -> &'static str {
         "gif" => "image/gif",
         "tif" | "tiff" => "image/tiff",
         "jxl" => "image/jxl",
+        "dng" => "image/x-adobe-dng",
         _ => "application/octet-stream",
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-fn process_image_for_export(
-    path: &str,
+fn load_export_base_image(
+    is_current_edit: bool,
+    state: &tauri::State<AppState>,
+    source_path_str: &str,
+    js_adjustments: &Value,
+    settings: &AppSettings,
+    app_handle: &tauri::AppHandle,
+) -> Result<DynamicImage, String> {
+    if is_current_edit {
+        match crate::get_original_image(state) {
+            Ok((orig_data_arc, _)) => composite_patches_on_image(&orig_data_arc, js_adjustments)
+                .map_err(|e| format!("Failed to composite AI patches: {}", e)),
+            Err(_) => {
+                let bytes = fs::read(source_path_str).map_err(|e| e.to_string())?;
+                load_and_composite(
+                    &bytes,
+                    source_path_str,
+                    js_adjustments,
+                    false,
+                    settings,
+                    app_handle,
+                    None,
+                )
+                .map_err(|e| format!("Failed to load fallback image: {}", e))
+            }
+        }
+    } else {
+        match read_file_mapped(Path::new(source_path_str)) {
+            Ok(mmap) => load_and_composite(
+                &mmap,
+                source_path_str,
+                js_adjustments,
+                false,
+                settings,
+                app_handle,
+                None,
+            )
+            .map_err(|e| format!("Failed to load from mmap: {}", e)),
+            Err(_) => {
+                let bytes = fs::read(source_path_str).map_err(|e| e.to_string())?;
+                load_and_composite(
+                    &bytes,
+                    source_path_str,
+                    js_adjustments,
+                    false,
+                    settings,
+                    app_handle,
+                    None,
+                )
+                .map_err(|e| format!("Failed to load from bytes: {}", e))
+            }
+        }
+    }
+}
+
+fn export_linear_dng(
     base_image: &DynamicImage,
     js_adjustments: &Value,
+    source_path_str: &str,
+    is_raw: bool,
     export_settings: &ExportSettings,
-    context: &GpuContext,
-    state: &tauri::State<AppState>,
+) -> Result<Vec<u8>, String> {
+    let (transformed_image, _) =
+        apply_all_transformations(Cow::Borrowed(base_image), js_adjustments);
+    let mut image = transformed_image.into_owned();
+
+    if let Some(resize_opts) = &export_settings.resize {
+        let (current_w, current_h) = image.dimensions();
+        let (target_w, target_h) = calculate_resize_target(current_w, current_h, resize_opts);
+
+        if target_w != current_w || target_h != current_h {
+            image = image.resize(target_w, target_h, imageops::FilterType::Lanczos3);
+        }
+    }
+
+    encode_source_dng(&image, source_path_str, is_raw, export_settings)
+}
+
+/// Encodes undeveloped pixels as a linear DNG. Shared by exports and size estimates so both
+/// write the same data and metadata.
+fn encode_source_dng(
+    image: &DynamicImage,
+    source_path_str: &str,
     is_raw: bool,
-    app_handle: &tauri::AppHandle,
-) -> Result<DynamicImage, String> {
-    let processed_image = process_image_for_export_pipeline(
-        path,
-        base_image,
-        js_adjustments,
-        context,
-        state,
-        is_raw,
-        "process_image_for_export",
-        app_handle,
-    )?;
+    export_settings: &ExportSettings,
+) -> Result<Vec<u8>, String> {
+    let source_extension = Path::new(source_path_str)
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let source_is_linear = is_raw || matches!(source_extension.as_str(), "exr" | "hdr");
 
-    apply_export_resize_and_watermark(processed_image, export_settings)
+    let metadata = if export_settings.keep_metadata {
+        DngMetadata::from_source(source_path_str)
+    } else {
+        DngMetadata::default()
+    };
+
+    encode_linear_dng(image, source_is_linear, &metadata)
 }
 
 fn build_single_mask_adjustments(all: &AllAdjustments, mask_index: usize) -> AllAdjustments {
@@ -583,79 +1032,129 @@ fn encode_image_to_bytes(
     image: &DynamicImage,
     output_format: &str,
     jpeg_quality: u8,
+    color_space: OutputColorSpace,
 ) -> Result<Vec<u8>, String> {
     let mut image_bytes = Vec::new();
     let mut cursor = Cursor::new(&mut image_bytes);
+    let output_format = output_format.to_lowercase();
+
+    if output_format == "jxl" {
+        let (width, height) = image.dimensions();
+        let has_alpha = image.color().has_alpha();
+        let lossless = jpeg_quality == 100;
+        // Lossy JPEG XL stores absolute XYB, which the encoder derives from sRGB input.
+        let image = if lossless || color_space.is_srgb() {
+            Cow::Borrowed(image)
+        } else {
+            Cow::Owned(convert_to_srgb(image, color_space))
+        };
 
-    match output_format.to_lowercase().as_str() {
-        "jxl" => {
-            let (width, height) = image.dimensions();
-            let has_alpha = image.color().has_alpha();
-
-            let jxl_data = if jpeg_quality == 100 {
-                if has_alpha {
-                    let rgba = image.to_rgba8();
-                    LosslessConfig::new()
-                        .encode(rgba.as_raw(), width, height, PixelLayout::Rgba8)
-                        .map_err(|e| format!("Failed to encode lossless JXL: {}", e))?
-                } else {
-                    let rgb = image.to_rgb8();
-                    LosslessConfig::new()
-                        .encode(rgb.as_raw(), width, height, PixelLayout::Rgb8)
-                        .map_err(|e| format!("Failed to encode lossless JXL: {}", e))?
-                }
+        let jxl_data = if lossless {
+            if has_alpha {
+                let rgba = image.to_rgba8();
+                LosslessConfig::new()
+                    .encode(rgba.as_raw(), width, height, PixelLayout::Rgba8)
+                    .map_err(|e| format!("Failed to encode lossless JXL: {}", e))?
             } else {
-                let jxl_quality = calibrated_jxl_quality(jpeg_quality as f32);
-                let distance = quality_to_distance(jxl_quality);
-
-                if has_alpha {
-                    let rgba = image.to_rgba8();
-                    LossyConfig::new(distance)
-                        .encode(rgba.as_raw(), width, height, PixelLayout::Rgba8)
-                        .map_err(|e| format!("Failed to encode lossy JXL: {}", e))?
-                } else {
-                    let rgb = image.to_rgb8();
-                    LossyConfig::new(distance)
-                        .encode(rgb.as_raw(), width, height, PixelLayout::Rgb8)
-                        .map_err(|e| format!("Failed to encode lossy JXL: {}", e))?
-                }
-            };
+                let rgb = image.to_rgb8();
+                LosslessConfig::new()
+                    .encode(rgb.as_raw(), width, height, PixelLayout::Rgb8)
+                    .map_err(|e| format!("Failed to encode lossless JXL: {}", e))?
+            }
+        } else {
+            let jxl_quality = calibrated_jxl_quality(jpeg_quality as f32);
+            let distance = quality_to_distance(jxl_quality);
+
+            if has_alpha {
+                let rgba = image.to_rgba8();
+                LossyConfig::new(distance)
+                    .encode(rgba.as_raw(), width, height, PixelLayout::Rgba8)
+                    .map_err(|e| format!("Failed to encode lossy JXL: {}", e))?
+            } else {
+                let rgb = image.to_rgb8();
+                LossyConfig::new(distance)
+                    .encode(rgb.as_raw(), width, height, PixelLayout::Rgb8)
+                    .map_err(|e| format!("Failed to encode lossy JXL: {}", e))?
+            }
+        };
 
+        if color_space.is_srgb() {
             return Ok(jxl_data);
         }
+        return embed_jxl_colour_encoding(&jxl_data, color_space);
+    }
+
+    let icc_profile = (!color_space.is_srgb()).then(|| build_icc_profile(color_space));
+
+    match output_format.as_str() {
         "webp" => {
-            let encoder = webp::Encoder::from_image(image)
+            let (width, height) = image.dimensions();
+            let has_alpha = image.color().has_alpha();
+            let image_8bit = if has_alpha {
+                DynamicImage::ImageRgba8(image.to_rgba8())
+            } else {
+                DynamicImage::ImageRgb8(image.to_rgb8())
+            };
+            let encoder = webp::Encoder::from_image(&image_8bit)
                 .map_err(|_| "Failed to create WebP encoder".to_string())?;
             let webp_mem = encoder.encode(jpeg_quality as f32);
-            return Ok(webp_mem.to_vec());
+            return match icc_profile {
+                Some(profile) => embed_webp_icc(&webp_mem, &profile, width, height, has_alpha),
+                None => Ok(webp_mem.to_vec()),
+            };
         }
         "jpg" | "jpeg" => {
             let rgb_image = image.to_rgb8();
-            let encoder = JpegEncoder::new_with_quality(&mut cursor, jpeg_quality);
+            let mut encoder = JpegEncoder::new_with_quality(&mut cursor, jpeg_quality);
+            if let Some(profile) = icc_profile {
+                encoder
+                    .set_icc_profile(profile)
+                    .map_err(|e| e.to_string())?;
+            }
             rgb_image
                 .write_with_encoder(encoder)
                 .map_err(|e| e.to_string())?;
         }
         "png" => {
             let image_to_encode = if image.as_rgb32f().is_some() {
-                DynamicImage::ImageRgb16(image.to_rgb16())
+                Cow::Owned(DynamicImage::ImageRgb16(image.to_rgb16()))
             } else {
-                image.clone()
+                Cow::Borrowed(image)
             };
 
+            let mut encoder = PngEncoder::new(&mut cursor);
+            if let Some(profile) = icc_profile {
+                encoder
+                    .set_icc_profile(profile)
+                    .map_err(|e| e.to_string())?;
+            }
             image_to_encode
-                .write_to(&mut cursor, image::ImageFormat::Png)
+                .write_with_encoder(encoder)
                 .map_err(|e| e.to_string())?;
         }
         "tiff" => {
+            let mut encoder = TiffEncoder::new(&mut cursor);
+            if let Some(profile) = icc_profile {
+                encoder
+                    .set_icc_profile(profile)
+                    .map_err(|e| e.to_string())?;
+            }
             DynamicImage::ImageRgb16(image.to_rgb16())
-                .write_to(&mut cursor, image::ImageFormat::Tiff)
+                .write_with_encoder(encoder)
                 .map_err(|e| e.to_string())?;
         }
         "avif" => {
+            let encoder = if jpeg_quality == AVIF_DEFAULT_QUALITY {
+                AvifEncoder::new(&mut cursor)
+            } else {
+                AvifEncoder::new_with_speed_quality(&mut cursor, 4, jpeg_quality)
+            };
             image
-                .write_to(&mut cursor, image::ImageFormat::Avif)
+                .write_with_encoder(encoder)
                 .map_err(|e| e.to_string())?;
+            if !color_space.is_srgb() {
+                return embed_avif_colr(&image_bytes, &avif_colr_payload(color_space));
+            }
         }
         _ => return Err(format!("Unsupported file format: {}", output_format)),
     };
@@ -739,7 +1238,8 @@ fn export_masks_for_image(
             )?;
             ensure_export_not_cancelled(cancellation_token)?;
 
-            let with_options = apply_export_resize_and_watermark(processed, export_settings)?;
+            let with_options =
+                apply_export_resize_and_watermark(processed, export_settings, source_path_str)?;
             let (out_w, out_h) = with_options.dimensions();
 
             let alpha_resized = imageops::resize(
@@ -759,6 +1259,7 @@ fn export_masks_for_image(
                 &mask_image_path,
                 source_path_str,
                 export_settings,
+                None,
             )?;
             ensure_export_not_cancelled(cancellation_token)?;
 
@@ -844,6 +1345,43 @@ fn export_adjustments_as_lut(
     Ok(cube_lut)
 }
 
+/// Virtual copy number appended to each export's file name, so copies of the same source
+/// (explicit `vc=` paths or repeated entries) don't overwrite each other.
+pub(crate) fn virtual_copy_suffixes(paths: &[String]) -> Vec<Option<u32>> {
+    let mut base_path_counts: HashMap<String, u32> = HashMap::new();
+    paths
+        .iter()
+        .map(|path_str| {
+            let (source_path, _) = parse_virtual_path(path_str);
+            let count = base_path_counts
+                .entry(source_path.to_string_lossy().to_string())
+                .or_insert(0);
+            *count += 1;
+
+            let mut explicit_vc = None;
+            if let Some(idx) = path_str.rfind("vc=") {
+                let id_str = path_str[idx + 3..].split('&').next().unwrap_or("");
+                if let Ok(id) = id_str.parse::<u32>() {
+                    explicit_vc = Some(id);
+                }
+            }
+            if explicit_vc.is_none() {
+                let lower = path_str.to_lowercase();
+                if let Some(idx) = lower.rfind("_vc") {
+                    let id_str: String = lower[idx + 3..]
+                        .chars()
+                        .take_while(|c| c.is_ascii_digit())
+                        .collect();
+                    if let Ok(id) = id_str.parse::<u32>() {
+                        explicit_vc = Some(id);
+                    }
+                }
+            }
+            explicit_vc.or((*count > 1).then_some(*count - 1))
+        })
+        .collect()
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn export_images_impl(
     paths: Vec<String>,
@@ -852,17 +1390,78 @@ pub(crate) async fn export_images_impl(
     base_origin_folders: Vec<String>,
     export_settings: ExportSettings,
     output_format: String,
+    sync: Option<ExportSyncOptions>,
     adjustments_mode: ExportAdjustmentsMode,
     state: tauri::State<'_, AppState>,
     app_handle: tauri::AppHandle,
-    completion_tx: Option<tokio::sync::oneshot::Sender<Result<(), usize>>>,
+    completion_tx: Option<tokio::sync::oneshot::Sender<Result<(), Vec<ExportFailure>>>>,
 ) -> Result<(), String> {
-    let cancellation_token = register_export_task(&state.export_task_token)?;
-    let task_guard = ExportTaskGuard::with_app_handle(
-        Arc::clone(&state.export_task_token),
-        Arc::clone(&cancellation_token),
-        app_handle.clone(),
-    );
+    let variant = ExportVariant {
+        output_format,
+        sub_folder: None,
+        export_settings,
+    };
+    export_variants_impl(
+        paths,
+        output_folder_or_file,
+        is_explicit_file_path,
+        base_origin_folders,
+        vec![variant],
+        sync,
+        adjustments_mode,
+        state,
+        app_handle,
+        completion_tx,
+    )
+    .await
+}
+
+fn validate_variants(variants: &[ExportVariant]) -> Result<(), String> {
+    let mut destinations = HashSet::new();
+    for variant in variants {
+        let destination = (
+            variant.output_format.to_lowercase(),
+            variant.sub_folder_path(),
+            variant.filename_template(),
+            variant.export_settings.preserve_folders,
+        );
+        if !destinations.insert(destination) {
+            return Err(format!(
+                "Two export variants would write the same {} files. Give them different sub-folders or filename templates.",
+                variant.output_format.to_uppercase()
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn export_variants_impl(
+    paths: Vec<String>,
+    output_folder_or_file: String,
+    is_explicit_file_path: bool,
+    base_origin_folders: Vec<String>,
+    variants: Vec<ExportVariant>,
+    sync: Option<ExportSyncOptions>,
+    adjustments_mode: ExportAdjustmentsMode,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+    completion_tx: Option<tokio::sync::oneshot::Sender<Result<(), Vec<ExportFailure>>>>,
+) -> Result<(), String> {
+    if variants.is_empty() {
+        return Err("Nothing to export: no output variants were given.".to_string());
+    }
+    validate_variants(&variants)?;
+
+    let cancellation_token = state
+        .task_registry
+        .register(EXPORT_TASK)
+        .ok_or_else(|| "An export is already in progress.".to_string())?;
+    let task_guard = ExportTaskGuard {
+        registry: Arc::clone(&state.task_registry),
+        cancellation_token: Arc::clone(&cancellation_token),
+        app_handle: app_handle.clone(),
+    };
     tokio::time::sleep(std::time::Duration::from_millis(10)).await;
 
     if cancellation_token.load(Ordering::SeqCst) {
@@ -911,41 +1510,26 @@ pub(crate) async fn export_images_impl(
         let total_paths = paths.len();
         let settings = load_settings(app_handle.clone()).unwrap_or_default();
 
-        let mut base_path_counts: HashMap<String, usize> = HashMap::new();
-        let mut export_items = Vec::with_capacity(total_paths);
-
-        for (i, path_str) in paths.into_iter().enumerate() {
-            let (source_path, _) = parse_virtual_path(&path_str);
-            let source_str = source_path.to_string_lossy().to_string();
-            let count = base_path_counts.entry(source_str.clone()).or_insert(0);
-            *count += 1;
+        // Sync mode only makes sense when exporting into a folder we can keep a manifest in.
+        let sync = sync.filter(|_| !is_explicit_file_path && !output_folder_or_file.is_empty());
+        let previous_manifest = sync
+            .as_ref()
+            .map(|_| Arc::new(ExportManifest::load(output_folder_path)));
+        let watermark_files: Vec<&Path> = variants
+            .iter()
+            .filter_map(|variant| variant.export_settings.watermark.as_ref())
+            .filter(|watermark| !watermark.path.is_empty())
+            .map(|watermark| Path::new(&watermark.path))
+            .collect();
+        let settings_hash = hash_export_settings(&variants, &watermark_files);
 
-            let mut explicit_vc = None;
-            if let Some(idx) = path_str.rfind("vc=") {
-                let id_str = path_str[idx + 3..].split('&').next().unwrap_or("");
-                if let Ok(id) = id_str.parse::<u32>() {
-                    explicit_vc = Some(id);
-                }
-            }
-            if explicit_vc.is_none() {
-                let lower = path_str.to_lowercase();
-                if let Some(idx) = lower.rfind("_vc") {
-                    let id_str: String = lower[idx + 3..]
-                        .chars()
-                        .take_while(|c| c.is_ascii_digit())
-                        .collect();
-                    if let Ok(id) = id_str.parse::<u32>() {
-                        explicit_vc = Some(id);
-                    }
-                }
-            }
-            export_items.push((i, path_str, *count, explicit_vc));
-        }
+        let vc_suffixes = virtual_copy_suffixes(&paths);
+        let export_items: Vec<_> = paths.into_iter().zip(vc_suffixes).enumerate().collect();
 
         let semaphore = Arc::new(tokio::sync::Semaphore::new(num_threads));
         let mut join_handles = Vec::new();
 
-        for (global_index, image_path_str, appearance_count, explicit_vc) in export_items {
+        for (global_index, (image_path_str, vc_suffix)) in export_items {
             if cancellation_token.load(Ordering::SeqCst) {
                 break;
             }
@@ -960,12 +1544,14 @@ pub(crate) async fn export_images_impl(
             let progress_counter_clone = Arc::clone(&progress_counter);
             let output_folder_path = output_folder_path.to_path_buf();
             let base_origin_folders = base_origin_folders.clone();
-            let export_settings = export_settings.clone();
-            let output_format = output_format.clone();
+            let variants = variants.clone();
+            let previous_manifest = previous_manifest.clone();
+            let settings_hash = settings_hash.clone();
             let settings = settings.clone();
             let cancellation_token_clone = Arc::clone(&cancellation_token);
             let adjustments_mode = adjustments_mode.clone();
 
+            let handle_path = image_path_str.clone();
             let handle = tokio::task::spawn_blocking(move || {
                 ensure_export_not_cancelled(&cancellation_token_clone)?;
 
@@ -977,7 +1563,8 @@ pub(crate) async fn export_images_impl(
                     ExportAdjustmentsMode::UseSidecars { active_path, .. } => {
                         Some(&source_path_str) == active_path.as_ref()
                     }
-                    ExportAdjustmentsMode::GlobalOverride(_) => false,
+                    ExportAdjustmentsMode::GlobalOverride(_)
+                    | ExportAdjustmentsMode::PerImage(_) => false,
                 };
 
                 let mut js_adjustments = match &adjustments_mode {
@@ -995,6 +1582,12 @@ pub(crate) async fn export_images_impl(
                         }
                     }
                     ExportAdjustmentsMode::GlobalOverride(adj) => adj.clone(),
+                    ExportAdjustmentsMode::PerImage(adjustments) => adjustments
+                        .get(&image_path_str)
+                        .cloned()
+                        .unwrap_or_else(|| {
+                            crate::exif_processing::load_sidecar(&sidecar_path).adjustments
+                        }),
                 };
 
                 hydrate_adjustments(&state, &mut js_adjustments);
@@ -1002,171 +1595,275 @@ pub(crate) async fn export_images_impl(
                 let original_path = std::path::Path::new(&source_path_str);
                 let file_date = exif_processing::get_creation_date_from_path(original_path);
 
-                let filename_template = export_settings
-                    .filename_template
-                    .as_deref()
-                    .unwrap_or("{original_filename}_edited");
-
-                let mut new_stem = generate_filename_from_template(
-                    filename_template,
-                    original_path,
-                    global_index + 1,
-                    total_paths,
-                    &file_date,
-                );
-
-                if let Some(vc_id) = explicit_vc {
-                    new_stem = format!("{}_VC{:02}", new_stem, vc_id);
-                } else if appearance_count > 1 {
-                    new_stem = format!("{}_VC{:02}", new_stem, appearance_count - 1);
-                }
-
-                let new_filename = format!("{}.{}", new_stem, output_format);
-                let output_path = if is_explicit_file_path && total_paths == 1 {
-                    output_folder_path
-                } else if export_settings.preserve_folders {
-                    if let Some(rel_dir) = relative_export_dir_for_preserved_folders(
-                        source_path.as_path(),
-                        &base_origin_folders,
-                    ) {
-                        let full_dir = output_folder_path.join(rel_dir);
-                        if let Err(e) = std::fs::create_dir_all(&full_dir) {
-                            log::warn!("Failed to create export subdirectory: {}", e);
-                        }
-                        full_dir.join(&new_filename)
-                    } else {
-                        output_folder_path.join(&new_filename)
+                let manifest_entry = previous_manifest.as_ref().map(|_| {
+                    ManifestEntry::new(
+                        calculate_full_job_hash(&source_path_str, &js_adjustments),
+                        &settings_hash,
+                        &source_path,
+                    )
+                });
+                let previous_entry = previous_manifest
+                    .as_ref()
+                    .and_then(|manifest| manifest.entries.get(&image_path_str))
+                    .filter(|previous| {
+                        manifest_entry.as_ref().is_some_and(|current| {
+                            previous.is_up_to_date(current, &output_folder_path)
+                        })
+                    })
+                    .cloned();
+                let skipped = previous_entry.is_some();
+                let mut written_outputs: Vec<PathBuf> = Vec::new();
+
+                let result: Result<Vec<Option<u8>>, String> = (|| {
+                    if skipped {
+                        return Ok(Vec::new());
                     }
-                } else {
-                    output_folder_path.join(&new_filename)
-                };
-
-                let extension = output_format.to_lowercase();
+                    let single_output = is_explicit_file_path && total_paths == 1;
+                    let mut base_image: Option<DynamicImage> = None;
+                    let mut developed: HashMap<(bool, OutputColorSpace), DynamicImage> =
+                        HashMap::new();
+                    let mut hdr_developed: HashMap<(bool, OutputColorSpace), DynamicImage> =
+                        HashMap::new();
+                    let adjustments_for_export = |strip_masks: bool| {
+                        let mut adjustments = js_adjustments.clone();
+                        if strip_masks && let Some(obj) = adjustments.as_object_mut() {
+                            obj.insert("masks".to_string(), serde_json::json!([]));
+                        }
+                        adjustments
+                    };
+                    let mut chosen_qualities = Vec::with_capacity(variants.len());
 
-                let result: Result<(), String> = (|| {
-                    if extension == "cube" {
-                        let cube_bytes = export_adjustments_as_lut(
-                            &js_adjustments,
-                            &source_path_str,
-                            &context_clone,
-                            &state,
-                            &app_handle_clone,
-                            &cancellation_token_clone,
-                        )?;
+                    for variant in variants.iter() {
                         ensure_export_not_cancelled(&cancellation_token_clone)?;
-                        #[cfg(target_os = "android")]
-                        {
-                            let file_name = output_path
-                                .file_name()
-                                .and_then(|name| name.to_str())
-                                .ok_or_else(|| "Missing Android LUT file name".to_string())?;
-                            crate::android_integration::save_file_bytes_to_android_downloads(
-                                file_name,
-                                "application/octet-stream",
-                                &cube_bytes,
-                            )?;
+                        let export_settings = &variant.export_settings;
+                        let extension = variant.output_format.to_lowercase();
+
+                        let mut new_stem = generate_filename_from_template(
+                            variant.filename_template(),
+                            original_path,
+                            global_index + 1,
+                            total_paths,
+                            &file_date,
+                        );
+
+                        if let Some(vc_id) = vc_suffix {
+                            new_stem = format!("{}_VC{:02}", new_stem, vc_id);
                         }
-                        #[cfg(not(target_os = "android"))]
-                        fs::write(&output_path, cube_bytes).map_err(|e| e.to_string())?;
-                        ensure_export_not_cancelled(&cancellation_token_clone)?;
-                        return Ok(());
-                    }
 
-                    let base_image = if is_current_edit {
-                        match crate::get_original_image(&state) {
-                            Ok((orig_data_arc, _)) => {
-                                composite_patches_on_image(&orig_data_arc, &js_adjustments)
-                                    .map_err(|e| format!("Failed to composite AI patches: {}", e))?
-                            }
-                            Err(_) => {
-                                let bytes =
-                                    fs::read(&source_path_str).map_err(|e| e.to_string())?;
-                                load_and_composite(
-                                    &bytes,
-                                    &source_path_str,
-                                    &js_adjustments,
-                                    false,
-                                    &settings,
-                                    None,
+                        let new_filename = format!("{}.{}", new_stem, extension);
+                        let output_path = if single_output && variants.len() == 1 {
+                            output_folder_path.clone()
+                        } else {
+                            let mut dir = if single_output {
+                                output_folder_path
+                                    .parent()
+                                    .map(Path::to_path_buf)
+                                    .unwrap_or_default()
+                            } else {
+                                output_folder_path.clone()
+                            };
+                            if export_settings.preserve_folders
+                                && let Some(rel_dir) = relative_export_dir_for_preserved_folders(
+                                    source_path.as_path(),
+                                    &base_origin_folders,
                                 )
-                                .map_err(|e| format!("Failed to load fallback image: {}", e))?
+                            {
+                                dir = dir.join(rel_dir);
+                            }
+                            if let Some(sub_folder) = variant.sub_folder_path() {
+                                dir = dir.join(sub_folder);
+                            }
+                            if dir != output_folder_path
+                                && let Err(e) = std::fs::create_dir_all(&dir)
+                            {
+                                log::warn!("Failed to create export subdirectory: {}", e);
                             }
+                            dir.join(&new_filename)
+                        };
+
+                        if extension == "cube" {
+                            let cube_bytes = export_adjustments_as_lut(
+                                &js_adjustments,
+                                &source_path_str,
+                                &context_clone,
+                                &state,
+                                &app_handle_clone,
+                                &cancellation_token_clone,
+                            )?;
+                            ensure_export_not_cancelled(&cancellation_token_clone)?;
+                            #[cfg(target_os = "android")]
+                            {
+                                let file_name = output_path
+                                    .file_name()
+                                    .and_then(|name| name.to_str())
+                                    .ok_or_else(|| "Missing Android LUT file name".to_string())?;
+                                crate::android_integration::save_file_bytes_to_android_downloads(
+                                    file_name,
+                                    "application/octet-stream",
+                                    &cube_bytes,
+                                )?;
+                            }
+                            #[cfg(not(target_os = "android"))]
+                            fs::write(&output_path, cube_bytes).map_err(|e| e.to_string())?;
+                            written_outputs.push(output_path);
+                            continue;
                         }
-                    } else {
-                        match read_file_mapped(Path::new(&source_path_str)) {
-                            Ok(mmap) => load_and_composite(
-                                &mmap,
+
+                        if base_image.is_none() {
+                            base_image = Some(load_export_base_image(
+                                is_current_edit,
+                                &state,
                                 &source_path_str,
                                 &js_adjustments,
-                                false,
                                 &settings,
-                                None,
-                            )
-                            .map_err(|e| format!("Failed to load from mmap: {}", e))?,
-                            Err(_) => {
-                                let bytes =
-                                    fs::read(&source_path_str).map_err(|e| e.to_string())?;
-                                load_and_composite(
-                                    &bytes,
-                                    &source_path_str,
-                                    &js_adjustments,
-                                    false,
-                                    &settings,
-                                    None,
-                                )
-                                .map_err(|e| format!("Failed to load from bytes: {}", e))?
+                                &app_handle_clone,
+                            )?);
+                            ensure_export_not_cancelled(&cancellation_token_clone)?;
+                        }
+                        let base_image = base_image.as_ref().unwrap();
+
+                        if extension == "dng" {
+                            let dng_bytes = export_linear_dng(
+                                base_image,
+                                &js_adjustments,
+                                &source_path_str,
+                                is_raw,
+                                export_settings,
+                            )?;
+                            ensure_export_not_cancelled(&cancellation_token_clone)?;
+                            #[cfg(target_os = "android")]
+                            {
+                                let file_name = output_path
+                                    .file_name()
+                                    .and_then(|name| name.to_str())
+                                    .ok_or_else(|| "Missing Android DNG file name".to_string())?;
+                                crate::android_integration::save_image_bytes_to_android_gallery(
+                                    file_name,
+                                    mime_type_for_extension(&extension),
+                                    &dng_bytes,
+                                )?;
                             }
+                            #[cfg(not(target_os = "android"))]
+                            fs::write(&output_path, dng_bytes).map_err(|e| e.to_string())?;
+
+                            if export_settings.preserve_timestamps {
+                                set_timestamps_from_exif(Path::new(&source_path_str), &output_path);
+                            }
+                            written_outputs.push(output_path);
+                            continue;
                         }
-                    };
-                    ensure_export_not_cancelled(&cancellation_token_clone)?;
 
-                    let mut main_export_adjustments = js_adjustments.clone();
-                    if export_settings.export_masks
-                        && let Some(obj) = main_export_adjustments.as_object_mut()
-                    {
-                        obj.insert("masks".to_string(), serde_json::json!([]));
-                    }
+                        let strip_masks = export_settings.export_masks;
+                        let color_space = export_settings.output_color_space;
+                        let render_key = (strip_masks, color_space);
+                        if !developed.contains_key(&render_key) {
+                            let processed = process_image_for_export_pipeline(
+                                &source_path_str,
+                                base_image,
+                                &adjustments_for_export(strip_masks),
+                                &context_clone,
+                                &state,
+                                is_raw,
+                                color_space,
+                                "process_image_for_export",
+                                &app_handle_clone,
+                            )?;
+                            developed.insert(render_key, processed);
+                            ensure_export_not_cancelled(&cancellation_token_clone)?;
+                        }
 
-                    let final_image = process_image_for_export(
-                        &source_path_str,
-                        &base_image,
-                        &main_export_adjustments,
-                        &export_settings,
-                        &context_clone,
-                        &state,
-                        is_raw,
-                        &app_handle_clone,
-                    )?;
-                    ensure_export_not_cancelled(&cancellation_token_clone)?;
-                    save_image_with_metadata(
-                        &final_image,
-                        &output_path,
-                        &source_path_str,
-                        &export_settings,
-                    )?;
-                    ensure_export_not_cancelled(&cancellation_token_clone)?;
-
-                    if export_settings.preserve_timestamps {
-                        set_timestamps_from_exif(Path::new(&source_path_str), &output_path);
-                    }
-                    ensure_export_not_cancelled(&cancellation_token_clone)?;
+                        let final_image = apply_export_resize_and_watermark(
+                            developed[&render_key].clone(),
+                            export_settings,
+                            &source_path_str,
+                        )?;
+                        ensure_export_not_cancelled(&cancellation_token_clone)?;
+
+                        let gain_map_jpeg = match &export_settings.hdr_gain_map {
+                            Some(gain_map_settings)
+                                if matches!(extension.as_str(), "jpg" | "jpeg") =>
+                            {
+                                if !hdr_developed.contains_key(&render_key) {
+                                    let hdr_render = render_export_image(
+                                        &source_path_str,
+                                        base_image,
+                                        &adjustments_for_export(strip_masks),
+                                        &context_clone,
+                                        &state,
+                                        is_raw,
+                                        color_space,
+                                        "process_image_for_export_hdr",
+                                        Some(HDR_RENDER_TONEMAPPER),
+                                    )?;
+                                    hdr_developed.insert(render_key, hdr_render);
+                                    ensure_export_not_cancelled(&cancellation_token_clone)?;
+                                }
+                                match compute_gain_map(
+                                    &final_image,
+                                    &hdr_developed[&render_key],
+                                    gain_map_settings,
+                                ) {
+                                    Some(gain_map) => Some(encode_gain_map_jpeg(&gain_map)?),
+                                    None => {
+                                        log::info!(
+                                            "'{}' has no highlights above SDR white; writing a plain JPEG",
+                                            source_path_str
+                                        );
+                                        None
+                                    }
+                                }
+                            }
+                            Some(_) => {
+                                log::warn!(
+                                    "HDR gain maps are only written for JPEG; '{}' is exported as SDR {}",
+                                    source_path_str,
+                                    extension
+                                );
+                                None
+                            }
+                            None => None,
+                        };
 
-                    if export_settings.export_masks {
-                        export_masks_for_image(
-                            &base_image,
-                            &js_adjustments,
-                            &export_settings,
+                        let variant_quality = save_image_with_metadata(
+                            &final_image,
                             &output_path,
                             &source_path_str,
-                            &context_clone,
-                            &state,
-                            is_raw,
-                            &app_handle_clone,
-                            &cancellation_token_clone,
+                            export_settings,
+                            gain_map_jpeg.as_deref(),
                         )?;
+                        if let Some(quality) = variant_quality {
+                            log::info!(
+                                "Exported '{}' at quality {} to stay under the file size limit",
+                                output_path.display(),
+                                quality
+                            );
+                        }
+                        chosen_qualities.push(variant_quality);
+                        ensure_export_not_cancelled(&cancellation_token_clone)?;
+
+                        if export_settings.preserve_timestamps {
+                            set_timestamps_from_exif(Path::new(&source_path_str), &output_path);
+                        }
+                        ensure_export_not_cancelled(&cancellation_token_clone)?;
+
+                        if export_settings.export_masks {
+                            export_masks_for_image(
+                                base_image,
+                                &js_adjustments,
+                                export_settings,
+                                &output_path,
+                                &source_path_str,
+                                &context_clone,
+                                &state,
+                                is_raw,
+                                &app_handle_clone,
+                                &cancellation_token_clone,
+                            )?;
+                        }
+                        written_outputs.push(output_path);
                     }
 
-                    Ok(())
+                    Ok(chosen_qualities)
                 })();
 
                 if !cancellation_token_clone.load(Ordering::SeqCst) {
@@ -1177,35 +1874,75 @@ pub(crate) async fn export_images_impl(
                         serde_json::json!({
                             "current": current_progress,
                             "total": total_paths,
-                            "path": &image_path_str
+                            "path": &image_path_str,
+                            "qualities": result.as_deref().unwrap_or_default(),
+                            "skipped": skipped
                         }),
                     );
                 }
 
                 drop(permit);
                 if cancellation_token_clone.load(Ordering::SeqCst) {
-                    Err("Export cancelled".to_string())
+                    Err(EXPORT_CANCELLED.to_string())
                 } else {
-                    result
+                    result.map(|_| {
+                        let entry = previous_entry.or_else(|| {
+                            manifest_entry.map(|current| {
+                                current.with_outputs(&written_outputs, &output_folder_path)
+                            })
+                        });
+                        ExportedImage {
+                            path: image_path_str,
+                            skipped,
+                            manifest_entry: entry,
+                        }
+                    })
                 }
             });
 
-            join_handles.push(handle);
+            join_handles.push((handle_path, handle));
         }
 
-        let mut results = Vec::new();
-        for handle in join_handles {
+        let mut errors = Vec::new();
+        let mut exported = Vec::new();
+        for (path, handle) in join_handles {
             match handle.await {
-                Ok(res) => results.push(res),
-                Err(e) => results.push(Err(format!("Thread crashed: {}", e))),
+                Ok(Ok(image)) => exported.push(image),
+                Ok(Err(error)) => errors.push(ExportFailure { path, error }),
+                Err(e) => errors.push(ExportFailure {
+                    path,
+                    error: format!("Thread crashed: {}", e),
+                }),
             }
         }
-
-        let errors: Vec<String> = results.into_iter().filter_map(Result::err).collect();
         let error_count = errors.len();
+
+        if let (Some(sync), Some(previous_manifest)) = (&sync, previous_manifest) {
+            let mut manifest = Arc::unwrap_or_clone(previous_manifest);
+            let skipped_count = exported.iter().filter(|image| image.skipped).count();
+            for image in exported {
+                if let Some(entry) = image.manifest_entry {
+                    manifest.entries.insert(image.path, entry);
+                }
+            }
+            if sync.delete_removed {
+                let deleted = manifest.prune_removed_sources(output_folder_path);
+                if deleted > 0 {
+                    log::info!("Sync export removed {} orphaned outputs", deleted);
+                }
+            }
+            log::info!(
+                "Sync export skipped {} unchanged of {} images",
+                skipped_count,
+                total_paths
+            );
+            if let Err(e) = manifest.save(output_folder_path) {
+                log::warn!("Failed to write export manifest: {}", e);
+            }
+        }
         let export_state = app_handle.state::<AppState>();
-        let finalized = finish_export_task(
-            &export_state.export_task_token,
+        let finalized = export_state.task_registry.finish(
+            EXPORT_TASK,
             &cancellation_token,
             |cancelled| {
                 if cancelled {
@@ -1214,10 +1951,10 @@ pub(crate) async fn export_images_impl(
                     return;
                 }
 
-                for error in &errors {
-                    log::error!("Export error: {}", error);
+                for failure in &errors {
+                    log::error!("Export error: {}", failure.error);
                     if total_paths == 1 {
-                        let _ = app_handle.emit("export-error", error.clone());
+                        let _ = app_handle.emit("export-error", failure.error.clone());
                     }
                 }
 
@@ -1242,7 +1979,7 @@ pub(crate) async fn export_images_impl(
 
         if let Some(tx) = completion_tx {
             if error_count > 0 {
-                let _ = tx.send(Err(error_count));
+                let _ = tx.send(Err(errors));
             } else {
                 let _ = tx.send(Ok(()));
             }
@@ -1263,6 +2000,7 @@ pub async fn export_images(
     output_format: String,
     current_edit_path: Option<String>,
     current_edit_adjustments: Option<Value>,
+    sync_options: Option<ExportSyncOptions>,
     state: tauri::State<'_, AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
@@ -1273,6 +2011,50 @@ pub async fn export_images(
         base_origin_folders,
         export_settings,
         output_format,
+        sync_options,
+        ExportAdjustmentsMode::UseSidecars {
+            active_path: current_edit_path,
+            active_adjustments: current_edit_adjustments,
+        },
+        state,
+        app_handle,
+        None,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn export_images_with_recipe(
+    paths: Vec<String>,
+    output_folder: String,
+    base_origin_folders: Vec<String>,
+    recipe: ExportRecipe,
+    current_edit_path: Option<String>,
+    current_edit_adjustments: Option<Value>,
+    sync_options: Option<ExportSyncOptions>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    log::info!(
+        "Exporting {} images with recipe '{}' ({} variants)",
+        paths.len(),
+        recipe.name,
+        recipe.variants.len()
+    );
+    let variants = recipe
+        .variants
+        .iter()
+        .map(|variant| ExportVariant::from_preset(&variant.preset, variant.sub_folder.clone()))
+        .collect();
+
+    export_variants_impl(
+        paths,
+        output_folder,
+        false,
+        base_origin_folders,
+        variants,
+        sync_options,
         ExportAdjustmentsMode::UseSidecars {
             active_path: current_edit_path,
             active_adjustments: current_edit_adjustments,
@@ -1284,6 +2066,146 @@ pub async fn export_images(
     .await
 }
 
+pub(crate) fn find_export_preset<'a>(
+    settings: &'a AppSettings,
+    name: &str,
+) -> Result<&'a ExportPreset, String> {
+    settings
+        .export_presets
+        .iter()
+        .find(|preset| preset.id == name || preset.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            let available: Vec<&str> = settings
+                .export_presets
+                .iter()
+                .filter(|preset| !preset.id.starts_with("__"))
+                .map(|preset| preset.name.as_str())
+                .collect();
+            format!(
+                "Export preset '{}' not found. Available presets: {}",
+                name,
+                available.join(", ")
+            )
+        })
+}
+
+/// Starts from the named preset (or the headless defaults) and applies the CLI overrides on top.
+fn headless_export_variant(
+    session: &crate::launch_request::HeadlessExportSession,
+    app_handle: &tauri::AppHandle,
+) -> Result<ExportVariant, String> {
+    let mut variant = match &session.preset {
+        Some(name) => {
+            let settings = load_settings(app_handle.clone())?;
+            let preset = find_export_preset(&settings, name)?;
+            println!("Using export preset '{}'", preset.name);
+            ExportVariant::from_preset(preset, None)
+        }
+        None => ExportVariant {
+            output_format: "jpeg".to_string(),
+            sub_folder: None,
+            export_settings: ExportSettings {
+                jpeg_quality: 90,
+                resize: None,
+                keep_metadata: false,
+                preserve_timestamps: true,
+                strip_gps: false,
+                filename_template: None,
+                watermark: None,
+                export_masks: false,
+                preserve_folders: true,
+                output_color_space: OutputColorSpace::Srgb,
+                output_sharpening: None,
+                max_file_size_bytes: None,
+                hdr_gain_map: None,
+            },
+        },
+    };
+
+    if let Some(format) = &session.format {
+        variant.output_format = format.clone();
+    }
+    let settings = &mut variant.export_settings;
+    if let Some(quality) = session.quality {
+        settings.jpeg_quality = quality;
+    }
+    if let Some(keep_metadata) = session.keep_metadata {
+        settings.keep_metadata = keep_metadata;
+    }
+    if let Some(strip_gps) = session.strip_gps {
+        settings.strip_gps = strip_gps;
+    }
+    if let Some(template) = &session.filename_template {
+        settings.filename_template = Some(template.clone());
+    }
+    if let Some(preserve_folders) = session.preserve_folders {
+        settings.preserve_folders = preserve_folders;
+    }
+    if let Some(export_masks) = session.export_masks {
+        settings.export_masks = export_masks;
+    }
+
+    if let Some(value) = session.resize {
+        settings.resize = Some(ResizeOptions {
+            mode: session
+                .resize_mode
+                .clone()
+                .or_else(|| settings.resize.as_ref().map(|r| r.mode.clone()))
+                .unwrap_or(ResizeMode::LongEdge),
+            value,
+            dont_enlarge: !session.allow_enlarge,
+        });
+    } else if let Some(resize) = settings.resize.as_mut() {
+        if let Some(mode) = &session.resize_mode {
+            resize.mode = mode.clone();
+        }
+        if session.allow_enlarge {
+            resize.dont_enlarge = false;
+        }
+    }
+
+    if session.watermark_image.is_some() || session.watermark_text.is_some() {
+        let placement = settings
+            .watermark
+            .take()
+            .unwrap_or_else(|| WatermarkSettings {
+                path: String::new(),
+                anchor: WatermarkAnchor::BottomRight,
+                scale: 10.0,
+                spacing: 5.0,
+                opacity: 75.0,
+                text: None,
+            });
+        settings.watermark = Some(match &session.watermark_text {
+            Some(text) => {
+                let style = placement
+                    .text
+                    .clone()
+                    .unwrap_or_else(|| TextWatermarkSettings {
+                        text: String::new(),
+                        font_family: None,
+                        font_size: 3.0,
+                        color: "#ffffff".to_string(),
+                    });
+                WatermarkSettings {
+                    text: Some(TextWatermarkSettings {
+                        text: text.clone(),
+                        ..style
+                    }),
+                    ..placement
+                }
+            }
+            None => WatermarkSettings {
+                path: session.watermark_image.clone().unwrap_or_default(),
+                text: None,
+                ..placement
+            },
+        });
+    }
+
+    Ok(variant)
+}
+
 pub async fn run_headless_export(
     session: crate::launch_request::HeadlessExportSession,
     app_handle: tauri::AppHandle,
@@ -1312,8 +2234,10 @@ pub async fn run_headless_export(
     }
 
     let output_path = std::path::Path::new(&session.output);
-    let is_explicit_file_path =
-        paths.len() == 1 && output_path.extension().is_some() && !output_path.is_dir();
+    let is_explicit_file_path = !session.sync
+        && paths.len() == 1
+        && output_path.extension().is_some()
+        && !output_path.is_dir();
 
     if is_explicit_file_path {
         if let Some(parent) = output_path.parent() {
@@ -1327,17 +2251,7 @@ pub async fn run_headless_export(
 
     println!("Found {} images to export. Processing...", paths.len());
 
-    let export_settings = ExportSettings {
-        jpeg_quality: session.quality,
-        resize: None,
-        keep_metadata: session.keep_metadata,
-        preserve_timestamps: true,
-        strip_gps: false,
-        filename_template: None,
-        watermark: None,
-        export_masks: false,
-        preserve_folders: true,
-    };
+    let variant = headless_export_variant(&session, &app_handle)?;
 
     let mut custom_adjustments = None;
     if let Some(adj_path) = &session.adjustments_override {
@@ -1368,8 +2282,11 @@ pub async fn run_headless_export(
         session.output,
         is_explicit_file_path,
         vec![session.source],
-        export_settings,
-        session.format,
+        variant.export_settings,
+        variant.output_format,
+        session.sync.then(|| ExportSyncOptions {
+            delete_removed: session.delete_removed,
+        }),
         mode,
         state.clone(),
         app_handle.clone(),
@@ -1379,7 +2296,7 @@ pub async fn run_headless_export(
 
     match rx.await {
         Ok(Ok(())) => Ok(()),
-        Ok(Err(errors)) => Err(format!("Export completed with {} errors.", errors)),
+        Ok(Err(errors)) => Err(format!("Export completed with {} errors.", errors.len())),
         Err(_) => Err("Export task panicked or was cancelled.".to_string()),
     }
 }
@@ -1389,16 +2306,16 @@ pub fn cancel_export(
     state: tauri::State<AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    match request_export_cancellation(&state.export_task_token, || {
+    match state.task_registry.request_cancellation(EXPORT_TASK, || {
         let _ = app_handle.emit("export-cancelling", ());
     }) {
-        ExportCancellationRequest::Requested => {
+        CancellationRequest::Requested => {
             log::info!("Export cancellation requested; workers will stop at the next checkpoint");
         }
-        ExportCancellationRequest::AlreadyRequested => {
+        CancellationRequest::AlreadyRequested => {
             log::info!("Export cancellation was already requested");
         }
-        ExportCancellationRequest::NoActiveTask => {
+        CancellationRequest::NoActiveTask => {
             return Err("No export task is currently running.".to_string());
         }
     }
@@ -1502,6 +2419,10 @@ pub async fn estimate_export_sizes(
         let mut all_adjustments =
             get_all_adjustments_from_json(&adjustments_clone, is_raw, tm_override);
         all_adjustments.global.show_clipping = 0;
+        configure_render(
+            &mut all_adjustments.global,
+            export_settings.output_color_space,
+        );
 
         let lut = adjustments_clone["lutPath"]
             .as_str()
@@ -1523,11 +2444,16 @@ pub async fn estimate_export_sizes(
             "estimate_export_size",
         )?;
 
-        let preview_bytes = encode_image_to_bytes(
-            &processed_preview,
-            &output_format,
-            export_settings.jpeg_quality,
-        )?;
+        let preview_bytes = if output_format.eq_ignore_ascii_case("dng") {
+            encode_source_dng(&preview_image, &source_path_str, is_raw, &export_settings)?
+        } else {
+            encode_image_to_bytes(
+                &processed_preview,
+                &output_format,
+                export_quality(&output_format, &export_settings),
+                export_settings.output_color_space,
+            )?
+        };
         let preview_byte_size = preview_bytes.len();
 
         let (transformed_full_res, _) =
@@ -1568,9 +2494,15 @@ pub async fn estimate_export_sizes(
             }
         };
 
-        let original_image =
-            load_base_image_from_bytes(file_data, &source_path_str, true, &settings, None)
-                .map_err(|e| e.to_string())?;
+        let original_image = load_base_image_from_bytes(
+            file_data,
+            &source_path_str,
+            true,
+            &settings,
+            &app_handle,
+            None,
+        )
+        .map_err(|e| e.to_string())?;
 
         let raw_scale_factor = if is_raw {
             crate::raw_processing::get_fast_demosaic_scale_factor(
@@ -1640,6 +2572,10 @@ pub async fn estimate_export_sizes(
         let mut all_adjustments =
             get_all_adjustments_from_json(&js_adjustments, is_raw, tm_override);
         all_adjustments.global.show_clipping = 0;
+        configure_render(
+            &mut all_adjustments.global,
+            export_settings.output_color_space,
+        );
 
         let lut = js_adjustments["lutPath"]
             .as_str()
@@ -1661,11 +2597,16 @@ pub async fn estimate_export_sizes(
             "estimate_batch_export_size",
         )?;
 
-        let preview_bytes = encode_image_to_bytes(
-            &processed_preview,
-            &output_format,
-            export_settings.jpeg_quality,
-        )?;
+        let preview_bytes = if output_format.eq_ignore_ascii_case("dng") {
+            encode_source_dng(&preview_base, &source_path_str, is_raw, &export_settings)?
+        } else {
+            encode_image_to_bytes(
+                &processed_preview,
+                &output_format,
+                export_quality(&output_format, &export_settings),
+                export_settings.output_color_space,
+            )?
+        };
         let single_image_estimated_size = preview_bytes.len();
 
         let full_w = (shrunk_w as f32 / raw_scale_factor).round() as u32;
@@ -1688,5 +2629,12 @@ pub async fn estimate_export_sizes(
         (single_image_estimated_size as f64 * pixel_ratio) as usize
     };
 
+    let single_image_extrapolated_size = match export_settings.max_file_size_bytes {
+        Some(max_bytes) if supports_quality_search(&output_format.to_lowercase()) => {
+            single_image_extrapolated_size.min(max_bytes as usize)
+        }
+        _ => single_image_extrapolated_size,
+    };
+
     Ok(single_image_extrapolated_size * paths.len())
 }
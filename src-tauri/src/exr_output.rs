@@ -0,0 +1,192 @@
+use std::path::{Path, PathBuf};
+
+use exr::prelude::{Encoding, Image, SpecificChannels, Vec2, WritableImage, f16};
+use image::DynamicImage;
+use rayon::prelude::*;
+
+use crate::app_settings::AppSettings;
+use crate::dng_writer::{DngMetadata, encode_linear_dng};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExrPrecision {
+    Half,
+    Float,
+}
+
+impl ExrPrecision {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        match settings.merge_exr_precision.as_deref() {
+            Some("float") => ExrPrecision::Float,
+            _ => ExrPrecision::Half,
+        }
+    }
+}
+
+pub fn scene_referred_merges_enabled(settings: &AppSettings) -> bool {
+    settings.scene_referred_merges.unwrap_or(false)
+}
+
+pub fn dng_merges_enabled(settings: &AppSettings) -> bool {
+    settings.merge_dng_output.unwrap_or(false)
+}
+
+/// Writes a merged result as a linear DNG carrying the EXIF of `source_path_str`, usually the
+/// first image of the merge.
+pub fn write_merged_dng(
+    image: &DynamicImage,
+    output_path: &Path,
+    source_path_str: &str,
+    settings: &AppSettings,
+) -> Result<(), String> {
+    let metadata = DngMetadata::from_source(source_path_str);
+    let dng_bytes = encode_linear_dng(image, scene_referred_merges_enabled(settings), &metadata)?;
+    std::fs::write(output_path, dng_bytes)
+        .map_err(|e| format!("Failed to save {}: {}", output_path.display(), e))
+}
+
+/// Saves a merged result (HDR, panorama) as `<stem>.dng` when DNG merges are on, as
+/// `<stem>.exr` when scene-referred merges are on, and otherwise as a 32-bit TIFF or an 8-bit
+/// PNG depending on the pixel data.
+pub fn save_merged_image(
+    image: DynamicImage,
+    output_dir: &Path,
+    stem: &str,
+    source_path_str: &str,
+    settings: &AppSettings,
+) -> Result<PathBuf, String> {
+    if dng_merges_enabled(settings) {
+        let output_path = output_dir.join(format!("{}.dng", stem));
+        write_merged_dng(&image, &output_path, source_path_str, settings)?;
+        return Ok(output_path);
+    }
+
+    if scene_referred_merges_enabled(settings) {
+        let output_path = output_dir.join(format!("{}.exr", stem));
+        write_scene_referred_exr(&image, &output_path, ExrPrecision::from_settings(settings))?;
+        return Ok(output_path);
+    }
+
+    let (output_filename, image_to_save) = if image.color().has_alpha() {
+        (
+            format!("{}.png", stem),
+            DynamicImage::ImageRgba8(image.to_rgba8()),
+        )
+    } else if image.as_rgb32f().is_some() {
+        (format!("{}.tiff", stem), image)
+    } else {
+        (
+            format!("{}.png", stem),
+            DynamicImage::ImageRgb8(image.to_rgb8()),
+        )
+    };
+
+    let output_path = output_dir.join(output_filename);
+    image_to_save
+        .save(&output_path)
+        .map_err(|e| format!("Failed to save {}: {}", output_path.display(), e))?;
+    Ok(output_path)
+}
+
+/// Scales linear radiance so the median luminance lands on middle grey. Ratios between pixels,
+/// and therefore the full dynamic range of the merge, are left untouched.
+pub fn normalize_scene_exposure(image: DynamicImage) -> DynamicImage {
+    const MIDDLE_GREY: f32 = 0.18;
+    const MAX_SAMPLES: usize = 1 << 18;
+
+    let mut rgb = image.to_rgb32f();
+    let pixel_count = (rgb.width() * rgb.height()) as usize;
+    let step = (pixel_count / MAX_SAMPLES).max(1);
+
+    let mut luminances: Vec<f32> = rgb
+        .as_raw()
+        .par_chunks(3)
+        .step_by(step)
+        .map(|p| 0.2126 * p[0] + 0.7152 * p[1] + 0.0722 * p[2])
+        .filter(|l| l.is_finite() && *l > 0.0)
+        .collect();
+    if luminances.is_empty() {
+        return DynamicImage::ImageRgb32F(rgb);
+    }
+
+    let mid = luminances.len() / 2;
+    let (_, median, _) = luminances.select_nth_unstable_by(mid, f32::total_cmp);
+    let scale = MIDDLE_GREY / *median;
+
+    rgb.par_iter_mut().for_each(|v| {
+        *v = if v.is_finite() {
+            v.max(0.0) * scale
+        } else {
+            0.0
+        };
+    });
+    DynamicImage::ImageRgb32F(rgb)
+}
+
+/// Writes linear, scene-referred pixel data to OpenEXR without clamping highlights.
+pub fn write_scene_referred_exr(
+    image: &DynamicImage,
+    output_path: &Path,
+    precision: ExrPrecision,
+) -> Result<(), String> {
+    let size = Vec2(image.width() as usize, image.height() as usize);
+    let has_alpha = image.color().has_alpha();
+
+    let result = if has_alpha {
+        let rgba = image.to_rgba32f();
+        let pixel = move |pos: Vec2<usize>| rgba.get_pixel(pos.x() as u32, pos.y() as u32).0;
+        match precision {
+            ExrPrecision::Half => Image::from_encoded_channels(
+                size,
+                Encoding::FAST_LOSSLESS,
+                SpecificChannels::rgba(|pos: Vec2<usize>| {
+                    let [r, g, b, a] = pixel(pos);
+                    (
+                        f16::from_f32(r),
+                        f16::from_f32(g),
+                        f16::from_f32(b),
+                        f16::from_f32(a),
+                    )
+                }),
+            )
+            .write()
+            .to_file(output_path),
+            ExrPrecision::Float => Image::from_encoded_channels(
+                size,
+                Encoding::FAST_LOSSLESS,
+                SpecificChannels::rgba(|pos: Vec2<usize>| {
+                    let [r, g, b, a] = pixel(pos);
+                    (r, g, b, a)
+                }),
+            )
+            .write()
+            .to_file(output_path),
+        }
+    } else {
+        let rgb = image.to_rgb32f();
+        let pixel = move |pos: Vec2<usize>| rgb.get_pixel(pos.x() as u32, pos.y() as u32).0;
+        match precision {
+            ExrPrecision::Half => Image::from_encoded_channels(
+                size,
+                Encoding::FAST_LOSSLESS,
+                SpecificChannels::rgb(|pos: Vec2<usize>| {
+                    let [r, g, b] = pixel(pos);
+                    (f16::from_f32(r), f16::from_f32(g), f16::from_f32(b))
+                }),
+            )
+            .write()
+            .to_file(output_path),
+            ExrPrecision::Float => Image::from_encoded_channels(
+                size,
+                Encoding::FAST_LOSSLESS,
+                SpecificChannels::rgb(|pos: Vec2<usize>| {
+                    let [r, g, b] = pixel(pos);
+                    (r, g, b)
+                }),
+            )
+            .write()
+            .to_file(output_path),
+        }
+    };
+
+    result.map_err(|e| format!("Failed to write OpenEXR file: {}", e))
+}
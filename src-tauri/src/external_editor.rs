@@ -0,0 +1,156 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime};
+
+use image::DynamicImage;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use crate::app_settings::{ExternalEditor, load_settings};
+use crate::exif_processing::write_rrexif_sidecar;
+use crate::export_processing::develop_image;
+use crate::file_management::parse_virtual_path;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Watching stops once the file has not been saved for this long.
+const WATCH_TIMEOUT: Duration = Duration::from_secs(6 * 60 * 60);
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ExternalEditUpdate {
+    source_path: String,
+    path: String,
+}
+
+/// Prefers `<stem>.tiff` so the library groups the edit with its original, and never overwrites
+/// an existing file.
+fn round_trip_path(source_path: &Path) -> PathBuf {
+    let parent = source_path.parent().unwrap_or(Path::new(""));
+    let stem = source_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    [format!("{}.tiff", stem), format!("{}.tif", stem)]
+        .into_iter()
+        .chain((1..).map(|n| match n {
+            1 => format!("{}-edit.tiff", stem),
+            n => format!("{}-edit-{}.tiff", stem, n),
+        }))
+        .map(|name| parent.join(name))
+        .find(|path| !path.exists())
+        .expect("round trip names are unbounded")
+}
+
+fn launch_editor(editor: &ExternalEditor, file: &Path) -> Result<(), String> {
+    if editor.command.trim().is_empty() {
+        return Err(format!("No command is set for {}", editor.name));
+    }
+    let file = file.to_string_lossy();
+    let mut args: Vec<String> = editor
+        .args
+        .iter()
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| arg.replace("{file}", &file))
+        .collect();
+    if !editor.args.iter().any(|arg| arg.contains("{file}")) {
+        args.push(file.into_owned());
+    }
+
+    Command::new(editor.command.trim())
+        .args(&args)
+        .spawn()
+        .map_err(|e| format!("Failed to launch {}: {}", editor.name, e))?;
+    Ok(())
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Polls the round-trip file and reports every save. A save counts once the modification time
+/// is unchanged between two polls, so a file that is still being written is not picked up.
+async fn watch_round_trip(source_path: String, path: PathBuf, app_handle: AppHandle) {
+    let mut last_seen = modified_time(&path);
+    let mut pending: Option<SystemTime> = None;
+    let mut last_change = Instant::now();
+
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        // Editors that save through a temporary file briefly remove the original.
+        let current = modified_time(&path);
+        if current.is_none() || current == last_seen {
+            if last_change.elapsed() > WATCH_TIMEOUT {
+                log::info!("Stopped watching {}", path.display());
+                return;
+            }
+            continue;
+        }
+        if pending != current {
+            pending = current;
+            continue;
+        }
+
+        last_seen = current;
+        pending = None;
+        last_change = Instant::now();
+        log::info!("External edit saved: {}", path.display());
+        let _ = app_handle.emit(
+            "external-edit-updated",
+            ExternalEditUpdate {
+                source_path: source_path.clone(),
+                path: path.to_string_lossy().into_owned(),
+            },
+        );
+    }
+}
+
+/// Renders the edit to a 16-bit TIFF next to the original, opens it in the editor and keeps
+/// watching it, so every save in the editor shows up in the library. Returns the TIFF path.
+#[tauri::command]
+pub async fn edit_in_external_editor(
+    path: String,
+    editor_id: String,
+    adjustments: Value,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    let editor = settings
+        .external_editors
+        .iter()
+        .find(|editor| editor.id == editor_id || editor.name.eq_ignore_ascii_case(&editor_id))
+        .cloned()
+        .ok_or_else(|| format!("Unknown external editor '{}'", editor_id))?;
+
+    let (source_path, _) = parse_virtual_path(&path);
+    let source = source_path.to_string_lossy().into_owned();
+    let output_path = round_trip_path(&source_path);
+
+    let app = app_handle.clone();
+    let render_path = output_path.clone();
+    tauri::async_runtime::spawn_blocking(move || -> Result<(), String> {
+        let developed = develop_image(&path, &adjustments, &app)?;
+        DynamicImage::ImageRgb16(developed.to_rgb16())
+            .save(&render_path)
+            .map_err(|e| format!("Failed to save {}: {}", render_path.display(), e))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))??;
+
+    if let Err(e) = launch_editor(&editor, &output_path) {
+        let _ = fs::remove_file(&output_path);
+        return Err(e);
+    }
+    let _ = write_rrexif_sidecar(&source, &output_path);
+    log::info!("Opened {} in {}", output_path.display(), editor.name);
+
+    tauri::async_runtime::spawn(watch_round_trip(
+        source,
+        output_path.clone(),
+        app_handle.clone(),
+    ));
+    Ok(output_path.to_string_lossy().into_owned())
+}
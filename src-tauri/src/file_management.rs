@@ -21,7 +21,7 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sysinfo::Disks;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 use uuid::Uuid;
 use walkdir::WalkDir;
 
@@ -42,6 +42,7 @@ use crate::image_processing::{
 };
 use crate::mask_generation::MaskDefinition;
 use crate::preset_converter;
+use crate::relative_adjustments::RelativeAdjustments;
 use crate::tagging::COLOR_TAG_PREFIX;
 
 fn resolve_thumbnail_cache_dir(app_handle: &AppHandle) -> std::result::Result<PathBuf, String> {
@@ -128,8 +129,8 @@ fn emit_image_metadata_loaded(
     );
 }
 
-fn enqueue_metadata(
-    app_handle: &AppHandle,
+fn enqueue_metadata<R: Runtime>(
+    app_handle: &AppHandle<R>,
     virtual_path: String,
     image_path: PathBuf,
     sidecar_path: PathBuf,
@@ -218,6 +219,62 @@ pub struct Preset {
     pub preset_type: Option<String>,
 }
 
+/// The crop, transform and lens keys the presets panel groups under "geometry".
+const PRESET_GEOMETRY_KEYS: &[&str] = &[
+    "crop",
+    "aspectRatio",
+    "rotation",
+    "flipHorizontal",
+    "flipVertical",
+    "orientationSteps",
+    "transformDistortion",
+    "transformVertical",
+    "transformHorizontal",
+    "transformRotate",
+    "transformAspect",
+    "transformScale",
+    "transformXOffset",
+    "transformYOffset",
+    "lensCorrectionMode",
+    "lensMaker",
+    "lensModel",
+    "lensDistortionAmount",
+    "lensVignetteAmount",
+    "lensTcaAmount",
+    "lensDistortionEnabled",
+    "lensTcaEnabled",
+    "lensVignetteEnabled",
+];
+
+impl Preset {
+    /// The adjustments to paste, without masks or crop/transform values the preset opts out of.
+    /// As in the presets panel, an unset flag means the preset carries whatever it contains.
+    pub(crate) fn applicable_adjustments(&self) -> Value {
+        let mut adjustments = self.adjustments.clone();
+        let Some(map) = adjustments.as_object_mut() else {
+            return adjustments;
+        };
+
+        let has_masks = map
+            .get("masks")
+            .and_then(Value::as_array)
+            .is_some_and(|masks| !masks.is_empty());
+        if !self.include_masks.unwrap_or(has_masks) {
+            map.remove("masks");
+        }
+
+        let has_geometry = PRESET_GEOMETRY_KEYS
+            .iter()
+            .any(|key| map.contains_key(*key));
+        if !self.include_crop_transform.unwrap_or(has_geometry) {
+            for key in PRESET_GEOMETRY_KEYS {
+                map.remove(*key);
+            }
+        }
+        adjustments
+    }
+}
+
 #[derive(Serialize)]
 struct ExportPresetFile<'a> {
     creator: &'a str,
@@ -527,7 +584,7 @@ fn match_disk_kind(disks: &Disks, canonical: &Path) -> Option<bool> {
     best_match.map(|(_, is_hdd)| is_hdd)
 }
 
-fn update_rotational_disk_flag(path: &str, app_handle: &AppHandle) {
+fn update_rotational_disk_flag<R: Runtime>(path: &str, app_handle: &AppHandle<R>) {
     let state = app_handle.state::<crate::AppState>();
     let Ok(canonical) = Path::new(path).canonicalize() else {
         return;
@@ -562,7 +619,10 @@ fn update_rotational_disk_flag(path: &str, app_handle: &AppHandle) {
 }
 
 #[tauri::command]
-pub fn list_images_in_dir(path: String, app_handle: AppHandle) -> Result<Vec<ImageFile>, String> {
+pub fn list_images_in_dir<R: Runtime>(
+    path: String,
+    app_handle: AppHandle<R>,
+) -> Result<Vec<ImageFile>, String> {
     let settings = load_settings(app_handle.clone()).unwrap_or_default();
     let enable_xmp_sync = settings.enable_xmp_sync.unwrap_or(false);
 
@@ -1559,6 +1619,7 @@ pub fn generate_thumbnail_data(
                     &adjustments,
                     true,
                     &settings,
+                    app_handle,
                     None,
                 )?;
 
@@ -1726,6 +1787,7 @@ pub fn generate_thumbnail_data(
                 &adjustments,
                 true,
                 &settings,
+                app_handle,
                 None,
             )?,
             Err(e) => {
@@ -1737,6 +1799,7 @@ pub fn generate_thumbnail_data(
                     &adjustments,
                     true,
                     &settings,
+                    app_handle,
                     None,
                 )?
             }
@@ -2578,6 +2641,179 @@ pub fn save_metadata_and_update_thumbnail(
     Ok(())
 }
 
+fn write_sidecar_metadata(path: &str, metadata: &ImageMetadata, settings: &AppSettings) {
+    let (source_path, sidecar_path) = parse_virtual_path(path);
+
+    if let Ok(json_string) = serde_json::to_string_pretty(metadata) {
+        let _ = std::fs::write(&sidecar_path, json_string);
+    }
+
+    if settings.enable_xmp_sync.unwrap_or(false) {
+        sync_metadata_to_xmp(
+            &source_path,
+            metadata,
+            settings.create_xmp_if_missing.unwrap_or(false),
+        );
+    }
+}
+
+/// Merges `adjustments` over the image's saved edit, the same way pasting does in the editor.
+pub(crate) fn paste_adjustments_into_sidecar(
+    path: &str,
+    adjustments: &Value,
+    lens_db: Option<&crate::lens_correction::LensDatabase>,
+    settings: &AppSettings,
+) {
+    let (_, sidecar_path) = parse_virtual_path(path);
+
+    let mut existing_metadata = crate::exif_processing::load_sidecar(&sidecar_path);
+
+    let mut new_adjustments = existing_metadata.adjustments;
+    if new_adjustments.is_null() {
+        new_adjustments = serde_json::json!({});
+    }
+
+    if let (Some(new_map), Some(pasted_map)) =
+        (new_adjustments.as_object_mut(), adjustments.as_object())
+    {
+        for (k, v) in pasted_map {
+            new_map.insert(k.clone(), v.clone());
+        }
+    }
+
+    resolve_lens_params_in_adjustments(&mut new_adjustments, &existing_metadata.exif, lens_db);
+
+    existing_metadata.adjustments = new_adjustments;
+    write_sidecar_metadata(path, &existing_metadata, settings);
+}
+
+pub(crate) fn apply_relative_adjustments_to_sidecar(
+    path: &str,
+    relative: &RelativeAdjustments,
+    lens_db: Option<&crate::lens_correction::LensDatabase>,
+    settings: &AppSettings,
+) {
+    let (_, sidecar_path) = parse_virtual_path(path);
+
+    let mut existing_metadata = crate::exif_processing::load_sidecar(&sidecar_path);
+    relative.apply(&mut existing_metadata.adjustments);
+    resolve_lens_params_in_adjustments(
+        &mut existing_metadata.adjustments,
+        &existing_metadata.exif,
+        lens_db,
+    );
+
+    write_sidecar_metadata(path, &existing_metadata, settings);
+}
+
+pub(crate) fn reset_sidecar_adjustments(path: &str, settings: &AppSettings) {
+    let (_, sidecar_path) = parse_virtual_path(path);
+
+    let mut existing_metadata = crate::exif_processing::load_sidecar(&sidecar_path);
+    existing_metadata.adjustments = serde_json::json!({});
+    write_sidecar_metadata(path, &existing_metadata, settings);
+}
+
+/// Runs the auto-adjust analysis on the source image and merges the result into its saved edit.
+/// Returns the decoded image so callers can reuse it for thumbnails.
+pub(crate) fn apply_auto_adjustments_to_sidecar(
+    path: &str,
+    settings: &AppSettings,
+    app_handle: &AppHandle,
+) -> Result<DynamicImage, String> {
+    let (source_path, sidecar_path) = parse_virtual_path(path);
+    let source_path_str = source_path.to_string_lossy().to_string();
+
+    let file_bytes = fs::read(&source_path).map_err(|e| e.to_string())?;
+    let image = image_loader::load_base_image_from_bytes(
+        &file_bytes,
+        &source_path_str,
+        true,
+        settings,
+        app_handle,
+        None,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let auto_results = perform_auto_analysis(&image);
+    let auto_adjustments_json = auto_results_to_json(&auto_results);
+
+    let mut existing_metadata = crate::exif_processing::load_sidecar(&sidecar_path);
+
+    if existing_metadata.adjustments.is_null() {
+        existing_metadata.adjustments = serde_json::json!({});
+    }
+
+    if let (Some(existing_map), Some(auto_map)) = (
+        existing_metadata.adjustments.as_object_mut(),
+        auto_adjustments_json.as_object(),
+    ) {
+        for (k, v) in auto_map {
+            if k == "sectionVisibility" {
+                if let Some(existing_vis_val) = existing_map.get_mut(k) {
+                    if let (Some(existing_vis), Some(auto_vis)) =
+                        (existing_vis_val.as_object_mut(), v.as_object())
+                    {
+                        for (vis_k, vis_v) in auto_vis {
+                            existing_vis.insert(vis_k.clone(), vis_v.clone());
+                        }
+                    }
+                } else {
+                    existing_map.insert(k.clone(), v.clone());
+                }
+            } else {
+                existing_map.insert(k.clone(), v.clone());
+            }
+        }
+    }
+
+    write_sidecar_metadata(path, &existing_metadata, settings);
+    Ok(image)
+}
+
+/// Regenerates the thumbnails of images whose sidecars were just rewritten, reporting progress
+/// on the thumbnail queue that the caller has already grown by `paths.len()`.
+fn regenerate_thumbnails_for_paths(
+    paths: &[String],
+    settings: &AppSettings,
+    app_handle: &AppHandle,
+) {
+    let state = app_handle.state::<AppState>();
+    let thumb_cache_dir = match resolve_thumbnail_cache_dir(app_handle) {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::warn!("Unable to initialize thumbnail cache directory: {}", e);
+            for path in paths {
+                emit_thumbnail_cache_setup_error(app_handle, path, &e);
+            }
+            for _ in 0..paths.len() {
+                increment_thumbnail_progress(&state, app_handle);
+            }
+            return;
+        }
+    };
+
+    let gpu_context = gpu_processing::get_or_init_gpu_context(&state, app_handle).ok();
+
+    paths.par_iter().for_each(|path_str| {
+        let result = generate_single_thumbnail_and_cache(
+            path_str,
+            &thumb_cache_dir,
+            gpu_context.as_ref(),
+            None,
+            true,
+            app_handle,
+            settings,
+        );
+
+        if let Some((thumbnail_path, rating, is_edited)) = result {
+            emit_thumbnail_generated(app_handle, path_str, &thumbnail_path, rating, is_edited);
+        }
+
+        increment_thumbnail_progress(&state, app_handle);
+    });
+}
+
 #[tauri::command]
 pub async fn apply_adjustments_to_paths(
     paths: Vec<String>,
@@ -2589,8 +2825,6 @@ pub async fn apply_adjustments_to_paths(
 
     tauri::async_runtime::spawn_blocking(move || {
         let settings = load_settings(app_handle.clone()).unwrap_or_default();
-        let enable_xmp_sync = settings.enable_xmp_sync.unwrap_or(false);
-        let create_xmp_if_missing = settings.create_xmp_if_missing.unwrap_or(false);
 
         let lens_db = app_handle
             .state::<AppState>()
@@ -2600,75 +2834,44 @@ pub async fn apply_adjustments_to_paths(
             .clone();
 
         paths.par_iter().for_each(|path| {
-            let (_, sidecar_path) = parse_virtual_path(path);
-
-            let mut existing_metadata = crate::exif_processing::load_sidecar(&sidecar_path);
+            paste_adjustments_into_sidecar(path, &adjustments, lens_db.as_deref(), &settings);
+        });
 
-            let mut new_adjustments = existing_metadata.adjustments;
-            if new_adjustments.is_null() {
-                new_adjustments = serde_json::json!({});
-            }
+        regenerate_thumbnails_for_paths(&paths, &settings, &app_handle);
+    });
 
-            if let (Some(new_map), Some(pasted_map)) =
-                (new_adjustments.as_object_mut(), adjustments.as_object())
-            {
-                for (k, v) in pasted_map {
-                    new_map.insert(k.clone(), v.clone());
-                }
-            }
+    Ok(())
+}
 
-            resolve_lens_params_in_adjustments(
-                &mut new_adjustments,
-                &existing_metadata.exif,
-                lens_db.as_deref(),
-            );
+/// Like `apply_adjustments_to_paths`, but combines the changes with each image's saved edit
+/// instead of overwriting it.
+#[tauri::command]
+pub async fn apply_relative_adjustments_to_paths(
+    paths: Vec<String>,
+    relative: RelativeAdjustments,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    if relative.is_empty() {
+        return Ok(());
+    }
+    let state = app_handle.state::<AppState>();
+    add_to_thumbnail_queue(&state, paths.len(), &app_handle);
 
-            existing_metadata.adjustments = new_adjustments;
+    tauri::async_runtime::spawn_blocking(move || {
+        let settings = load_settings(app_handle.clone()).unwrap_or_default();
 
-            if let Ok(json_string) = serde_json::to_string_pretty(&existing_metadata) {
-                let _ = std::fs::write(&sidecar_path, json_string);
-            }
+        let lens_db = app_handle
+            .state::<AppState>()
+            .lens_db
+            .lock()
+            .unwrap()
+            .clone();
 
-            if enable_xmp_sync {
-                let source_path = parse_virtual_path(path).0;
-                sync_metadata_to_xmp(&source_path, &existing_metadata, create_xmp_if_missing);
-            }
+        paths.par_iter().for_each(|path| {
+            apply_relative_adjustments_to_sidecar(path, &relative, lens_db.as_deref(), &settings);
         });
 
-        let state = app_handle.state::<AppState>();
-        let thumb_cache_dir = match resolve_thumbnail_cache_dir(&app_handle) {
-            Ok(dir) => dir,
-            Err(e) => {
-                log::warn!("Unable to initialize thumbnail cache directory: {}", e);
-                for path in &paths {
-                    emit_thumbnail_cache_setup_error(&app_handle, path, &e);
-                }
-                for _ in 0..paths.len() {
-                    increment_thumbnail_progress(&state, &app_handle);
-                }
-                return;
-            }
-        };
-
-        let gpu_context = gpu_processing::get_or_init_gpu_context(&state, &app_handle).ok();
-
-        paths.par_iter().for_each(|path_str| {
-            let result = generate_single_thumbnail_and_cache(
-                path_str,
-                &thumb_cache_dir,
-                gpu_context.as_ref(),
-                None,
-                true,
-                &app_handle,
-                &settings,
-            );
-
-            if let Some((thumbnail_path, rating, is_edited)) = result {
-                emit_thumbnail_generated(&app_handle, path_str, &thumbnail_path, rating, is_edited);
-            }
-
-            increment_thumbnail_progress(&state, &app_handle);
-        });
+        regenerate_thumbnails_for_paths(&paths, &settings, &app_handle);
     });
 
     Ok(())
@@ -2684,25 +2887,27 @@ pub async fn reset_adjustments_for_paths(
 
     tauri::async_runtime::spawn_blocking(move || {
         let settings = load_settings(app_handle.clone()).unwrap_or_default();
-        let enable_xmp_sync = settings.enable_xmp_sync.unwrap_or(false);
-        let create_xmp_if_missing = settings.create_xmp_if_missing.unwrap_or(false);
 
         paths.par_iter().for_each(|path| {
-            let (_, sidecar_path) = parse_virtual_path(path);
+            reset_sidecar_adjustments(path, &settings);
+        });
 
-            let mut existing_metadata = crate::exif_processing::load_sidecar(&sidecar_path);
+        regenerate_thumbnails_for_paths(&paths, &settings, &app_handle);
+    });
 
-            existing_metadata.adjustments = serde_json::json!({});
+    Ok(())
+}
 
-            if let Ok(json_string) = serde_json::to_string_pretty(&existing_metadata) {
-                let _ = std::fs::write(&sidecar_path, json_string);
-            }
+#[tauri::command]
+pub async fn apply_auto_adjustments_to_paths(
+    paths: Vec<String>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    add_to_thumbnail_queue(&state, paths.len(), &app_handle);
 
-            if enable_xmp_sync {
-                let source_path = parse_virtual_path(path).0;
-                sync_metadata_to_xmp(&source_path, &existing_metadata, create_xmp_if_missing);
-            }
-        });
+    tauri::async_runtime::spawn_blocking(move || {
+        let settings = load_settings(app_handle.clone()).unwrap_or_default();
 
         let state = app_handle.state::<AppState>();
         let thumb_cache_dir = match resolve_thumbnail_cache_dir(&app_handle) {
@@ -2721,19 +2926,23 @@ pub async fn reset_adjustments_for_paths(
 
         let gpu_context = gpu_processing::get_or_init_gpu_context(&state, &app_handle).ok();
 
-        paths.par_iter().for_each(|path_str| {
+        paths.par_iter().for_each(|path| {
+            let loaded_image = apply_auto_adjustments_to_sidecar(path, &settings, &app_handle)
+                .map_err(|e| eprintln!("Failed to apply auto adjustments to {}: {}", path, e))
+                .ok();
+
             let result = generate_single_thumbnail_and_cache(
-                path_str,
+                path,
                 &thumb_cache_dir,
                 gpu_context.as_ref(),
-                None,
+                loaded_image.as_ref(),
                 true,
                 &app_handle,
                 &settings,
             );
 
             if let Some((thumbnail_path, rating, is_edited)) = result {
-                emit_thumbnail_generated(&app_handle, path_str, &thumbnail_path, rating, is_edited);
+                emit_thumbnail_generated(&app_handle, path, &thumbnail_path, rating, is_edited);
             }
 
             increment_thumbnail_progress(&state, &app_handle);
@@ -2743,113 +2952,303 @@ pub async fn reset_adjustments_for_paths(
     Ok(())
 }
 
-#[tauri::command]
-pub async fn apply_auto_adjustments_to_paths(
-    paths: Vec<String>,
+fn find_library_preset(items: &[PresetItem], name: &str) -> Option<Preset> {
+    items.iter().find_map(|item| match item {
+        PresetItem::Preset(p) if p.id == name || p.name.eq_ignore_ascii_case(name) => {
+            Some(p.clone())
+        }
+        PresetItem::Preset(_) => None,
+        PresetItem::Folder(f) => f
+            .children
+            .iter()
+            .find(|p| p.id == name || p.name.eq_ignore_ascii_case(name))
+            .cloned(),
+    })
+}
+
+/// Looks the preset up in the library first, then falls back to treating the argument as a
+/// preset file (`.rrpreset`, `.xmp`, `.lrtemplate`) or a bare adjustments JSON file.
+pub(crate) fn resolve_cli_preset(
+    name_or_file: &str,
+    app_handle: &AppHandle,
+) -> Result<Preset, String> {
+    if let Some(preset) = find_library_preset(&load_presets(app_handle.clone())?, name_or_file) {
+        return Ok(preset);
+    }
+    if !Path::new(name_or_file).is_file() {
+        return Err(format!(
+            "'{}' is neither a saved preset nor a preset file",
+            name_or_file
+        ));
+    }
+
+    match parse_preset_file(name_or_file) {
+        Ok(items) => items
+            .into_iter()
+            .flat_map(|item| match item {
+                PresetItem::Preset(p) => vec![p],
+                PresetItem::Folder(f) => f.children,
+            })
+            .next()
+            .ok_or_else(|| format!("Preset file '{}' contains no presets", name_or_file)),
+        Err(parse_error) => {
+            let content = fs::read_to_string(name_or_file).map_err(|e| e.to_string())?;
+            match serde_json::from_str::<Value>(&content) {
+                Ok(adjustments) if adjustments.is_object() => Ok(Preset {
+                    id: Uuid::new_v4().to_string(),
+                    name: preset_file_display_name(name_or_file),
+                    adjustments,
+                    include_masks: None,
+                    include_crop_transform: None,
+                    preset_type: None,
+                }),
+                _ => Err(parse_error),
+            }
+        }
+    }
+}
+
+pub(crate) fn expand_cli_paths(
+    paths: &[String],
+    app_handle: &AppHandle,
+) -> Result<Vec<String>, String> {
+    let mut expanded = Vec::new();
+    for path in paths {
+        let path_buf = Path::new(path);
+        if path_buf.is_dir() {
+            expanded.extend(
+                list_images_recursive(path.clone(), app_handle.clone())?
+                    .into_iter()
+                    .map(|img| img.path),
+            );
+        } else if path_buf.exists() {
+            expanded.push(path.clone());
+        } else {
+            return Err(format!("Path does not exist: {}", path));
+        }
+    }
+    Ok(expanded)
+}
+
+pub async fn run_headless_edit(
+    session: crate::launch_request::HeadlessEditSession,
     app_handle: AppHandle,
 ) -> Result<(), String> {
-    let state = app_handle.state::<AppState>();
-    add_to_thumbnail_queue(&state, paths.len(), &app_handle);
+    use crate::launch_request::HeadlessEditAction;
 
-    tauri::async_runtime::spawn_blocking(move || {
+    if matches!(&session.action, HeadlessEditAction::ApplyPreset(name) if name.is_empty()) {
+        return Err("Usage: rapidraw apply-preset <preset-name|file> <paths...>".to_string());
+    }
+    if session.paths.is_empty() {
+        return Err("No image paths given.".to_string());
+    }
+    let paths = expand_cli_paths(&session.paths, &app_handle)?;
+    if paths.is_empty() {
+        return Err("No supported images found at the given paths.".to_string());
+    }
+
+    let preset_adjustments = match &session.action {
+        HeadlessEditAction::ApplyPreset(name_or_file) => {
+            let preset = resolve_cli_preset(name_or_file, &app_handle)?;
+            println!(
+                "Applying preset '{}' to {} images...",
+                preset.name,
+                paths.len()
+            );
+            Some(preset.applicable_adjustments())
+        }
+        HeadlessEditAction::Auto => {
+            println!("Applying auto adjustments to {} images...", paths.len());
+            None
+        }
+        HeadlessEditAction::Reset => {
+            println!("Resetting adjustments for {} images...", paths.len());
+            None
+        }
+    };
+
+    let total = paths.len();
+    let failed = tauri::async_runtime::spawn_blocking(move || {
         let settings = load_settings(app_handle.clone()).unwrap_or_default();
-        let enable_xmp_sync = settings.enable_xmp_sync.unwrap_or(false);
-        let create_xmp_if_missing = settings.create_xmp_if_missing.unwrap_or(false);
+        let lens_db = app_handle
+            .state::<AppState>()
+            .lens_db
+            .lock()
+            .unwrap()
+            .clone();
 
-        let state = app_handle.state::<AppState>();
-        let thumb_cache_dir = match resolve_thumbnail_cache_dir(&app_handle) {
-            Ok(dir) => dir,
-            Err(e) => {
-                log::warn!("Unable to initialize thumbnail cache directory: {}", e);
-                for path in &paths {
-                    emit_thumbnail_cache_setup_error(&app_handle, path, &e);
-                }
-                for _ in 0..paths.len() {
-                    increment_thumbnail_progress(&state, &app_handle);
+        paths
+            .par_iter()
+            .map(|path| {
+                let result = match (&session.action, &preset_adjustments) {
+                    (HeadlessEditAction::ApplyPreset(_), Some(adjustments)) => {
+                        paste_adjustments_into_sidecar(
+                            path,
+                            adjustments,
+                            lens_db.as_deref(),
+                            &settings,
+                        );
+                        Ok(())
+                    }
+                    (HeadlessEditAction::Auto, _) => {
+                        apply_auto_adjustments_to_sidecar(path, &settings, &app_handle).map(|_| ())
+                    }
+                    (HeadlessEditAction::Reset, _) => {
+                        reset_sidecar_adjustments(path, &settings);
+                        Ok(())
+                    }
+                    (HeadlessEditAction::ApplyPreset(_), None) => Ok(()),
+                };
+                if let Err(e) = &result {
+                    eprintln!("Failed to update {}: {}", path, e);
                 }
-                return;
-            }
-        };
+                result.is_err()
+            })
+            .filter(|failed| *failed)
+            .count()
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
 
-        let gpu_context = gpu_processing::get_or_init_gpu_context(&state, &app_handle).ok();
+    println!("Updated {} of {} images.", total - failed, total);
+    if failed > 0 {
+        Err(format!("{} images could not be updated.", failed))
+    } else {
+        Ok(())
+    }
+}
 
-        paths.par_iter().for_each(|path| {
-            let loaded_image: Option<DynamicImage> = (|| -> Result<DynamicImage, String> {
-                let (source_path, sidecar_path) = parse_virtual_path(path);
-                let source_path_str = source_path.to_string_lossy().to_string();
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LensMatch {
+    maker: String,
+    model: String,
+}
 
-                let file_bytes = fs::read(&source_path).map_err(|e| e.to_string())?;
-                let image = image_loader::load_base_image_from_bytes(
-                    &file_bytes,
-                    &source_path_str,
-                    true,
-                    &settings,
-                    None,
-                )
-                .map_err(|e| e.to_string())?;
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImageInfo {
+    path: String,
+    source_path: String,
+    sidecar_path: String,
+    virtual_copy_id: Option<String>,
+    is_raw: bool,
+    is_edited: bool,
+    rating: u8,
+    color_label: Option<String>,
+    tags: Vec<String>,
+    lens_match: Option<LensMatch>,
+    exif: std::collections::BTreeMap<String, String>,
+}
 
-                let auto_results = perform_auto_analysis(&image);
-                let auto_adjustments_json = auto_results_to_json(&auto_results);
+fn collect_image_info(path: &str, settings: &AppSettings, app_handle: &AppHandle) -> ImageInfo {
+    let (source_path, sidecar_path) = parse_virtual_path(path);
+    let source_path_str = source_path.to_string_lossy().to_string();
+    let metadata = crate::exif_processing::load_sidecar(&sidecar_path);
 
-                let mut existing_metadata = crate::exif_processing::load_sidecar(&sidecar_path);
+    let is_raw = is_raw_file(&source_path_str);
+    let tm_override = crate::image_processing::resolve_tonemapper_override(settings, is_raw);
+    let is_edited =
+        crate::image_processing::is_image_edited(&metadata.adjustments, is_raw, tm_override);
 
-                if existing_metadata.adjustments.is_null() {
-                    existing_metadata.adjustments = serde_json::json!({});
-                }
+    let (color_tags, tags): (Vec<String>, Vec<String>) = metadata
+        .tags
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|tag| tag.starts_with(COLOR_TAG_PREFIX));
+    let color_label = color_tags
+        .first()
+        .map(|tag| tag.trim_start_matches(COLOR_TAG_PREFIX).to_string());
 
-                if let (Some(existing_map), Some(auto_map)) = (
-                    existing_metadata.adjustments.as_object_mut(),
-                    auto_adjustments_json.as_object(),
-                ) {
-                    for (k, v) in auto_map {
-                        if k == "sectionVisibility" {
-                            if let Some(existing_vis_val) = existing_map.get_mut(k) {
-                                if let (Some(existing_vis), Some(auto_vis)) =
-                                    (existing_vis_val.as_object_mut(), v.as_object())
-                                {
-                                    for (vis_k, vis_v) in auto_vis {
-                                        existing_vis.insert(vis_k.clone(), vis_v.clone());
-                                    }
-                                }
-                            } else {
-                                existing_map.insert(k.clone(), v.clone());
-                            }
-                        } else {
-                            existing_map.insert(k.clone(), v.clone());
-                        }
-                    }
-                }
+    let exif = if is_cloud_placeholder(&source_path) {
+        HashMap::new()
+    } else {
+        match read_file_mapped(&source_path) {
+            Ok(mmap) => crate::exif_processing::read_exif_data(&source_path_str, &mmap),
+            Err(_) => HashMap::new(),
+        }
+    };
 
-                if let Ok(json_string) = serde_json::to_string_pretty(&existing_metadata) {
-                    let _ = std::fs::write(&sidecar_path, json_string);
-                }
+    let lens_match = match (exif.get("Make"), exif.get("LensModel")) {
+        (Some(maker), Some(model)) if !model.trim().is_empty() => {
+            crate::lens_correction::autodetect_lens(
+                maker.clone(),
+                model.clone(),
+                app_handle.state::<AppState>(),
+            )
+            .ok()
+            .flatten()
+            .map(|(maker, model)| LensMatch { maker, model })
+        }
+        _ => None,
+    };
 
-                if enable_xmp_sync {
-                    sync_metadata_to_xmp(&source_path, &existing_metadata, create_xmp_if_missing);
-                }
-                Ok(image)
-            })()
-            .map_err(|e| eprintln!("Failed to apply auto adjustments to {}: {}", path, e))
-            .ok();
+    ImageInfo {
+        path: path.to_string(),
+        source_path: source_path_str,
+        sidecar_path: sidecar_path.to_string_lossy().to_string(),
+        virtual_copy_id: path.rsplit_once("?vc=").map(|(_, id)| id.to_string()),
+        is_raw,
+        is_edited,
+        rating: metadata.rating,
+        color_label,
+        tags,
+        lens_match,
+        exif: exif.into_iter().collect(),
+    }
+}
 
-            let result = generate_single_thumbnail_and_cache(
-                path,
-                &thumb_cache_dir,
-                gpu_context.as_ref(),
-                loaded_image.as_ref(),
-                true,
-                &app_handle,
-                &settings,
-            );
+fn print_image_info(info: &ImageInfo) {
+    println!("{}", info.path);
+    println!("  Sidecar:    {}", info.sidecar_path);
+    println!("  Rating:     {}", info.rating);
+    if let Some(color) = &info.color_label {
+        println!("  Color:      {}", color);
+    }
+    if !info.tags.is_empty() {
+        println!("  Tags:       {}", info.tags.join(", "));
+    }
+    println!(
+        "  Edited:     {}",
+        if info.is_edited { "yes" } else { "no" }
+    );
+    if let Some(lens) = &info.lens_match {
+        println!("  Lens match: {} {}", lens.maker, lens.model);
+    }
+    if !info.exif.is_empty() {
+        println!("  EXIF:");
+        for (key, value) in &info.exif {
+            println!("    {}: {}", key, value);
+        }
+    }
+    println!();
+}
 
-            if let Some((thumbnail_path, rating, is_edited)) = result {
-                emit_thumbnail_generated(&app_handle, path, &thumbnail_path, rating, is_edited);
-            }
+pub async fn run_headless_info(
+    session: crate::launch_request::HeadlessInfoSession,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    if session.paths.is_empty() {
+        return Err("Usage: rapidraw info <path|dir>... [--json]".to_string());
+    }
+    let paths = expand_cli_paths(&session.paths, &app_handle)?;
 
-            increment_thumbnail_progress(&state, &app_handle);
-        });
-    });
+    let infos = tauri::async_runtime::spawn_blocking(move || {
+        let settings = load_settings(app_handle.clone()).unwrap_or_default();
+        paths
+            .par_iter()
+            .map(|path| collect_image_info(path, &settings, &app_handle))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
 
+    if session.json {
+        let json = serde_json::to_string_pretty(&infos).map_err(|e| e.to_string())?;
+        println!("{}", json);
+    } else {
+        infos.iter().for_each(print_image_info);
+    }
     Ok(())
 }
 
@@ -9,9 +9,15 @@ use tauri::{AppHandle, Emitter};
 
 use crate::app_settings::load_settings;
 use crate::app_state::AppState;
+use crate::exr_output::{
+    ExrPrecision, dng_merges_enabled, scene_referred_merges_enabled, write_merged_dng,
+    write_scene_referred_exr,
+};
 use crate::file_management::parse_virtual_path;
 use crate::formats::is_raw_file;
-use crate::image_processing::apply_cpu_default_raw_processing;
+use crate::image_processing::{
+    apply_cpu_default_raw_processing, apply_linear_to_srgb, apply_srgb_to_linear,
+};
 
 const MEMORY_BUDGET_BYTES: u64 = 2 * 1024 * 1024 * 1024;
 
@@ -1970,6 +1976,8 @@ pub async fn stitch_focus_stack(
         .collect();
 
     let focus_result_handle = state.focus_stack_result.clone();
+    let scene_referred =
+        scene_referred_merges_enabled(&load_settings(app_handle.clone()).unwrap_or_default());
 
     let task = tokio::task::spawn_blocking(move || -> Result<(), String> {
         let progress = {
@@ -1987,7 +1995,12 @@ pub async fn stitch_focus_stack(
 
         progress("Creating preview...");
         let final_image = result.image.to_rgb32f();
-        let preview = make_preview(&final_image, 1200)?;
+        let preview = if scene_referred {
+            let display = apply_linear_to_srgb(DynamicImage::ImageRgb32F(final_image.clone()));
+            make_preview(&display.into_rgb32f(), 1200)?
+        } else {
+            make_preview(&final_image, 1200)?
+        };
         let depth_preview = make_depth_preview(&result, n)?;
 
         let (dbg_w, dbg_h) = (result.image.w, result.image.h);
@@ -2039,7 +2052,7 @@ fn load_frames(
     let n = paths.len();
 
     progress(&format!("Loading frame 1 of {}...", n));
-    let first = decode_frame(&paths[0], &settings)?;
+    let first = decode_frame(&paths[0], &settings, app_handle)?;
     let (w, h) = (first.w, first.h);
     let estimated = (n as u64) * (w as u64) * (h as u64) * 3 * 4;
 
@@ -2048,7 +2061,7 @@ fn load_frames(
         frames.push(first);
         for (i, p) in paths.iter().enumerate().skip(1) {
             progress(&format!("Loading frame {} of {}...", i + 1, n));
-            let f = decode_frame(p, &settings)?;
+            let f = decode_frame(p, &settings, app_handle)?;
             check_dims(i, &f, w, h)?;
             frames.push(f);
         }
@@ -2059,7 +2072,7 @@ fn load_frames(
         drop(first);
         for (i, p) in paths.iter().enumerate().skip(1) {
             progress(&format!("Loading frame {} of {}...", i + 1, n));
-            let f = decode_frame(p, &settings)?;
+            let f = decode_frame(p, &settings, app_handle)?;
             check_dims(i, &f, w, h)?;
             disk.write(i, &f)?;
         }
@@ -2085,12 +2098,18 @@ fn check_dims(i: usize, f: &PlanarRgb, w: usize, h: usize) -> Result<(), String>
 fn decode_frame(
     path: &str,
     settings: &crate::app_settings::AppSettings,
+    app_handle: &AppHandle,
 ) -> Result<PlanarRgb, String> {
     let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
-    let mut dyn_img =
-        crate::image_loader::load_base_image_from_bytes(&bytes, path, false, settings, None)
-            .map_err(|e| format!("Failed to decode {}: {}", path, e))?;
-    if is_raw_file(path) {
+    let mut dyn_img = crate::image_loader::load_base_image_from_bytes(
+        &bytes, path, false, settings, app_handle, None,
+    )
+    .map_err(|e| format!("Failed to decode {}: {}", path, e))?;
+    if scene_referred_merges_enabled(settings) {
+        if !is_raw_file(path) {
+            dyn_img = apply_srgb_to_linear(DynamicImage::ImageRgb32F(dyn_img.to_rgb32f()));
+        }
+    } else if is_raw_file(path) {
         apply_cpu_default_raw_processing(&mut dyn_img);
     }
     Ok(PlanarRgb::from_rgb32f(&dyn_img.to_rgb32f()))
@@ -2138,6 +2157,7 @@ fn make_depth_preview(result: &StackResult, n_frames: usize) -> Result<String, S
 pub async fn save_focus_stack(
     first_path_str: String,
     state: tauri::State<'_, AppState>,
+    app_handle: AppHandle,
 ) -> Result<String, String> {
     let focus_image = state
         .focus_stack_result
@@ -2152,12 +2172,33 @@ pub async fn save_focus_stack(
         .ok_or_else(|| "Could not determine output directory.".to_string())?;
     let stem = first_path.file_stem().unwrap_or_default().to_string_lossy();
 
-    let output_path = parent_dir.join(format!("{}_Stacked.tiff", stem));
-
-    let rgb16 = focus_image.to_rgb16();
-    rgb16
-        .save_with_format(&output_path, ImageFormat::Tiff)
-        .map_err(|e| format!("Failed to save {}: {}", output_path.display(), e))?;
+    let settings = load_settings(app_handle).unwrap_or_default();
+
+    let output_path = if dng_merges_enabled(&settings) {
+        let output_path = parent_dir.join(format!("{}_Stacked.dng", stem));
+        write_merged_dng(
+            &focus_image,
+            &output_path,
+            &first_path.to_string_lossy(),
+            &settings,
+        )?;
+        output_path
+    } else if scene_referred_merges_enabled(&settings) {
+        let output_path = parent_dir.join(format!("{}_Stacked.exr", stem));
+        write_scene_referred_exr(
+            &focus_image,
+            &output_path,
+            ExrPrecision::from_settings(&settings),
+        )?;
+        output_path
+    } else {
+        let output_path = parent_dir.join(format!("{}_Stacked.tiff", stem));
+        let rgb16 = focus_image.to_rgb16();
+        rgb16
+            .save_with_format(&output_path, ImageFormat::Tiff)
+            .map_err(|e| format!("Failed to save {}: {}", output_path.display(), e))?;
+        output_path
+    };
 
     crate::exif_processing::write_rrexif_sidecar(&first_path_str, &output_path).ok();
 
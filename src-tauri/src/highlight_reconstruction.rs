@@ -0,0 +1,158 @@
+use rayon::prelude::*;
+
+use crate::demosaic::CfaPattern;
+
+/// Photosites at this fraction of their clip level count as clipped.
+const CLIP_THRESHOLD: f32 = 0.987;
+/// How far from a clipped photosite unclipped ones are sampled for the colour estimate.
+const SAMPLE_RADIUS: usize = 4;
+
+/// Mean of each colour around a photosite. Colours without a sample fall back to `fallback`.
+fn neighbourhood_means(
+    data: &[f32],
+    width: usize,
+    height: usize,
+    pattern: &CfaPattern,
+    row: usize,
+    col: usize,
+    fallback: f32,
+) -> [f32; 3] {
+    let mut sum = [0.0f32; 3];
+    let mut count = [0u32; 3];
+    for r in row.saturating_sub(1)..(row + 2).min(height) {
+        for c in col.saturating_sub(1)..(col + 2).min(width) {
+            let color = pattern.color_at(r, c);
+            sum[color] += data[r * width + c].max(0.0);
+            count[color] += 1;
+        }
+    }
+    [0, 1, 2].map(|c| {
+        if count[c] > 0 {
+            sum[c] / count[c] as f32
+        } else {
+            fallback
+        }
+    })
+}
+
+/// The value the other two colours around a photosite suggest for its own colour, averaged in
+/// cube-root space so that one very bright channel does not dominate.
+fn opposed_value(means: [f32; 3], color: usize) -> f32 {
+    let roots = means.map(f32::cbrt);
+    let opposed: f32 = (0..3)
+        .filter(|&c| c != color)
+        .map(|c| roots[c])
+        .sum::<f32>()
+        / 2.0;
+    opposed * opposed * opposed
+}
+
+/// Grows a mask by `radius` photosites in every direction.
+fn dilate(mask: &[bool], width: usize, height: usize, radius: usize) -> Vec<bool> {
+    let mut rows = vec![false; mask.len()];
+    rows.par_chunks_mut(width)
+        .enumerate()
+        .for_each(|(row, line)| {
+            let src = &mask[row * width..(row + 1) * width];
+            for (col, value) in line.iter_mut().enumerate() {
+                let start = col.saturating_sub(radius);
+                let end = (col + radius + 1).min(width);
+                *value = src[start..end].iter().any(|&m| m);
+            }
+        });
+
+    let mut out = vec![false; mask.len()];
+    out.par_chunks_mut(width)
+        .enumerate()
+        .for_each(|(row, line)| {
+            let start = row.saturating_sub(radius);
+            let end = (row + radius + 1).min(height);
+            for (col, value) in line.iter_mut().enumerate() {
+                *value = (start..end).any(|r| rows[r * width + col]);
+            }
+        });
+    out
+}
+
+/// Rebuilds clipped photosites with the "inpaint opposed" method. Unclipped photosites around
+/// clipped areas give, per colour, how far that colour sits from the average of the other two.
+/// Clipped photosites are then set to the average of their unclipped neighbours of the other
+/// colours plus that offset, so bright areas keep their hue instead of turning grey.
+///
+/// `data` must be white balanced and normalized so that `clip[c]` is the level colour `c` clips
+/// at. Reconstructed values end up above that level.
+pub fn reconstruct_opposed(
+    data: &mut [f32],
+    width: usize,
+    height: usize,
+    pattern: &CfaPattern,
+    clip: [f32; 3],
+) {
+    let thresholds = clip.map(|c| c * CLIP_THRESHOLD);
+    let clipped: Vec<bool> = data
+        .par_iter()
+        .enumerate()
+        .map(|(i, &value)| value >= thresholds[pattern.color_at(i / width, i % width)])
+        .collect();
+    if !clipped.iter().any(|&c| c) {
+        return;
+    }
+    let near_clipped = dilate(&clipped, width, height, SAMPLE_RADIUS);
+
+    let source: &[f32] = data;
+    let (sum, count) = (0..height)
+        .into_par_iter()
+        .map(|row| {
+            let mut sum = [0.0f64; 3];
+            let mut count = [0u64; 3];
+            for col in 0..width {
+                let i = row * width + col;
+                if clipped[i] || !near_clipped[i] {
+                    continue;
+                }
+                let color = pattern.color_at(row, col);
+                let value = source[i];
+                if value < 0.2 * thresholds[color] {
+                    continue;
+                }
+                let means = neighbourhood_means(source, width, height, pattern, row, col, value);
+                sum[color] += (value - opposed_value(means, color)) as f64;
+                count[color] += 1;
+            }
+            (sum, count)
+        })
+        .reduce(
+            || ([0.0; 3], [0; 3]),
+            |(sa, ca), (sb, cb)| {
+                (
+                    [sa[0] + sb[0], sa[1] + sb[1], sa[2] + sb[2]],
+                    [ca[0] + cb[0], ca[1] + cb[1], ca[2] + cb[2]],
+                )
+            },
+        );
+    let chrominance = [0, 1, 2].map(|c| {
+        if count[c] > 100 {
+            (sum[c] / count[c] as f64) as f32
+        } else {
+            0.0
+        }
+    });
+
+    let reconstructed: Vec<(usize, f32)> = (0..data.len())
+        .into_par_iter()
+        .filter(|&i| clipped[i])
+        .map(|i| {
+            let (row, col) = (i / width, i % width);
+            let color = pattern.color_at(row, col);
+            let value = source[i];
+            let means = neighbourhood_means(source, width, height, pattern, row, col, value);
+            (
+                i,
+                value.max(opposed_value(means, color) + chrominance[color]),
+            )
+        })
+        .collect();
+    for (i, value) in reconstructed {
+        data[i] = value;
+    }
+}
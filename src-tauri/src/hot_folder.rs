@@ -0,0 +1,303 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use rayon::prelude::*;
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+use crate::app_settings::{HotFolderSettings, load_settings};
+use crate::export_processing::{
+    ExportAdjustmentsMode, ExportVariant, export_images_impl, find_export_preset,
+};
+use crate::file_management::{
+    self, apply_auto_adjustments_to_sidecar, paste_adjustments_into_sidecar, resolve_cli_preset,
+};
+use crate::formats::is_supported_image_file;
+use crate::launch_request::HeadlessWatchSession;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, PartialEq)]
+struct FileSnapshot {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Polls the input folder. A file counts as fully written once its size and modification time
+/// are unchanged between two consecutive polls.
+struct HotFolderWatcher {
+    input_folder: PathBuf,
+    seen: HashSet<PathBuf>,
+    pending: HashMap<PathBuf, FileSnapshot>,
+    prepared: HashSet<PathBuf>,
+}
+
+impl HotFolderWatcher {
+    fn new(input_folder: &Path) -> Self {
+        // Only files that arrive after watching starts are processed.
+        Self {
+            input_folder: input_folder.to_path_buf(),
+            seen: list_supported_files(input_folder).into_iter().collect(),
+            pending: HashMap::new(),
+            prepared: HashSet::new(),
+        }
+    }
+
+    fn poll(&mut self) -> Vec<PathBuf> {
+        let present = list_supported_files(&self.input_folder);
+        let mut ready = Vec::new();
+        let mut pending = HashMap::new();
+
+        for path in &present {
+            if self.seen.contains(path) {
+                continue;
+            }
+            let Ok(metadata) = fs::metadata(path) else {
+                continue;
+            };
+            let snapshot = FileSnapshot {
+                len: metadata.len(),
+                modified: metadata.modified().ok(),
+            };
+            if snapshot.len > 0 && self.pending.get(path) == Some(&snapshot) {
+                ready.push(path.clone());
+            }
+            pending.insert(path.clone(), snapshot);
+        }
+
+        // Forget files that were moved away, so a new file with the same name is picked up again.
+        let present: HashSet<PathBuf> = present.into_iter().collect();
+        self.seen.retain(|path| present.contains(path));
+        self.prepared.retain(|path| present.contains(path));
+        self.pending = pending;
+
+        ready.sort();
+        ready
+    }
+}
+
+fn list_supported_files(folder: &Path) -> Vec<PathBuf> {
+    fs::read_dir(folder)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && is_supported_image_file(path))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Applies the configured edits once per file, then exports the batch. If the export cannot
+/// start (e.g. another export is running) the files stay pending and are retried on the next poll.
+async fn process_new_files(
+    watcher: &mut HotFolderWatcher,
+    config: &HotFolderSettings,
+    files: Vec<PathBuf>,
+    app_handle: &AppHandle,
+) -> Result<(), String> {
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    let variant =
+        ExportVariant::from_preset(find_export_preset(&settings, &config.export_preset)?, None);
+    let preset_adjustments = match config.preset.as_deref() {
+        Some(name) if !name.is_empty() => {
+            Some(resolve_cli_preset(name, app_handle)?.applicable_adjustments())
+        }
+        _ => None,
+    };
+
+    let paths: Vec<String> = files
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    let to_prepare: Vec<String> = files
+        .iter()
+        .filter(|path| !watcher.prepared.contains(*path))
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+
+    if !to_prepare.is_empty() && (config.auto_adjust || preset_adjustments.is_some()) {
+        let auto_adjust = config.auto_adjust;
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let lens_db = app_handle
+                .state::<AppState>()
+                .lens_db
+                .lock()
+                .unwrap()
+                .clone();
+            to_prepare.par_iter().for_each(|path| {
+                if auto_adjust
+                    && let Err(e) = apply_auto_adjustments_to_sidecar(path, &settings, &app_handle)
+                {
+                    log::warn!("Hot folder: auto adjust failed for {}: {}", path, e);
+                }
+                if let Some(adjustments) = &preset_adjustments {
+                    paste_adjustments_into_sidecar(
+                        path,
+                        adjustments,
+                        lens_db.as_deref(),
+                        &settings,
+                    );
+                }
+            });
+        })
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?;
+    }
+    watcher.prepared.extend(files.iter().cloned());
+
+    fs::create_dir_all(&config.output_folder)
+        .map_err(|e| format!("Failed to create output directory: {}", e))?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    export_images_impl(
+        paths.clone(),
+        config.output_folder.clone(),
+        false,
+        vec![config.input_folder.clone()],
+        variant.export_settings,
+        variant.output_format,
+        None,
+        ExportAdjustmentsMode::UseSidecars {
+            active_path: None,
+            active_adjustments: None,
+        },
+        app_handle.state::<AppState>(),
+        app_handle.clone(),
+        Some(tx),
+    )
+    .await?;
+
+    let outcome = rx.await;
+    // Failed files are not retried, but only successfully exported ones are archived.
+    watcher.seen.extend(files);
+    let exported: Vec<String> = match outcome {
+        Ok(Ok(())) => {
+            log::info!(
+                "Hot folder: exported {} images to {}",
+                paths.len(),
+                config.output_folder
+            );
+            paths
+        }
+        Ok(Err(failed)) => {
+            log::warn!(
+                "Hot folder: {} of {} images failed to export",
+                failed.len(),
+                paths.len()
+            );
+            let failed: HashSet<&str> = failed.iter().map(|f| f.path.as_str()).collect();
+            paths
+                .iter()
+                .filter(|path| !failed.contains(path.as_str()))
+                .cloned()
+                .collect()
+        }
+        Err(_) => return Err("Hot folder export was cancelled.".to_string()),
+    };
+
+    if let Some(archive_folder) = config.archive_folder.as_deref().filter(|f| !f.is_empty())
+        && !exported.is_empty()
+    {
+        fs::create_dir_all(archive_folder)
+            .map_err(|e| format!("Failed to create archive directory: {}", e))?;
+        file_management::move_files(exported, archive_folder.to_string(), app_handle.clone())?;
+    }
+
+    Ok(())
+}
+
+async fn poll_hot_folder(
+    watcher: &mut HotFolderWatcher,
+    config: &HotFolderSettings,
+    app_handle: &AppHandle,
+) {
+    let files = watcher.poll();
+    if files.is_empty() {
+        return;
+    }
+    log::info!(
+        "Hot folder: {} new images in {}",
+        files.len(),
+        config.input_folder
+    );
+    if let Err(e) = process_new_files(watcher, config, files, app_handle).await {
+        log::warn!("Hot folder: {}", e);
+    }
+}
+
+/// Runs for the lifetime of the GUI. Settings are re-read on every poll, so enabling the hot
+/// folder or changing its options takes effect without a restart.
+pub fn start_hot_folder_watcher(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut watcher: Option<HotFolderWatcher> = None;
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let config = load_settings(app_handle.clone())
+                .ok()
+                .and_then(|settings| settings.hot_folder)
+                .filter(|config| config.enabled && Path::new(&config.input_folder).is_dir());
+            let Some(config) = config else {
+                watcher = None;
+                continue;
+            };
+
+            match watcher.as_mut() {
+                Some(watcher) if watcher.input_folder == Path::new(&config.input_folder) => {
+                    poll_hot_folder(watcher, &config, &app_handle).await;
+                }
+                _ => {
+                    log::info!("Watching hot folder {}", config.input_folder);
+                    watcher = Some(HotFolderWatcher::new(Path::new(&config.input_folder)));
+                }
+            }
+        }
+    });
+}
+
+/// Options given on the command line override the hot folder saved in the settings.
+pub async fn run_headless_watch(
+    session: HeadlessWatchSession,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let saved = load_settings(app_handle.clone())?
+        .hot_folder
+        .unwrap_or_default();
+    let config = HotFolderSettings {
+        enabled: true,
+        input_folder: session.input.unwrap_or(saved.input_folder),
+        output_folder: session.output.unwrap_or(saved.output_folder),
+        preset: session.preset.or(saved.preset),
+        auto_adjust: session.auto_adjust.unwrap_or(saved.auto_adjust),
+        export_preset: session.export_preset.unwrap_or(saved.export_preset),
+        archive_folder: session.archive.or(saved.archive_folder),
+    };
+
+    if config.input_folder.is_empty() || config.output_folder.is_empty() {
+        return Err("Usage: rapidraw watch <folder> --output <folder> --export-preset <name> [--preset <name|file>] [--auto] [--archive <folder>]".to_string());
+    }
+    if !Path::new(&config.input_folder).is_dir() {
+        return Err(format!(
+            "Watch folder does not exist: {}",
+            config.input_folder
+        ));
+    }
+    find_export_preset(&load_settings(app_handle.clone())?, &config.export_preset)?;
+    if let Some(preset) = config.preset.as_deref().filter(|p| !p.is_empty()) {
+        resolve_cli_preset(preset, &app_handle)?;
+    }
+
+    println!(
+        "Watching {} for new images (press Ctrl+C to stop)...",
+        config.input_folder
+    );
+    let mut watcher = HotFolderWatcher::new(Path::new(&config.input_folder));
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        poll_hot_folder(&mut watcher, &config, &app_handle).await;
+    }
+}
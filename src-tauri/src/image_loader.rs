@@ -1,6 +1,10 @@
 use crate::Cursor;
 use crate::app_settings::{AppSettings, load_settings};
 use crate::app_state::{AppState, LoadedImage};
+use crate::calibration_frames::CalibrationFrames;
+use crate::dcp_profiles::DcpProfile;
+use crate::defect_pixels::DefectMaps;
+use crate::demosaic::DemosaicMethod;
 use crate::exif_processing;
 use crate::file_management::{parse_virtual_path, read_file_mapped};
 use crate::formats::is_raw_file;
@@ -9,6 +13,7 @@ use crate::image_processing::{
     apply_orientation, apply_srgb_to_linear, remove_raw_artifacts_and_enhance,
 };
 use crate::mask_generation::{MaskDefinition, SubMask, generate_mask_bitmap};
+use crate::raw_processing::RawDevelopOptions;
 use anyhow::{Context, Result, anyhow};
 use base64::{Engine as _, engine::general_purpose};
 use exif::{Reader as ExifReader, Tag};
@@ -27,6 +32,7 @@ use std::sync::{
     atomic::{AtomicUsize, Ordering},
 };
 use std::time::Instant;
+use tauri::AppHandle;
 
 #[derive(serde::Serialize)]
 pub struct LoadImageResult {
@@ -70,10 +76,18 @@ pub fn load_and_composite(
     adjustments: &Value,
     use_fast_raw_dev: bool,
     settings: &AppSettings,
+    app_handle: &AppHandle,
     cancel_token: Option<(Arc<AtomicUsize>, usize)>,
 ) -> Result<DynamicImage> {
-    let base_image =
-        load_base_image_from_bytes(base_image, path, use_fast_raw_dev, settings, cancel_token)?;
+    let base_image = load_base_image_with_options(
+        base_image,
+        path,
+        use_fast_raw_dev,
+        &RawDevelopOptions::from_adjustments(adjustments),
+        settings,
+        app_handle,
+        cancel_token,
+    )?;
     composite_patches_on_image(&base_image, adjustments)
 }
 
@@ -82,9 +96,64 @@ pub fn load_base_image_from_bytes(
     path_for_ext_check: &str,
     use_fast_raw_dev: bool,
     settings: &AppSettings,
+    app_handle: &AppHandle,
+    cancel_token: Option<(Arc<AtomicUsize>, usize)>,
+) -> Result<DynamicImage> {
+    load_base_image_with_options(
+        bytes,
+        path_for_ext_check,
+        use_fast_raw_dev,
+        &RawDevelopOptions::default(),
+        settings,
+        app_handle,
+        cancel_token,
+    )
+}
+
+/// Images developed with non-default options are cached apart from the default development.
+pub fn decoded_cache_key(source_path: &str, options: &RawDevelopOptions) -> String {
+    let mut key = source_path.to_string();
+    if options.demosaic != DemosaicMethod::Default {
+        key.push_str(&format!("#demosaic={}", options.demosaic.as_str()));
+    }
+    if let Some(profile) = &options.camera_profile {
+        key.push_str(&format!("#dcp={}", profile));
+        if options.profile_tone_curve {
+            key.push_str("+tone");
+        }
+    }
+    if let Some(dark) = &options.dark_frame {
+        key.push_str(&format!("#dark={}", dark));
+    }
+    if let Some(flat) = &options.flat_field {
+        key.push_str(&format!("#flat={}", flat));
+    }
+    key
+}
+
+fn load_camera_profile(options: &RawDevelopOptions) -> Option<DcpProfile> {
+    let path = options.camera_profile.as_deref()?;
+    match crate::dcp_profiles::load_dcp(path, options.profile_tone_curve) {
+        Ok(profile) => Some(profile),
+        Err(e) => {
+            log::warn!("Failed to load camera profile '{}': {}", path, e);
+            None
+        }
+    }
+}
+
+pub fn load_base_image_with_options(
+    bytes: &[u8],
+    path_for_ext_check: &str,
+    use_fast_raw_dev: bool,
+    options: &RawDevelopOptions,
+    settings: &AppSettings,
+    app_handle: &AppHandle,
     cancel_token: Option<(Arc<AtomicUsize>, usize)>,
 ) -> Result<DynamicImage> {
     let highlight_compression = settings.raw_highlight_compression.unwrap_or(2.5);
+    let reconstruct_highlights = settings.raw_highlight_mode == "reconstruct";
+    let detect_defects = settings.raw_defect_detection.unwrap_or(false);
     let linear_mode = settings.linear_raw_mode.clone();
     let color_nr_setting = settings.raw_preprocessing_color_nr.unwrap_or(0.5);
     let color_nr_amount = if color_nr_setting <= 0.0 {
@@ -103,11 +172,22 @@ pub fn load_base_image_from_bytes(
     );
 
     if is_raw_file(path_for_ext_check) {
+        let demosaic = options.demosaic;
+        let camera_profile = load_camera_profile(options);
+        let calibration =
+            CalibrationFrames::load(options.dark_frame.as_deref(), options.flat_field.as_deref());
+        let defect_maps = DefectMaps::new(app_handle);
         match panic::catch_unwind(move || {
             crate::raw_processing::develop_raw_image(
                 bytes,
                 use_fast_raw_dev,
+                demosaic,
+                camera_profile.as_ref(),
+                &calibration,
+                defect_maps.as_ref(),
+                detect_defects,
                 highlight_compression,
+                reconstruct_highlights,
                 linear_mode,
                 cancel_token,
             )
@@ -732,6 +812,73 @@ pub fn composite_patches_on_image(
     Ok(composited_image)
 }
 
+/// Re-develops the open raw when its demosaic, camera profile or calibration frame adjustments
+/// changed since it was decoded, and drops the caches built from the previous development.
+pub fn redevelop_if_options_changed(
+    state: &AppState,
+    adjustments: &Value,
+    settings: &AppSettings,
+    app_handle: &AppHandle,
+) -> Result<(), String> {
+    let options = RawDevelopOptions::from_adjustments(adjustments);
+    let path = match state.original_image.lock().unwrap().as_ref() {
+        Some(loaded) if loaded.is_raw && loaded.develop_options != options => loaded.path.clone(),
+        _ => return Ok(()),
+    };
+
+    let (source_path, _) = parse_virtual_path(&path);
+    let source_path_str = source_path.to_string_lossy().to_string();
+    let cache_key = decoded_cache_key(&source_path_str, &options);
+
+    let cached = state.decoded_image_cache.lock().unwrap().get(&cache_key);
+    let image = match cached {
+        Some((image, _)) => image,
+        None => {
+            let start = Instant::now();
+            let bytes = read_file_mapped(&source_path).map_err(|e| e.to_string())?;
+            let image = Arc::new(
+                load_base_image_with_options(
+                    &bytes,
+                    &source_path_str,
+                    false,
+                    &options,
+                    settings,
+                    app_handle,
+                    None,
+                )
+                .map_err(|e| e.to_string())?,
+            );
+            let exif = exif_processing::read_exif_data(&source_path_str, &bytes);
+            state
+                .decoded_image_cache
+                .lock()
+                .unwrap()
+                .insert(cache_key, image.clone(), exif);
+            log::info!(
+                "Re-developed '{}' with {:?} in {:?}",
+                source_path_str,
+                options,
+                start.elapsed()
+            );
+            image
+        }
+    };
+
+    match state.original_image.lock().unwrap().as_mut() {
+        Some(loaded) if loaded.path == path => {
+            loaded.image = image;
+            loaded.develop_options = options;
+        }
+        _ => return Ok(()),
+    }
+    *state.cached_preview.lock().unwrap() = None;
+    *state.gpu_image_cache.lock().unwrap() = None;
+    *state.full_warped_cache.lock().unwrap() = None;
+    *state.full_transformed_cache.lock().unwrap() = None;
+    state.geometry_cache.lock().unwrap().clear();
+    Ok(())
+}
+
 #[tauri::command]
 pub fn is_image_cached(path: String, state: tauri::State<'_, AppState>) -> bool {
     let (source_path, _) = parse_virtual_path(&path);
@@ -778,12 +925,12 @@ pub async fn load_image(
     let settings = load_settings(app_handle.clone()).unwrap_or_default();
 
     let path_clone = source_path_str.clone();
+    let develop_options = RawDevelopOptions::from_adjustments(&metadata.adjustments);
+    let cache_key = decoded_cache_key(&source_path_str, &develop_options);
+    let options_clone = develop_options.clone();
+    let app_handle_clone = app_handle.clone();
 
-    let cached_data = state
-        .decoded_image_cache
-        .lock()
-        .unwrap()
-        .get(&source_path_str);
+    let cached_data = state.decoded_image_cache.lock().unwrap().get(&cache_key);
 
     let (pristine_arc, exif_data) = if let Some((cached_img, cached_exif)) = cached_data {
         (cached_img, cached_exif)
@@ -807,11 +954,13 @@ pub async fn load_image(
                             return Err("Load cancelled".to_string());
                         }
 
-                        let img = load_base_image_from_bytes(
+                        let img = load_base_image_with_options(
                             &mmap,
                             &path_clone,
                             false,
+                            &options_clone,
                             &settings,
+                            &app_handle_clone,
                             cancel_token.clone(),
                         )
                         .map_err(|e| e.to_string())?;
@@ -832,11 +981,13 @@ pub async fn load_image(
                             return Err("Load cancelled".to_string());
                         }
 
-                        let img = load_base_image_from_bytes(
+                        let img = load_base_image_with_options(
                             &bytes,
                             &path_clone,
                             false,
+                            &options_clone,
                             &settings,
+                            &app_handle_clone,
                             cancel_token.clone(),
                         )
                         .map_err(|e| e.to_string())?;
@@ -852,7 +1003,7 @@ pub async fn load_image(
         let arc_img = Arc::new(pristine_img);
 
         state.decoded_image_cache.lock().unwrap().insert(
-            source_path_str.clone(),
+            cache_key,
             arc_img.clone(),
             exif_data_loaded.clone(),
         );
@@ -876,6 +1027,7 @@ pub async fn load_image(
         path,
         image: pristine_arc,
         is_raw,
+        develop_options,
     });
 
     Ok(LoadImageResult {
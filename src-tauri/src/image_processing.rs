@@ -1505,6 +1505,12 @@ pub struct GlobalAdjustments {
     pub halation_amount: f32,
     pub flare_amount: f32,
     pub sharpness_threshold: f32,
+
+    pub output_transfer: u32,
+    pub output_gamma: f32,
+    _pad_out1: f32,
+    _pad_out2: f32,
+    pub output_gamut_matrix: GpuMat3,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, Pod, Zeroable, Default)]
@@ -1731,19 +1737,19 @@ fn convert_points_to_aligned(frontend_points: Vec<serde_json::Value>) -> [Point;
     aligned_points
 }
 
-const WP_D65: Vec2 = Vec2::new(0.3127, 0.3290);
-const PRIMARIES_SRGB: [Vec2; 3] = [
+pub(crate) const WP_D65: Vec2 = Vec2::new(0.3127, 0.3290);
+pub(crate) const PRIMARIES_SRGB: [Vec2; 3] = [
     Vec2::new(0.64, 0.33),
     Vec2::new(0.30, 0.60),
     Vec2::new(0.15, 0.06),
 ];
-const PRIMARIES_REC2020: [Vec2; 3] = [
+pub(crate) const PRIMARIES_REC2020: [Vec2; 3] = [
     Vec2::new(0.708, 0.292),
     Vec2::new(0.170, 0.797),
     Vec2::new(0.131, 0.046),
 ];
 
-fn xy_to_xyz(xy: Vec2) -> Vec3 {
+pub(crate) fn xy_to_xyz(xy: Vec2) -> Vec3 {
     if xy.y < 1e-6 {
         Vec3::ZERO
     } else {
@@ -1751,7 +1757,7 @@ fn xy_to_xyz(xy: Vec2) -> Vec3 {
     }
 }
 
-fn primaries_to_xyz_matrix(primaries: &[Vec2; 3], white_point: Vec2) -> Mat3 {
+pub(crate) fn primaries_to_xyz_matrix(primaries: &[Vec2; 3], white_point: Vec2) -> Mat3 {
     let r_xyz = xy_to_xyz(primaries[0]);
     let g_xyz = xy_to_xyz(primaries[1]);
     let b_xyz = xy_to_xyz(primaries[2]);
@@ -1772,7 +1778,7 @@ fn rotate_and_scale_primary(primary: Vec2, white_point: Vec2, scale: f32, rotati
     white_point + p_rotated
 }
 
-fn mat3_to_gpu_mat3(m: Mat3) -> GpuMat3 {
+pub(crate) fn mat3_to_gpu_mat3(m: Mat3) -> GpuMat3 {
     GpuMat3 {
         col0: [m.x_axis.x, m.x_axis.y, m.x_axis.z, 0.0],
         col1: [m.y_axis.x, m.y_axis.y, m.y_axis.z, 0.0],
@@ -1780,7 +1786,7 @@ fn mat3_to_gpu_mat3(m: Mat3) -> GpuMat3 {
     }
 }
 
-fn calculate_agx_matrices_glam() -> (Mat3, Mat3) {
+pub(crate) fn calculate_agx_matrices_glam() -> (Mat3, Mat3) {
     let pipe_work_profile_to_xyz = primaries_to_xyz_matrix(&PRIMARIES_SRGB, WP_D65);
     let base_profile_to_xyz = primaries_to_xyz_matrix(&PRIMARIES_REC2020, WP_D65);
     let xyz_to_base_profile = base_profile_to_xyz.inverse();
@@ -2328,6 +2334,12 @@ fn get_global_adjustments_from_json(
             SCALES.sharpness_threshold,
             Some(15.0),
         ),
+
+        output_transfer: 0,
+        output_gamma: 0.0,
+        _pad_out1: 0.0,
+        _pad_out2: 0.0,
+        output_gamut_matrix: GpuMat3::default(),
     }
 }
 
@@ -0,0 +1,825 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::app_settings::load_settings;
+use crate::denoising::denoise_and_save;
+use crate::export_processing::{ExportAdjustmentsMode, ExportSettings, export_images_impl};
+use crate::image_processing::get_or_init_gpu_context;
+use crate::negative_conversion::{NegativeConversionParams, convert_negative_and_save};
+use crate::tagging::index_image_tags;
+
+pub const EXPORT_TASK: &str = "export";
+const JOB_TASK: &str = "job";
+const QUEUE_FILE_NAME: &str = "job_queue.json";
+const DEFAULT_MAX_RETRIES: u32 = 2;
+const HISTORY_LIMIT: usize = 100;
+pub const EXPORT_CANCELLED: &str = "Export cancelled";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancellationRequest {
+    Requested,
+    AlreadyRequested,
+    NoActiveTask,
+}
+
+/// Cancellation tokens of the running long tasks, keyed by task kind. Only one task of each
+/// kind can be active at a time.
+#[derive(Default)]
+pub struct TaskRegistry {
+    tokens: Mutex<HashMap<&'static str, Arc<AtomicBool>>>,
+}
+
+impl TaskRegistry {
+    /// Returns `None` if a task of this kind is already running.
+    pub fn register(&self, task: &'static str) -> Option<Arc<AtomicBool>> {
+        let mut tokens = self.tokens.lock().unwrap();
+        if tokens.contains_key(task) {
+            return None;
+        }
+        let token = Arc::new(AtomicBool::new(false));
+        tokens.insert(task, Arc::clone(&token));
+        Some(token)
+    }
+
+    pub fn is_active(&self, task: &str) -> bool {
+        self.tokens.lock().unwrap().contains_key(task)
+    }
+
+    pub fn request_cancellation<F>(&self, task: &str, on_requested: F) -> CancellationRequest
+    where
+        F: FnOnce(),
+    {
+        let tokens = self.tokens.lock().unwrap();
+        let Some(token) = tokens.get(task) else {
+            return CancellationRequest::NoActiveTask;
+        };
+
+        if token.swap(true, Ordering::SeqCst) {
+            CancellationRequest::AlreadyRequested
+        } else {
+            on_requested();
+            CancellationRequest::Requested
+        }
+    }
+
+    /// Unregisters the task if `token` still belongs to the active one. `on_finish` is told
+    /// whether the task was cancelled.
+    pub fn finish<F>(&self, task: &str, token: &Arc<AtomicBool>, on_finish: F) -> bool
+    where
+        F: FnOnce(bool),
+    {
+        let mut tokens = self.tokens.lock().unwrap();
+        match tokens.get(task) {
+            Some(current) if Arc::ptr_eq(current, token) => {}
+            _ => return false,
+        }
+        tokens.remove(task);
+
+        on_finish(token.load(Ordering::SeqCst));
+        true
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum JobKind {
+    #[serde(rename_all = "camelCase")]
+    Export {
+        output_folder: String,
+        #[serde(default)]
+        base_origin_folders: Vec<String>,
+        export_settings: ExportSettings,
+        output_format: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Denoise {
+        intensity: f32,
+        method: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    ConvertNegatives {
+        params: NegativeConversionParams,
+    },
+    Tagging,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum JobItemStatus {
+    Pending,
+    Done,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JobItem {
+    pub path: String,
+    pub status: JobItemStatus,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub output: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    pub id: String,
+    pub name: String,
+    pub kind: JobKind,
+    #[serde(default)]
+    pub priority: i32,
+    pub status: JobStatus,
+    pub items: Vec<JobItem>,
+    #[serde(default)]
+    pub max_retries: u32,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub created_at: String,
+    #[serde(default)]
+    pub finished_at: Option<String>,
+}
+
+#[derive(Default)]
+pub struct JobQueueState {
+    jobs: Mutex<Vec<Job>>,
+    wake: Notify,
+    /// Set while the running job owns the active export, so pausing it never cancels an
+    /// export started from the export panel.
+    exporting: AtomicBool,
+}
+
+fn queue_file_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
+    Ok(data_dir.join(QUEUE_FILE_NAME))
+}
+
+fn load_queue(app_handle: &AppHandle) -> Vec<Job> {
+    let Ok(path) = queue_file_path(app_handle) else {
+        return Vec::new();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        log::warn!("Ignoring unreadable job queue at {}: {}", path.display(), e);
+        Vec::new()
+    })
+}
+
+fn save_queue(app_handle: &AppHandle, jobs: &[Job]) -> Result<(), String> {
+    let path = queue_file_path(app_handle)?;
+    let json = serde_json::to_string_pretty(jobs).map_err(|e| e.to_string())?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json).map_err(|e| e.to_string())?;
+    fs::rename(&tmp_path, &path).map_err(|e| e.to_string())
+}
+
+/// Runs `f` on the queue and persists the result, so a crash never loses more than the item
+/// that was being processed.
+fn with_jobs<R>(app_handle: &AppHandle, f: impl FnOnce(&mut Vec<Job>) -> R) -> R {
+    let state = app_handle.state::<AppState>();
+    let mut jobs = state.job_queue.jobs.lock().unwrap();
+    let result = f(&mut jobs);
+    if let Err(e) = save_queue(app_handle, &jobs) {
+        log::warn!("Failed to save job queue: {}", e);
+    }
+    result
+}
+
+fn emit_queue_updated(app_handle: &AppHandle) {
+    let _ = app_handle.emit("job-queue-updated", ());
+}
+
+fn find_job<'a>(jobs: &'a mut [Job], id: &str) -> Result<&'a mut Job, String> {
+    jobs.iter_mut()
+        .find(|job| job.id == id)
+        .ok_or_else(|| format!("Job not found: {}", id))
+}
+
+fn next_queued_job(app_handle: &AppHandle) -> Option<String> {
+    let state = app_handle.state::<AppState>();
+    let jobs = state.job_queue.jobs.lock().unwrap();
+    // Highest priority first; `max_by_key` keeps the last maximum, so walk oldest-last.
+    jobs.iter()
+        .rev()
+        .filter(|job| job.status == JobStatus::Queued)
+        .max_by_key(|job| job.priority)
+        .map(|job| job.id.clone())
+}
+
+fn pending_paths(app_handle: &AppHandle, job_id: &str) -> Vec<String> {
+    let state = app_handle.state::<AppState>();
+    let jobs = state.job_queue.jobs.lock().unwrap();
+    jobs.iter()
+        .find(|job| job.id == job_id)
+        .map(|job| {
+            job.items
+                .iter()
+                .filter(|item| item.status == JobItemStatus::Pending)
+                .map(|item| item.path.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Failed items stay pending until they have been retried `max_retries` times.
+fn record_item_result(
+    app_handle: &AppHandle,
+    job_id: &str,
+    path: &str,
+    result: Result<Option<String>, String>,
+) {
+    let progress = with_jobs(app_handle, |jobs| {
+        let job = find_job(jobs, job_id).ok()?;
+        let max_retries = job.max_retries;
+        let item = job
+            .items
+            .iter_mut()
+            .find(|item| item.path == path && item.status == JobItemStatus::Pending)?;
+        match result {
+            Ok(output) => {
+                item.status = JobItemStatus::Done;
+                item.error = None;
+                item.output = output;
+            }
+            Err(error) => {
+                item.attempts += 1;
+                item.error = Some(error);
+                if item.attempts > max_retries {
+                    item.status = JobItemStatus::Failed;
+                }
+            }
+        }
+        let status = item.status;
+        let indexing = matches!(job.kind, JobKind::Tagging);
+        let finished = job
+            .items
+            .iter()
+            .filter(|item| item.status != JobItemStatus::Pending)
+            .count();
+        let progress = serde_json::json!({
+            "jobId": job_id,
+            "path": path,
+            "status": status,
+            "current": finished,
+            "total": job.items.len(),
+        });
+        Some((progress, indexing))
+    });
+    if let Some((progress, indexing)) = progress {
+        // Tagging jobs also drive the indexing indicator of the library search.
+        if indexing {
+            let _ = app_handle.emit("indexing-progress", &progress);
+        }
+        let _ = app_handle.emit("job-progress", progress);
+    }
+}
+
+async fn run_export_job(
+    app_handle: &AppHandle,
+    job_id: &str,
+    kind: &JobKind,
+    token: &AtomicBool,
+) -> Result<(), String> {
+    let JobKind::Export {
+        output_folder,
+        base_origin_folders,
+        export_settings,
+        output_format,
+    } = kind
+    else {
+        return Err("Not an export job.".to_string());
+    };
+    let state = app_handle.state::<AppState>();
+    fs::create_dir_all(output_folder)
+        .map_err(|e| format!("Failed to create output directory: {}", e))?;
+
+    loop {
+        // Wait for exports started from the export panel to finish.
+        while state.task_registry.is_active(EXPORT_TASK) {
+            if token.load(Ordering::SeqCst) {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+        if token.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let paths = pending_paths(app_handle, job_id);
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        state.job_queue.exporting.store(true, Ordering::SeqCst);
+        let started = export_images_impl(
+            paths.clone(),
+            output_folder.clone(),
+            false,
+            base_origin_folders.clone(),
+            export_settings.clone(),
+            output_format.clone(),
+            None,
+            ExportAdjustmentsMode::UseSidecars {
+                active_path: None,
+                active_adjustments: None,
+            },
+            app_handle.state::<AppState>(),
+            app_handle.clone(),
+            Some(tx),
+        )
+        .await;
+        let outcome = match started {
+            Ok(()) => rx.await,
+            // Another export started in the meantime.
+            Err(_) if state.task_registry.is_active(EXPORT_TASK) => {
+                state.job_queue.exporting.store(false, Ordering::SeqCst);
+                continue;
+            }
+            Err(e) => {
+                state.job_queue.exporting.store(false, Ordering::SeqCst);
+                return Err(e);
+            }
+        };
+        state.job_queue.exporting.store(false, Ordering::SeqCst);
+
+        let failures: HashMap<String, String> = match outcome {
+            Ok(Ok(())) => HashMap::new(),
+            Ok(Err(failures)) => failures.into_iter().map(|f| (f.path, f.error)).collect(),
+            Err(_) => return Ok(()),
+        };
+        // Items skipped by a cancelled export stay pending for the next run.
+        let interrupted = failures.values().any(|error| error == EXPORT_CANCELLED);
+        for path in paths {
+            match failures.get(&path) {
+                None => record_item_result(app_handle, job_id, &path, Ok(None)),
+                Some(_) if interrupted => {}
+                Some(error) => record_item_result(app_handle, job_id, &path, Err(error.clone())),
+            }
+        }
+        if interrupted {
+            return Ok(());
+        }
+    }
+}
+
+type ItemProcessor = Arc<dyn Fn(&str) -> Result<Option<String>, String> + Send + Sync>;
+
+/// Builds the per-item work of a job, along with how many items may run at once.
+async fn item_processor(
+    app_handle: &AppHandle,
+    kind: &JobKind,
+) -> Result<(ItemProcessor, usize), String> {
+    let state = app_handle.state::<AppState>();
+    let app = app_handle.clone();
+    let mut concurrency = 1;
+    let processor: ItemProcessor = match kind.clone() {
+        JobKind::Denoise { intensity, method } => {
+            let ai_session = if method == "ai" {
+                Some(
+                    crate::ai_processing::get_or_init_denoise_model(
+                        app_handle,
+                        &state.ai_state,
+                        &state.ai_init_lock,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?,
+                )
+            } else {
+                None
+            };
+            Arc::new(move |path: &str| {
+                denoise_and_save(path, intensity, &method, &app, ai_session.clone(), None).map(Some)
+            })
+        }
+        JobKind::ConvertNegatives { params } => {
+            Arc::new(move |path: &str| convert_negative_and_save(path, &params, &app).map(Some))
+        }
+        JobKind::Tagging => {
+            let settings = load_settings(app_handle.clone()).unwrap_or_default();
+            let clip_models = crate::ai_processing::get_or_init_clip_models(
+                app_handle,
+                &state.ai_state,
+                &state.ai_init_lock,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+            let gpu_context = get_or_init_gpu_context(&state, app_handle).ok();
+            let custom_ai_tags = settings.custom_ai_tags;
+            let ai_tag_count = settings.ai_tag_count.unwrap_or(10) as usize;
+            concurrency = settings.tagging_thread_count.unwrap_or(3).max(1) as usize;
+            Arc::new(move |path: &str| {
+                index_image_tags(
+                    path,
+                    &app,
+                    &clip_models,
+                    gpu_context.as_ref(),
+                    custom_ai_tags.clone(),
+                    ai_tag_count,
+                )
+                .map(|_| None)
+            })
+        }
+        JobKind::Export { .. } => return Err("Exports are not processed per item.".to_string()),
+    };
+    Ok((processor, concurrency))
+}
+
+async fn run_item_job(
+    app_handle: &AppHandle,
+    job_id: &str,
+    kind: &JobKind,
+    token: &AtomicBool,
+) -> Result<(), String> {
+    let (processor, concurrency) = item_processor(app_handle, kind).await?;
+    let indexing = matches!(kind, JobKind::Tagging);
+    if indexing {
+        let _ = app_handle.emit("indexing-started", ());
+    }
+    loop {
+        if token.load(Ordering::SeqCst) {
+            break;
+        }
+        let batch: Vec<String> = pending_paths(app_handle, job_id)
+            .into_iter()
+            .take(concurrency)
+            .collect();
+        if batch.is_empty() {
+            break;
+        }
+        let results = futures::future::join_all(batch.into_iter().map(|path| {
+            let processor = Arc::clone(&processor);
+            let item_path = path.clone();
+            async move {
+                let result = tauri::async_runtime::spawn_blocking(move || processor(&item_path))
+                    .await
+                    .map_err(|e| format!("Task failed: {}", e))
+                    .and_then(|result| result);
+                (path, result)
+            }
+        }))
+        .await;
+        for (path, result) in results {
+            record_item_result(app_handle, job_id, &path, result);
+        }
+    }
+    if indexing {
+        let _ = app_handle.emit("indexing-finished", ());
+    }
+    Ok(())
+}
+
+async fn run_job(app_handle: &AppHandle, job_id: &str) {
+    let state = app_handle.state::<AppState>();
+    let Some(token) = state.task_registry.register(JOB_TASK) else {
+        return;
+    };
+
+    let kind = with_jobs(app_handle, |jobs| {
+        let job = find_job(jobs, job_id).ok()?;
+        job.status = JobStatus::Running;
+        job.error = None;
+        Some(job.kind.clone())
+    });
+    emit_queue_updated(app_handle);
+
+    let result = match &kind {
+        Some(kind @ JobKind::Export { .. }) => {
+            run_export_job(app_handle, job_id, kind, &token).await
+        }
+        Some(kind) => run_item_job(app_handle, job_id, kind, &token).await,
+        None => Ok(()),
+    };
+    state.task_registry.finish(JOB_TASK, &token, |_| {});
+
+    let finished = with_jobs(app_handle, |jobs| {
+        let job = find_job(jobs, job_id).ok()?;
+        // Paused or cancelled while running.
+        if job.status != JobStatus::Running {
+            return None;
+        }
+        if let Err(e) = result {
+            log::error!("Job '{}' failed: {}", job.name, e);
+            job.status = JobStatus::Failed;
+            job.error = Some(e);
+        } else if job
+            .items
+            .iter()
+            .any(|item| item.status == JobItemStatus::Pending)
+        {
+            job.status = JobStatus::Paused;
+        } else if job
+            .items
+            .iter()
+            .any(|item| item.status == JobItemStatus::Failed)
+        {
+            job.status = JobStatus::Failed;
+        } else {
+            job.status = JobStatus::Completed;
+        }
+        let finished = job.status.is_finished().then(|| {
+            job.finished_at = Some(chrono::Utc::now().to_rfc3339());
+            serde_json::json!({
+                "jobId": job_id,
+                "status": job.status,
+                "folders": output_folders(job),
+            })
+        });
+        prune_history(jobs);
+        finished
+    });
+    if let Some(finished) = finished {
+        let _ = app_handle.emit("job-finished", finished);
+    }
+    emit_queue_updated(app_handle);
+}
+
+/// Folders the job wrote files into, so the library only reloads when it shows one of them.
+fn output_folders(job: &Job) -> Vec<String> {
+    let mut folders: Vec<String> = match &job.kind {
+        JobKind::Export { output_folder, .. } => vec![output_folder.clone()],
+        _ => job
+            .items
+            .iter()
+            .filter_map(|item| Path::new(item.output.as_deref()?).parent())
+            .map(|folder| folder.to_string_lossy().into_owned())
+            .collect(),
+    };
+    folders.sort();
+    folders.dedup();
+    folders
+}
+
+fn prune_history(jobs: &mut Vec<Job>) {
+    let finished = jobs.iter().filter(|job| job.status.is_finished()).count();
+    let mut to_remove = finished.saturating_sub(HISTORY_LIMIT);
+    jobs.retain(|job| {
+        if to_remove > 0 && job.status.is_finished() {
+            to_remove -= 1;
+            false
+        } else {
+            true
+        }
+    });
+}
+
+/// Restores the persisted queue and processes it one job at a time. Jobs that were running when
+/// the app last closed resume with their unfinished items.
+pub fn start_job_worker(app_handle: AppHandle) {
+    let mut restored = load_queue(&app_handle);
+    for job in restored
+        .iter_mut()
+        .filter(|job| job.status == JobStatus::Running)
+    {
+        job.status = JobStatus::Queued;
+    }
+    *app_handle
+        .state::<AppState>()
+        .job_queue
+        .jobs
+        .lock()
+        .unwrap() = restored;
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match next_queued_job(&app_handle) {
+                Some(job_id) => run_job(&app_handle, &job_id).await,
+                None => {
+                    app_handle
+                        .state::<AppState>()
+                        .job_queue
+                        .wake
+                        .notified()
+                        .await
+                }
+            }
+        }
+    });
+}
+
+fn wake_worker(app_handle: &AppHandle) {
+    app_handle.state::<AppState>().job_queue.wake.notify_one();
+}
+
+fn interrupt_running_job(app_handle: &AppHandle) {
+    let state = app_handle.state::<AppState>();
+    state.task_registry.request_cancellation(JOB_TASK, || {});
+    if state.job_queue.exporting.load(Ordering::SeqCst) {
+        state.task_registry.request_cancellation(EXPORT_TASK, || {
+            let _ = app_handle.emit("export-cancelling", ());
+        });
+    }
+}
+
+/// Tagging follows the folder being browsed, so a new tagging job cancels any unfinished one
+/// instead of piling up behind it. Returns whether the running job was cancelled.
+fn supersede_tagging_jobs(jobs: &mut Vec<Job>) -> bool {
+    let mut interrupted = false;
+    for job in jobs
+        .iter_mut()
+        .filter(|job| matches!(job.kind, JobKind::Tagging) && !job.status.is_finished())
+    {
+        interrupted |= job.status == JobStatus::Running;
+        job.status = JobStatus::Cancelled;
+        job.finished_at = Some(chrono::Utc::now().to_rfc3339());
+    }
+    prune_history(jobs);
+    interrupted
+}
+
+#[tauri::command]
+pub fn enqueue_job(
+    kind: JobKind,
+    paths: Vec<String>,
+    name: Option<String>,
+    priority: Option<i32>,
+    max_retries: Option<u32>,
+    app_handle: AppHandle,
+) -> Result<Job, String> {
+    if paths.is_empty() {
+        return Err("No images given for the job.".to_string());
+    }
+    let name = name.unwrap_or_else(|| {
+        let action = match &kind {
+            JobKind::Export { .. } => "Export",
+            JobKind::Denoise { .. } => "Denoise",
+            JobKind::ConvertNegatives { .. } => "Convert negatives",
+            JobKind::Tagging => "AI tagging",
+        };
+        format!("{} ({} images)", action, paths.len())
+    });
+    let job = Job {
+        id: Uuid::new_v4().to_string(),
+        name,
+        kind,
+        priority: priority.unwrap_or(0),
+        status: JobStatus::Queued,
+        items: paths
+            .into_iter()
+            .map(|path| JobItem {
+                path,
+                status: JobItemStatus::Pending,
+                attempts: 0,
+                error: None,
+                output: None,
+            })
+            .collect(),
+        max_retries: max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+        error: None,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        finished_at: None,
+    };
+
+    let interrupted = with_jobs(&app_handle, |jobs| {
+        let superseded = if matches!(job.kind, JobKind::Tagging) {
+            supersede_tagging_jobs(jobs)
+        } else {
+            false
+        };
+        jobs.push(job.clone());
+        superseded
+    });
+    if interrupted {
+        interrupt_running_job(&app_handle);
+    }
+    emit_queue_updated(&app_handle);
+    wake_worker(&app_handle);
+    Ok(job)
+}
+
+#[tauri::command]
+pub fn get_job_queue(app_handle: AppHandle) -> Vec<Job> {
+    app_handle
+        .state::<AppState>()
+        .job_queue
+        .jobs
+        .lock()
+        .unwrap()
+        .clone()
+}
+
+#[tauri::command]
+pub fn pause_job(id: String, app_handle: AppHandle) -> Result<(), String> {
+    let interrupted = with_jobs(&app_handle, |jobs| {
+        let job = find_job(jobs, &id)?;
+        match job.status {
+            JobStatus::Queued => {
+                job.status = JobStatus::Paused;
+                Ok(false)
+            }
+            JobStatus::Running => {
+                job.status = JobStatus::Paused;
+                Ok(true)
+            }
+            _ => Err("Only queued or running jobs can be paused.".to_string()),
+        }
+    })?;
+    if interrupted {
+        interrupt_running_job(&app_handle);
+    }
+    emit_queue_updated(&app_handle);
+    Ok(())
+}
+
+/// Requeues a paused job, or retries the failed items of a failed job.
+#[tauri::command]
+pub fn resume_job(id: String, app_handle: AppHandle) -> Result<(), String> {
+    with_jobs(&app_handle, |jobs| {
+        let job = find_job(jobs, &id)?;
+        match job.status {
+            JobStatus::Paused => {}
+            JobStatus::Failed => {
+                for item in job
+                    .items
+                    .iter_mut()
+                    .filter(|item| item.status == JobItemStatus::Failed)
+                {
+                    item.status = JobItemStatus::Pending;
+                    item.attempts = 0;
+                }
+                job.finished_at = None;
+            }
+            _ => return Err("Only paused or failed jobs can be resumed.".to_string()),
+        }
+        job.status = JobStatus::Queued;
+        Ok(())
+    })?;
+    emit_queue_updated(&app_handle);
+    wake_worker(&app_handle);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cancel_job(id: String, app_handle: AppHandle) -> Result<(), String> {
+    let interrupted = with_jobs(&app_handle, |jobs| {
+        let job = find_job(jobs, &id)?;
+        if job.status.is_finished() {
+            return Err("The job has already finished.".to_string());
+        }
+        let was_running = job.status == JobStatus::Running;
+        job.status = JobStatus::Cancelled;
+        job.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        Ok(was_running)
+    })?;
+    if interrupted {
+        interrupt_running_job(&app_handle);
+    }
+    emit_queue_updated(&app_handle);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_job_priority(id: String, priority: i32, app_handle: AppHandle) -> Result<(), String> {
+    with_jobs(&app_handle, |jobs| {
+        find_job(jobs, &id)?.priority = priority;
+        Ok::<(), String>(())
+    })?;
+    emit_queue_updated(&app_handle);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn clear_job_history(app_handle: AppHandle) {
+    with_jobs(&app_handle, |jobs| {
+        jobs.retain(|job| !job.status.is_finished())
+    });
+    emit_queue_updated(&app_handle);
+}
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use tauri::Emitter;
 
+use crate::export_processing::ResizeMode;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExternalEditSession {
@@ -11,14 +13,75 @@ pub struct ExternalEditSession {
     pub jpeg_quality: u8,
 }
 
+/// Options left as `None` fall back to the named preset, or to the built-in headless defaults
+/// when no preset is given.
 #[derive(Clone, Debug)]
 pub struct HeadlessExportSession {
     pub source: String,
     pub output: String,
-    pub format: String,
-    pub quality: u8,
-    pub keep_metadata: bool,
+    pub preset: Option<String>,
+    pub format: Option<String>,
+    pub quality: Option<u8>,
+    pub keep_metadata: Option<bool>,
+    pub strip_gps: Option<bool>,
+    pub resize: Option<u32>,
+    pub resize_mode: Option<ResizeMode>,
+    pub allow_enlarge: bool,
+    pub filename_template: Option<String>,
+    pub preserve_folders: Option<bool>,
+    pub export_masks: Option<bool>,
+    pub watermark_image: Option<String>,
+    pub watermark_text: Option<String>,
     pub adjustments_override: Option<String>,
+    pub sync: bool,
+    pub delete_removed: bool,
+}
+
+#[derive(Clone, Debug)]
+pub enum HeadlessEditAction {
+    /// Preset name or ID from the preset library, or a path to a preset file.
+    ApplyPreset(String),
+    Auto,
+    Reset,
+}
+
+#[derive(Clone, Debug)]
+pub struct HeadlessEditSession {
+    pub action: HeadlessEditAction,
+    pub paths: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct HeadlessInfoSession {
+    pub paths: Vec<String>,
+    pub json: bool,
+}
+
+/// Options left as `None` fall back to the hot folder saved in the settings.
+#[derive(Clone, Debug, Default)]
+pub struct HeadlessWatchSession {
+    pub input: Option<String>,
+    pub output: Option<String>,
+    pub preset: Option<String>,
+    pub export_preset: Option<String>,
+    pub auto_adjust: Option<bool>,
+    pub archive: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct HeadlessRecipeSession {
+    /// Recipe name or ID from the settings, or a path to a recipe file.
+    pub recipe: String,
+    pub paths: Vec<String>,
+    pub output: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct HeadlessScriptSession {
+    pub script: String,
+    pub paths: Vec<String>,
+    pub dry_run: bool,
+    pub output: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -27,6 +90,25 @@ pub enum LaunchRequest {
     OpenFile(String),
     EditSession(ExternalEditSession),
     HeadlessExport(HeadlessExportSession),
+    HeadlessEdit(HeadlessEditSession),
+    HeadlessInfo(HeadlessInfoSession),
+    HeadlessWatch(HeadlessWatchSession),
+    HeadlessRecipe(HeadlessRecipeSession),
+    HeadlessScript(HeadlessScriptSession),
+}
+
+impl LaunchRequest {
+    pub fn is_headless(&self) -> bool {
+        matches!(
+            self,
+            LaunchRequest::HeadlessExport(_)
+                | LaunchRequest::HeadlessEdit(_)
+                | LaunchRequest::HeadlessInfo(_)
+                | LaunchRequest::HeadlessWatch(_)
+                | LaunchRequest::HeadlessRecipe(_)
+                | LaunchRequest::HeadlessScript(_)
+        )
+    }
 }
 
 #[derive(Serialize, Default)]
@@ -42,10 +124,22 @@ pub fn parse_launch_args(args: &[String]) -> LaunchRequest {
 
         let mut source = String::new();
         let mut output = String::new();
-        let mut format = String::from("jpeg");
-        let mut quality = 90;
-        let mut keep_metadata = false;
+        let mut preset = None;
+        let mut format = None;
+        let mut quality = None;
+        let mut keep_metadata = None;
+        let mut strip_gps = None;
+        let mut resize = None;
+        let mut resize_mode = None;
+        let mut allow_enlarge = false;
+        let mut filename_template = None;
+        let mut preserve_folders = None;
+        let mut export_masks = None;
+        let mut watermark_image = None;
+        let mut watermark_text = None;
         let mut adjustments_override = None;
+        let mut sync = false;
+        let mut delete_removed = false;
 
         if let Some(src) = iter.next()
             && !src.starts_with('-')
@@ -60,17 +154,41 @@ pub fn parse_launch_args(args: &[String]) -> LaunchRequest {
                         output = out.clone();
                     }
                 }
+                "--preset" => preset = iter.next().cloned(),
                 "--format" => {
                     if let Some(fmt) = iter.next() {
-                        format = fmt.clone();
+                        format = Some(fmt.clone());
                     }
                 }
                 "--quality" => {
                     if let Some(q) = iter.next() {
-                        quality = q.parse().unwrap_or(90);
+                        quality = Some(q.parse().unwrap_or(90));
                     }
                 }
-                "--keep-metadata" => keep_metadata = true,
+                "--keep-metadata" => keep_metadata = Some(true),
+                "--strip-metadata" => keep_metadata = Some(false),
+                "--strip-gps" => strip_gps = Some(true),
+                "--resize" => resize = iter.next().and_then(|v| v.parse().ok()),
+                "--resize-mode" => {
+                    if let Some(mode) = iter.next() {
+                        resize_mode = parse_resize_mode(mode);
+                        if resize_mode.is_none() {
+                            eprintln!("Warning: ignoring unknown resize mode '{}'", mode);
+                        }
+                    }
+                }
+                "--allow-enlarge" => allow_enlarge = true,
+                "--template" => filename_template = iter.next().cloned(),
+                "--preserve-folders" => preserve_folders = Some(true),
+                "--flatten" => preserve_folders = Some(false),
+                "--export-masks" => export_masks = Some(true),
+                "--watermark" => watermark_image = iter.next().cloned(),
+                "--watermark-text" => watermark_text = iter.next().cloned(),
+                "--sync" => sync = true,
+                "--delete-removed" => {
+                    sync = true;
+                    delete_removed = true;
+                }
                 "--adjustments" => {
                     if let Some(adj) = iter.next() {
                         adjustments_override = Some(adj.clone());
@@ -83,13 +201,29 @@ pub fn parse_launch_args(args: &[String]) -> LaunchRequest {
         return LaunchRequest::HeadlessExport(HeadlessExportSession {
             source,
             output,
+            preset,
             format,
             quality,
             keep_metadata,
+            strip_gps,
+            resize,
+            resize_mode,
+            allow_enlarge,
+            filename_template,
+            preserve_folders,
+            export_masks,
+            watermark_image,
+            watermark_text,
             adjustments_override,
+            sync,
+            delete_removed,
         });
     }
 
+    if let Some(request) = parse_headless_subcommand(args) {
+        return request;
+    }
+
     let mut edit: Option<String> = None;
     let mut output: Option<String> = None;
     let mut format: Option<String> = None;
@@ -136,6 +270,102 @@ pub fn parse_launch_args(args: &[String]) -> LaunchRequest {
     }
 }
 
+fn parse_headless_subcommand(args: &[String]) -> Option<LaunchRequest> {
+    let (command, rest) = args.split_first()?;
+    let edit = |action: HeadlessEditAction, paths: &[String]| {
+        LaunchRequest::HeadlessEdit(HeadlessEditSession {
+            action,
+            paths: paths.to_vec(),
+        })
+    };
+    match command.as_str() {
+        "apply-preset" => {
+            let (preset, paths) = rest
+                .split_first()
+                .map(|(preset, paths)| (preset.clone(), paths))
+                .unwrap_or_default();
+            Some(edit(HeadlessEditAction::ApplyPreset(preset), paths))
+        }
+        "auto" => Some(edit(HeadlessEditAction::Auto, rest)),
+        "reset" => Some(edit(HeadlessEditAction::Reset, rest)),
+        "info" => Some(LaunchRequest::HeadlessInfo(HeadlessInfoSession {
+            paths: rest
+                .iter()
+                .filter(|a| !a.starts_with("--"))
+                .cloned()
+                .collect(),
+            json: rest.iter().any(|a| a == "--json"),
+        })),
+        "watch" => Some(LaunchRequest::HeadlessWatch(parse_watch_args(rest))),
+        "recipe" => Some(LaunchRequest::HeadlessRecipe(parse_recipe_args(rest))),
+        "script" => Some(LaunchRequest::HeadlessScript(parse_script_args(rest))),
+        _ => None,
+    }
+}
+
+fn parse_watch_args(args: &[String]) -> HeadlessWatchSession {
+    let mut session = HeadlessWatchSession::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--output" => session.output = iter.next().cloned(),
+            "--preset" => session.preset = iter.next().cloned(),
+            "--export-preset" => session.export_preset = iter.next().cloned(),
+            "--auto" => session.auto_adjust = Some(true),
+            "--no-auto" => session.auto_adjust = Some(false),
+            "--archive" => session.archive = iter.next().cloned(),
+            s if !s.starts_with('-') && session.input.is_none() => {
+                session.input = Some(s.to_string())
+            }
+            _ => {}
+        }
+    }
+    session
+}
+
+fn parse_recipe_args(args: &[String]) -> HeadlessRecipeSession {
+    let mut session = HeadlessRecipeSession {
+        recipe: String::new(),
+        paths: Vec::new(),
+        output: None,
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--output" => session.output = iter.next().cloned(),
+            s if s.starts_with('-') => {}
+            s if session.recipe.is_empty() => session.recipe = s.to_string(),
+            s => session.paths.push(s.to_string()),
+        }
+    }
+    session
+}
+
+fn parse_script_args(args: &[String]) -> HeadlessScriptSession {
+    let mut session = HeadlessScriptSession::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--dry-run" => session.dry_run = true,
+            "--output" => session.output = iter.next().cloned(),
+            s if s.starts_with('-') => {}
+            s if session.script.is_empty() => session.script = s.to_string(),
+            s => session.paths.push(s.to_string()),
+        }
+    }
+    session
+}
+
+fn parse_resize_mode(value: &str) -> Option<ResizeMode> {
+    match value.to_lowercase().replace(['-', '_'], "").as_str() {
+        "longedge" | "long" => Some(ResizeMode::LongEdge),
+        "shortedge" | "short" => Some(ResizeMode::ShortEdge),
+        "width" => Some(ResizeMode::Width),
+        "height" => Some(ResizeMode::Height),
+        _ => None,
+    }
+}
+
 fn handle_file_open(app_handle: &tauri::AppHandle, path: PathBuf) {
     if let Some(path_str) = path.to_str()
         && let Err(e) = app_handle.emit("open-with-file", path_str)
@@ -154,9 +384,14 @@ pub fn emit_launch_request(app_handle: &tauri::AppHandle, request: LaunchRequest
         LaunchRequest::OpenFile(path) => {
             handle_file_open(app_handle, PathBuf::from(path));
         }
-        LaunchRequest::HeadlessExport(_) => {
+        LaunchRequest::HeadlessExport(_)
+        | LaunchRequest::HeadlessEdit(_)
+        | LaunchRequest::HeadlessInfo(_)
+        | LaunchRequest::HeadlessWatch(_)
+        | LaunchRequest::HeadlessRecipe(_)
+        | LaunchRequest::HeadlessScript(_) => {
             println!(
-                "Error: Headless export cannot be attached to an already running GUI instance."
+                "Error: Headless commands cannot be attached to an already running GUI instance."
             );
         }
         LaunchRequest::None => {}
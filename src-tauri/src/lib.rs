@@ -12,20 +12,33 @@ mod ai_processing;
 mod android_integration;
 mod app_settings;
 mod app_state;
+mod automation_server;
 mod cache_utils;
+mod calibration_frames;
 mod camera_tethering;
+mod color_management;
 mod culling;
+mod dcp_profiles;
+mod defect_pixels;
+mod demosaic;
 mod denoising;
+mod dng_writer;
 mod exif_processing;
+mod export_manifest;
 mod export_processing;
+mod exr_output;
+mod external_editor;
 mod file_management;
 mod focus_stacking;
 mod formats;
 mod gpu_processing;
 mod hdr_deghosting;
+mod highlight_reconstruction;
+mod hot_folder;
 mod image_loader;
 mod image_processing;
 mod inpainting;
+mod job_queue;
 mod launch_request;
 mod lens_blur;
 mod lens_correction;
@@ -36,9 +49,15 @@ mod negative_conversion;
 mod panorama_stitching;
 mod panorama_utils;
 mod preset_converter;
+mod processing_recipes;
 mod raw_processing;
+mod relative_adjustments;
+mod scripting;
 mod tagging;
 mod tagging_utils;
+mod text_watermark;
+mod ultra_hdr;
+mod web_gallery;
 mod window_customizer;
 
 use std::collections::{HashMap, hash_map::DefaultHasher};
@@ -47,7 +66,7 @@ use std::hash::{Hash, Hasher};
 use std::io::Cursor;
 use std::io::Write;
 use std::panic;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::atomic::Ordering;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
 
@@ -71,7 +90,6 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::{Emitter, Manager, ipc::Response};
 use tempfile::NamedTempFile;
-use tokio::sync::Mutex as TokioMutex;
 
 #[cfg(target_os = "linux")]
 use webkit2gtk_nvidia_quirk::{
@@ -79,8 +97,11 @@ use webkit2gtk_nvidia_quirk::{
 };
 
 use crate::cache_utils::{
-    DecodedImageCache, GEOMETRY_KEYS, calculate_full_job_hash, calculate_geometry_hash,
-    calculate_transform_hash, calculate_visual_hash,
+    GEOMETRY_KEYS, calculate_full_job_hash, calculate_geometry_hash, calculate_transform_hash,
+    calculate_visual_hash,
+};
+use crate::exr_output::{
+    normalize_scene_exposure, save_merged_image, scene_referred_merges_enabled,
 };
 use crate::file_management::{parse_virtual_path, read_file_mapped};
 use crate::formats::is_raw_file;
@@ -336,6 +357,8 @@ fn process_preview_job(
     let context = get_or_init_gpu_context(&state, app_handle)?;
     hydrate_adjustments(&state, &mut adjustments_json);
     let adjustments_clone = adjustments_json;
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    image_loader::redevelop_if_options_changed(&state, &adjustments_clone, &settings, app_handle)?;
 
     let loaded_image_guard = state.original_image.lock().unwrap();
     let loaded_image = loaded_image_guard
@@ -345,7 +368,6 @@ fn process_preview_job(
     drop(loaded_image_guard);
 
     let new_transform_hash = calculate_transform_hash(&adjustments_clone);
-    let settings = load_settings(app_handle.clone()).unwrap_or_default();
     let live_quality = settings.live_preview_quality.as_deref().unwrap_or("high");
 
     let default_preview_dim = settings.editor_preview_resolution.unwrap_or(1920);
@@ -1248,6 +1270,7 @@ async fn generate_all_community_previews(
             &source_path_str,
             true,
             &settings,
+            &app_handle,
             None,
         )
         .map_err(|e| e.to_string())?;
@@ -1407,6 +1430,40 @@ async fn save_temp_file(bytes: Vec<u8>) -> Result<String, String> {
     Ok(path.to_string_lossy().to_string())
 }
 
+/// Merges a bracketed set into one image. Returns the image to save (scene-referred when that
+/// setting is on) and a display-referred version for previews.
+pub(crate) fn merge_hdr_images(
+    paths: &[String],
+    app_handle: &tauri::AppHandle,
+    settings: &AppSettings,
+) -> Result<(DynamicImage, DynamicImage), String> {
+    let mut frames = load_hdr_frames(paths, app_handle, settings)?;
+    assert_uniform_dimensions(&frames)?;
+    align_hdr_frames(&mut frames, app_handle);
+
+    let images: Vec<HDRInput> = frames
+        .iter()
+        .map(|(path, img, exposure, gains)| {
+            HDRInput::with_image(img, *exposure, *gains)
+                .map_err(|e| format!("Failed to prepare HDR input for {}: {}", path, e))
+        })
+        .collect::<Result<Vec<HDRInput>, String>>()?;
+
+    log::info!("Starting HDR merge of {} images", images.len());
+    let hdr_merged = hdr_merge_images(&mut images.into()).map_err(|e| e.to_string())?;
+    let hdr_display = apply_linear_to_srgb(
+        image_hdr::stretch::apply_histogram_stretch(&hdr_merged).map_err(|e| e.to_string())?,
+    );
+    log::info!("HDR merge completed");
+
+    let hdr_result = if scene_referred_merges_enabled(settings) {
+        normalize_scene_exposure(hdr_merged)
+    } else {
+        hdr_display.clone()
+    };
+    Ok((hdr_result, hdr_display))
+}
+
 #[tauri::command]
 async fn merge_hdr(
     paths: Vec<String>,
@@ -1420,27 +1477,10 @@ async fn merge_hdr(
     let hdr_result_handle = state.hdr_result.clone();
     let settings = load_settings(app_handle.clone()).unwrap_or_default();
 
-    let mut frames = load_hdr_frames(&paths, &app_handle, &settings)?;
-    assert_uniform_dimensions(&frames)?;
-    align_hdr_frames(&mut frames, &app_handle);
-
-    let images: Vec<HDRInput> = frames
-        .iter()
-        .map(|(path, img, exposure, gains)| {
-            HDRInput::with_image(img, *exposure, *gains)
-                .map_err(|e| format!("Failed to prepare HDR input for {}: {}", path, e))
-        })
-        .collect::<Result<Vec<HDRInput>, String>>()?;
-
-    log::info!("Starting HDR merge of {} images", images.len());
-    let mut hdr_merged = hdr_merge_images(&mut images.into()).map_err(|e| e.to_string())?;
-    hdr_merged =
-        image_hdr::stretch::apply_histogram_stretch(&hdr_merged).map_err(|e| e.to_string())?;
-    hdr_merged = apply_linear_to_srgb(hdr_merged);
-    log::info!("HDR merge completed");
+    let (hdr_result, hdr_display) = merge_hdr_images(&paths, &app_handle, &settings)?;
 
     let mut buf = Cursor::new(Vec::new());
-    if let Err(e) = hdr_merged.to_rgb8().write_to(&mut buf, ImageFormat::Png) {
+    if let Err(e) = hdr_display.to_rgb8().write_to(&mut buf, ImageFormat::Png) {
         return Err(format!("Failed to encode hdr preview: {}", e));
     }
 
@@ -1449,7 +1489,7 @@ async fn merge_hdr(
 
     let _ = app_handle.emit("hdr-progress", "Creating preview...");
 
-    *hdr_result_handle.lock().unwrap() = Some(hdr_merged);
+    *hdr_result_handle.lock().unwrap() = Some(hdr_result);
 
     let _ = app_handle.emit(
         "hdr-complete",
@@ -1464,6 +1504,7 @@ async fn merge_hdr(
 async fn save_hdr(
     first_path_str: String,
     state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
 ) -> Result<String, String> {
     let hdr_image = state.hdr_result.lock().unwrap().take().ok_or_else(|| {
         "No hdr image found in memory to save. It might have already been saved.".to_string()
@@ -1478,28 +1519,16 @@ async fn save_hdr(
         .and_then(|s| s.to_str())
         .unwrap_or("hdr");
 
-    let (output_filename, image_to_save): (String, DynamicImage) = if hdr_image.color().has_alpha()
-    {
-        (
-            format!("{}_Hdr.png", stem),
-            DynamicImage::ImageRgba8(hdr_image.to_rgba8()),
-        )
-    } else if hdr_image.as_rgb32f().is_some() {
-        (format!("{}_Hdr.tiff", stem), hdr_image)
-    } else {
-        (
-            format!("{}_Hdr.png", stem),
-            DynamicImage::ImageRgb8(hdr_image.to_rgb8()),
-        )
-    };
-
-    let output_path = parent_dir.join(output_filename);
-
-    image_to_save
-        .save(&output_path)
-        .map_err(|e| format!("Failed to save hdr image: {}", e))?;
-
+    let settings = load_settings(app_handle).unwrap_or_default();
     let (real_path, _) = crate::file_management::parse_virtual_path(&first_path_str);
+    let output_path = save_merged_image(
+        hdr_image,
+        parent_dir,
+        &format!("{}_Hdr", stem),
+        &real_path.to_string_lossy(),
+        &settings,
+    )?;
+
     let _ =
         crate::exif_processing::write_rrexif_sidecar(&real_path.to_string_lossy(), &output_path);
 
@@ -1557,6 +1586,7 @@ async fn generate_preview_for_path(
                 &js_adjustments,
                 false,
                 &settings,
+                &app_handle,
                 None,
             )
             .map_err(|e| e.to_string())?,
@@ -1573,6 +1603,7 @@ async fn generate_preview_for_path(
                     &js_adjustments,
                     false,
                     &settings,
+                    &app_handle,
                     None,
                 )
                 .map_err(|e| e.to_string())?
@@ -1911,7 +1942,7 @@ pub fn run() {
 
     let args: Vec<String> = std::env::args().skip(1).collect();
     let launch_req = parse_launch_args(&args);
-    let is_headless = matches!(launch_req, LaunchRequest::HeadlessExport(_));
+    let is_headless = launch_req.is_headless();
 
     #[cfg(not(any(target_os = "android", target_os = "ios")))]
     {
@@ -2103,6 +2134,94 @@ pub fn run() {
                 return Ok(());
             }
 
+            if let LaunchRequest::HeadlessInfo(session) = launch_req {
+                let app_handle_clone = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    match file_management::run_headless_info(session, app_handle_clone.clone()).await {
+                        Ok(_) => app_handle_clone.exit(0),
+                        Err(e) => {
+                            eprintln!("Headless info failed: {}", e);
+                            app_handle_clone.exit(1);
+                        }
+                    }
+                });
+
+                return Ok(());
+            }
+
+            if let LaunchRequest::HeadlessWatch(session) = launch_req {
+                let app_handle_clone = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = hot_folder::run_headless_watch(session, app_handle_clone.clone()).await {
+                        eprintln!("Headless watch failed: {}", e);
+                        app_handle_clone.exit(1);
+                    }
+                });
+
+                return Ok(());
+            }
+
+            if let LaunchRequest::HeadlessRecipe(session) = launch_req {
+                let app_handle_clone = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    match processing_recipes::run_headless_recipe(session, app_handle_clone.clone()).await {
+                        Ok(_) => {
+                            println!("Recipe completed successfully.");
+                            app_handle_clone.exit(0);
+                        }
+                        Err(e) => {
+                            eprintln!("Recipe failed: {}", e);
+                            app_handle_clone.exit(1);
+                        }
+                    }
+                });
+
+                return Ok(());
+            }
+
+            if let LaunchRequest::HeadlessScript(session) = launch_req {
+                let app_handle_clone = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    match scripting::run_headless_script(session, app_handle_clone.clone()).await {
+                        Ok(_) => app_handle_clone.exit(0),
+                        Err(e) => {
+                            eprintln!("Script failed: {}", e);
+                            app_handle_clone.exit(1);
+                        }
+                    }
+                });
+
+                return Ok(());
+            }
+
+            if let LaunchRequest::HeadlessEdit(session) = launch_req {
+                let app_handle_clone = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    match file_management::run_headless_edit(session, app_handle_clone.clone()).await {
+                        Ok(_) => app_handle_clone.exit(0),
+                        Err(e) => {
+                            eprintln!("Headless edit failed: {}", e);
+                            app_handle_clone.exit(1);
+                        }
+                    }
+                });
+
+                return Ok(());
+            }
+
+            if settings.automation_server_enabled.unwrap_or(false) {
+                automation_server::start_automation_server(
+                    app_handle.clone(),
+                    settings
+                        .automation_server_port
+                        .unwrap_or(automation_server::DEFAULT_AUTOMATION_PORT),
+                );
+            }
+
+            #[cfg(not(target_os = "android"))]
+            hot_folder::start_hot_folder_watcher(app_handle.clone());
+
+            job_queue::start_job_worker(app_handle.clone());
             start_preview_worker(app_handle.clone());
             start_analytics_worker(app_handle.clone());
             file_management::start_thumbnail_workers(app_handle.clone());
@@ -2269,44 +2388,7 @@ pub fn run() {
             crate::register_exit_handler();
             Ok(())
         })
-        .manage(AppState {
-            window_setup_complete: AtomicBool::new(false),
-            gpu_crash_flag_path: Mutex::new(None),
-            original_image: Mutex::new(None),
-            cached_preview: Mutex::new(None),
-            gpu_context: Mutex::new(None),
-            gpu_image_cache: Mutex::new(None),
-            gpu_processor: Mutex::new(None),
-            ai_state: Mutex::new(None),
-            ai_init_lock: TokioMutex::new(()),
-            export_task_token: Arc::new(Mutex::new(None)),
-            hdr_result: Arc::new(Mutex::new(None)),
-            panorama_result: Arc::new(Mutex::new(None)),
-            focus_stack_result: Arc::new(Mutex::new(None)),
-            denoise_result: Arc::new(Mutex::new(None)),
-            indexing_task_handle: Mutex::new(None),
-            lut_cache: Mutex::new(HashMap::new()),
-            initial_file_path: Mutex::new(None),
-            pending_edit_session: Mutex::new(None),
-            thumbnail_cancellation_token: Arc::new(AtomicBool::new(false)),
-            thumbnail_progress: Mutex::new(ThumbnailProgressTracker { total: 0, completed: 0 }),
-            preview_worker_tx: Mutex::new(None),
-            analytics_worker_tx: Mutex::new(None),
-            mask_cache: Mutex::new(HashMap::new()),
-            patch_cache: Mutex::new(HashMap::new()),
-            geometry_cache: Mutex::new(HashMap::new()),
-            thumbnail_geometry_cache: Mutex::new(HashMap::new()),
-            lens_db: Mutex::new(None),
-            load_image_generation: Arc::new(AtomicUsize::new(0)),
-            full_warped_cache: Mutex::new(None),
-            full_transformed_cache: Mutex::new(None),
-            decoded_image_cache: Mutex::new(DecodedImageCache::new(5)),
-            thumbnail_manager: ThumbnailManager::new(),
-            metadata_manager: MetadataManager::new(),
-            disks_cache: Mutex::new(None),
-            disks_cache_refreshing: AtomicBool::new(false),
-            camera_session: Mutex::new(camera_tethering::CameraSession::new()),
-        })
+        .manage(AppState::default())
         .invoke_handler(tauri::generate_handler![
             apply_adjustments,
             generate_preview_for_path,
@@ -2324,6 +2406,13 @@ pub fn run() {
             lut_processing::import_luts,
             lut_processing::remove_lut,
             lut_processing::generate_lut_previews,
+            dcp_profiles::list_camera_profiles,
+            dcp_profiles::import_camera_profiles,
+            dcp_profiles::remove_camera_profile,
+            calibration_frames::build_master_calibration_frame,
+            defect_pixels::get_defect_map_info,
+            defect_pixels::add_defects_to_map,
+            defect_pixels::clear_defect_map,
             fetch_community_presets,
             generate_all_community_previews,
             save_temp_file,
@@ -2348,7 +2437,6 @@ pub fn run() {
             inpainting::invoke_generative_replace_with_mask_def,
             inpainting::generate_manual_cleanup_patch,
             denoising::apply_denoising,
-            denoising::batch_denoise_images,
             denoising::save_denoised_image,
             focus_stacking::stitch_focus_stack,
             focus_stacking::save_focus_stack,
@@ -2357,8 +2445,21 @@ pub fn run() {
             panorama_stitching::stitch_panorama,
             panorama_stitching::save_panorama,
             export_processing::export_images,
+            export_processing::export_images_with_recipe,
+            web_gallery::export_web_gallery,
             export_processing::cancel_export,
             export_processing::estimate_export_sizes,
+            processing_recipes::load_processing_recipe_file,
+            processing_recipes::run_processing_recipe,
+            scripting::run_batch_script,
+            external_editor::edit_in_external_editor,
+            job_queue::enqueue_job,
+            job_queue::get_job_queue,
+            job_queue::pause_job,
+            job_queue::resume_job,
+            job_queue::cancel_job,
+            job_queue::set_job_priority,
+            job_queue::clear_job_history,
             image_processing::calculate_auto_adjustments,
             mask_generation::generate_mask_overlay,
             file_management::update_exif_fields,
@@ -2382,6 +2483,8 @@ pub fn run() {
             file_management::delete_files_with_associated,
             file_management::save_metadata_and_update_thumbnail,
             file_management::apply_adjustments_to_paths,
+            file_management::apply_relative_adjustments_to_paths,
+            relative_adjustments::combine_relative_adjustments,
             file_management::load_metadata,
             file_management::load_presets,
             file_management::save_presets,
@@ -2403,7 +2506,6 @@ pub fn run() {
             file_management::save_albums,
             file_management::add_to_album,
             file_management::get_album_images,
-            tagging::start_background_indexing,
             tagging::clear_ai_tags,
             tagging::clear_all_tags,
             tagging::add_tag_for_paths,
@@ -2414,7 +2516,6 @@ pub fn run() {
             lens_correction::autodetect_lens,
             lens_correction::get_lens_distortion_params,
             negative_conversion::preview_negative_conversion,
-            negative_conversion::convert_negatives,
             camera_tethering::tether_list_cameras,
             camera_tethering::tether_connect,
             camera_tethering::tether_get_settings,
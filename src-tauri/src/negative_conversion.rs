@@ -16,7 +16,6 @@ use tauri::AppHandle;
 use crate::AppState;
 use crate::image_processing::downscale_f32_image;
 use crate::load_settings;
-use tauri::Emitter;
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct NegativeConversionParams {
@@ -215,6 +214,7 @@ pub async fn preview_negative_conversion(
                                 &source_path_str,
                                 false,
                                 &settings,
+                                &app_handle,
                                 None,
                             )
                             .map_err(|e| e.to_string())?,
@@ -226,6 +226,7 @@ pub async fn preview_negative_conversion(
                                     &source_path_str,
                                     false,
                                     &settings,
+                                    &app_handle,
                                     None,
                                 )
                                 .map_err(|e| e.to_string())?
@@ -242,6 +243,7 @@ pub async fn preview_negative_conversion(
                             &source_path_str,
                             false,
                             &settings,
+                            &app_handle,
                             None,
                         )
                         .map_err(|e| e.to_string())?,
@@ -253,6 +255,7 @@ pub async fn preview_negative_conversion(
                                 &source_path_str,
                                 false,
                                 &settings,
+                                &app_handle,
                                 None,
                             )
                             .map_err(|e| e.to_string())?
@@ -280,68 +283,52 @@ pub async fn preview_negative_conversion(
     Ok(format!("data:image/jpeg;base64,{}", base64_str))
 }
 
-#[tauri::command]
-pub async fn convert_negatives(
-    paths: Vec<String>,
-    params: NegativeConversionParams,
-    app_handle: AppHandle,
-) -> Result<Vec<String>, String> {
-    tokio::task::spawn_blocking(move || {
-        let mut results = Vec::new();
-
-        for (i, path_str) in paths.iter().enumerate() {
-            let _ = app_handle.emit(
-                "negative-batch-progress",
-                serde_json::json!({
-                    "current": i + 1,
-                    "total": paths.len(),
-                    "path": path_str
-                }),
-            );
-
-            let (source_path, _) = parse_virtual_path(path_str);
-            let real_path = source_path.to_string_lossy().to_string();
-
-            let settings = load_settings(app_handle.clone()).unwrap_or_default();
-
-            let img = match read_file_mapped(Path::new(&real_path)) {
-                Ok(mmap) => load_base_image_from_bytes(&mmap, &real_path, false, &settings, None),
-                Err(_) => {
-                    let bytes = fs::read(&real_path).unwrap_or_default();
-                    load_base_image_from_bytes(&bytes, &real_path, false, &settings, None)
-                }
-            }
-            .map_err(|e| e.to_string())?;
-
-            let bounds_ref = downscale_f32_image(&img, 1080, 1080);
-            let ref_rgb = bounds_ref.to_rgb32f();
-            let (ref_w, ref_h) = ref_rgb.dimensions();
-            let log_pixels: Vec<f32> = ref_rgb
-                .as_raw()
-                .par_iter()
-                .map(|&v| -v.clamp(1e-6, 1.0).log10())
-                .collect();
-            let bounds = analyze_bounds(&log_pixels, ref_w as usize, ref_h as usize);
-
-            let processed = run_pipeline(&img, &params, Some(bounds));
-
-            let p = Path::new(&real_path);
-            let parent = p.parent().unwrap_or(Path::new(""));
-            let stem = p.file_stem().unwrap_or_default().to_string_lossy();
-            let filename = format!("{}_Positive.tiff", stem);
-            let out_path = parent.join(&filename);
-
-            processed
-                .to_rgb16()
-                .save(&out_path)
-                .map_err(|e| format!("Failed to save {}: {}", filename, e))?;
-
-            let _ = crate::exif_processing::write_rrexif_sidecar(&real_path, &out_path);
-            results.push(out_path.to_string_lossy().to_string());
+/// Converts one negative and writes the positive as a 16-bit TIFF next to the source.
+/// Returns the path of the written file.
+pub(crate) fn convert_negative_and_save(
+    path_str: &str,
+    params: &NegativeConversionParams,
+    app_handle: &AppHandle,
+) -> Result<String, String> {
+    let (source_path, _) = parse_virtual_path(path_str);
+    let real_path = source_path.to_string_lossy().to_string();
+
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+
+    let img = match read_file_mapped(Path::new(&real_path)) {
+        Ok(mmap) => {
+            load_base_image_from_bytes(&mmap, &real_path, false, &settings, app_handle, None)
+        }
+        Err(_) => {
+            let bytes = fs::read(&real_path).unwrap_or_default();
+            load_base_image_from_bytes(&bytes, &real_path, false, &settings, app_handle, None)
         }
+    }
+    .map_err(|e| e.to_string())?;
+
+    let bounds_ref = downscale_f32_image(&img, 1080, 1080);
+    let ref_rgb = bounds_ref.to_rgb32f();
+    let (ref_w, ref_h) = ref_rgb.dimensions();
+    let log_pixels: Vec<f32> = ref_rgb
+        .as_raw()
+        .par_iter()
+        .map(|&v| -v.clamp(1e-6, 1.0).log10())
+        .collect();
+    let bounds = analyze_bounds(&log_pixels, ref_w as usize, ref_h as usize);
+
+    let processed = run_pipeline(&img, params, Some(bounds));
+
+    let p = Path::new(&real_path);
+    let parent = p.parent().unwrap_or(Path::new(""));
+    let stem = p.file_stem().unwrap_or_default().to_string_lossy();
+    let filename = format!("{}_Positive.tiff", stem);
+    let out_path = parent.join(&filename);
+
+    processed
+        .to_rgb16()
+        .save(&out_path)
+        .map_err(|e| format!("Failed to save {}: {}", filename, e))?;
 
-        Ok(results)
-    })
-    .await
-    .map_err(|e| e.to_string())?
+    let _ = crate::exif_processing::write_rrexif_sidecar(&real_path, &out_path);
+    Ok(out_path.to_string_lossy().to_string())
 }
@@ -1,5 +1,6 @@
 use crate::app_settings::load_settings;
 use crate::app_state::AppState;
+use crate::exr_output::{save_merged_image, scene_referred_merges_enabled};
 use crate::file_management::parse_virtual_path;
 use base64::{Engine as _, engine::general_purpose};
 use image::ImageFormat;
@@ -14,7 +15,9 @@ use std::time::Instant;
 use tauri::{AppHandle, Emitter};
 
 use crate::formats::is_raw_file;
-use crate::image_processing::apply_cpu_default_raw_processing;
+use crate::image_processing::{
+    apply_cpu_default_raw_processing, apply_linear_to_srgb, apply_srgb_to_linear,
+};
 use crate::panorama_utils::{processing, stitching};
 
 pub const BRIEF_DESCRIPTOR_SIZE: usize = 256;
@@ -68,6 +71,8 @@ pub async fn stitch_panorama(
         .collect();
 
     let panorama_result_handle = state.panorama_result.clone();
+    let scene_referred =
+        scene_referred_merges_enabled(&load_settings(app_handle.clone()).unwrap_or_default());
 
     let task = tokio::task::spawn_blocking(move || {
         let panorama_result = stitch_images(source_paths, app_handle.clone());
@@ -86,7 +91,11 @@ pub async fn stitch_panorama(
                 let preview_f32 =
                     crate::image_processing::downscale_f32_image(&panorama_image, new_w, new_h);
 
-                let preview_u8 = preview_f32.to_rgb8();
+                let preview_u8 = if scene_referred {
+                    apply_linear_to_srgb(preview_f32).to_rgb8()
+                } else {
+                    preview_f32.to_rgb8()
+                };
 
                 let mut buf = Cursor::new(Vec::new());
 
@@ -125,6 +134,7 @@ pub async fn stitch_panorama(
 pub async fn save_panorama(
     first_path_str: String,
     state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
 ) -> Result<String, String> {
     let panorama_image = state
         .panorama_result
@@ -145,35 +155,26 @@ pub async fn save_panorama(
         .and_then(|s| s.to_str())
         .unwrap_or("panorama");
 
-    let (output_filename, image_to_save): (String, DynamicImage) =
-        if panorama_image.color().has_alpha() {
-            (
-                format!("{}_Pano.png", stem),
-                DynamicImage::ImageRgba8(panorama_image.to_rgba8()),
-            )
-        } else if panorama_image.as_rgb32f().is_some() {
-            (format!("{}_Pano.tiff", stem), panorama_image)
-        } else {
-            (
-                format!("{}_Pano.png", stem),
-                DynamicImage::ImageRgb8(panorama_image.to_rgb8()),
-            )
-        };
-
-    let output_path = parent_dir.join(output_filename);
-
-    image_to_save
-        .save(&output_path)
-        .map_err(|e| format!("Failed to save panorama image: {}", e))?;
-
+    let settings = load_settings(app_handle).unwrap_or_default();
     let (real_path, _) = crate::file_management::parse_virtual_path(&first_path_str);
+    let output_path = save_merged_image(
+        panorama_image,
+        parent_dir,
+        &format!("{}_Pano", stem),
+        &real_path.to_string_lossy(),
+        &settings,
+    )?;
+
     let _ =
         crate::exif_processing::write_rrexif_sidecar(&real_path.to_string_lossy(), &output_path);
 
     Ok(output_path.to_string_lossy().to_string())
 }
 
-fn stitch_images(image_paths: Vec<String>, app_handle: AppHandle) -> Result<DynamicImage, String> {
+pub(crate) fn stitch_images(
+    image_paths: Vec<String>,
+    app_handle: AppHandle,
+) -> Result<DynamicImage, String> {
     if image_paths.len() < 2 {
         return Err("At least two images are required for a panorama.".to_string());
     }
@@ -185,6 +186,7 @@ fn stitch_images(image_paths: Vec<String>, app_handle: AppHandle) -> Result<Dyna
     );
 
     let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    let scene_referred = scene_referred_merges_enabled(&settings);
 
     let start_time = Instant::now();
     let _ = app_handle.emit("panorama-progress", "Loading and preparing images...");
@@ -215,15 +217,25 @@ fn stitch_images(image_paths: Vec<String>, app_handle: AppHandle) -> Result<Dyna
                 filename,
                 false,
                 &settings,
+                &app_handle,
                 None,
             )
             .map_err(|e| format!("Failed to load image {}: {}", filename, e))?;
 
+            let linear_f32 = scene_referred.then(|| {
+                if is_raw_file(filename) {
+                    dynamic_image.to_rgb32f()
+                } else {
+                    apply_srgb_to_linear(DynamicImage::ImageRgb32F(dynamic_image.to_rgb32f()))
+                        .into_rgb32f()
+                }
+            });
+
             if is_raw_file(filename) {
                 apply_cpu_default_raw_processing(&mut dynamic_image);
             }
 
-            let image_f32 = dynamic_image.to_rgb32f();
+            let image_f32 = linear_f32.unwrap_or_else(|| dynamic_image.to_rgb32f());
 
             let color_full_u8 = dynamic_image.to_rgb8();
             let gray_full = image::imageops::colorops::grayscale(&color_full_u8);
@@ -0,0 +1,613 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::app_settings::{AppSettings, load_settings};
+use crate::denoising::denoise_and_save;
+use crate::exif_processing::{
+    load_sidecar, read_exif_data_from_bytes, read_rrexif_sidecar, write_rrexif_sidecar,
+};
+use crate::export_processing::{
+    ExportAdjustmentsMode, ExportVariant, develop_image, export_variants_impl, find_export_preset,
+};
+use crate::exr_output::save_merged_image;
+use crate::file_management::{
+    expand_cli_paths, parse_virtual_path, resolve_cli_preset, resolve_lens_params_in_adjustments,
+};
+use crate::launch_request::HeadlessRecipeSession;
+use crate::panorama_stitching::stitch_images;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeExportVariant {
+    /// Name or ID of a saved export preset.
+    pub export_preset: String,
+    #[serde(default)]
+    pub sub_folder: Option<String>,
+}
+
+fn default_denoise_intensity() -> f32 {
+    0.5
+}
+
+fn default_denoise_method() -> String {
+    "ai".to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RecipeStep {
+    #[serde(rename_all = "camelCase")]
+    Denoise {
+        #[serde(default = "default_denoise_intensity")]
+        intensity: f32,
+        #[serde(default = "default_denoise_method")]
+        method: String,
+    },
+    /// Preset name or ID from the preset library, or a path to a preset file.
+    ApplyPreset {
+        preset: String,
+    },
+    Adjust {
+        adjustments: Value,
+    },
+    LensCorrection,
+    Reset,
+    /// Renders the adjustments into the pixels, so later steps start from the developed image.
+    Develop,
+    /// Merges consecutive groups of `group_size` images, or all inputs when it is not set.
+    #[serde(rename_all = "camelCase")]
+    MergeHdr {
+        #[serde(default)]
+        group_size: Option<usize>,
+    },
+    #[serde(rename_all = "camelCase")]
+    StitchPanorama {
+        #[serde(default)]
+        group_size: Option<usize>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Export {
+        variants: Vec<RecipeExportVariant>,
+        #[serde(default)]
+        output_folder: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeNode {
+    pub id: String,
+    /// IDs of the upstream nodes. A node without inputs starts from the images the recipe runs on.
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    #[serde(flatten)]
+    pub step: RecipeStep,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessingRecipe {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub nodes: Vec<RecipeNode>,
+}
+
+/// An image flowing between nodes. Steps that change pixels write an intermediate file to the
+/// run's work folder; the other steps only change the adjustments.
+#[derive(Clone)]
+struct RecipeImage {
+    path: String,
+    /// The original the image came from, used for its EXIF data.
+    source: String,
+    adjustments: Value,
+}
+
+/// Validates the graph and returns the node indices in an order where every node comes after
+/// its inputs.
+fn execution_order(recipe: &ProcessingRecipe) -> Result<Vec<usize>, String> {
+    let mut index_by_id = HashMap::new();
+    for (index, node) in recipe.nodes.iter().enumerate() {
+        if index_by_id.insert(node.id.as_str(), index).is_some() {
+            return Err(format!("Duplicate node ID '{}'", node.id));
+        }
+    }
+    if !recipe
+        .nodes
+        .iter()
+        .any(|node| matches!(node.step, RecipeStep::Export { .. }))
+    {
+        return Err("The recipe has no export step.".to_string());
+    }
+
+    let mut pending_inputs = vec![0usize; recipe.nodes.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); recipe.nodes.len()];
+    for (index, node) in recipe.nodes.iter().enumerate() {
+        for input in &node.inputs {
+            let &input_index = index_by_id
+                .get(input.as_str())
+                .ok_or_else(|| format!("Node '{}' uses unknown input '{}'", node.id, input))?;
+            pending_inputs[index] += 1;
+            dependents[input_index].push(index);
+        }
+    }
+
+    let mut ready: VecDeque<usize> = (0..recipe.nodes.len())
+        .filter(|&index| pending_inputs[index] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(recipe.nodes.len());
+    while let Some(index) = ready.pop_front() {
+        order.push(index);
+        for &dependent in &dependents[index] {
+            pending_inputs[dependent] -= 1;
+            if pending_inputs[dependent] == 0 {
+                ready.push_back(dependent);
+            }
+        }
+    }
+
+    if order.len() != recipe.nodes.len() {
+        return Err("The recipe contains a cycle.".to_string());
+    }
+    Ok(order)
+}
+
+fn merge_adjustments(target: &mut Value, patch: &Value) {
+    if !target.is_object() {
+        *target = json!({});
+    }
+    if let (Some(target_map), Some(patch_map)) = (target.as_object_mut(), patch.as_object()) {
+        for (key, value) in patch_map {
+            target_map.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+fn file_stem(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "image".to_string())
+}
+
+/// Each image gets its own folder, so inputs with the same file name do not overwrite each other.
+fn item_dir(node_dir: &Path, index: usize) -> Result<PathBuf, String> {
+    let dir = node_dir.join(index.to_string());
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create work folder: {}", e))?;
+    Ok(dir)
+}
+
+fn group_images(
+    images: Vec<RecipeImage>,
+    group_size: Option<usize>,
+) -> Result<Vec<Vec<RecipeImage>>, String> {
+    let group_size = group_size.unwrap_or(images.len()).max(1);
+    let groups: Vec<Vec<RecipeImage>> = images
+        .chunks(group_size)
+        .map(|group| group.to_vec())
+        .collect();
+    if groups.iter().any(|group| group.len() < 2) {
+        return Err(format!(
+            "Merging needs groups of at least two images, got {} images in groups of {}.",
+            images.len(),
+            group_size
+        ));
+    }
+    Ok(groups)
+}
+
+async fn run_blocking<T, F>(task: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn denoise_step(
+    images: Vec<RecipeImage>,
+    intensity: f32,
+    method: &str,
+    ai_session: Option<Arc<Mutex<ort::session::Session>>>,
+    node_dir: &Path,
+    app_handle: &AppHandle,
+) -> Result<Vec<RecipeImage>, String> {
+    images
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut image)| {
+            let dir = item_dir(node_dir, index)?;
+            image.path = denoise_and_save(
+                &image.path,
+                intensity,
+                method,
+                app_handle,
+                ai_session.clone(),
+                Some(&dir),
+            )?;
+            Ok(image)
+        })
+        .collect()
+}
+
+fn lens_correction_step(
+    images: Vec<RecipeImage>,
+    app_handle: &AppHandle,
+) -> Result<Vec<RecipeImage>, String> {
+    let lens_db = app_handle
+        .state::<AppState>()
+        .lens_db
+        .lock()
+        .unwrap()
+        .clone();
+    Ok(images
+        .into_iter()
+        .map(|mut image| {
+            let (source_path, _) = parse_virtual_path(&image.path);
+            let exif = read_rrexif_sidecar(&source_path).or_else(|| {
+                fs::read(&source_path)
+                    .ok()
+                    .map(|bytes| read_exif_data_from_bytes(&source_path.to_string_lossy(), &bytes))
+            });
+            merge_adjustments(
+                &mut image.adjustments,
+                &json!({ "lensCorrectionMode": "auto" }),
+            );
+            resolve_lens_params_in_adjustments(&mut image.adjustments, &exif, lens_db.as_deref());
+            image
+        })
+        .collect())
+}
+
+fn develop_step(
+    images: Vec<RecipeImage>,
+    node_dir: &Path,
+    app_handle: &AppHandle,
+) -> Result<Vec<RecipeImage>, String> {
+    images
+        .into_iter()
+        .enumerate()
+        .map(|(index, image)| {
+            let developed = develop_image(&image.path, &image.adjustments, app_handle)?;
+            let output_path =
+                item_dir(node_dir, index)?.join(format!("{}.tiff", file_stem(&image.path)));
+            DynamicImage::ImageRgb16(developed.to_rgb16())
+                .save(&output_path)
+                .map_err(|e| format!("Failed to save {}: {}", output_path.display(), e))?;
+            let _ = write_rrexif_sidecar(&image.source, &output_path);
+            Ok(RecipeImage {
+                path: output_path.to_string_lossy().into_owned(),
+                source: image.source,
+                adjustments: json!({}),
+            })
+        })
+        .collect()
+}
+
+/// Runs `merge` on every group and saves each result as `<first stem>_<suffix>`.
+fn merge_step<F>(
+    groups: Vec<Vec<RecipeImage>>,
+    suffix: &str,
+    node_dir: &Path,
+    settings: &AppSettings,
+    merge: F,
+) -> Result<Vec<RecipeImage>, String>
+where
+    F: Fn(Vec<String>) -> Result<DynamicImage, String>,
+{
+    groups
+        .into_iter()
+        .enumerate()
+        .map(|(index, group)| {
+            let paths: Vec<String> = group
+                .iter()
+                .map(|image| {
+                    parse_virtual_path(&image.path)
+                        .0
+                        .to_string_lossy()
+                        .into_owned()
+                })
+                .collect();
+            let first = &group[0];
+            let merged = merge(paths)?;
+            let output_path = save_merged_image(
+                merged,
+                &item_dir(node_dir, index)?,
+                &format!("{}_{}", file_stem(&first.path), suffix),
+                &first.source,
+                settings,
+            )?;
+            let _ = write_rrexif_sidecar(&first.source, &output_path);
+            Ok(RecipeImage {
+                path: output_path.to_string_lossy().into_owned(),
+                source: first.source.clone(),
+                adjustments: json!({}),
+            })
+        })
+        .collect()
+}
+
+async fn export_step(
+    images: &[RecipeImage],
+    variants: &[RecipeExportVariant],
+    output_folder: &str,
+    app_handle: &AppHandle,
+) -> Result<(), String> {
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    let variants = variants
+        .iter()
+        .map(|variant| {
+            find_export_preset(&settings, &variant.export_preset)
+                .map(|preset| ExportVariant::from_preset(preset, variant.sub_folder.clone()))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    fs::create_dir_all(output_folder)
+        .map_err(|e| format!("Failed to create output directory: {}", e))?;
+
+    let paths: Vec<String> = images.iter().map(|image| image.path.clone()).collect();
+    let adjustments = images
+        .iter()
+        .map(|image| (image.path.clone(), image.adjustments.clone()))
+        .collect();
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    export_variants_impl(
+        paths.clone(),
+        output_folder.to_string(),
+        false,
+        Vec::new(),
+        variants,
+        None,
+        ExportAdjustmentsMode::PerImage(adjustments),
+        app_handle.state::<AppState>(),
+        app_handle.clone(),
+        Some(tx),
+    )
+    .await?;
+
+    match rx.await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(failures)) => Err(format!(
+            "{} of {} images failed to export",
+            failures.len(),
+            paths.len()
+        )),
+        Err(_) => Err("The export was cancelled.".to_string()),
+    }
+}
+
+async fn run_node(
+    node: &RecipeNode,
+    images: Vec<RecipeImage>,
+    node_dir: PathBuf,
+    output_folder: &str,
+    app_handle: &AppHandle,
+) -> Result<Vec<RecipeImage>, String> {
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    let app = app_handle.clone();
+    match &node.step {
+        RecipeStep::ApplyPreset { preset } => {
+            let preset_adjustments =
+                resolve_cli_preset(preset, app_handle)?.applicable_adjustments();
+            Ok(images
+                .into_iter()
+                .map(|mut image| {
+                    merge_adjustments(&mut image.adjustments, &preset_adjustments);
+                    image
+                })
+                .collect())
+        }
+        RecipeStep::Adjust { adjustments } => Ok(images
+            .into_iter()
+            .map(|mut image| {
+                merge_adjustments(&mut image.adjustments, adjustments);
+                image
+            })
+            .collect()),
+        RecipeStep::Reset => Ok(images
+            .into_iter()
+            .map(|mut image| {
+                image.adjustments = json!({});
+                image
+            })
+            .collect()),
+        RecipeStep::LensCorrection => {
+            run_blocking(move || lens_correction_step(images, &app)).await
+        }
+        RecipeStep::Denoise { intensity, method } => {
+            let ai_session = if method == "ai" {
+                let state = app_handle.state::<AppState>();
+                Some(
+                    crate::ai_processing::get_or_init_denoise_model(
+                        app_handle,
+                        &state.ai_state,
+                        &state.ai_init_lock,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?,
+                )
+            } else {
+                None
+            };
+            let (intensity, method) = (*intensity, method.clone());
+            run_blocking(move || {
+                denoise_step(images, intensity, &method, ai_session, &node_dir, &app)
+            })
+            .await
+        }
+        RecipeStep::Develop => run_blocking(move || develop_step(images, &node_dir, &app)).await,
+        RecipeStep::MergeHdr { group_size } => {
+            let groups = group_images(images, *group_size)?;
+            run_blocking(move || {
+                merge_step(groups, "Hdr", &node_dir, &settings, |paths| {
+                    crate::merge_hdr_images(&paths, &app, &settings).map(|(merged, _)| merged)
+                })
+            })
+            .await
+        }
+        RecipeStep::StitchPanorama { group_size } => {
+            let groups = group_images(images, *group_size)?;
+            run_blocking(move || {
+                merge_step(groups, "Pano", &node_dir, &settings, |paths| {
+                    stitch_images(paths, app.clone())
+                })
+            })
+            .await
+        }
+        RecipeStep::Export {
+            variants,
+            output_folder: node_output_folder,
+        } => {
+            let output_folder = node_output_folder.as_deref().unwrap_or(output_folder);
+            export_step(&images, variants, output_folder, app_handle).await?;
+            Ok(images)
+        }
+    }
+}
+
+pub(crate) async fn run_recipe(
+    recipe: &ProcessingRecipe,
+    paths: Vec<String>,
+    output_folder: &str,
+    app_handle: &AppHandle,
+) -> Result<(), String> {
+    let order = execution_order(recipe)?;
+    if paths.is_empty() {
+        return Err("No images given for the recipe.".to_string());
+    }
+    let work_dir =
+        tempfile::tempdir().map_err(|e| format!("Failed to create work folder: {}", e))?;
+
+    let inputs: Vec<RecipeImage> = paths
+        .into_iter()
+        .map(|path| {
+            let (source_path, sidecar_path) = parse_virtual_path(&path);
+            RecipeImage {
+                source: source_path.to_string_lossy().into_owned(),
+                adjustments: load_sidecar(&sidecar_path).adjustments,
+                path,
+            }
+        })
+        .collect();
+
+    let mut outputs: HashMap<&str, Vec<RecipeImage>> = HashMap::new();
+    for (step, &index) in order.iter().enumerate() {
+        let node = &recipe.nodes[index];
+        let _ = app_handle.emit(
+            "recipe-progress",
+            json!({ "node": node.id, "current": step + 1, "total": order.len() }),
+        );
+
+        let images = if node.inputs.is_empty() {
+            inputs.clone()
+        } else {
+            node.inputs
+                .iter()
+                .flat_map(|input| outputs[input.as_str()].iter().cloned())
+                .collect()
+        };
+        let node_dir = work_dir.path().join(index.to_string());
+        let result = run_node(node, images, node_dir, output_folder, app_handle)
+            .await
+            .map_err(|e| format!("Step '{}' failed: {}", node.id, e))?;
+        outputs.insert(node.id.as_str(), result);
+    }
+
+    Ok(())
+}
+
+/// Looks the recipe up by name or ID in the settings, then falls back to reading a JSON file.
+fn resolve_recipe(name_or_file: &str, settings: &AppSettings) -> Result<ProcessingRecipe, String> {
+    if let Some(recipe) = settings
+        .processing_recipes
+        .iter()
+        .find(|recipe| recipe.id == name_or_file || recipe.name.eq_ignore_ascii_case(name_or_file))
+    {
+        return Ok(recipe.clone());
+    }
+    if !Path::new(name_or_file).is_file() {
+        return Err(format!(
+            "'{}' is neither a saved recipe nor a recipe file",
+            name_or_file
+        ));
+    }
+    load_processing_recipe_file(name_or_file.to_string())
+}
+
+#[tauri::command]
+pub fn load_processing_recipe_file(path: String) -> Result<ProcessingRecipe, String> {
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let mut recipe: ProcessingRecipe =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid recipe file: {}", e))?;
+    execution_order(&recipe)?;
+    if recipe.id.is_empty() {
+        recipe.id = Uuid::new_v4().to_string();
+    }
+    Ok(recipe)
+}
+
+#[tauri::command]
+pub async fn run_processing_recipe(
+    recipe: ProcessingRecipe,
+    paths: Vec<String>,
+    output_folder: String,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Running recipe '{}' on {} images", recipe.name, paths.len());
+    let result = run_recipe(&recipe, paths, &output_folder, &app_handle).await;
+    match &result {
+        Ok(()) => {
+            let _ = app_handle.emit("recipe-complete", &recipe.name);
+        }
+        Err(e) => {
+            log::error!("Recipe '{}' failed: {}", recipe.name, e);
+            let _ = app_handle.emit("recipe-error", e);
+        }
+    }
+    result
+}
+
+pub async fn run_headless_recipe(
+    session: HeadlessRecipeSession,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    if session.recipe.is_empty() || session.paths.is_empty() {
+        return Err(
+            "Usage: rapidraw recipe <recipe-name|file> <paths...> --output <folder>".to_string(),
+        );
+    }
+    let settings = load_settings(app_handle.clone())?;
+    let recipe = resolve_recipe(&session.recipe, &settings)?;
+    let output_folder = session.output.unwrap_or_default();
+    let needs_output = recipe.nodes.iter().any(|node| {
+        matches!(
+            &node.step,
+            RecipeStep::Export {
+                output_folder: None,
+                ..
+            }
+        )
+    });
+    if needs_output && output_folder.is_empty() {
+        return Err("The recipe needs an output folder (--output <folder>).".to_string());
+    }
+
+    let paths = expand_cli_paths(&session.paths, &app_handle)?;
+    println!(
+        "Running recipe '{}' on {} images...",
+        recipe.name,
+        paths.len()
+    );
+    run_recipe(&recipe, paths, &output_folder, &app_handle).await
+}
@@ -1,28 +1,78 @@
+use crate::calibration_frames::CalibrationFrames;
+use crate::dcp_profiles::DcpProfile;
+use crate::defect_pixels::DefectMaps;
+use crate::demosaic::{CfaPattern, DemosaicMethod, Mosaic, demosaic};
 use crate::image_processing::apply_orientation;
 use anyhow::{Result, anyhow};
 use image::{DynamicImage, ImageBuffer, Rgba};
 use rawler::{
     decoders::{Orientation, RawDecodeParams},
     imgop::develop::{DemosaicAlgorithm, Intermediate, ProcessingStep, RawDevelop},
-    rawimage::{RawImage, RawPhotometricInterpretation},
+    rawimage::{BlackLevel, RawImage, RawImageData, RawPhotometricInterpretation, WhiteLevel},
     rawsource::RawSource,
 };
+use rayon::prelude::*;
+use serde_json::Value;
 use std::sync::{
     Arc,
     atomic::{AtomicUsize, Ordering},
 };
 
+/// Per-image choices from the adjustments that change how a raw is developed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RawDevelopOptions {
+    pub demosaic: DemosaicMethod,
+    pub camera_profile: Option<String>,
+    pub profile_tone_curve: bool,
+    pub dark_frame: Option<String>,
+    pub flat_field: Option<String>,
+}
+
+fn path_adjustment(adjustments: &Value, key: &str) -> Option<String> {
+    adjustments[key]
+        .as_str()
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+}
+
+impl RawDevelopOptions {
+    pub fn from_adjustments(adjustments: &Value) -> Self {
+        Self {
+            demosaic: DemosaicMethod::from_adjustments(adjustments),
+            camera_profile: path_adjustment(adjustments, "cameraProfile"),
+            profile_tone_curve: adjustments["cameraProfileToneCurve"]
+                .as_bool()
+                .unwrap_or(false),
+            dark_frame: path_adjustment(adjustments, "darkFrame"),
+            flat_field: path_adjustment(adjustments, "flatField"),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn develop_raw_image(
     file_bytes: &[u8],
     fast_demosaic: bool,
+    demosaic_method: DemosaicMethod,
+    camera_profile: Option<&DcpProfile>,
+    calibration: &CalibrationFrames,
+    defect_maps: Option<&DefectMaps>,
+    detect_defects: bool,
     highlight_compression: f32,
+    reconstruct_highlights: bool,
     linear_mode: String,
     cancel_token: Option<(Arc<AtomicUsize>, usize)>,
 ) -> Result<DynamicImage> {
     let (developed_image, orientation) = develop_internal(
         file_bytes,
         fast_demosaic,
+        demosaic_method,
+        camera_profile,
+        calibration,
+        defect_maps,
+        detect_defects,
         highlight_compression,
+        reconstruct_highlights,
         linear_mode,
         cancel_token,
     )?;
@@ -45,10 +95,164 @@ fn srgb_to_linear(value: f32) -> f32 {
     }
 }
 
+/// Processed mosaics are stored with room above the white level, so reconstructed highlights
+/// survive until the highlight handling after development.
+const MOSAIC_HEADROOM: f32 = 4.0;
+
+/// The sensor data of a CFA raw, black-subtracted, normalized to the white level and white
+/// balanced, as demosaicing and highlight reconstruction work best on neutral data.
+struct NormalizedMosaic {
+    data: Vec<f32>,
+    width: usize,
+    height: usize,
+    pattern: CfaPattern,
+    wb: [f32; 3],
+}
+
+pub fn black_level_at(black: &BlackLevel, row: usize, col: usize) -> f32 {
+    if black.levels.is_empty() || black.width == 0 || black.height == 0 {
+        return 0.0;
+    }
+    let index = ((row % black.height) * black.width + col % black.width) * black.cpp;
+    black.levels.get(index).map(|r| r.as_f32()).unwrap_or(0.0)
+}
+
+fn normalize_mosaic(raw_image: &RawImage) -> Option<NormalizedMosaic> {
+    if raw_image.cpp != 1 {
+        return None;
+    }
+    let RawPhotometricInterpretation::Cfa(config) = &raw_image.photometric else {
+        return None;
+    };
+    let pattern = CfaPattern::new(config.cfa.width, config.cfa.height, |row, col| {
+        config.cfa.color_at(row, col)
+    });
+    if !pattern.is_rgb() {
+        return None;
+    }
+    let RawImageData::Integer(data) = &raw_image.data else {
+        return None;
+    };
+
+    let (width, height) = (raw_image.width, raw_image.height);
+    let white = raw_image
+        .whitelevel
+        .0
+        .first()
+        .cloned()
+        .unwrap_or(u16::MAX as u32) as f32;
+    let black = &raw_image.blacklevel;
+
+    let wb = [0, 1, 2].map(|c| {
+        let coeff = raw_image.wb_coeffs[c] / raw_image.wb_coeffs[1];
+        if coeff.is_finite() && coeff > 0.0 {
+            coeff
+        } else {
+            1.0
+        }
+    });
+
+    let data = data
+        .par_iter()
+        .enumerate()
+        .map(|(i, &value)| {
+            let (row, col) = (i / width, i % width);
+            let black = black_level_at(black, row, col);
+            let value = (value as f32 - black) / (white - black).max(1.0);
+            value.max(0.0) * wb[pattern.color_at(row, col)]
+        })
+        .collect();
+
+    Some(NormalizedMosaic {
+        data,
+        width,
+        height,
+        pattern,
+        wb,
+    })
+}
+
+#[inline]
+fn to_stored(value: f32, wb: f32) -> u16 {
+    ((value / wb).clamp(0.0, MOSAIC_HEADROOM) / MOSAIC_HEADROOM * u16::MAX as f32).round() as u16
+}
+
+fn stored_white_level() -> u32 {
+    (u16::MAX as f32 / MOSAIC_HEADROOM).round() as u32
+}
+
+/// Hands a processed mosaic back to rawler for its own demosaic.
+fn store_mosaic(raw_image: &mut RawImage, mosaic: &NormalizedMosaic) {
+    let data = mosaic
+        .data
+        .par_iter()
+        .enumerate()
+        .map(|(i, &value)| {
+            let color = mosaic.pattern.color_at(i / mosaic.width, i % mosaic.width);
+            to_stored(value, mosaic.wb[color])
+        })
+        .collect();
+
+    raw_image.data = RawImageData::Integer(data);
+    raw_image.blacklevel = BlackLevel::new(&[0u16], 1, 1, 1);
+    raw_image.whitelevel = WhiteLevel(vec![stored_white_level()]);
+}
+
+/// Demosaics with one of our own algorithms and hands rawler a three-channel linear image, so
+/// it only applies white balance, colour calibration and cropping. Returns false when `method`
+/// does not apply to this sensor.
+fn apply_demosaic(
+    raw_image: &mut RawImage,
+    mosaic: &NormalizedMosaic,
+    method: DemosaicMethod,
+) -> bool {
+    let input = Mosaic {
+        data: &mosaic.data,
+        width: mosaic.width,
+        height: mosaic.height,
+        pattern: &mosaic.pattern,
+    };
+    let Some(rgb) = demosaic(&input, method) else {
+        return false;
+    };
+
+    let wb = mosaic.wb;
+    let data: Vec<u16> = rgb
+        .par_iter()
+        .flat_map_iter(|px| (0..3).map(move |c| to_stored(px[c], wb[c])))
+        .collect();
+
+    raw_image.data = RawImageData::Integer(data);
+    raw_image.cpp = 3;
+    raw_image.photometric = RawPhotometricInterpretation::LinearRaw;
+    raw_image.blacklevel = BlackLevel::new(&[0u16; 3], 1, 1, 3);
+    raw_image.whitelevel = WhiteLevel(vec![stored_white_level(); 3]);
+    true
+}
+
+/// The camera RGB of a neutral surface, from the white balance multipliers.
+fn camera_neutral(wb_coeffs: [f32; 4]) -> [f32; 3] {
+    [0, 1, 2].map(|c| {
+        let neutral = wb_coeffs[1] / wb_coeffs[c];
+        if neutral.is_finite() && neutral > 0.0 {
+            neutral
+        } else {
+            1.0
+        }
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 fn develop_internal(
     file_bytes: &[u8],
     fast_demosaic: bool,
+    demosaic_method: DemosaicMethod,
+    camera_profile: Option<&DcpProfile>,
+    calibration: &CalibrationFrames,
+    defect_maps: Option<&DefectMaps>,
+    detect_defects: bool,
     highlight_compression: f32,
+    reconstruct_highlights: bool,
     linear_mode: String,
     cancel_token: Option<(Arc<AtomicUsize>, usize)>,
 ) -> Result<(DynamicImage, Orientation)> {
@@ -78,6 +282,53 @@ fn develop_internal(
 
     let is_linear_format = is_linear_raw_format(&raw_image);
 
+    raw_image.wb_coeffs =
+        crate::multi_exposure::neutralize_wb_if_multiexposure(raw_image.wb_coeffs, file_bytes);
+
+    if !is_linear_format {
+        calibration.apply(&mut raw_image);
+        let defect_map = crate::defect_pixels::camera_key(
+            &metadata.make,
+            &metadata.model,
+            metadata.exif.serial_number.as_deref(),
+        )
+        .zip(defect_maps)
+        .and_then(|(key, maps)| maps.get(&key));
+        crate::defect_pixels::correct_defects(
+            &mut raw_image,
+            defect_map.as_deref(),
+            detect_defects,
+        );
+    }
+
+    check_cancel()?;
+    let mut custom_demosaic = false;
+    let process_mosaic = reconstruct_highlights || demosaic_method != DemosaicMethod::Default;
+    if !is_linear_format
+        && process_mosaic
+        && let Some(mut mosaic) = normalize_mosaic(&raw_image)
+    {
+        if reconstruct_highlights {
+            crate::highlight_reconstruction::reconstruct_opposed(
+                &mut mosaic.data,
+                mosaic.width,
+                mosaic.height,
+                &mosaic.pattern,
+                mosaic.wb,
+            );
+            check_cancel()?;
+        }
+        custom_demosaic = demosaic_method != DemosaicMethod::Default
+            && apply_demosaic(&mut raw_image, &mosaic, demosaic_method);
+        if !custom_demosaic && reconstruct_highlights {
+            store_mosaic(&mut raw_image, &mosaic);
+        }
+    }
+
+    let profile_transform = camera_profile
+        .filter(|_| !is_linear_format)
+        .and_then(|profile| profile.transform(camera_neutral(raw_image.wb_coeffs)));
+
     let (apply_ungamma, apply_calibration) = match linear_mode.as_str() {
         "gamma" => (true, true),
         "skip_calib" => (false, false),
@@ -110,6 +361,10 @@ fn develop_internal(
                 && step != ProcessingStep::Demosaic
                 && (apply_calibration || step != ProcessingStep::Calibrate)
         });
+    } else if custom_demosaic {
+        developer
+            .steps
+            .retain(|&step| step != ProcessingStep::SRgb && step != ProcessingStep::Demosaic);
     } else if fast_demosaic {
         developer.demosaic_algorithm = DemosaicAlgorithm::Speed;
         developer.steps.retain(|&step| step != ProcessingStep::SRgb);
@@ -117,8 +372,11 @@ fn develop_internal(
         developer.steps.retain(|&step| step != ProcessingStep::SRgb);
     }
 
-    raw_image.wb_coeffs =
-        crate::multi_exposure::neutralize_wb_if_multiexposure(raw_image.wb_coeffs, file_bytes);
+    if profile_transform.is_some() {
+        developer
+            .steps
+            .retain(|&step| step != ProcessingStep::Calibrate);
+    }
 
     check_cancel()?;
     let mut developed_intermediate = developer.develop_intermediate(&raw_image)?;
@@ -154,6 +412,10 @@ fn develop_internal(
                 let mut g = (p[1] * rescale_factor).max(0.0);
                 let mut b = (p[2] * rescale_factor).max(0.0);
 
+                if let Some(transform) = &profile_transform {
+                    [r, g, b] = transform.apply([r, g, b]).map(|c| c.max(0.0));
+                }
+
                 if is_linear_format && apply_ungamma {
                     r = srgb_to_linear(r.clamp(0.0, 1.0));
                     g = srgb_to_linear(g.clamp(0.0, 1.0));
@@ -162,7 +424,12 @@ fn develop_internal(
 
                 let max_c = r.max(g).max(b);
 
-                let (final_r, final_g, final_b) = if max_c > 1.0 {
+                let (final_r, final_g, final_b) = if max_c > 1.0 && reconstruct_highlights {
+                    // Reconstructed highlights already carry their hue, so they are only scaled
+                    // into range.
+                    let scale = (clamp_limit / max_c).min(1.0);
+                    (r * scale, g * scale, b * scale)
+                } else if max_c > 1.0 {
                     let min_c = r.min(g).min(b);
                     let compression_factor =
                         (1.0 - (max_c - 1.0) / (safe_highlight_compression - 1.0)).clamp(0.0, 1.0);
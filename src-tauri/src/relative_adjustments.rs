@@ -0,0 +1,331 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// Changes applied on top of each image's saved edit instead of replacing it. Keys follow the
+/// adjustments JSON, and nested objects such as `hsl` are matched field by field.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RelativeAdjustments {
+    /// Written as-is, like a regular paste.
+    #[serde(default)]
+    pub set: Map<String, Value>,
+    /// Added to the current value, e.g. `{"exposure": 0.3, "temperature": -5}`.
+    #[serde(default)]
+    pub add: Map<String, Value>,
+    /// Multiplies the current value, e.g. `{"contrast": 1.2}`.
+    #[serde(default)]
+    pub multiply: Map<String, Value>,
+    /// Moves the current values towards these by `strength`, e.g. a preset at 50%.
+    #[serde(default)]
+    pub blend: Map<String, Value>,
+    #[serde(default = "default_strength")]
+    pub strength: f64,
+    /// Values assumed for fields missing from a saved edit. Missing numbers otherwise start at 0.
+    #[serde(default)]
+    pub defaults: Map<String, Value>,
+    /// Also applies `add` and `multiply` to the matching fields of every mask.
+    #[serde(default)]
+    pub include_masks: bool,
+}
+
+fn default_strength() -> f64 {
+    1.0
+}
+
+impl RelativeAdjustments {
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+            && self.add.is_empty()
+            && self.multiply.is_empty()
+            && self.blend.is_empty()
+    }
+
+    pub fn apply(&self, adjustments: &mut Value) {
+        if !adjustments.is_object() {
+            *adjustments = Value::Object(Map::new());
+        }
+        let Some(map) = adjustments.as_object_mut() else {
+            return;
+        };
+
+        for (key, value) in &self.set {
+            map.insert(key.clone(), value.clone());
+        }
+        self.apply_relative(map, true);
+        let strength = self.strength.clamp(0.0, 1.0);
+        if strength > 0.0 {
+            blend(map, &self.blend, Some(&self.defaults), strength);
+        }
+
+        if self.include_masks {
+            let masks = map.get_mut("masks").and_then(Value::as_array_mut);
+            for mask in masks.into_iter().flatten() {
+                if let Some(mask_adjustments) =
+                    mask.get_mut("adjustments").and_then(Value::as_object_mut)
+                {
+                    self.apply_relative(mask_adjustments, false);
+                }
+            }
+        }
+    }
+
+    /// Masks only carry the fields they use, so missing fields are never created for them.
+    fn apply_relative(&self, map: &mut Map<String, Value>, create_missing: bool) {
+        let defaults = create_missing.then_some(&self.defaults);
+        combine(map, &self.add, defaults, &|current, delta| current + delta);
+        combine(map, &self.multiply, defaults, &|current, factor| {
+            current * factor
+        });
+    }
+}
+
+/// Slider ranges of the main adjustments, so repeated batch edits cannot push them out of range.
+fn value_range(key: &str) -> Option<(f64, f64)> {
+    match key {
+        "exposure" => Some((-5.0, 5.0)),
+        "brightness" | "contrast" | "highlights" | "shadows" | "whites" | "blacks"
+        | "temperature" | "tint" | "vibrance" | "saturation" | "clarity" | "dehaze"
+        | "structure" | "sharpness" => Some((-100.0, 100.0)),
+        _ => None,
+    }
+}
+
+fn number(key: &str, value: f64) -> Value {
+    let value = match value_range(key) {
+        Some((min, max)) => value.clamp(min, max),
+        None => value,
+    };
+    serde_json::Number::from_f64(value).map_or(Value::Null, Value::Number)
+}
+
+fn nested_defaults<'a>(
+    defaults: Option<&'a Map<String, Value>>,
+    key: &str,
+) -> Option<&'a Map<String, Value>> {
+    defaults.and_then(|d| d.get(key)).and_then(Value::as_object)
+}
+
+/// Fields missing from `target` are only created when `defaults` is given.
+fn combine(
+    target: &mut Map<String, Value>,
+    changes: &Map<String, Value>,
+    defaults: Option<&Map<String, Value>>,
+    op: &dyn Fn(f64, f64) -> f64,
+) {
+    for (key, change) in changes {
+        if !target.contains_key(key) {
+            let Some(defaults) = defaults else {
+                continue;
+            };
+            let initial = match (defaults.get(key), change) {
+                (Some(default), _) => default.clone(),
+                (None, Value::Object(_)) => Value::Object(Map::new()),
+                (None, _) => Value::from(0.0),
+            };
+            target.insert(key.clone(), initial);
+        }
+
+        match (target.get_mut(key), change) {
+            (Some(Value::Object(current)), Value::Object(nested)) => {
+                combine(current, nested, nested_defaults(defaults, key), op);
+            }
+            (Some(Value::Number(current)), Value::Number(amount)) => {
+                let current = current.as_f64().unwrap_or(0.0);
+                let result = op(current, amount.as_f64().unwrap_or(0.0));
+                target.insert(key.clone(), number(key, result));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn curve_points(value: &Value) -> Option<Vec<(f64, f64)>> {
+    let points = value.as_array()?;
+    if points.is_empty() {
+        return None;
+    }
+    points
+        .iter()
+        .map(|point| Some((point.get("x")?.as_f64()?, point.get("y")?.as_f64()?)))
+        .collect()
+}
+
+/// Linear interpolation between the points, flat outside of them.
+fn evaluate_curve(points: &[(f64, f64)], x: f64) -> f64 {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    if x <= first.0 {
+        return first.1;
+    }
+    if x >= last.0 {
+        return last.1;
+    }
+    for pair in points.windows(2) {
+        let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+        if x <= x2 {
+            let range = x2 - x1;
+            return if range == 0.0 {
+                y1
+            } else {
+                y1 + (x - x1) / range * (y2 - y1)
+            };
+        }
+    }
+    x
+}
+
+/// Evaluates both curves at the union of their x positions and interpolates each point, like
+/// the preset strength slider in the editor.
+fn blend_curve(from: &[(f64, f64)], to: &[(f64, f64)], strength: f64) -> Value {
+    let mut xs: Vec<f64> = from.iter().chain(to).map(|&(x, _)| x).collect();
+    xs.sort_by(f64::total_cmp);
+    xs.dedup();
+    xs.into_iter()
+        .map(|x| {
+            let (y_from, y_to) = (evaluate_curve(from, x), evaluate_curve(to, x));
+            let y = (y_from + (y_to - y_from) * strength).clamp(0.0, 255.0);
+            serde_json::json!({ "x": x, "y": y })
+        })
+        .collect()
+}
+
+/// Numbers are interpolated, curves point by point and objects field by field. Anything else,
+/// such as LUT paths, is taken from `targets` as soon as the strength is above zero.
+fn blend(
+    current: &mut Map<String, Value>,
+    targets: &Map<String, Value>,
+    defaults: Option<&Map<String, Value>>,
+    strength: f64,
+) {
+    for (key, target) in targets {
+        if !current.contains_key(key)
+            && let Some(default) = defaults.and_then(|d| d.get(key))
+        {
+            current.insert(key.clone(), default.clone());
+        }
+
+        match (current.get_mut(key), target) {
+            (Some(Value::Object(existing)), Value::Object(nested)) => {
+                blend(existing, nested, nested_defaults(defaults, key), strength);
+            }
+            (existing, Value::Number(to)) if existing.as_ref().is_none_or(|v| v.is_number()) => {
+                let from = existing.and_then(|v| v.as_f64()).unwrap_or(0.0);
+                let to = to.as_f64().unwrap_or(from);
+                current.insert(key.clone(), number(key, from + (to - from) * strength));
+            }
+            (Some(existing), to) if strength < 1.0 => {
+                let blended = curve_points(existing)
+                    .zip(curve_points(to))
+                    .map(|(from, to)| blend_curve(&from, &to, strength));
+                current.insert(key.clone(), blended.unwrap_or_else(|| to.clone()));
+            }
+            _ => {
+                current.insert(key.clone(), target.clone());
+            }
+        }
+    }
+}
+
+/// Combines `relative` with in-memory adjustments, for the image that is open in the editor.
+#[tauri::command]
+pub fn combine_relative_adjustments(
+    mut adjustments: Value,
+    relative: RelativeAdjustments,
+) -> Value {
+    relative.apply(&mut adjustments);
+    adjustments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn relative(value: Value) -> RelativeAdjustments {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn multiply_scales_global_and_mask_fields() {
+        let mut adjustments = json!({
+            "contrast": 20.0,
+            "masks": [{ "adjustments": { "contrast": 20.0 } }, { "adjustments": { "exposure": 1.0 } }],
+        });
+        relative(json!({ "multiply": { "contrast": 1.2 }, "includeMasks": true }))
+            .apply(&mut adjustments);
+
+        assert_eq!(adjustments["contrast"], json!(24.0));
+        assert_eq!(
+            adjustments["masks"][0]["adjustments"]["contrast"],
+            json!(24.0)
+        );
+        assert_eq!(
+            adjustments["masks"][1]["adjustments"],
+            json!({ "exposure": 1.0 })
+        );
+    }
+
+    #[test]
+    fn blend_moves_numbers_towards_the_targets() {
+        let mut adjustments = json!({ "exposure": 1.0, "hsl": { "reds": { "hue": 10.0 } } });
+        relative(json!({
+            "blend": { "exposure": 3.0, "contrast": 40.0, "hsl": { "reds": { "hue": 30.0 } } },
+            "strength": 0.5,
+            "defaults": { "contrast": 0.0 },
+        }))
+        .apply(&mut adjustments);
+
+        assert_eq!(adjustments["exposure"], json!(2.0));
+        assert_eq!(adjustments["contrast"], json!(20.0));
+        assert_eq!(adjustments["hsl"]["reds"]["hue"], json!(20.0));
+    }
+
+    #[test]
+    fn blend_interpolates_curves_point_by_point() {
+        let mut adjustments =
+            json!({ "curves": { "luma": [{ "x": 0, "y": 0 }, { "x": 255, "y": 255 }] } });
+        relative(json!({
+            "blend": { "curves": { "luma": [
+                { "x": 0, "y": 20 },
+                { "x": 128, "y": 160 },
+                { "x": 255, "y": 255 },
+            ] } },
+            "strength": 0.5,
+        }))
+        .apply(&mut adjustments);
+
+        let points = curve_points(&adjustments["curves"]["luma"]).unwrap();
+        assert_eq!(points, vec![(0.0, 10.0), (128.0, 144.0), (255.0, 255.0)]);
+    }
+
+    #[test]
+    fn blend_at_full_strength_takes_the_target_curve() {
+        let target = json!([{ "x": 0, "y": 0 }, { "x": 64, "y": 90 }, { "x": 255, "y": 255 }]);
+        let mut adjustments = json!({ "curves": { "luma": [{ "x": 0, "y": 0 }, { "x": 200, "y": 180 }, { "x": 255, "y": 255 }] } });
+        relative(json!({ "blend": { "curves": { "luma": target.clone() } } }))
+            .apply(&mut adjustments);
+
+        assert_eq!(adjustments["curves"]["luma"], target);
+    }
+
+    #[test]
+    fn blend_replaces_values_that_cannot_be_interpolated() {
+        let mut adjustments = json!({ "lutPath": "a.cube", "toneMapper": "basic" });
+        relative(json!({
+            "blend": { "lutPath": "b.cube", "toneMapper": "agx" },
+            "strength": 0.25,
+        }))
+        .apply(&mut adjustments);
+
+        assert_eq!(adjustments["lutPath"], json!("b.cube"));
+        assert_eq!(adjustments["toneMapper"], json!("agx"));
+    }
+
+    #[test]
+    fn blend_with_zero_strength_changes_nothing() {
+        let mut adjustments = json!({ "exposure": 1.0 });
+        relative(json!({ "blend": { "exposure": 3.0, "lutPath": "b.cube" }, "strength": 0.0 }))
+            .apply(&mut adjustments);
+
+        assert_eq!(adjustments, json!({ "exposure": 1.0 }));
+    }
+}
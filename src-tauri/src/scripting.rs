@@ -0,0 +1,567 @@
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use rhai::{AST, Array, Dynamic, Engine, EvalAltResult, Map, Scope};
+use serde::Serialize;
+use serde_json::{Value, json};
+use tauri::{AppHandle, Manager};
+
+use crate::AppState;
+use crate::app_settings::{AppSettings, load_settings};
+use crate::exif_processing::{load_sidecar, read_exif_data_from_bytes, read_rrexif_sidecar};
+use crate::export_processing::{
+    ExportAdjustmentsMode, ExportVariant, export_images_impl, find_export_preset,
+};
+use crate::file_management::{
+    expand_cli_paths, parse_virtual_path, read_file_mapped, resolve_cli_preset,
+    sync_metadata_to_xmp,
+};
+use crate::image_processing::ImageMetadata;
+use crate::launch_request::HeadlessScriptSession;
+use crate::tagging::{COLOR_TAG_PREFIX, USER_TAG_PREFIX};
+
+/// Scripts are stopped after this many operations, so an endless loop cannot hang a batch.
+const MAX_SCRIPT_OPERATIONS: u64 = 50_000_000;
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptImageReport {
+    pub path: String,
+    pub changes: Vec<String>,
+    /// Export presets the script queued this image for.
+    pub exports: Vec<String>,
+    /// Lines printed by the script.
+    pub output: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptReport {
+    pub dry_run: bool,
+    pub images: Vec<ScriptImageReport>,
+}
+
+/// Collects what the script printed and queued while it runs on one image.
+#[derive(Default)]
+struct ScriptContext {
+    output: Vec<String>,
+    exports: Vec<String>,
+}
+
+struct ScriptImage {
+    rating: u8,
+    tags: Vec<String>,
+    color: String,
+    adjustments: Value,
+}
+
+fn build_engine(
+    context: Rc<RefCell<ScriptContext>>,
+    settings: Rc<AppSettings>,
+    app_handle: &AppHandle,
+) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_SCRIPT_OPERATIONS);
+
+    let ctx = context.clone();
+    engine.on_print(move |text| ctx.borrow_mut().output.push(text.to_string()));
+    let ctx = context.clone();
+    engine.on_debug(move |text, _, _| ctx.borrow_mut().output.push(text.to_string()));
+
+    let app = app_handle.clone();
+    let presets: RefCell<HashMap<String, Dynamic>> = RefCell::new(HashMap::new());
+    engine.register_fn(
+        "preset",
+        move |name: &str| -> Result<Dynamic, Box<EvalAltResult>> {
+            if let Some(adjustments) = presets.borrow().get(name) {
+                return Ok(adjustments.clone());
+            }
+            let preset = resolve_cli_preset(name, &app)?;
+            let adjustments = rhai::serde::to_dynamic(preset.applicable_adjustments())?;
+            presets
+                .borrow_mut()
+                .insert(name.to_string(), adjustments.clone());
+            Ok(adjustments)
+        },
+    );
+
+    engine.register_fn(
+        "export_image",
+        move |preset: &str| -> Result<(), Box<EvalAltResult>> {
+            let name = find_export_preset(&settings, preset)?.name.clone();
+            let mut ctx = context.borrow_mut();
+            if !ctx.exports.contains(&name) {
+                ctx.exports.push(name);
+            }
+            Ok(())
+        },
+    );
+
+    engine
+}
+
+/// Reads EXIF without caching it in the sidecar, so a dry run leaves every file untouched.
+fn read_image_exif(source_path: &Path, metadata: &ImageMetadata) -> HashMap<String, String> {
+    if let Some(exif) = read_rrexif_sidecar(source_path) {
+        return exif;
+    }
+    if let Some(exif) = metadata.exif.as_ref().filter(|exif| !exif.is_empty()) {
+        return exif.clone();
+    }
+    read_file_mapped(source_path)
+        .map(|mmap| read_exif_data_from_bytes(&source_path.to_string_lossy(), &mmap))
+        .unwrap_or_default()
+}
+
+fn parse_iso(exif: &HashMap<String, String>) -> i64 {
+    ["ISOSpeed", "PhotographicSensitivity", "ISOSpeedRatings"]
+        .iter()
+        .filter_map(|key| exif.get(*key))
+        .find_map(|value| {
+            value
+                .split(|c: char| !c.is_ascii_digit())
+                .find(|part| !part.is_empty())
+                .and_then(|part| part.parse().ok())
+        })
+        .unwrap_or(0)
+}
+
+/// Scripts see user tags without their prefix, the same way the metadata panel shows them.
+fn image_from_metadata(metadata: &ImageMetadata) -> ScriptImage {
+    let (color_tags, prefixed_tags): (Vec<String>, Vec<String>) = metadata
+        .tags
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|tag| tag.starts_with(COLOR_TAG_PREFIX));
+    let mut tags: Vec<String> = Vec::new();
+    for tag in prefixed_tags {
+        let tag = tag
+            .strip_prefix(USER_TAG_PREFIX)
+            .map(str::to_string)
+            .unwrap_or(tag);
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    ScriptImage {
+        rating: metadata.rating,
+        tags,
+        color: color_tags
+            .first()
+            .map(|tag| tag.trim_start_matches(COLOR_TAG_PREFIX).to_string())
+            .unwrap_or_default(),
+        adjustments: if metadata.adjustments.is_object() {
+            metadata.adjustments.clone()
+        } else {
+            json!({})
+        },
+    }
+}
+
+fn image_from_scope(scope: &Scope) -> Result<ScriptImage, String> {
+    let rating = scope
+        .get_value::<i64>("rating")
+        .ok_or("`rating` must be a number")?;
+    let mut tags: Vec<String> = Vec::new();
+    for tag in scope
+        .get_value::<Array>("tags")
+        .ok_or("`tags` must be an array")?
+    {
+        let tag = tag.to_string().trim().to_string();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    let color = scope
+        .get_value::<Dynamic>("color")
+        .filter(|color| !color.is_unit())
+        .map(|color| color.to_string())
+        .unwrap_or_default();
+    let adjustments: Value = scope
+        .get_value::<Dynamic>("adjustments")
+        .map(|adjustments| rhai::serde::from_dynamic(&adjustments))
+        .transpose()
+        .map_err(|e| format!("Invalid adjustments: {}", e))?
+        .filter(Value::is_object)
+        .ok_or("`adjustments` must be a map")?;
+
+    Ok(ScriptImage {
+        rating: rating.clamp(0, 5) as u8,
+        tags,
+        color: color.trim().to_lowercase(),
+        adjustments,
+    })
+}
+
+fn format_value(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => "unset".to_string(),
+        Some(Value::Object(_)) | Some(Value::Array(_)) => "…".to_string(),
+        Some(value) => value.to_string(),
+    }
+}
+
+fn describe_changes(before: &ScriptImage, after: &ScriptImage) -> Vec<String> {
+    let mut changes = Vec::new();
+    if before.rating != after.rating {
+        changes.push(format!("rating: {} → {}", before.rating, after.rating));
+    }
+    if before.color != after.color {
+        let label = |color: &str| {
+            if color.is_empty() {
+                "none".to_string()
+            } else {
+                color.to_string()
+            }
+        };
+        changes.push(format!(
+            "color: {} → {}",
+            label(&before.color),
+            label(&after.color)
+        ));
+    }
+    for tag in after.tags.iter().filter(|tag| !before.tags.contains(tag)) {
+        changes.push(format!("tag: + {}", tag));
+    }
+    for tag in before.tags.iter().filter(|tag| !after.tags.contains(tag)) {
+        changes.push(format!("tag: - {}", tag));
+    }
+
+    let keys: BTreeSet<&String> = before
+        .adjustments
+        .as_object()
+        .into_iter()
+        .chain(after.adjustments.as_object())
+        .flat_map(|map| map.keys())
+        .collect();
+    for key in keys {
+        let (old, new) = (before.adjustments.get(key), after.adjustments.get(key));
+        if old != new {
+            changes.push(format!(
+                "{}: {} → {}",
+                key,
+                format_value(old),
+                format_value(new)
+            ));
+        }
+    }
+    changes
+}
+
+/// Tags the image already had keep their stored form (AI tags are unprefixed); tags added by the
+/// script become user tags.
+fn restore_tag_prefixes(stored: &[String], tags: Vec<String>) -> Vec<String> {
+    let mut restored = Vec::new();
+    for tag in tags {
+        let user_tag = format!("{}{}", USER_TAG_PREFIX, tag);
+        let had_ai_tag = stored.contains(&tag);
+        if had_ai_tag {
+            restored.push(tag);
+        }
+        if !had_ai_tag || stored.contains(&user_tag) {
+            restored.push(user_tag);
+        }
+    }
+    restored
+}
+
+fn write_script_changes(
+    path: &str,
+    sidecar_path: &Path,
+    mut metadata: ImageMetadata,
+    before: &ScriptImage,
+    after: ScriptImage,
+    settings: &AppSettings,
+) -> Result<(), String> {
+    metadata.rating = after.rating;
+    let mut tags = restore_tag_prefixes(metadata.tags.as_deref().unwrap_or_default(), after.tags);
+    if !after.color.is_empty() {
+        tags.push(format!("{}{}", COLOR_TAG_PREFIX, after.color));
+    }
+    metadata.tags = if tags.is_empty() { None } else { Some(tags) };
+    if after.adjustments != before.adjustments {
+        metadata.adjustments = after.adjustments;
+    }
+
+    let json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+    fs::write(sidecar_path, json).map_err(|e| format!("Failed to write sidecar: {}", e))?;
+
+    if settings.enable_xmp_sync.unwrap_or(false) {
+        sync_metadata_to_xmp(
+            &parse_virtual_path(path).0,
+            &metadata,
+            settings.create_xmp_if_missing.unwrap_or(false),
+        );
+    }
+    Ok(())
+}
+
+fn run_on_image(
+    engine: &Engine,
+    ast: &AST,
+    context: &RefCell<ScriptContext>,
+    path: &str,
+    dry_run: bool,
+    settings: &AppSettings,
+) -> ScriptImageReport {
+    let (source_path, sidecar_path) = parse_virtual_path(path);
+    let metadata = load_sidecar(&sidecar_path);
+    let before = image_from_metadata(&metadata);
+    let exif = read_image_exif(&source_path, &metadata);
+
+    let mut scope = Scope::new();
+    scope.push_constant("path", path.to_string());
+    scope.push_constant(
+        "file_name",
+        source_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    );
+    scope.push_constant("iso", parse_iso(&exif));
+    scope.push_constant(
+        "exif",
+        exif.into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect::<Map>(),
+    );
+    scope.push("rating", before.rating as i64);
+    scope.push(
+        "tags",
+        before
+            .tags
+            .iter()
+            .map(|tag| tag.clone().into())
+            .collect::<Array>(),
+    );
+    scope.push("color", before.color.clone());
+    scope.push(
+        "adjustments",
+        rhai::serde::to_dynamic(&before.adjustments).unwrap_or_else(|_| Map::new().into()),
+    );
+
+    let result = engine
+        .run_ast_with_scope(&mut scope, ast)
+        .map_err(|e| e.to_string())
+        .and_then(|_| image_from_scope(&scope));
+    let ScriptContext { output, exports } = context.take();
+
+    let mut report = ScriptImageReport {
+        path: path.to_string(),
+        output,
+        ..Default::default()
+    };
+    match result {
+        Ok(after) => {
+            report.changes = describe_changes(&before, &after);
+            report.exports = exports;
+            if !dry_run && !report.changes.is_empty() {
+                report.error =
+                    write_script_changes(path, &sidecar_path, metadata, &before, after, settings)
+                        .err();
+            }
+        }
+        Err(e) => report.error = Some(e),
+    }
+    report
+}
+
+async fn export_script_images(
+    report: &ScriptReport,
+    output_folder: &str,
+    app_handle: &AppHandle,
+) -> Result<(), String> {
+    let mut batches: Vec<(&str, Vec<String>)> = Vec::new();
+    for image in report.images.iter().filter(|image| image.error.is_none()) {
+        for preset in &image.exports {
+            match batches
+                .iter()
+                .position(|(name, _)| *name == preset.as_str())
+            {
+                Some(index) => batches[index].1.push(image.path.clone()),
+                None => batches.push((preset, vec![image.path.clone()])),
+            }
+        }
+    }
+    if batches.is_empty() {
+        return Ok(());
+    }
+    if output_folder.is_empty() {
+        return Err("The script exports images, but no output folder was given.".to_string());
+    }
+    fs::create_dir_all(output_folder)
+        .map_err(|e| format!("Failed to create output directory: {}", e))?;
+
+    let settings = load_settings(app_handle.clone()).unwrap_or_default();
+    for (preset, paths) in batches {
+        let variant = ExportVariant::from_preset(find_export_preset(&settings, preset)?, None);
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        export_images_impl(
+            paths.clone(),
+            output_folder.to_string(),
+            false,
+            Vec::new(),
+            variant.export_settings,
+            variant.output_format,
+            None,
+            ExportAdjustmentsMode::UseSidecars {
+                active_path: None,
+                active_adjustments: None,
+            },
+            app_handle.state::<AppState>(),
+            app_handle.clone(),
+            Some(tx),
+        )
+        .await?;
+
+        match rx.await {
+            Ok(Ok(())) => {}
+            Ok(Err(failures)) => {
+                return Err(format!(
+                    "{} of {} images failed to export with '{}'",
+                    failures.len(),
+                    paths.len(),
+                    preset
+                ));
+            }
+            Err(_) => return Err("The export was cancelled.".to_string()),
+        }
+    }
+    Ok(())
+}
+
+/// Runs the script once per image. In a dry run nothing is written and no exports are started;
+/// the report lists what would have changed.
+pub(crate) async fn run_script(
+    script: String,
+    paths: Vec<String>,
+    dry_run: bool,
+    output_folder: Option<String>,
+    app_handle: &AppHandle,
+) -> Result<ScriptReport, String> {
+    let app = app_handle.clone();
+    let report = tauri::async_runtime::spawn_blocking(move || -> Result<ScriptReport, String> {
+        let settings = Rc::new(load_settings(app.clone()).unwrap_or_default());
+        let context = Rc::new(RefCell::new(ScriptContext::default()));
+        let engine = build_engine(context.clone(), settings.clone(), &app);
+        let ast = engine
+            .compile(&script)
+            .map_err(|e| format!("Script error: {}", e))?;
+
+        let images = paths
+            .iter()
+            .map(|path| run_on_image(&engine, &ast, &context, path, dry_run, &settings))
+            .collect();
+        Ok(ScriptReport { dry_run, images })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))??;
+
+    if !dry_run {
+        export_script_images(
+            &report,
+            output_folder.as_deref().unwrap_or_default(),
+            app_handle,
+        )
+        .await?;
+    }
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn run_batch_script(
+    script: String,
+    paths: Vec<String>,
+    dry_run: bool,
+    output_folder: Option<String>,
+    app_handle: AppHandle,
+) -> Result<ScriptReport, String> {
+    log::info!(
+        "Running script on {} images{}",
+        paths.len(),
+        if dry_run { " (dry run)" } else { "" }
+    );
+    run_script(script, paths, dry_run, output_folder, &app_handle).await
+}
+
+fn print_script_report(report: &ScriptReport) {
+    for image in &report.images {
+        if image.changes.is_empty()
+            && image.exports.is_empty()
+            && image.output.is_empty()
+            && image.error.is_none()
+        {
+            continue;
+        }
+        println!("{}", image.path);
+        for line in &image.output {
+            println!("  > {}", line);
+        }
+        for change in &image.changes {
+            println!("  {}", change);
+        }
+        for preset in &image.exports {
+            println!("  export: {}", preset);
+        }
+        if let Some(error) = &image.error {
+            println!("  error: {}", error);
+        }
+    }
+
+    let changed = report
+        .images
+        .iter()
+        .filter(|image| !image.changes.is_empty() && image.error.is_none())
+        .count();
+    let failed = report
+        .images
+        .iter()
+        .filter(|image| image.error.is_some())
+        .count();
+    println!(
+        "{} of {} images {}{}.",
+        changed,
+        report.images.len(),
+        if report.dry_run {
+            "would change (dry run)"
+        } else {
+            "changed"
+        },
+        if failed > 0 {
+            format!(", {} failed", failed)
+        } else {
+            String::new()
+        }
+    );
+}
+
+pub async fn run_headless_script(
+    session: HeadlessScriptSession,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    if session.script.is_empty() || session.paths.is_empty() {
+        return Err(
+            "Usage: rapidraw script <script.rhai> <path|dir>... [--dry-run] [--output <folder>]"
+                .to_string(),
+        );
+    }
+    let script = fs::read_to_string(&session.script)
+        .map_err(|e| format!("Failed to read script {}: {}", session.script, e))?;
+    let paths = expand_cli_paths(&session.paths, &app_handle)?;
+    if paths.is_empty() {
+        return Err("No supported images found at the given paths.".to_string());
+    }
+
+    let report = run_script(script, paths, session.dry_run, session.output, &app_handle).await?;
+    print_script_report(&report);
+
+    if report.images.iter().any(|image| image.error.is_some()) {
+        return Err("The script failed on some images.".to_string());
+    }
+    Ok(())
+}
@@ -115,6 +115,12 @@ struct GlobalAdjustments {
     halation_amount: f32,
     flare_amount: f32,
     sharpness_threshold: f32,
+
+    output_transfer: u32,
+    output_gamma: f32,
+    _pad_out1: f32,
+    _pad_out2: f32,
+    output_gamut_matrix: mat3x3<f32>,
 }
 
 struct MaskAdjustments {
@@ -212,6 +218,7 @@ const HSL_RANGES: array<HslRange, 8> = array<HslRange, 8>(
 @group(0) @binding(11) var flare_sampler: sampler;
 
 const LUMA_COEFF = vec3<f32>(0.2126, 0.7152, 0.0722);
+const HDR_RENDER_SCALE: f32 = 16.0;
 
 fn get_luma(c: vec3<f32>) -> f32 {
     return dot(c, LUMA_COEFF);
@@ -243,6 +250,20 @@ fn linear_to_srgb_extended(c: vec3<f32>) -> vec3<f32> {
     return select(higher, lower, safe_c <= cutoff);
 }
 
+// Re-encodes an sRGB-curve value in the export space's transfer curve. 0 leaves sRGB output
+// untouched, 1 is the sRGB curve (Display P3), 2 is Rec. 709 and 3 a pure gamma.
+fn encode_output_transfer(c: vec3<f32>) -> vec3<f32> {
+    let linear = srgb_to_linear(clamp(c, vec3<f32>(0.0), vec3<f32>(1.0)));
+    if (adjustments.global.output_transfer == 2u) {
+        let higher = 1.099 * pow(linear, vec3<f32>(0.45)) - 0.099;
+        return select(higher, linear * 4.5, linear < vec3<f32>(0.018));
+    }
+    if (adjustments.global.output_transfer == 3u) {
+        return pow(linear, vec3<f32>(1.0 / adjustments.global.output_gamma));
+    }
+    return c;
+}
+
 fn linear_to_vlog(c: vec3<f32>) -> vec3<f32> {
     let safe_c = max(c, vec3<f32>(0.0));
     let low = 5.6 * safe_c + 0.125;
@@ -1820,6 +1841,20 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
         }
     }
 
+    if (adjustments.global.output_transfer != 0u) {
+        // Wide-gamut exports render in the export primaries from here on, so colours outside
+        // sRGB survive the tonemapper and the final clamp.
+        composite_rgb_linear = adjustments.global.output_gamut_matrix * composite_rgb_linear;
+    }
+
+    if (adjustments.global.tonemapper_mode == 2u) {
+        // Un-tonemapped render used to build HDR gain maps. Scene-linear values are scaled so
+        // highlights up to HDR_RENDER_SCALE fit the 8-bit target, then sRGB-encoded for precision.
+        let hdr_encoded = linear_to_srgb(max(composite_rgb_linear, vec3<f32>(0.0)) / HDR_RENDER_SCALE);
+        textureStore(output_texture, id.xy, vec4<f32>(hdr_encoded, original_alpha));
+        return;
+    }
+
     var default_tonemapped: vec3<f32>;
     if (adjustments.global.tonemapper_mode == 1u) {
         default_tonemapped = agx_full_transform(composite_rgb_linear);
@@ -1897,6 +1932,10 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
         }
     }
 
+    if (adjustments.global.output_transfer != 0u) {
+        final_rgb = encode_output_transfer(final_rgb);
+    }
+
     let dither_amount = 1.0 / 255.0;
     final_rgb += dither(id.xy) * dither_amount;
 
@@ -1,5 +1,4 @@
 use anyhow::Result;
-use futures::stream::{self, StreamExt};
 use image::{DynamicImage, imageops::FilterType};
 use ndarray::{Array, Axis};
 use ort::session::Session;
@@ -8,17 +7,16 @@ use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Emitter, Manager, State};
+use std::sync::Mutex;
+use tauri::AppHandle;
 use tokenizers::Tokenizer;
-use tokio::task::JoinHandle;
 use walkdir::WalkDir;
 
+use crate::ai_processing::ClipModels;
+use crate::candidates::TAG_CANDIDATES;
 use crate::file_management::{self, parse_virtual_path};
-use crate::formats::is_supported_image_file;
 use crate::hierarchy::TAG_HIERARCHY;
-use crate::image_processing::ImageMetadata;
-use crate::{AppState, candidates::TAG_CANDIDATES};
+use crate::image_processing::{GpuContext, ImageMetadata};
 
 pub const COLOR_TAG_PREFIX: &str = "color:";
 pub const USER_TAG_PREFIX: &str = "user:";
@@ -248,168 +246,62 @@ pub fn generate_tags_with_clip(
     Ok(final_tags)
 }
 
-#[tauri::command]
-pub async fn start_background_indexing(
-    folder_path: String,
-    app_handle: AppHandle,
-    state: State<'_, AppState>,
+/// Generates AI tags for an image that has none yet and merges them into its sidecar.
+pub(crate) fn index_image_tags(
+    path_str: &str,
+    app_handle: &AppHandle,
+    clip_models: &ClipModels,
+    gpu_context: Option<&GpuContext>,
+    custom_ai_tags: Option<Vec<String>>,
+    ai_tag_count: usize,
 ) -> Result<(), String> {
-    if let Some(handle) = state.indexing_task_handle.lock().unwrap().take() {
-        println!("Cancelling previous indexing task.");
-        handle.abort();
-    }
+    let (_, sidecar_path) = parse_virtual_path(path_str);
+    let mut metadata = crate::exif_processing::load_sidecar(&sidecar_path);
 
-    let settings = crate::load_settings(app_handle.clone())?;
-    if !settings.enable_ai_tagging.unwrap_or(false) {
+    let should_generate_tags = match &metadata.tags {
+        None => true,
+        Some(tags) => !tags
+            .iter()
+            .any(|tag| !tag.starts_with(COLOR_TAG_PREFIX) && !tag.starts_with(USER_TAG_PREFIX)),
+    };
+    if !should_generate_tags {
         return Ok(());
     }
 
-    let max_concurrent_tasks = settings.tagging_thread_count.unwrap_or(3).max(1) as usize;
-    let custom_ai_tags = settings.custom_ai_tags.clone();
-    let ai_tag_count = settings.ai_tag_count.unwrap_or(10) as usize;
-
-    let clip_models = crate::ai_processing::get_or_init_clip_models(
-        &app_handle,
-        &state.ai_state,
-        &state.ai_init_lock,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let app_handle_clone = app_handle.clone();
-
-    let task: JoinHandle<()> = tokio::spawn(async move {
-        let _ = app_handle_clone.emit("indexing-started", ());
-        println!("Starting background indexing for: {}", folder_path);
-        println!(
-            "Using {} concurrent threads for AI tagging.",
-            max_concurrent_tasks
-        );
-
-        let state_clone = app_handle_clone.state::<AppState>();
-        let gpu_context =
-            crate::gpu_processing::get_or_init_gpu_context(&state_clone, &app_handle).ok();
-
-        let image_paths: Vec<PathBuf> = match fs::read_dir(&folder_path) {
-            Ok(entries) => entries
-                .filter_map(Result::ok)
-                .map(|entry| entry.path())
-                .filter(|path| {
-                    path.is_file() && is_supported_image_file(path.to_string_lossy().as_ref())
-                })
-                .collect(),
-            Err(e) => {
-                eprintln!("Failed to read directory '{}': {}", folder_path, e);
-                let _ = app_handle_clone
-                    .emit("indexing-error", format!("Failed to read directory: {}", e));
-                *app_handle_clone
-                    .state::<AppState>()
-                    .indexing_task_handle
-                    .lock()
-                    .unwrap() = None;
-                return;
-            }
-        };
-
-        println!(
-            "Found {} images to process in {}",
-            image_paths.len(),
-            folder_path
-        );
-        let total_images = image_paths.len();
-        let processed_count = Arc::new(Mutex::new(0));
-        let custom_ai_tags_shared = Arc::new(custom_ai_tags);
-
-        stream::iter(image_paths)
-            .for_each_concurrent(max_concurrent_tasks, |path| {
-                let app_handle_inner = app_handle_clone.clone();
-                let clip_models_inner = clip_models.clone();
-                let gpu_context_inner = gpu_context.clone();
-                let processed_count_inner = Arc::clone(&processed_count);
-                let tags_inner = Arc::clone(&custom_ai_tags_shared);
-
-                async move {
-                    let path_str = path.to_string_lossy().to_string();
-                    let (_, sidecar_path) = parse_virtual_path(&path_str);
-
-                    let mut metadata = crate::exif_processing::load_sidecar(&sidecar_path);
-
-                    let should_generate_tags = match &metadata.tags {
-                        None => true,
-                        Some(tags) => !tags.iter().any(|tag| {
-                            !tag.starts_with(COLOR_TAG_PREFIX) && !tag.starts_with(USER_TAG_PREFIX)
-                        }),
-                    };
-
-                    if should_generate_tags {
-                        match file_management::get_cached_or_generate_thumbnail_image(
-                            &path_str,
-                            &app_handle_inner,
-                            gpu_context_inner.as_ref(),
-                        ) {
-                            Ok(image) => {
-                                if let Ok(ai_tags) = generate_tags_with_clip(
-                                    &image,
-                                    &clip_models_inner.model,
-                                    &clip_models_inner.tokenizer,
-                                    (*tags_inner).clone(),
-                                    ai_tag_count,
-                                ) {
-                                    println!("Found AI tags for {}: {:?}", path_str, ai_tags);
-
-                                    let mut existing_tags: HashSet<String> =
-                                        metadata.tags.unwrap_or_default().into_iter().collect();
-
-                                    for tag in ai_tags {
-                                        existing_tags.insert(tag);
-                                    }
-
-                                    let mut final_tags: Vec<String> =
-                                        existing_tags.into_iter().collect();
-                                    final_tags.sort_unstable();
-
-                                    metadata.tags = Some(final_tags);
-
-                                    if let Ok(json_string) = serde_json::to_string_pretty(&metadata)
-                                    {
-                                        let _ = fs::write(sidecar_path, json_string);
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!(
-                                    "Could not get or generate image for tagging {}: {}",
-                                    path_str, e
-                                );
-                            }
-                        }
-                    }
-
-                    let mut count = processed_count_inner.lock().unwrap();
-                    *count += 1;
-                    let _ = app_handle_inner.emit(
-                        "indexing-progress",
-                        serde_json::json!({
-                            "current": *count,
-                            "total": total_images
-                        }),
-                    );
-                }
-            })
-            .await;
-
-        println!("Background indexing finished for: {}", folder_path);
-        let _ = app_handle_clone.emit("indexing-finished", ());
+    let image =
+        file_management::get_cached_or_generate_thumbnail_image(path_str, app_handle, gpu_context)
+            .map_err(|e| {
+                format!(
+                    "Could not get or generate image for tagging {}: {}",
+                    path_str, e
+                )
+            })?;
+
+    if let Ok(ai_tags) = generate_tags_with_clip(
+        &image,
+        &clip_models.model,
+        &clip_models.tokenizer,
+        custom_ai_tags,
+        ai_tag_count,
+    ) {
+        println!("Found AI tags for {}: {:?}", path_str, ai_tags);
+
+        let mut existing_tags: HashSet<String> =
+            metadata.tags.unwrap_or_default().into_iter().collect();
+
+        for tag in ai_tags {
+            existing_tags.insert(tag);
+        }
 
-        *app_handle_clone
-            .state::<AppState>()
-            .indexing_task_handle
-            .lock()
-            .unwrap() = None;
-    });
+        let mut final_tags: Vec<String> = existing_tags.into_iter().collect();
+        final_tags.sort_unstable();
 
-    *state.indexing_task_handle.lock().unwrap() = Some(task);
+        metadata.tags = Some(final_tags);
 
+        if let Ok(json_string) = serde_json::to_string_pretty(&metadata) {
+            let _ = fs::write(sidecar_path, json_string);
+        }
+    }
     Ok(())
 }
 
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont, point};
+use chrono::{DateTime, Utc};
+use image::{Rgba, RgbaImage};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::exif_processing;
+use crate::file_management::read_file_mapped;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TextWatermarkSettings {
+    pub text: String,
+    #[serde(default)]
+    pub font_family: Option<String>,
+    pub font_size: f32,
+    #[serde(default = "default_text_color")]
+    pub color: String,
+}
+
+fn default_text_color() -> String {
+    "#ffffff".to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+static FONT_DATABASE: Lazy<fontdb::Database> = Lazy::new(|| {
+    let mut db = fontdb::Database::new();
+    db.load_system_fonts();
+    #[cfg(target_os = "android")]
+    db.load_fonts_dir("/system/fonts");
+    db
+});
+
+fn load_font(family: Option<&str>) -> Result<FontVec, String> {
+    let db = &*FONT_DATABASE;
+    let mut families = Vec::new();
+    if let Some(name) = family.map(str::trim).filter(|n| !n.is_empty()) {
+        families.push(fontdb::Family::Name(name));
+    }
+    families.push(fontdb::Family::SansSerif);
+
+    let id = db
+        .query(&fontdb::Query {
+            families: &families,
+            ..Default::default()
+        })
+        .or_else(|| db.faces().next().map(|face| face.id))
+        .ok_or_else(|| "No fonts available for the text watermark.".to_string())?;
+
+    db.with_face_data(id, |data, index| {
+        FontVec::try_from_vec_and_index(data.to_vec(), index)
+    })
+    .ok_or_else(|| "Failed to read watermark font data.".to_string())?
+    .map_err(|e| format!("Failed to parse watermark font: {}", e))
+}
+
+fn parse_hex_color(color: &str) -> [u8; 3] {
+    let hex = color.trim().trim_start_matches('#');
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2).unwrap_or("ff"), 16).ok();
+    match hex.len() {
+        3 => {
+            let short: Vec<u8> = hex
+                .chars()
+                .filter_map(|c| c.to_digit(16).map(|d| (d * 17) as u8))
+                .collect();
+            if short.len() == 3 {
+                [short[0], short[1], short[2]]
+            } else {
+                [255, 255, 255]
+            }
+        }
+        6 | 8 => match (channel(0), channel(2), channel(4)) {
+            (Some(r), Some(g), Some(b)) => [r, g, b],
+            _ => [255, 255, 255],
+        },
+        _ => [255, 255, 255],
+    }
+}
+
+fn format_focal_length(value: &str) -> String {
+    let number = value.trim_end_matches("mm").trim();
+    match number.parse::<f32>() {
+        Ok(v) if v.fract().abs() < 0.05 => format!("{}mm", v.round()),
+        Ok(v) => format!("{:.1}mm", v),
+        Err(_) => value.to_string(),
+    }
+}
+
+fn build_token_map(source_path: &str) -> HashMap<&'static str, String> {
+    let path = Path::new(source_path);
+    let exif = match read_file_mapped(path) {
+        Ok(mmap) => exif_processing::read_exif_data(source_path, &mmap),
+        Err(_) => HashMap::new(),
+    };
+    let get = |keys: &[&str]| {
+        keys.iter()
+            .filter_map(|k| exif.get(*k))
+            .map(|v| v.trim().trim_matches('"').to_string())
+            .find(|v| !v.is_empty())
+            .unwrap_or_default()
+    };
+
+    let make = get(&["Make"]);
+    let model = get(&["Model"]);
+    let camera = if model.to_lowercase().starts_with(&make.to_lowercase()) {
+        model.clone()
+    } else {
+        format!("{} {}", make, model).trim().to_string()
+    };
+
+    let aperture = get(&["FNumber", "ApertureValue"]);
+    let shutter = get(&["ExposureTime"]);
+    let focal = get(&["FocalLength"]);
+
+    let date: DateTime<Utc> = exif_processing::get_creation_date_from_path(path);
+    let local_date = date.with_timezone(&chrono::Local);
+
+    let mut tokens = HashMap::new();
+    tokens.insert("artist", get(&["Artist"]));
+    tokens.insert("copyright", get(&["Copyright"]));
+    tokens.insert("make", make);
+    tokens.insert("model", model);
+    tokens.insert("camera", camera);
+    tokens.insert("lens", get(&["LensModel", "LensSpecification"]));
+    tokens.insert(
+        "focal",
+        if focal.is_empty() {
+            focal
+        } else {
+            format_focal_length(&focal)
+        },
+    );
+    tokens.insert(
+        "aperture",
+        aperture.trim_start_matches("f/").trim().to_string(),
+    );
+    tokens.insert("shutter", shutter.trim_end_matches('s').trim().to_string());
+    tokens.insert("iso", get(&["PhotographicSensitivity", "ISOSpeed"]));
+    tokens.insert(
+        "original_filename",
+        path.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string(),
+    );
+    tokens.insert("YYYY", local_date.format("%Y").to_string());
+    tokens.insert("MM", local_date.format("%m").to_string());
+    tokens.insert("DD", local_date.format("%d").to_string());
+    tokens.insert("hh", local_date.format("%H").to_string());
+    tokens.insert("mm", local_date.format("%M").to_string());
+    tokens
+}
+
+pub fn expand_watermark_tokens(template: &str, source_path: &str) -> String {
+    if !template.contains('{') {
+        return template.to_string();
+    }
+    expand_tokens(template, &build_token_map(source_path))
+}
+
+/// Replaces each `{name}` in a single left-to-right pass. Substituted values are never scanned
+/// again, so EXIF text containing braces is written verbatim; unknown names are left as-is.
+fn expand_tokens(template: &str, tokens: &HashMap<&'static str, String>) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        result.push_str(&rest[..open]);
+        let candidate = &rest[open..];
+        let value = candidate
+            .find('}')
+            .and_then(|close| Some((tokens.get(&candidate[1..close])?, close)));
+        match value {
+            Some((value, close)) => {
+                result.push_str(value);
+                rest = &candidate[close + 1..];
+            }
+            None => {
+                result.push('{');
+                rest = &candidate[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Rasterizes the (already expanded) watermark text. `font_size` is a percentage of the
+/// image's short edge so the watermark keeps its proportions across export sizes.
+pub fn render_text_watermark(
+    text: &str,
+    settings: &TextWatermarkSettings,
+    base_min_dim: f32,
+    opacity: f32,
+    align: TextAlign,
+) -> Result<Option<RgbaImage>, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.iter().all(|l| l.trim().is_empty()) {
+        return Ok(None);
+    }
+
+    let px = base_min_dim * (settings.font_size / 100.0);
+    if px < 1.0 {
+        return Ok(None);
+    }
+
+    let font = load_font(settings.font_family.as_deref())?;
+    let scaled = font.as_scaled(PxScale::from(px));
+    let line_height = scaled.height() + scaled.line_gap();
+
+    let line_widths: Vec<f32> = lines
+        .iter()
+        .map(|line| {
+            let mut width = 0.0;
+            let mut previous = None;
+            for c in line.chars() {
+                let id = scaled.glyph_id(c);
+                if let Some(prev) = previous {
+                    width += scaled.kern(prev, id);
+                }
+                width += scaled.h_advance(id);
+                previous = Some(id);
+            }
+            width
+        })
+        .collect();
+
+    let max_width = line_widths.iter().cloned().fold(0.0, f32::max);
+    let width = max_width.ceil() as u32 + 2;
+    let height = (line_height * lines.len() as f32).ceil() as u32 + 2;
+    if width <= 2 || height <= 2 {
+        return Ok(None);
+    }
+
+    let [r, g, b] = parse_hex_color(&settings.color);
+    let opacity = opacity.clamp(0.0, 1.0);
+    let mut canvas = RgbaImage::from_pixel(width, height, Rgba([r, g, b, 0]));
+
+    for (i, line) in lines.iter().enumerate() {
+        let offset_x = match align {
+            TextAlign::Left => 1.0,
+            TextAlign::Center => 1.0 + (max_width - line_widths[i]) / 2.0,
+            TextAlign::Right => 1.0 + max_width - line_widths[i],
+        };
+        let baseline = 1.0 + scaled.ascent() + line_height * i as f32;
+
+        let mut caret = offset_x;
+        let mut previous = None;
+        for c in line.chars() {
+            let id = scaled.glyph_id(c);
+            if let Some(prev) = previous {
+                caret += scaled.kern(prev, id);
+            }
+            let glyph = id.with_scale_and_position(PxScale::from(px), point(caret, baseline));
+            caret += scaled.h_advance(id);
+            previous = Some(id);
+
+            let Some(outlined) = font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                let x = bounds.min.x as i32 + gx as i32;
+                let y = bounds.min.y as i32 + gy as i32;
+                if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+                    return;
+                }
+                let pixel = canvas.get_pixel_mut(x as u32, y as u32);
+                let alpha = (coverage.clamp(0.0, 1.0) * opacity * 255.0).round() as u8;
+                pixel[3] = pixel[3].max(alpha);
+            });
+        }
+    }
+
+    Ok(Some(canvas))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens() -> HashMap<&'static str, String> {
+        HashMap::from([
+            ("artist", "Jane {model}".to_string()),
+            ("model", "X100V".to_string()),
+            ("YYYY", "2024".to_string()),
+            ("mm", "05".to_string()),
+        ])
+    }
+
+    #[test]
+    fn expands_known_tokens() {
+        assert_eq!(
+            expand_tokens("{model} · {YYYY}-{mm}", &tokens()),
+            "X100V · 2024-05"
+        );
+    }
+
+    #[test]
+    fn does_not_expand_substituted_values() {
+        assert_eq!(expand_tokens("© {artist}", &tokens()), "© Jane {model}");
+    }
+
+    #[test]
+    fn keeps_unknown_and_unbalanced_braces() {
+        assert_eq!(
+            expand_tokens("{unknown} {{model}} {model", &tokens()),
+            "{unknown} {X100V} {model"
+        );
+        assert_eq!(expand_tokens("", &tokens()), "");
+    }
+}
@@ -0,0 +1,297 @@
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, GrayImage, imageops};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Tonemapper mode understood by the GPU shader that skips tonemapping and curves, producing a
+/// scene-linear render divided by `HDR_RENDER_SCALE` and sRGB-encoded.
+pub const HDR_RENDER_TONEMAPPER: u32 = 2;
+/// Must match `HDR_RENDER_SCALE` in shader.wgsl.
+const HDR_RENDER_SCALE: f32 = 16.0;
+
+const GAIN_MAP_DOWNSCALE: u32 = 4;
+const GAIN_MAP_QUALITY: u8 = 85;
+// Offsets keep the ratio stable in the shadows; identical values are written to the metadata
+// so decoders reconstruct exactly what was encoded.
+const OFFSET_SDR: f32 = 1.0 / 64.0;
+const OFFSET_HDR: f32 = 1.0 / 64.0;
+const MIN_USEFUL_LOG2_BOOST: f32 = 0.05;
+
+const XMP_NAMESPACE: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+const ISO_NAMESPACE: &[u8] = b"urn:iso:std:iso:ts:21496:-1\0";
+const ISO_DENOMINATOR: u32 = 1_000_000;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HdrGainMapSettings {
+    /// Maximum brightness of the HDR rendition above SDR white, in stops.
+    pub headroom_stops: f32,
+}
+
+pub struct GainMap {
+    image: GrayImage,
+    max_log2_boost: f32,
+}
+
+fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn luminance(rgb: [f32; 3]) -> f32 {
+    0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2]
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Builds a single-channel gain map from the final SDR export and the un-tonemapped render of
+/// the same edit. Only highlights that the SDR rendition had to compress are boosted, so the
+/// base look (tonemapper, curves, LUTs) is kept everywhere else. Returns `None` when the image
+/// has no highlight detail worth encoding.
+pub fn compute_gain_map(
+    sdr: &DynamicImage,
+    hdr_render: &DynamicImage,
+    settings: &HdrGainMapSettings,
+) -> Option<GainMap> {
+    let width = (sdr.width() / GAIN_MAP_DOWNSCALE).max(1);
+    let height = (sdr.height() / GAIN_MAP_DOWNSCALE).max(1);
+    let sdr_small = sdr
+        .resize_exact(width, height, imageops::FilterType::Triangle)
+        .to_rgb32f();
+    let hdr_small = hdr_render
+        .resize_exact(width, height, imageops::FilterType::Triangle)
+        .to_rgb32f();
+
+    let max_allowed = settings.headroom_stops.clamp(0.5, 6.0);
+    let log_boosts: Vec<f32> = sdr_small
+        .as_raw()
+        .par_chunks(3)
+        .zip(hdr_small.as_raw().par_chunks(3))
+        .map(|(s, h)| {
+            let sdr_y = luminance([
+                srgb_to_linear(s[0]),
+                srgb_to_linear(s[1]),
+                srgb_to_linear(s[2]),
+            ]);
+            let hdr_y = luminance([
+                srgb_to_linear(h[0]) * HDR_RENDER_SCALE,
+                srgb_to_linear(h[1]) * HDR_RENDER_SCALE,
+                srgb_to_linear(h[2]) * HDR_RENDER_SCALE,
+            ]);
+            let ratio = (hdr_y + OFFSET_HDR) / (sdr_y + OFFSET_SDR);
+            let highlight_weight = smoothstep(0.18, 0.6, sdr_y);
+            (ratio.max(1.0).log2() * highlight_weight).clamp(0.0, max_allowed)
+        })
+        .collect();
+
+    let max_log2_boost = log_boosts.iter().cloned().fold(0.0, f32::max);
+    if max_log2_boost < MIN_USEFUL_LOG2_BOOST {
+        return None;
+    }
+
+    let pixels = log_boosts
+        .iter()
+        .map(|v| ((v / max_log2_boost) * 255.0).round().clamp(0.0, 255.0) as u8)
+        .collect();
+    let image = GrayImage::from_raw(width, height, pixels)?;
+    Some(GainMap {
+        image: imageops::blur(&image, 0.75),
+        max_log2_boost,
+    })
+}
+
+fn app_segment(marker: u8, namespace: &[u8], payload: &[u8]) -> Result<Vec<u8>, String> {
+    let length = 2 + namespace.len() + payload.len();
+    if length > u16::MAX as usize {
+        return Err("Gain map metadata segment is too large".to_string());
+    }
+    let mut segment = Vec::with_capacity(length + 2);
+    segment.extend_from_slice(&[0xFF, marker]);
+    segment.extend_from_slice(&(length as u16).to_be_bytes());
+    segment.extend_from_slice(namespace);
+    segment.extend_from_slice(payload);
+    Ok(segment)
+}
+
+/// Offset just past the leading APPn/COM segments, where new metadata segments are inserted.
+fn metadata_insert_offset(jpeg: &[u8]) -> Result<usize, String> {
+    if jpeg.len() < 4 || jpeg[0] != 0xFF || jpeg[1] != 0xD8 {
+        return Err("Not a JPEG stream".to_string());
+    }
+    let mut pos = 2;
+    while pos + 4 <= jpeg.len() && jpeg[pos] == 0xFF {
+        let marker = jpeg[pos + 1];
+        if !(0xE0..=0xEF).contains(&marker) && marker != 0xFE {
+            break;
+        }
+        let length = u16::from_be_bytes([jpeg[pos + 2], jpeg[pos + 3]]) as usize;
+        pos += 2 + length;
+    }
+    Ok(pos.min(jpeg.len()))
+}
+
+fn insert_segments(jpeg: &[u8], segments: &[Vec<u8>]) -> Result<Vec<u8>, String> {
+    let offset = metadata_insert_offset(jpeg)?;
+    let extra: usize = segments.iter().map(Vec::len).sum();
+    let mut out = Vec::with_capacity(jpeg.len() + extra);
+    out.extend_from_slice(&jpeg[..offset]);
+    for segment in segments {
+        out.extend_from_slice(segment);
+    }
+    out.extend_from_slice(&jpeg[offset..]);
+    Ok(out)
+}
+
+fn gain_map_xmp(max_log2_boost: f32) -> String {
+    format!(
+        r#"<x:xmpmeta xmlns:x="adobe:ns:meta/" x:xmptk="RapidRAW">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about="" xmlns:hdrgm="http://ns.adobe.com/hdr-gain-map/1.0/" hdrgm:Version="1.0" hdrgm:GainMapMin="0" hdrgm:GainMapMax="{max:.6}" hdrgm:Gamma="1" hdrgm:OffsetSDR="{offset_sdr:.6}" hdrgm:OffsetHDR="{offset_hdr:.6}" hdrgm:HDRCapacityMin="0" hdrgm:HDRCapacityMax="{max:.6}" hdrgm:BaseRenditionIsHDR="False"/>
+  </rdf:RDF>
+</x:xmpmeta>"#,
+        max = max_log2_boost,
+        offset_sdr = OFFSET_SDR,
+        offset_hdr = OFFSET_HDR,
+    )
+}
+
+fn primary_xmp(gain_map_length: usize) -> String {
+    format!(
+        r#"<x:xmpmeta xmlns:x="adobe:ns:meta/" x:xmptk="RapidRAW">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about="" xmlns:Container="http://ns.google.com/photos/1.0/container/" xmlns:Item="http://ns.google.com/photos/1.0/container/item/" xmlns:hdrgm="http://ns.adobe.com/hdr-gain-map/1.0/" hdrgm:Version="1.0">
+      <Container:Directory>
+        <rdf:Seq>
+          <rdf:li rdf:parseType="Resource">
+            <Container:Item Item:Semantic="Primary" Item:Mime="image/jpeg"/>
+          </rdf:li>
+          <rdf:li rdf:parseType="Resource">
+            <Container:Item Item:Semantic="GainMap" Item:Mime="image/jpeg" Item:Length="{gain_map_length}"/>
+          </rdf:li>
+        </rdf:Seq>
+      </Container:Directory>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>"#
+    )
+}
+
+/// ISO 21496-1 gain map metadata, single channel with a common denominator.
+fn iso_gain_map_metadata(max_log2_boost: f32) -> Vec<u8> {
+    let fraction = |v: f32| (v * ISO_DENOMINATOR as f32).round() as i32;
+    let mut data = Vec::with_capacity(40);
+    data.extend_from_slice(&0u16.to_be_bytes()); // minimum version
+    data.extend_from_slice(&0u16.to_be_bytes()); // writer version
+    data.push(0x08); // flags: common denominator, single channel
+    data.extend_from_slice(&ISO_DENOMINATOR.to_be_bytes());
+    data.extend_from_slice(&0u32.to_be_bytes()); // base HDR headroom
+    data.extend_from_slice(&(fraction(max_log2_boost) as u32).to_be_bytes()); // alternate headroom
+    data.extend_from_slice(&0i32.to_be_bytes()); // gain map min
+    data.extend_from_slice(&fraction(max_log2_boost).to_be_bytes()); // gain map max
+    data.extend_from_slice(&ISO_DENOMINATOR.to_be_bytes()); // gamma
+    data.extend_from_slice(&fraction(OFFSET_SDR).to_be_bytes());
+    data.extend_from_slice(&fraction(OFFSET_HDR).to_be_bytes());
+    data
+}
+
+pub fn encode_gain_map_jpeg(gain_map: &GainMap) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let encoder = JpegEncoder::new_with_quality(Cursor::new(&mut bytes), GAIN_MAP_QUALITY);
+    DynamicImage::ImageLuma8(gain_map.image.clone())
+        .write_with_encoder(encoder)
+        .map_err(|e| format!("Failed to encode gain map: {}", e))?;
+
+    let segments = [
+        app_segment(
+            0xE1,
+            XMP_NAMESPACE,
+            gain_map_xmp(gain_map.max_log2_boost).as_bytes(),
+        )?,
+        app_segment(
+            0xE2,
+            ISO_NAMESPACE,
+            &iso_gain_map_metadata(gain_map.max_log2_boost),
+        )?,
+    ];
+    insert_segments(&bytes, &segments)
+}
+
+fn mpf_segment(primary_length: u32, gain_map_length: u32, gain_map_offset: u32) -> Vec<u8> {
+    const ENTRY_COUNT: u16 = 3;
+    const IFD_OFFSET: u32 = 8;
+    const MP_ENTRY_OFFSET: u32 = IFD_OFFSET + 2 + ENTRY_COUNT as u32 * 12 + 4;
+
+    let mut tiff = Vec::with_capacity(MP_ENTRY_OFFSET as usize + 32);
+    tiff.extend_from_slice(b"MM\0\x2A");
+    tiff.extend_from_slice(&IFD_OFFSET.to_be_bytes());
+    tiff.extend_from_slice(&ENTRY_COUNT.to_be_bytes());
+    // MPFVersion
+    tiff.extend_from_slice(&0xB000u16.to_be_bytes());
+    tiff.extend_from_slice(&7u16.to_be_bytes());
+    tiff.extend_from_slice(&4u32.to_be_bytes());
+    tiff.extend_from_slice(b"0100");
+    // NumberOfImages
+    tiff.extend_from_slice(&0xB001u16.to_be_bytes());
+    tiff.extend_from_slice(&4u16.to_be_bytes());
+    tiff.extend_from_slice(&1u32.to_be_bytes());
+    tiff.extend_from_slice(&2u32.to_be_bytes());
+    // MPEntry
+    tiff.extend_from_slice(&0xB002u16.to_be_bytes());
+    tiff.extend_from_slice(&7u16.to_be_bytes());
+    tiff.extend_from_slice(&32u32.to_be_bytes());
+    tiff.extend_from_slice(&MP_ENTRY_OFFSET.to_be_bytes());
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+
+    // Primary image: baseline JPEG, representative image.
+    tiff.extend_from_slice(&0x0003_0000u32.to_be_bytes());
+    tiff.extend_from_slice(&primary_length.to_be_bytes());
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    // Gain map: offset is relative to the start of this TIFF header.
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    tiff.extend_from_slice(&gain_map_length.to_be_bytes());
+    tiff.extend_from_slice(&gain_map_offset.to_be_bytes());
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    tiff
+}
+
+/// Wraps an encoded SDR JPEG and its gain map JPEG into an Ultra HDR file (MPF container).
+pub fn assemble_ultra_hdr_jpeg(primary: &[u8], gain_map_jpeg: &[u8]) -> Result<Vec<u8>, String> {
+    const MPF_NAMESPACE: &[u8] = b"MPF\0";
+
+    let xmp = app_segment(
+        0xE1,
+        XMP_NAMESPACE,
+        primary_xmp(gain_map_jpeg.len()).as_bytes(),
+    )?;
+    let iso = app_segment(0xE2, ISO_NAMESPACE, &[0, 0, 0, 0])?;
+    let placeholder = app_segment(0xE2, MPF_NAMESPACE, &mpf_segment(0, 0, 0))?;
+
+    let insert_at = metadata_insert_offset(primary)?;
+    let primary_length = primary.len() + xmp.len() + iso.len() + placeholder.len();
+    // Position of the MPF TIFF header: after the marker, length and namespace.
+    let tiff_start = insert_at + xmp.len() + iso.len() + 4 + MPF_NAMESPACE.len();
+    let gain_map_offset = primary_length - tiff_start;
+
+    let mpf = app_segment(
+        0xE2,
+        MPF_NAMESPACE,
+        &mpf_segment(
+            primary_length as u32,
+            gain_map_jpeg.len() as u32,
+            gain_map_offset as u32,
+        ),
+    )?;
+
+    let mut out = insert_segments(primary, &[xmp, iso, mpf])?;
+    out.extend_from_slice(gain_map_jpeg);
+    Ok(out)
+}
@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::AppState;
+use crate::color_management::OutputColorSpace;
+use crate::exif_processing;
+use crate::export_processing::{
+    ExportAdjustmentsMode, ExportSettings, ExportVariant, OutputSharpening, ResizeMode,
+    ResizeOptions, SharpeningTarget, WatermarkSettings, export_variants_impl,
+    virtual_copy_suffixes,
+};
+use crate::file_management::{
+    generate_filename_from_template, parse_virtual_path, read_exif_for_paths,
+};
+use crate::tagging::COLOR_TAG_PREFIX;
+
+const GALLERY_TEMPLATE: &str = include_str!("templates/web_gallery.html");
+const GALLERY_MANIFEST_NAME: &str = "gallery.json";
+const IMAGES_FOLDER: &str = "images";
+const THUMBNAILS_FOLDER: &str = "thumbs";
+const GALLERY_FILENAME_TEMPLATE: &str = "{sequence}_{original_filename}";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WebGallerySettings {
+    pub title: String,
+    /// "jpg" or "webp".
+    #[serde(default = "default_image_format")]
+    pub image_format: String,
+    pub image_size: u32,
+    pub thumbnail_size: u32,
+    pub quality: u8,
+    #[serde(default)]
+    pub watermark: Option<WatermarkSettings>,
+}
+
+fn default_image_format() -> String {
+    "jpg".to_string()
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct GalleryExif {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    camera: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lens: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    focal_length: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aperture: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shutter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iso: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    captured: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GalleryImage {
+    title: String,
+    file: String,
+    thumbnail: String,
+    width: u32,
+    height: u32,
+    rating: u8,
+    tags: Vec<String>,
+    exif: GalleryExif,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GalleryManifest {
+    title: String,
+    generated: String,
+    images: Vec<GalleryImage>,
+}
+
+fn gallery_exif(exif: &HashMap<String, String>) -> GalleryExif {
+    let get = |keys: &[&str]| {
+        keys.iter()
+            .filter_map(|k| exif.get(*k))
+            .map(|v| v.trim().trim_matches('"').to_string())
+            .find(|v| !v.is_empty())
+    };
+    let make = get(&["Make"]).unwrap_or_default();
+    let camera = get(&["Model"]).map(|model| {
+        if make.is_empty() || model.to_lowercase().starts_with(&make.to_lowercase()) {
+            model
+        } else {
+            format!("{} {}", make, model)
+        }
+    });
+
+    GalleryExif {
+        camera,
+        lens: get(&["LensModel", "LensSpecification"]),
+        focal_length: get(&["FocalLength"]),
+        aperture: get(&["FNumber", "ApertureValue"]),
+        shutter: get(&["ExposureTime"]),
+        iso: get(&["PhotographicSensitivity", "ISOSpeed"]),
+        captured: get(&["DateTimeOriginal", "DateTime"]),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_index_html(manifest: &GalleryManifest) -> Result<String, String> {
+    // The data is inlined so the page also works when opened straight from disk.
+    let data = serde_json::to_string(manifest)
+        .map_err(|e| e.to_string())?
+        .replace("</", "<\\/");
+    Ok(GALLERY_TEMPLATE
+        .replace("{{TITLE}}", &escape_html(&manifest.title))
+        .replace("{{DATA}}", &data))
+}
+
+fn gallery_variants(settings: &WebGallerySettings) -> Vec<ExportVariant> {
+    let variant =
+        |sub_folder: &str, size: u32, watermark: Option<WatermarkSettings>| ExportVariant {
+            output_format: settings.image_format.clone(),
+            sub_folder: Some(sub_folder.to_string()),
+            export_settings: ExportSettings {
+                jpeg_quality: settings.quality,
+                resize: Some(ResizeOptions {
+                    mode: ResizeMode::LongEdge,
+                    value: size,
+                    dont_enlarge: true,
+                }),
+                keep_metadata: false,
+                preserve_timestamps: false,
+                strip_gps: true,
+                filename_template: Some(GALLERY_FILENAME_TEMPLATE.to_string()),
+                watermark,
+                export_masks: false,
+                preserve_folders: false,
+                output_color_space: OutputColorSpace::Srgb,
+                output_sharpening: Some(OutputSharpening {
+                    target: SharpeningTarget::Screen,
+                    amount: 50.0,
+                }),
+                max_file_size_bytes: None,
+                hdr_gain_map: None,
+            },
+        };
+    vec![
+        variant(
+            IMAGES_FOLDER,
+            settings.image_size,
+            settings.watermark.clone(),
+        ),
+        variant(THUMBNAILS_FOLDER, settings.thumbnail_size, None),
+    ]
+}
+
+/// Writes `index.html` and `gallery.json` for the renditions that made it to disk.
+fn write_gallery_page(
+    output_folder: &Path,
+    paths: &[String],
+    settings: &WebGallerySettings,
+    exif_by_path: &HashMap<String, HashMap<String, String>>,
+) -> Result<usize, String> {
+    let total = paths.len();
+    let vc_suffixes = virtual_copy_suffixes(paths);
+    let mut images = Vec::with_capacity(total);
+
+    for (index, (path_str, vc_suffix)) in paths.iter().zip(vc_suffixes).enumerate() {
+        let (source_path, sidecar_path) = parse_virtual_path(path_str);
+        let file_date = exif_processing::get_creation_date_from_path(&source_path);
+        let mut stem = generate_filename_from_template(
+            GALLERY_FILENAME_TEMPLATE,
+            &source_path,
+            index + 1,
+            total,
+            &file_date,
+        );
+        if let Some(vc_id) = vc_suffix {
+            stem = format!("{}_VC{:02}", stem, vc_id);
+        }
+        let file_name = format!("{}.{}", stem, settings.image_format);
+        let file = format!("{}/{}", IMAGES_FOLDER, file_name);
+        let thumbnail = format!("{}/{}", THUMBNAILS_FOLDER, file_name);
+
+        let Ok((width, height)) = image::image_dimensions(output_folder.join(&file)) else {
+            log::warn!("Skipping '{}' in web gallery: rendition missing", path_str);
+            continue;
+        };
+
+        let metadata = exif_processing::load_sidecar(&sidecar_path);
+        let tags = metadata
+            .tags
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|tag| !tag.starts_with(COLOR_TAG_PREFIX))
+            .collect();
+
+        images.push(GalleryImage {
+            title: source_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string(),
+            file,
+            thumbnail,
+            width,
+            height,
+            rating: metadata.rating,
+            tags,
+            exif: exif_by_path
+                .get(path_str)
+                .map(gallery_exif)
+                .unwrap_or_default(),
+        });
+    }
+
+    let count = images.len();
+    let manifest = GalleryManifest {
+        title: settings.title.clone(),
+        generated: chrono::Utc::now().to_rfc3339(),
+        images,
+    };
+    let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    fs::write(output_folder.join(GALLERY_MANIFEST_NAME), json).map_err(|e| e.to_string())?;
+    fs::write(
+        output_folder.join("index.html"),
+        render_index_html(&manifest)?,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn export_web_gallery(
+    paths: Vec<String>,
+    output_folder: String,
+    settings: WebGallerySettings,
+    current_edit_path: Option<String>,
+    current_edit_adjustments: Option<Value>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    if paths.is_empty() {
+        return Err("No images selected for the web gallery.".to_string());
+    }
+    if !matches!(settings.image_format.as_str(), "jpg" | "webp") {
+        return Err(format!(
+            "Unsupported web gallery format: {}",
+            settings.image_format
+        ));
+    }
+    fs::create_dir_all(&output_folder).map_err(|e| e.to_string())?;
+
+    let exif_by_path = read_exif_for_paths(paths.clone(), state.clone()).await?;
+    let (completion_tx, completion_rx) = tokio::sync::oneshot::channel();
+
+    export_variants_impl(
+        paths.clone(),
+        output_folder.clone(),
+        false,
+        Vec::new(),
+        gallery_variants(&settings),
+        None,
+        ExportAdjustmentsMode::UseSidecars {
+            active_path: current_edit_path,
+            active_adjustments: current_edit_adjustments,
+        },
+        state,
+        app_handle,
+        Some(completion_tx),
+    )
+    .await?;
+
+    tokio::spawn(async move {
+        match completion_rx.await {
+            Ok(Ok(())) => {}
+            Ok(Err(failed)) => log::warn!(
+                "{} web gallery images failed to export and are left out of the page",
+                failed.len()
+            ),
+            Err(_) => return,
+        }
+        let output_folder = Path::new(&output_folder);
+        match write_gallery_page(output_folder, &paths, &settings, &exif_by_path) {
+            Ok(count) => log::info!(
+                "Web gallery with {} images written to {}",
+                count,
+                output_folder.display()
+            ),
+            Err(e) => log::error!("Failed to write web gallery page: {}", e),
+        }
+    });
+
+    Ok(())
+}
@@ -4,11 +4,13 @@ import { motion, AnimatePresence } from 'framer-motion';
 import { useTranslation } from 'react-i18next';
 import Slider from '../ui/Slider';
 import ColorWheel from '../ui/ColorWheel';
+import CameraProfileControl from '../ui/CameraProfileControl';
 import { ColorAdjustment, ColorCalibration, HueSatLum, INITIAL_ADJUSTMENTS } from '../../utils/adjustments';
 import { Adjustments, ColorGrading } from '../../utils/adjustments';
 import { AppSettings } from '../ui/AppProperties';
 import Text from '../ui/Text';
 import { TextColors, TextVariants, TextWeights } from '../../types/typography';
+import { useEditorStore } from '../../store/useEditorStore';
 
 interface ColorProps {
   color: string;
@@ -406,6 +408,7 @@ export default function ColorPanel({
   const [activeColor, setActiveColor] = useState('reds');
   const adjustmentVisibility = appSettings?.adjustmentVisibility || {};
   const isWgpuEnabled = appSettings?.useWgpuRenderer !== false;
+  const isRaw = useEditorStore((state) => state.selectedImage?.isRaw ?? false);
 
   const HSL_COLORS = useMemo<Array<ColorProps>>(
     () => [
@@ -509,6 +512,24 @@ export default function ColorPanel({
         />
       </div>
 
+      {!isForMask && isRaw && (
+        <div className="p-2 bg-bg-tertiary rounded-md">
+          <CameraProfileControl
+            profilePath={adjustments.cameraProfile ?? null}
+            useToneCurve={adjustments.cameraProfileToneCurve ?? false}
+            onProfileChange={(path) =>
+              setAdjustments((prev: Partial<Adjustments>) => ({ ...prev, [ColorAdjustment.CameraProfile]: path }))
+            }
+            onToneCurveChange={(enabled) =>
+              setAdjustments((prev: Partial<Adjustments>) => ({
+                ...prev,
+                [ColorAdjustment.CameraProfileToneCurve]: enabled,
+              }))
+            }
+          />
+        </div>
+      )}
+
       <div className="p-2 bg-bg-tertiary rounded-md">
         <Text variant={TextVariants.heading} className="mb-2">
           {t('adjustments.color.presence')}
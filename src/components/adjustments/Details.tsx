@@ -1,9 +1,13 @@
 import { useTranslation } from 'react-i18next';
 import Slider from '../ui/Slider';
-import { Adjustments, DetailsAdjustment } from '../../utils/adjustments';
+import Dropdown from '../ui/Dropdown';
+import CalibrationFrameControl from '../ui/CalibrationFrameControl';
+import DefectMapControl from '../ui/DefectMapControl';
+import { Adjustments, DemosaicMethod, DetailsAdjustment } from '../../utils/adjustments';
 import { AppSettings } from '../ui/AppProperties';
 import Text from '../ui/Text';
-import { TextVariants } from '../../types/typography';
+import { TextColors, TextVariants } from '../../types/typography';
+import { useEditorStore } from '../../store/useEditorStore';
 
 interface DetailsPanelProps {
   adjustments: Adjustments;
@@ -21,6 +25,14 @@ export default function DetailsPanel({
   onDragStateChange,
 }: DetailsPanelProps) {
   const { t } = useTranslation();
+  const isRaw = useEditorStore((state) => state.selectedImage?.isRaw ?? false);
+
+  const demosaicOptions = [
+    { label: t('adjustments.details.demosaicDefault'), value: DemosaicMethod.Default },
+    { label: t('adjustments.details.demosaicRcd'), value: DemosaicMethod.Rcd },
+    { label: t('adjustments.details.demosaicMarkesteijn'), value: DemosaicMethod.Markesteijn },
+    { label: t('adjustments.details.demosaicBilinear'), value: DemosaicMethod.Bilinear },
+  ];
 
   const handleAdjustmentChange = (key: string, value: string) => {
     const numericValue = parseInt(value, 10);
@@ -158,6 +170,59 @@ export default function DetailsPanel({
           />
         </div>
       )}
+
+      {!isForMask && isRaw && (
+        <div className="p-2 bg-bg-tertiary rounded-md">
+          <Text variant={TextVariants.heading} className="mb-2">
+            {t('adjustments.details.demosaic')}
+          </Text>
+          <Dropdown
+            options={demosaicOptions}
+            value={adjustments.demosaic ?? DemosaicMethod.Default}
+            onChange={(value: DemosaicMethod) =>
+              setAdjustments((prev: Partial<Adjustments>) => ({ ...prev, [DetailsAdjustment.Demosaic]: value }))
+            }
+          />
+          <Text variant={TextVariants.small} color={TextColors.secondary} className="mt-2">
+            {t('adjustments.details.demosaicDesc')}
+          </Text>
+        </div>
+      )}
+
+      {!isForMask && isRaw && (
+        <div className="p-2 bg-bg-tertiary rounded-md">
+          <Text variant={TextVariants.heading} className="mb-2">
+            {t('adjustments.details.calibrationFrames')}
+          </Text>
+          <div className="space-y-2">
+            <CalibrationFrameControl
+              kind="dark"
+              label={t('adjustments.details.darkFrame')}
+              path={adjustments.darkFrame ?? null}
+              onChange={(path) =>
+                setAdjustments((prev: Partial<Adjustments>) => ({ ...prev, [DetailsAdjustment.DarkFrame]: path }))
+              }
+            />
+            <CalibrationFrameControl
+              kind="flat"
+              label={t('adjustments.details.flatField')}
+              path={adjustments.flatField ?? null}
+              onChange={(path) =>
+                setAdjustments((prev: Partial<Adjustments>) => ({ ...prev, [DetailsAdjustment.FlatField]: path }))
+              }
+            />
+          </div>
+          <Text variant={TextVariants.small} color={TextColors.secondary} className="mt-2">
+            {t('adjustments.details.calibrationFramesDesc')}
+          </Text>
+        </div>
+      )}
+
+      {!isForMask && isRaw && (
+        <div className="p-2 bg-bg-tertiary rounded-md">
+          <DefectMapControl />
+        </div>
+      )}
     </div>
   );
 }
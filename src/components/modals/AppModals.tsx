@@ -18,6 +18,7 @@ import ConfirmModal from './ConfirmModal';
 import ImportSettingsModal from './ImportSettingsModal';
 import CullingModal from './CullingModal';
 import CollageModal from './CollageModal';
+import ScriptModal from './ScriptModal';
 import { AppSettings, Invokes, AlbumItem, Album, AlbumGroup } from '../ui/AppProperties';
 import { CopyPasteSettings } from '../../utils/adjustments';
 
@@ -31,7 +32,7 @@ export interface AppModalsProps {
   handleSaveFocusStack: () => Promise<string>;
   refreshImageList: () => Promise<void>;
   handleApplyDenoise: (intensity: number, method: 'ai' | 'bm3d') => Promise<void>;
-  handleBatchDenoise: (intensity: number, method: 'ai' | 'bm3d', paths: string[]) => Promise<string[]>;
+  handleBatchDenoise: (intensity: number, method: 'ai' | 'bm3d', paths: string[]) => Promise<void>;
   handleSaveDenoisedImage: () => Promise<string>;
   handleCreateFolder: (folderName: string) => Promise<void>;
   handleRenameFolder: (newName: string) => Promise<void>;
@@ -75,6 +76,7 @@ export default function AppModals(props: AppModalsProps) {
     denoiseModalState,
     cullingModalState,
     collageModalState,
+    scriptModalState,
     setUI,
   } = useUIStore(
     useShallow((state) => ({
@@ -98,6 +100,7 @@ export default function AppModals(props: AppModalsProps) {
       denoiseModalState: state.denoiseModalState,
       cullingModalState: state.cullingModalState,
       collageModalState: state.collageModalState,
+      scriptModalState: state.scriptModalState,
       setUI: state.setUI,
     })),
   );
@@ -244,13 +247,6 @@ export default function AppModals(props: AppModalsProps) {
         isOpen={negativeModalState.isOpen}
         onClose={() => setUI((state) => ({ negativeModalState: { ...state.negativeModalState, isOpen: false } }))}
         targetPaths={negativeModalState.targetPaths}
-        onSave={(savedPaths) => {
-          props.refreshImageList().then(() => {
-            if (selectedImage && negativeModalState.targetPaths.includes(selectedImage.path) && savedPaths.length > 0) {
-              props.handleImageSelect(savedPaths[0]);
-            }
-          });
-        }}
       />
       <DenoiseModal
         isOpen={denoiseModalState.isOpen}
@@ -357,6 +353,12 @@ export default function AppModals(props: AppModalsProps) {
         sourceImages={collageModalState.sourceImages}
         thumbnails={thumbnails}
       />
+      <ScriptModal
+        isOpen={scriptModalState.isOpen}
+        onApplied={props.refreshImageList}
+        onClose={() => setUI({ scriptModalState: { isOpen: false, targetPaths: [] } })}
+        targetPaths={scriptModalState.targetPaths}
+      />
     </>
   );
 }
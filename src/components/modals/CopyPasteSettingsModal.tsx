@@ -22,6 +22,7 @@ const DEFAULT_SETTINGS: CopyPasteSettings = {
   includedAdjustments: COPYABLE_ADJUSTMENT_KEYS,
   knownAdjustments: [],
   autoSync: false,
+  relativeMasks: false,
 };
 
 interface PasteModeSwitchProps {
@@ -41,6 +42,7 @@ const PasteModeSwitch = ({ selectedMode, onModeChange, isVisible }: PasteModeSwi
     () => [
       { id: PasteMode.Merge, label: t('modals.copyPaste.modeMerge') },
       { id: PasteMode.Replace, label: t('modals.copyPaste.modeReplace') },
+      { id: PasteMode.Relative, label: t('modals.copyPaste.modeRelative') },
     ],
     [t],
   );
@@ -59,7 +61,7 @@ const PasteModeSwitch = ({ selectedMode, onModeChange, isVisible }: PasteModeSwi
 
     if (isInitialAnimation.current && containerRef.current.offsetWidth > 0) {
       let initialX;
-      if (selectedMode === PasteMode.Replace) {
+      if (selectedMode !== PasteMode.Merge) {
         initialX = containerRef.current.offsetWidth;
       } else {
         initialX = -targetStyle.width;
@@ -210,7 +212,18 @@ export default function CopyPasteSettingsModal({ isOpen, onClose, onSave, settin
               <b>{t('modals.copyPaste.modeMerge')}:</b> {t('modals.copyPaste.descMerge')}
               <br />
               <b>{t('modals.copyPaste.modeReplace')}:</b> {t('modals.copyPaste.descReplace')}
+              <br />
+              <b>{t('modals.copyPaste.modeRelative')}:</b> {t('modals.copyPaste.descRelative')}
             </Text>
+            {localSettings.mode === PasteMode.Relative && (
+              <div className="mt-3">
+                <Switch
+                  label={t('modals.copyPaste.relativeMasksLabel')}
+                  checked={!!localSettings.relativeMasks}
+                  onChange={(checked) => setLocalSettings((p) => ({ ...p, relativeMasks: checked }))}
+                />
+              </div>
+            )}
           </div>
 
           <div>
@@ -7,13 +7,12 @@ import Dropdown from '../ui/Dropdown';
 import Slider from '../ui/Slider';
 import Text from '../ui/Text';
 import { TextColors, TextVariants, TextWeights } from '../../types/typography';
-import { listen } from '@tauri-apps/api/event';
 
 interface DenoiseModalProps {
   isOpen: boolean;
   onClose(): void;
   onDenoise(intensity: number, method: 'ai' | 'bm3d'): void;
-  onBatchDenoise(intensity: number, method: 'ai' | 'bm3d', paths: string[]): Promise<string[]>;
+  onBatchDenoise(intensity: number, method: 'ai' | 'bm3d', paths: string[]): Promise<void>;
   onSave(): Promise<string>;
   onOpenFile(path: string): void;
   error: string | null;
@@ -231,7 +230,6 @@ export default function DenoiseModal({
   const [method, setMethod] = useState<'ai' | 'bm3d'>('ai');
   const [isSaving, setIsSaving] = useState(false);
   const [savedPath, setSavedPath] = useState<string | null>(null);
-  const [batchProgress, setBatchProgress] = useState<{ current: number; total: number; path: string } | null>(null);
   const isBatch = targetPaths.length > 1;
   const mouseDownTarget = useRef<EventTarget | null>(null);
 
@@ -243,21 +241,9 @@ export default function DenoiseModal({
     [t],
   );
 
-  useEffect(() => {
-    const unlisten = listen('denoise-batch-progress', (e: any) => {
-      setBatchProgress(e.payload);
-    });
-    return () => {
-      unlisten.then((f) => f());
-    };
-  }, []);
-
-  const currentStatusText =
-    isBatch && batchProgress
-      ? t('modals.denoise.batchProgressText', { current: batchProgress.current, total: batchProgress.total })
-      : aiModelDownloadStatus?.includes('NIND')
-        ? t('modals.denoise.downloadingText', { status: aiModelDownloadStatus })
-        : progressMessage || t('modals.denoise.initializing');
+  const currentStatusText = aiModelDownloadStatus?.includes('NIND')
+    ? t('modals.denoise.downloadingText', { status: aiModelDownloadStatus })
+    : progressMessage || t('modals.denoise.initializing');
 
   useEffect(() => {
     if (isOpen) {
@@ -272,7 +258,6 @@ export default function DenoiseModal({
         setIsMounted(false);
         setSavedPath(null);
         setIsSaving(false);
-        setBatchProgress(null);
       }, 300);
       return () => clearTimeout(timer);
     }
@@ -305,7 +290,6 @@ export default function DenoiseModal({
         console.error('Batch denoise failed:', e);
       } finally {
         setIsSaving(false);
-        setBatchProgress(null);
       }
     } else {
       onDenoise(intensity / 100, method);
@@ -1,7 +1,6 @@
 import { useState, useEffect, useCallback, useRef } from 'react';
 import { useTranslation, Trans } from 'react-i18next';
 import { invoke } from '@tauri-apps/api/core';
-import { listen } from '@tauri-apps/api/event';
 import { RotateCcw, ZoomIn, ZoomOut, Maximize, Save, Loader2, Eye, EyeOff, Info } from 'lucide-react';
 import { AnimatePresence, motion } from 'framer-motion';
 import Button from '../ui/Button';
@@ -31,21 +30,14 @@ interface NegativeConversionModalProps {
   isOpen: boolean;
   onClose(): void;
   targetPaths: string[];
-  onSave(savedPaths: string[]): void;
 }
 
-export default function NegativeConversionModal({
-  isOpen,
-  onClose,
-  targetPaths,
-  onSave,
-}: NegativeConversionModalProps) {
+export default function NegativeConversionModal({ isOpen, onClose, targetPaths }: NegativeConversionModalProps) {
   const { t } = useTranslation();
   const [params, setParams] = useState<NegativeParams>(DEFAULT_PARAMS);
   const [previewUrl, setPreviewUrl] = useState<string | null>(null);
   const [isLoading, setIsLoading] = useState(true);
   const [isSaving, setIsSaving] = useState(false);
-  const [progress, setProgress] = useState<{ current: number; total: number } | null>(null);
 
   const [isMounted, setIsMounted] = useState(false);
   const [show, setShow] = useState(false);
@@ -59,15 +51,6 @@ export default function NegativeConversionModal({
   const [originalUrl, setOriginalUrl] = useState<string | null>(null);
   const selectedImagePath = targetPaths.length > 0 ? targetPaths[0] : null;
 
-  useEffect(() => {
-    const unlisten = listen('negative-batch-progress', (e: any) => {
-      setProgress(e.payload);
-    });
-    return () => {
-      unlisten.then((f) => f());
-    };
-  }, []);
-
   useEffect(() => {
     if (!isDragging) return;
     const handleWindowMouseMove = (e: MouseEvent) => {
@@ -161,7 +144,6 @@ export default function NegativeConversionModal({
         setZoom(1);
         setPan({ x: 0, y: 0 });
         setIsLoading(true);
-        setProgress(null);
       }, 300);
     }
   }, [isOpen, selectedImagePath, updatePreview]);
@@ -175,19 +157,16 @@ export default function NegativeConversionModal({
   const handleSave = async () => {
     if (targetPaths.length === 0) return;
     setIsSaving(true);
-    setProgress(null);
     try {
-      const savedPaths: string[] = await invoke('convert_negatives', {
+      await invoke('enqueue_job', {
+        kind: { type: 'convertNegatives', params },
         paths: targetPaths,
-        params,
       });
-      onSave(savedPaths);
       onClose();
     } catch (e) {
-      console.error('Failed to batch save negatives', e);
+      console.error('Failed to queue negative conversion', e);
     } finally {
       setIsSaving(false);
-      setProgress(null);
     }
   };
 
@@ -451,12 +430,7 @@ export default function NegativeConversionModal({
                 {isSaving ? (
                   <>
                     <Loader2 className="animate-spin mr-2" size={16} />
-                    {progress && progress.total > 1
-                      ? t('modals.negativeConversion.convertingProgress', {
-                          current: progress.current,
-                          total: progress.total,
-                        })
-                      : t('modals.negativeConversion.converting')}
+                    {t('modals.negativeConversion.converting')}
                   </>
                 ) : (
                   <>
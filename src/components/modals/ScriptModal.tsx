@@ -0,0 +1,199 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
+import { useTranslation } from 'react-i18next';
+import { FolderOpen, Loader2 } from 'lucide-react';
+import { Invokes } from '../ui/AppProperties';
+import Button from '../ui/Button';
+import Text from '../ui/Text';
+import { TextColors, TextVariants } from '../../types/typography';
+
+interface ScriptImageReport {
+  path: string;
+  changes: Array<string>;
+  exports: Array<string>;
+  output: Array<string>;
+  error: string | null;
+}
+
+interface ScriptReport {
+  dryRun: boolean;
+  images: Array<ScriptImageReport>;
+}
+
+interface ScriptModalProps {
+  isOpen: boolean;
+  onClose(): void;
+  onApplied(): void;
+  targetPaths: Array<string>;
+}
+
+const EXAMPLE_SCRIPT = `if iso > 6400 {
+    adjustments.lumaNoiseReduction = (adjustments.lumaNoiseReduction ?? 0) + 15;
+    tags.push("high-iso");
+}`;
+
+export default function ScriptModal({ isOpen, onClose, onApplied, targetPaths }: ScriptModalProps) {
+  const { t } = useTranslation();
+  const [script, setScript] = useState(EXAMPLE_SCRIPT);
+  const [outputFolder, setOutputFolder] = useState<string | null>(null);
+  const [report, setReport] = useState<ScriptReport | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [isRunning, setIsRunning] = useState(false);
+  const [isMounted, setIsMounted] = useState(false);
+  const [show, setShow] = useState(false);
+
+  useEffect(() => {
+    if (isOpen) {
+      setReport(null);
+      setError(null);
+      setIsMounted(true);
+      const timer = setTimeout(() => setShow(true), 10);
+      return () => clearTimeout(timer);
+    } else {
+      setShow(false);
+      const timer = setTimeout(() => setIsMounted(false), 300);
+      return () => clearTimeout(timer);
+    }
+  }, [isOpen]);
+
+  const handleChooseFolder = async () => {
+    const folder = await open({ title: t('modals.script.selectFolderTitle'), directory: true });
+    if (typeof folder === 'string') {
+      setOutputFolder(folder);
+    }
+  };
+
+  const handleRun = async (dryRun: boolean) => {
+    if (!script.trim() || targetPaths.length === 0 || isRunning) return;
+
+    setIsRunning(true);
+    setError(null);
+    setReport(null);
+    try {
+      const result: ScriptReport = await invoke(Invokes.RunBatchScript, {
+        script,
+        paths: targetPaths,
+        dryRun,
+        outputFolder,
+      });
+      setReport(result);
+      if (!dryRun) {
+        onApplied();
+      }
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsRunning(false);
+    }
+  };
+
+  if (!isMounted) {
+    return null;
+  }
+
+  const reportedImages =
+    report?.images.filter(
+      (image) => image.changes.length > 0 || image.exports.length > 0 || image.output.length > 0 || image.error,
+    ) ?? [];
+  const changedCount = report?.images.filter((image) => image.changes.length > 0 && !image.error).length ?? 0;
+
+  return (
+    <div
+      aria-modal="true"
+      className={`fixed inset-0 flex items-center justify-center z-50 bg-black/30 backdrop-blur-xs transition-opacity duration-300 ease-in-out ${
+        show ? 'opacity-100' : 'opacity-0'
+      }`}
+      onClick={onClose}
+      role="dialog"
+    >
+      <div
+        className={`bg-surface rounded-lg shadow-xl p-6 w-full max-w-2xl transform transition-all duration-300 ease-out ${
+          show ? 'scale-100 opacity-100 translate-y-0' : 'scale-95 opacity-0 -translate-y-4'
+        }`}
+        onClick={(e: any) => e.stopPropagation()}
+      >
+        <Text variant={TextVariants.title} className="mb-1">
+          {t('modals.script.title', { count: targetPaths.length })}
+        </Text>
+        <Text variant={TextVariants.small} color={TextColors.secondary} className="mb-4">
+          {t('modals.script.description')}
+        </Text>
+
+        <textarea
+          className="w-full h-48 bg-bg-primary border border-surface rounded-md p-2 font-mono text-sm text-text-primary focus:ring-accent focus:border-accent"
+          onChange={(e) => setScript(e.target.value)}
+          spellCheck={false}
+          value={script}
+        />
+
+        <div className="flex items-center gap-2 mt-3">
+          <button
+            className="flex items-center gap-2 px-3 py-1.5 bg-bg-primary hover:bg-card-active rounded-md text-sm text-text-primary transition-colors"
+            onClick={handleChooseFolder}
+          >
+            <FolderOpen size={16} />
+            {t('modals.script.exportFolder')}
+          </button>
+          <Text variant={TextVariants.small} color={TextColors.secondary} className="truncate">
+            {outputFolder ?? t('modals.script.noExportFolder')}
+          </Text>
+        </div>
+
+        {(report || error) && (
+          <div className="mt-4 max-h-60 overflow-y-auto bg-bg-primary rounded-md p-3 space-y-2">
+            {error && <Text className="text-red-400">{error}</Text>}
+            {report && (
+              <Text variant={TextVariants.heading}>
+                {report.dryRun
+                  ? t('modals.script.dryRunSummary', { changed: changedCount, total: report.images.length })
+                  : t('modals.script.runSummary', { changed: changedCount, total: report.images.length })}
+              </Text>
+            )}
+            {reportedImages.map((image) => (
+              <div key={image.path}>
+                <Text className="truncate">{image.path.split(/[\\/]/).pop()}</Text>
+                {image.output.map((line, index) => (
+                  <Text key={`output-${index}`} variant={TextVariants.small} color={TextColors.secondary}>
+                    &gt; {line}
+                  </Text>
+                ))}
+                {image.changes.map((change) => (
+                  <Text key={change} variant={TextVariants.small} color={TextColors.secondary}>
+                    {change}
+                  </Text>
+                ))}
+                {image.exports.map((preset) => (
+                  <Text key={`export-${preset}`} variant={TextVariants.small} color={TextColors.secondary}>
+                    {t('modals.script.exportTo', { preset })}
+                  </Text>
+                ))}
+                {image.error && (
+                  <Text variant={TextVariants.small} className="text-red-400">
+                    {image.error}
+                  </Text>
+                )}
+              </div>
+            ))}
+          </div>
+        )}
+
+        <div className="flex justify-end gap-3 mt-6">
+          <button
+            className="px-4 py-2 rounded-md text-text-secondary hover:bg-bg-primary transition-colors"
+            onClick={onClose}
+          >
+            {t('modals.script.close')}
+          </button>
+          <Button className="bg-surface" disabled={isRunning || !script.trim()} onClick={() => handleRun(true)}>
+            {t('modals.script.dryRun')}
+          </Button>
+          <Button disabled={isRunning || !script.trim()} onClick={() => handleRun(false)}>
+            {isRunning && <Loader2 size={16} className="animate-spin" />}
+            {t('modals.script.run')}
+          </Button>
+        </div>
+      </div>
+    </div>
+  );
+}
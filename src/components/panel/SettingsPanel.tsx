@@ -31,6 +31,8 @@ import Dropdown, { OptionItem } from '../ui/Dropdown';
 import Switch from '../ui/Switch';
 import Input from '../ui/Input';
 import Slider from '../ui/Slider';
+import HotFolderSettings from '../ui/HotFolderSettings';
+import ExternalEditorSettings from '../ui/ExternalEditorSettings';
 import { ThemeProps, THEMES, DEFAULT_THEME_ID } from '../../utils/themes';
 import { useTranslation } from 'react-i18next';
 import { Invokes } from '../ui/AppProperties';
@@ -538,6 +540,7 @@ export default function SettingsPanel({
     editorPreviewResolution: appSettings?.editorPreviewResolution || 1920,
     thumbnailResolution: appSettings?.thumbnailResolution || 720,
     rawHighlightCompression: appSettings?.rawHighlightCompression ?? 2.5,
+    rawHighlightMode: appSettings?.rawHighlightMode || 'compress',
     processingBackend: appSettings?.processingBackend || 'auto',
     linuxGpuOptimization: appSettings?.linuxGpuOptimization ?? false,
     highResZoomMultiplier: appSettings?.highResZoomMultiplier || 1.0,
@@ -549,6 +552,7 @@ export default function SettingsPanel({
     rawPreprocessingColorNr: appSettings?.rawPreprocessingColorNr ?? 0.5,
     rawPreprocessingSharpening: appSettings?.rawPreprocessingSharpening ?? 0.35,
     applyPreprocessingToNonRaws: appSettings?.applyPreprocessingToNonRaws ?? false,
+    rawDefectDetection: appSettings?.rawDefectDetection ?? false,
   });
   const [restartRequired, setRestartRequired] = useState(false);
   const [activeCategory, setActiveCategory] = useState('general');
@@ -575,6 +579,14 @@ export default function SettingsPanel({
     [t],
   );
 
+  const exrPrecisionOptions = useMemo<OptionItem<string>[]>(
+    () => [
+      { value: 'half', label: t('settings.processing.exrPrecisionHalf') },
+      { value: 'float', label: t('settings.processing.exrPrecisionFloat') },
+    ],
+    [t],
+  );
+
   const filteredBackendOptions = useMemo<OptionItem<string>[]>(() => {
     const rawOptions = [
       { value: 'auto', label: t('settings.processing.backends.auto') },
@@ -600,6 +612,14 @@ export default function SettingsPanel({
     [t],
   );
 
+  const highlightModeOptions = useMemo<OptionItem<string>[]>(
+    () => [
+      { value: 'compress', label: t('settings.processing.preprocessing.highlightModeOptions.compress') },
+      { value: 'reconstruct', label: t('settings.processing.preprocessing.highlightModeOptions.reconstruct') },
+    ],
+    [t],
+  );
+
   const tonemapperOptions = useMemo<OptionItem<string>[]>(
     () => [
       { value: 'agx', label: t('settings.processing.preprocessing.tonemapperOptions.agx') },
@@ -646,6 +666,7 @@ export default function SettingsPanel({
       editorPreviewResolution: appSettings?.editorPreviewResolution || 1920,
       thumbnailResolution: appSettings?.thumbnailResolution || 720,
       rawHighlightCompression: appSettings?.rawHighlightCompression ?? 2.5,
+      rawHighlightMode: appSettings?.rawHighlightMode || 'compress',
       processingBackend: appSettings?.processingBackend || 'auto',
       linuxGpuOptimization: appSettings?.linuxGpuOptimization ?? false,
       highResZoomMultiplier: appSettings?.highResZoomMultiplier || 1.0,
@@ -656,6 +677,7 @@ export default function SettingsPanel({
       rawPreprocessingColorNr: appSettings?.rawPreprocessingColorNr ?? 0.5,
       rawPreprocessingSharpening: appSettings?.rawPreprocessingSharpening ?? 0.35,
       applyPreprocessingToNonRaws: appSettings?.applyPreprocessingToNonRaws ?? false,
+      rawDefectDetection: appSettings?.rawDefectDetection ?? false,
     });
     setRestartRequired(false);
   }, [appSettings]);
@@ -693,9 +715,11 @@ export default function SettingsPanel({
       await onSettingsChange({ ...appSettings, [key]: value });
       if (
         key === 'rawHighlightCompression' ||
+        key === 'rawHighlightMode' ||
         key === 'rawPreprocessingColorNr' ||
         key === 'rawPreprocessingSharpening' ||
-        key === 'applyPreprocessingToNonRaws'
+        key === 'applyPreprocessingToNonRaws' ||
+        key === 'rawDefectDetection'
       ) {
         await invoke('clear_image_caches');
       }
@@ -1920,6 +1944,91 @@ export default function SettingsPanel({
                         />
                       </SettingItem>
 
+                      <SettingItem
+                        label={t('settings.processing.sceneReferredMerges')}
+                        description={t('settings.processing.sceneReferredMergesDesc')}
+                      >
+                        <Switch
+                          checked={appSettings?.sceneReferredMerges ?? false}
+                          id="scene-referred-merges-toggle"
+                          label={t('settings.processing.sceneReferredMergesLabel')}
+                          onChange={(checked) => {
+                            onSettingsChange({ ...appSettings, sceneReferredMerges: checked });
+                          }}
+                        />
+                      </SettingItem>
+
+                      {appSettings?.sceneReferredMerges && (
+                        <SettingItem
+                          label={t('settings.processing.exrPrecision')}
+                          description={t('settings.processing.exrPrecisionDesc')}
+                        >
+                          <Dropdown
+                            onChange={(value: any) => onSettingsChange({ ...appSettings, mergeExrPrecision: value })}
+                            options={exrPrecisionOptions}
+                            value={appSettings?.mergeExrPrecision || 'half'}
+                            triggerClassName="bg-bg-primary"
+                          />
+                        </SettingItem>
+                      )}
+
+                      <SettingItem
+                        label={t('settings.processing.mergeDngOutput')}
+                        description={t('settings.processing.mergeDngOutputDesc')}
+                      >
+                        <Switch
+                          checked={appSettings?.mergeDngOutput ?? false}
+                          id="merge-dng-output-toggle"
+                          label={t('settings.processing.mergeDngOutputLabel')}
+                          onChange={(checked) => {
+                            onSettingsChange({ ...appSettings, mergeDngOutput: checked });
+                          }}
+                        />
+                      </SettingItem>
+
+                      {osPlatform !== 'android' && (
+                        <SettingItem
+                          label={t('settings.processing.automationServer')}
+                          description={t('settings.processing.automationServerDesc')}
+                        >
+                          <Switch
+                            checked={appSettings?.automationServerEnabled ?? false}
+                            id="automation-server-toggle"
+                            label={t('settings.processing.automationServerLabel')}
+                            onChange={(checked) => {
+                              onSettingsChange({ ...appSettings, automationServerEnabled: checked });
+                            }}
+                          />
+                        </SettingItem>
+                      )}
+
+                      {osPlatform !== 'android' && appSettings?.automationServerEnabled && (
+                        <SettingItem
+                          label={t('settings.processing.automationServerPort')}
+                          description={t('settings.processing.automationServerPortDesc')}
+                        >
+                          <Input
+                            type="number"
+                            value={String(appSettings?.automationServerPort ?? 7878)}
+                            onChange={(e) => {
+                              const port = parseInt(e.target.value);
+                              if (port >= 1024 && port <= 65535) {
+                                onSettingsChange({ ...appSettings, automationServerPort: port });
+                              }
+                            }}
+                            bgClassName="bg-bg-primary"
+                          />
+                        </SettingItem>
+                      )}
+
+                      {osPlatform !== 'android' && (
+                        <HotFolderSettings appSettings={appSettings} onSettingsChange={onSettingsChange} />
+                      )}
+
+                      {osPlatform !== 'android' && (
+                        <ExternalEditorSettings appSettings={appSettings} onSettingsChange={onSettingsChange} />
+                      )}
+
                       <SettingItem
                         label={t('settings.processing.workerThreads')}
                         description={t('settings.processing.workerThreadsDesc')}
@@ -2030,6 +2139,18 @@ export default function SettingsPanel({
                       {t('settings.processing.preprocessing.title')}
                     </Text>
                     <div className="space-y-8">
+                      <SettingItem
+                        label={t('settings.processing.preprocessing.highlightMode')}
+                        description={t('settings.processing.preprocessing.highlightModeDesc')}
+                      >
+                        <Dropdown
+                          onChange={(value: any) => handleProcessingSettingChange('rawHighlightMode', value)}
+                          options={highlightModeOptions}
+                          value={processingSettings.rawHighlightMode}
+                          triggerClassName="bg-bg-primary"
+                        />
+                      </SettingItem>
+
                       <SettingItem
                         label={t('settings.processing.preprocessing.highlightRecovery')}
                         description={t('settings.processing.preprocessing.highlightRecoveryDesc')}
@@ -2048,6 +2169,18 @@ export default function SettingsPanel({
                         />
                       </SettingItem>
 
+                      <SettingItem
+                        label={t('settings.processing.preprocessing.defectDetection')}
+                        description={t('settings.processing.preprocessing.defectDetectionDesc')}
+                      >
+                        <Switch
+                          checked={processingSettings.rawDefectDetection}
+                          id="raw-defect-detection-toggle"
+                          label={t('settings.processing.preprocessing.enableDefectDetection')}
+                          onChange={(checked) => handleProcessingSettingChange('rawDefectDetection', checked)}
+                        />
+                      </SettingItem>
+
                       <SettingItem
                         label={t('settings.processing.preprocessing.colorNr')}
                         description={t('settings.processing.preprocessing.colorNrDesc')}
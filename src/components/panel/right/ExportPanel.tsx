@@ -1,7 +1,18 @@
 import { useState, useEffect, useRef, useMemo, useCallback } from 'react';
 import { save, open } from '@tauri-apps/plugin-dialog';
 import { invoke } from '@tauri-apps/api/core';
-import { FileInput, CheckCircle, XCircle, Loader, Ban, ChevronDown, ChevronRight, Settings, X } from 'lucide-react';
+import {
+  FileInput,
+  CheckCircle,
+  XCircle,
+  Loader,
+  Ban,
+  ChevronDown,
+  ChevronRight,
+  ListPlus,
+  Settings,
+  X,
+} from 'lucide-react';
 import { motion, AnimatePresence } from 'framer-motion';
 import { useTranslation } from 'react-i18next';
 import debounce from 'lodash.debounce';
@@ -12,6 +23,7 @@ import Slider from '../../ui/Slider';
 import ImagePicker from '../../ui/ImagePicker';
 import {
   ExportPreset,
+  ExportRecipe,
   ExportSettings,
   FileFormat,
   FILE_FORMATS,
@@ -19,10 +31,21 @@ import {
   Status,
   ExportState,
   FileFormats,
+  OutputColorSpace,
+  SharpeningTarget,
+  WatermarkSettings,
   WatermarkAnchor,
+  WatermarkType,
+  WATERMARK_TEXT_VARIABLES,
+  WebGalleryOptions,
+  WebGallerySettings,
 } from '../../ui/ExportImportProperties';
 import { Invokes, SelectedImage, AppSettings, Panel } from '../../ui/AppProperties';
 import ExportPresetsList from '../../ui/ExportPresetsList';
+import ExportRecipesList from '../../ui/ExportRecipesList';
+import JobQueue from '../../ui/JobQueue';
+import ProcessingRecipesList from '../../ui/ProcessingRecipesList';
+import WebGalleryExport from '../../ui/WebGalleryExport';
 import { useExportSettings } from '../../../hooks/useExportSettings';
 import { useOsPlatform } from '../../../hooks/useOsPlatform';
 import Text from '../../ui/Text';
@@ -196,6 +219,26 @@ export default function ExportPanel({
     [t],
   );
 
+  const sharpeningTargetOptions = useMemo(
+    () => [
+      { label: t('export.sharpening.targets.screen'), value: SharpeningTarget.Screen },
+      { label: t('export.sharpening.targets.mattePaper'), value: SharpeningTarget.MattePaper },
+      { label: t('export.sharpening.targets.glossyPaper'), value: SharpeningTarget.GlossyPaper },
+    ],
+    [t],
+  );
+
+  const colorSpaceOptions = useMemo(
+    () => [
+      { label: t('export.advanced.colorSpaces.srgb'), value: OutputColorSpace.Srgb },
+      { label: t('export.advanced.colorSpaces.displayP3'), value: OutputColorSpace.DisplayP3 },
+      { label: t('export.advanced.colorSpaces.adobeRgb'), value: OutputColorSpace.AdobeRgb },
+      { label: t('export.advanced.colorSpaces.rec2020'), value: OutputColorSpace.Rec2020 },
+      { label: t('export.advanced.colorSpaces.proPhoto'), value: OutputColorSpace.ProPhoto },
+    ],
+    [t],
+  );
+
   const {
     fileFormat,
     setFileFormat,
@@ -231,8 +274,34 @@ export default function ExportPanel({
     setWatermarkSpacing,
     watermarkOpacity,
     setWatermarkOpacity,
+    watermarkType,
+    setWatermarkType,
+    watermarkText,
+    setWatermarkText,
+    watermarkFontFamily,
+    setWatermarkFontFamily,
+    watermarkFontSize,
+    setWatermarkFontSize,
+    watermarkColor,
+    setWatermarkColor,
     preserveFolders,
     setPreserveFolders,
+    outputColorSpace,
+    setOutputColorSpace,
+    enableOutputSharpening,
+    setEnableOutputSharpening,
+    outputSharpeningTarget,
+    setOutputSharpeningTarget,
+    outputSharpeningAmount,
+    setOutputSharpeningAmount,
+    enableMaxFileSize,
+    setEnableMaxFileSize,
+    maxFileSizeKb,
+    setMaxFileSizeKb,
+    enableHdrGainMap,
+    setEnableHdrGainMap,
+    hdrHeadroomStops,
+    setHdrHeadroomStops,
     handleApplyPreset,
     currentSettingsObject,
   } = useExportSettings();
@@ -240,6 +309,8 @@ export default function ExportPanel({
   const adjustmentsRef = useRef(useEditorStore.getState().adjustments);
 
   const [isAdvancedExpanded, setIsAdvancedExpanded] = useState(false);
+  const [syncExport, setSyncExport] = useState(false);
+  const [deleteRemovedOutputs, setDeleteRemovedOutputs] = useState(false);
   const initDone = useRef(false);
 
   useEffect(() => {
@@ -276,6 +347,7 @@ export default function ExportPanel({
   const filenameInputRef = useRef<HTMLInputElement>(null);
   const osPlatform = useOsPlatform();
   const isAndroid = osPlatform === 'android';
+  const syncOptions = syncExport && !isAndroid ? { deleteRemoved: deleteRemovedOutputs } : null;
   const activePanels = useUIStore((state) => state.activePanels);
   const isPanelReallyActive = Object.values(activePanels).includes(Panel.Export);
 
@@ -295,6 +367,9 @@ export default function ExportPanel({
   }, [isLibraryContext, multiSelectedPaths, selectedImage?.path]);
 
   const numImages = pathsToExport.length;
+  const supportsQuality = [FileFormats.Jpeg, FileFormats.Webp, FileFormats.Jxl, FileFormats.Avif].includes(
+    fileFormat as FileFormats,
+  );
 
   useEffect(() => {
     const fetchDims = async () => {
@@ -346,6 +421,38 @@ export default function ExportPanel({
     [t],
   );
 
+  const watermarkTypeOptions = useMemo(
+    () => [
+      { label: t('export.watermark.types.image'), value: WatermarkType.Image },
+      { label: t('export.watermark.types.text'), value: WatermarkType.Text },
+    ],
+    [t],
+  );
+
+  const buildWatermarkSettings = (): WatermarkSettings | null => {
+    if (!enableWatermark) return null;
+    const base = {
+      anchor: watermarkAnchor,
+      scale: watermarkScale,
+      spacing: watermarkSpacing,
+      opacity: watermarkOpacity,
+    };
+    if (watermarkType === WatermarkType.Text) {
+      if (!watermarkText.trim()) return null;
+      return {
+        ...base,
+        path: '',
+        text: {
+          text: watermarkText,
+          fontFamily: watermarkFontFamily,
+          fontSize: watermarkFontSize,
+          color: watermarkColor,
+        },
+      };
+    }
+    return watermarkPath ? { ...base, path: watermarkPath } : null;
+  };
+
   const debouncedEstimateSize = useMemo(
     () =>
       debounce(async (paths, currentAdj, currentPath, exportSettings, format) => {
@@ -381,19 +488,16 @@ export default function ExportPanel({
       keepMetadata,
       preserveTimestamps,
       preserveFolders,
+      outputColorSpace,
+      outputSharpening: enableOutputSharpening
+        ? { target: outputSharpeningTarget, amount: outputSharpeningAmount }
+        : null,
+      maxFileSizeBytes:
+        enableMaxFileSize && supportsQuality && maxFileSizeKb > 0 ? maxFileSizeKb * 1024 : null,
       resize: enableResize ? { mode: resizeMode, value: resizeValue, dontEnlarge } : null,
       stripGps,
       exportMasks: !isLibraryContext ? exportMasks : undefined,
-      watermark:
-        enableWatermark && watermarkPath
-          ? {
-              path: watermarkPath,
-              anchor: watermarkAnchor,
-              scale: watermarkScale,
-              spacing: watermarkSpacing,
-              opacity: watermarkOpacity,
-            }
-          : null,
+      watermark: buildWatermarkSettings(),
     };
     const format = FILE_FORMATS.find((f: FileFormat) => f.id === fileFormat)?.extensions[0] || 'jpeg';
     const runEstimate = () =>
@@ -434,9 +538,20 @@ export default function ExportPanel({
     watermarkScale,
     watermarkSpacing,
     watermarkOpacity,
+    watermarkType,
+    watermarkText,
+    watermarkFontFamily,
+    watermarkFontSize,
+    watermarkColor,
     debouncedEstimateSize,
     exportMasks,
     preserveFolders,
+    outputColorSpace,
+    enableOutputSharpening,
+    outputSharpeningTarget,
+    outputSharpeningAmount,
+    enableMaxFileSize,
+    maxFileSizeKb,
     isLibraryContext,
   ]);
 
@@ -454,47 +569,49 @@ export default function ExportPanel({
     }, 0);
   };
 
-  const handleExport = async () => {
-    if (numImages === 0 || isExporting) return;
-
-    let finalFilenameTemplate = filenameTemplate;
+  const getFinalFilenameTemplate = () => {
     if (
       numImages > 1 &&
       !filenameTemplate.includes('{sequence}') &&
       !filenameTemplate.includes('{original_filename}')
     ) {
-      finalFilenameTemplate = `${filenameTemplate}_{sequence}`;
+      const finalFilenameTemplate = `${filenameTemplate}_{sequence}`;
       setFilenameTemplate(finalFilenameTemplate);
+      return finalFilenameTemplate;
     }
+    return filenameTemplate;
+  };
 
-    const exportSettings: ExportSettings = {
-      filenameTemplate: finalFilenameTemplate,
-      jpegQuality,
-      keepMetadata,
-      preserveTimestamps,
-      preserveFolders,
-      resize: enableResize ? { mode: resizeMode, value: resizeValue, dontEnlarge } : null,
-      stripGps,
-      exportMasks: !isLibraryContext ? exportMasks : undefined,
-      watermark:
-        enableWatermark && watermarkPath
-          ? {
-              path: watermarkPath,
-              anchor: watermarkAnchor,
-              scale: watermarkScale,
-              spacing: watermarkSpacing,
-              opacity: watermarkOpacity,
-            }
-          : null,
-    };
+  const buildExportSettings = (finalFilenameTemplate: string): ExportSettings => ({
+    filenameTemplate: finalFilenameTemplate,
+    jpegQuality,
+    keepMetadata,
+    preserveTimestamps,
+    preserveFolders,
+    outputColorSpace,
+    outputSharpening: enableOutputSharpening
+      ? { target: outputSharpeningTarget, amount: outputSharpeningAmount }
+      : null,
+    maxFileSizeBytes: enableMaxFileSize && supportsQuality && maxFileSizeKb > 0 ? maxFileSizeKb * 1024 : null,
+    hdrGainMap: enableHdrGainMap && fileFormat === FileFormats.Jpeg ? { headroomStops: hdrHeadroomStops } : null,
+    resize: enableResize ? { mode: resizeMode, value: resizeValue, dontEnlarge } : null,
+    stripGps,
+    exportMasks: !isLibraryContext ? exportMasks : undefined,
+    watermark: buildWatermarkSettings(),
+  });
+
+  const handleExport = async () => {
+    if (numImages === 0 || isExporting) return;
 
+    const finalFilenameTemplate = getFinalFilenameTemplate();
+    const exportSettings = buildExportSettings(finalFilenameTemplate);
     const lastExportPath = appSettings?.exportPresets?.find((p) => p.id === '__last_used__')?.lastExportPath;
 
     try {
       const selectedFormat: any = FILE_FORMATS.find((f) => f.id === fileFormat);
 
       let outputFolderOrFile = '';
-      const shouldChooseOutputFile = numImages === 1 && !preserveFolders;
+      const shouldChooseOutputFile = numImages === 1 && !preserveFolders && !syncOptions;
       if (shouldChooseOutputFile) {
         const originalFilename = pathsToExport[0].split(/[\\/]/).pop() || '';
         const stem = originalFilename.substring(0, originalFilename.lastIndexOf('.')) || originalFilename;
@@ -545,6 +662,7 @@ export default function ExportPanel({
           outputFormat: selectedFormat.extensions[0],
           currentEditPath: selectedImage?.path || null,
           currentEditAdjustments: adjustmentsRef.current || null,
+          syncOptions,
         });
       }
     } catch (error) {
@@ -556,6 +674,109 @@ export default function ExportPanel({
     }
   };
 
+  const handleExportRecipe = async (recipe: ExportRecipe) => {
+    if (numImages === 0 || isExporting || recipe.variants.length === 0) return;
+
+    const lastExportPath = appSettings?.exportPresets?.find((p) => p.id === '__last_used__')?.lastExportPath;
+
+    try {
+      const outputFolder = isAndroid
+        ? ''
+        : ((await open({
+            title: t('ui.exportRecipes.selectFolderTitle'),
+            directory: true,
+            defaultPath: lastExportPath ?? undefined,
+          })) as string);
+
+      if (isAndroid || outputFolder) {
+        setExportState({ status: Status.Exporting, progress: { current: 0, total: numImages }, errorMessage: '' });
+        await invoke(Invokes.ExportImagesWithRecipe, {
+          paths: pathsToExport,
+          outputFolder,
+          baseOriginFolders: rootPaths,
+          recipe,
+          currentEditPath: selectedImage?.path || null,
+          currentEditAdjustments: adjustmentsRef.current || null,
+          syncOptions,
+        });
+      }
+    } catch (error) {
+      setExportState({
+        errorMessage: typeof error === 'string' ? error : t('export.status.failed'),
+        progress,
+        status: Status.Error,
+      });
+    }
+  };
+
+  const handleExportGallery = async (options: WebGalleryOptions) => {
+    if (numImages === 0 || isExporting) return;
+
+    const lastExportPath = appSettings?.exportPresets?.find((p) => p.id === '__last_used__')?.lastExportPath;
+
+    try {
+      const outputFolder = (await open({
+        title: t('ui.webGallery.selectFolderTitle'),
+        directory: true,
+        defaultPath: lastExportPath ?? undefined,
+      })) as string;
+
+      if (outputFolder) {
+        const settings: WebGallerySettings = {
+          ...options,
+          quality: jpegQuality,
+          watermark: buildWatermarkSettings(),
+        };
+        setExportState({ status: Status.Exporting, progress: { current: 0, total: numImages }, errorMessage: '' });
+        await invoke(Invokes.ExportWebGallery, {
+          paths: pathsToExport,
+          outputFolder,
+          settings,
+          currentEditPath: selectedImage?.path || null,
+          currentEditAdjustments: adjustmentsRef.current || null,
+        });
+      }
+    } catch (error) {
+      setExportState({
+        errorMessage: typeof error === 'string' ? error : t('export.status.failed'),
+        progress,
+        status: Status.Error,
+      });
+    }
+  };
+
+  const handleQueueExport = async () => {
+    if (numImages === 0) return;
+
+    const exportSettings = buildExportSettings(getFinalFilenameTemplate());
+    const lastExportPath = appSettings?.exportPresets?.find((p) => p.id === '__last_used__')?.lastExportPath;
+
+    try {
+      const selectedFormat: any = FILE_FORMATS.find((f) => f.id === fileFormat);
+      const outputFolder = (await open({
+        title: t('export.dialog.selectFolderTitle', { count: numImages }),
+        directory: true,
+        defaultPath: lastExportPath ?? undefined,
+      })) as string;
+
+      if (outputFolder) {
+        saveLastUsedPreset(outputFolder);
+        await invoke(Invokes.EnqueueJob, {
+          kind: {
+            type: 'export',
+            outputFolder,
+            baseOriginFolders: rootPaths,
+            exportSettings,
+            outputFormat: selectedFormat.extensions[0],
+          },
+          paths: pathsToExport,
+        });
+      }
+    } catch (error) {
+      console.error('Failed to queue export:', error);
+    }
+  };
+
   const handleCancel = async () => {
     setExportState((current: ExportState) =>
       current.status === Status.Exporting ? { status: Status.Cancelling } : {},
@@ -590,6 +811,23 @@ export default function ExportPanel({
                 currentSettings={currentSettingsObject}
                 onApplyPreset={handleApplyPreset}
               />
+              <ExportRecipesList
+                appSettings={appSettings}
+                onSettingsChange={onSettingsChange}
+                currentSettings={currentSettingsObject}
+                disabled={isExporting}
+                onExportRecipe={handleExportRecipe}
+              />
+              {!isAndroid && (
+                <ProcessingRecipesList
+                  appSettings={appSettings}
+                  onSettingsChange={onSettingsChange}
+                  disabled={isExporting}
+                  paths={pathsToExport}
+                />
+              )}
+              {!isAndroid && <WebGalleryExport disabled={isExporting} onExportGallery={handleExportGallery} />}
+              {!isAndroid && <JobQueue />}
             </div>
 
             <Section title={t('export.sections.fileSettings')}>
@@ -607,22 +845,72 @@ export default function ExportPanel({
                   </button>
                 ))}
               </div>
-              {[FileFormats.Jpeg, FileFormats.Webp, FileFormats.Jxl].includes(fileFormat as FileFormats) && (
+              {supportsQuality && (
                 <div className={isExporting ? 'opacity-50 pointer-events-none' : ''}>
-                  <Slider
-                    defaultValue={90}
-                    label={
-                      fileFormat === FileFormats.Jxl && jpegQuality === 100
-                        ? t('export.file.qualityLossless')
-                        : t('export.file.quality')
-                    }
-                    max={100}
-                    min={1}
-                    onChange={(e) => setJpegQuality(Number(e.target.value))}
-                    step={1}
-                    value={jpegQuality}
-                    fillOrigin="min"
+                  {fileFormat !== FileFormats.Avif && (
+                    <Slider
+                      defaultValue={90}
+                      label={
+                        fileFormat === FileFormats.Jxl && jpegQuality === 100
+                          ? t('export.file.qualityLossless')
+                          : t('export.file.quality')
+                      }
+                      max={100}
+                      min={1}
+                      onChange={(e) => setJpegQuality(Number(e.target.value))}
+                      step={1}
+                      value={jpegQuality}
+                      fillOrigin="min"
+                    />
+                  )}
+                  <Switch
+                    checked={enableMaxFileSize}
+                    disabled={isExporting}
+                    label={t('export.file.limitFileSize')}
+                    onChange={setEnableMaxFileSize}
+                    trackClassName="bg-surface"
                   />
+                  {enableMaxFileSize && (
+                    <div className="flex items-center gap-2 mt-2 pl-2 border-l-2 border-surface">
+                      <input
+                        className="w-28 bg-surface text-center rounded-md p-2 border border-surface focus:border-accent focus:ring-accent text-text-secondary focus:text-text-primary"
+                        disabled={isExporting}
+                        min="1"
+                        onChange={(e) => setMaxFileSizeKb(parseInt(e?.target?.value) || 0)}
+                        type="number"
+                        value={maxFileSizeKb}
+                      />
+                      <Text variant={TextVariants.label}>{t('export.file.maxFileSizeUnit')}</Text>
+                    </div>
+                  )}
+                </div>
+              )}
+              {fileFormat === FileFormats.Jpeg && (
+                <div className={isExporting ? 'opacity-50 pointer-events-none' : ''}>
+                  <Switch
+                    checked={enableHdrGainMap}
+                    disabled={isExporting}
+                    label={t('export.file.hdrGainMap')}
+                    onChange={setEnableHdrGainMap}
+                    trackClassName="bg-surface"
+                  />
+                  {enableHdrGainMap && (
+                    <div className="mt-2 pl-2 border-l-2 border-surface">
+                      <Slider
+                        defaultValue={3}
+                        label={t('export.file.hdrHeadroom')}
+                        max={4}
+                        min={1}
+                        onChange={(e) => setHdrHeadroomStops(Number(e.target.value))}
+                        step={0.5}
+                        value={hdrHeadroomStops}
+                        fillOrigin="min"
+                      />
+                      <Text variant={TextVariants.small} color={TextColors.secondary}>
+                        {t('export.file.hdrGainMapDescription')}
+                      </Text>
+                    </div>
+                  )}
                 </div>
               )}
             </Section>
@@ -693,6 +981,40 @@ export default function ExportPanel({
                   )}
                 </Section>
 
+                {fileFormat !== FileFormats.Dng && (
+                  <Section title={t('export.sections.sharpening')}>
+                    <Switch
+                      label={t('export.sharpening.sharpenFor')}
+                      checked={enableOutputSharpening}
+                      onChange={setEnableOutputSharpening}
+                      disabled={isExporting}
+                      trackClassName="bg-surface"
+                    />
+                    {enableOutputSharpening && (
+                      <div className="space-y-4 pl-2 border-l-2 border-surface">
+                        <Dropdown
+                          options={sharpeningTargetOptions}
+                          value={outputSharpeningTarget}
+                          onChange={(val) => setOutputSharpeningTarget(val as SharpeningTarget)}
+                          disabled={isExporting}
+                          className="w-full"
+                        />
+                        <Slider
+                          label={t('export.sharpening.amount')}
+                          min={0}
+                          max={100}
+                          step={1}
+                          value={outputSharpeningAmount}
+                          onChange={(e) => setOutputSharpeningAmount(Number(e.target.value))}
+                          disabled={isExporting}
+                          defaultValue={50}
+                          fillOrigin="min"
+                        />
+                      </div>
+                    )}
+                  </Section>
+                )}
+
                 {fileFormat == FileFormats.Jpeg && (
                   <Section title={t('export.sections.metadata')}>
                     <Switch
@@ -726,33 +1048,63 @@ export default function ExportPanel({
                   />
                   {enableWatermark && (
                     <div className="space-y-4 pl-2 border-l-2 border-surface">
-                      <div className={isExporting ? 'opacity-50 pointer-events-none' : ''}>
-                        <ImagePicker
-                          label={t('export.watermark.watermarkImage')}
-                          imageName={watermarkPath ? watermarkPath.split(/[\\/]/).pop() || null : null}
-                          onImageSelect={setWatermarkPath}
-                          onClear={() => setWatermarkPath(null)}
-                        />
-                      </div>
-                      {watermarkPath && (
+                      <Dropdown
+                        options={watermarkTypeOptions}
+                        value={watermarkType}
+                        onChange={(val) => setWatermarkType(val as WatermarkType)}
+                        disabled={isExporting}
+                        className="w-full"
+                      />
+                      {watermarkType === WatermarkType.Text && (
                         <>
-                          <Dropdown
-                            options={anchorOptions}
-                            value={watermarkAnchor}
-                            onChange={(val) => setWatermarkAnchor(val as WatermarkAnchor)}
+                          <input
+                            className="w-full bg-surface border border-surface rounded-md p-2 text-sm text-text-primary focus:ring-accent focus:border-accent"
                             disabled={isExporting}
-                            className="w-full"
+                            onChange={(e) => setWatermarkText(e.target.value)}
+                            placeholder={t('export.watermark.textPlaceholder')}
+                            type="text"
+                            value={watermarkText}
                           />
+                          <div className="flex flex-wrap gap-2">
+                            {WATERMARK_TEXT_VARIABLES.map((variable: string) => (
+                              <button
+                                className="px-2 py-1 bg-surface text-text-secondary text-xs rounded-md hover:bg-card-active transition-colors disabled:opacity-50"
+                                disabled={isExporting}
+                                key={variable}
+                                onClick={() => setWatermarkText(`${watermarkText}${variable}`)}
+                              >
+                                {variable}
+                              </button>
+                            ))}
+                          </div>
+                          <div className="flex items-center gap-2">
+                            <input
+                              className="grow bg-surface border border-surface rounded-md p-2 text-sm text-text-primary focus:ring-accent focus:border-accent"
+                              disabled={isExporting}
+                              onChange={(e) => setWatermarkFontFamily(e.target.value || null)}
+                              placeholder={t('export.watermark.fontPlaceholder')}
+                              type="text"
+                              value={watermarkFontFamily ?? ''}
+                            />
+                            <input
+                              className="w-10 h-9 bg-surface border border-surface rounded-md cursor-pointer"
+                              disabled={isExporting}
+                              onChange={(e) => setWatermarkColor(e.target.value)}
+                              title={t('export.watermark.color')}
+                              type="color"
+                              value={watermarkColor}
+                            />
+                          </div>
                           <div>
                             <Slider
-                              label={t('export.watermark.scale')}
-                              min={1}
-                              max={50}
-                              step={1}
-                              value={watermarkScale}
-                              onChange={(e) => setWatermarkScale(Number(e.target.value))}
+                              label={t('export.watermark.fontSize')}
+                              min={0.5}
+                              max={15}
+                              step={0.5}
+                              value={watermarkFontSize}
+                              onChange={(e) => setWatermarkFontSize(Number(e.target.value))}
                               disabled={isExporting}
-                              defaultValue={10}
+                              defaultValue={3}
                             />
                             <Slider
                               label={t('export.watermark.spacing')}
@@ -775,17 +1127,79 @@ export default function ExportPanel({
                               defaultValue={75}
                             />
                           </div>
-                          <WatermarkPreview
-                            imageAspectRatio={imageAspectRatio}
-                            watermarkImageAspectRatio={watermarkImageAspectRatio}
-                            watermarkPath={watermarkPath}
-                            anchor={watermarkAnchor as WatermarkAnchor}
-                            scale={watermarkScale}
-                            spacing={watermarkSpacing}
-                            opacity={watermarkOpacity}
+                          <Dropdown
+                            options={anchorOptions}
+                            value={watermarkAnchor}
+                            onChange={(val) => setWatermarkAnchor(val as WatermarkAnchor)}
+                            disabled={isExporting}
+                            className="w-full"
                           />
                         </>
                       )}
+                      {watermarkType === WatermarkType.Image && (
+                        <>
+                          <div className={isExporting ? 'opacity-50 pointer-events-none' : ''}>
+                            <ImagePicker
+                              label={t('export.watermark.watermarkImage')}
+                              imageName={watermarkPath ? watermarkPath.split(/[\\/]/).pop() || null : null}
+                              onImageSelect={setWatermarkPath}
+                              onClear={() => setWatermarkPath(null)}
+                            />
+                          </div>
+                          {watermarkPath && (
+                            <>
+                              <Dropdown
+                                options={anchorOptions}
+                                value={watermarkAnchor}
+                                onChange={(val) => setWatermarkAnchor(val as WatermarkAnchor)}
+                                disabled={isExporting}
+                                className="w-full"
+                              />
+                              <div>
+                                <Slider
+                                  label={t('export.watermark.scale')}
+                                  min={1}
+                                  max={50}
+                                  step={1}
+                                  value={watermarkScale}
+                                  onChange={(e) => setWatermarkScale(Number(e.target.value))}
+                                  disabled={isExporting}
+                                  defaultValue={10}
+                                />
+                                <Slider
+                                  label={t('export.watermark.spacing')}
+                                  min={0}
+                                  max={25}
+                                  step={1}
+                                  value={watermarkSpacing}
+                                  onChange={(e) => setWatermarkSpacing(Number(e.target.value))}
+                                  disabled={isExporting}
+                                  defaultValue={5}
+                                />
+                                <Slider
+                                  label={t('export.watermark.opacity')}
+                                  min={0}
+                                  max={100}
+                                  step={1}
+                                  value={watermarkOpacity}
+                                  onChange={(e) => setWatermarkOpacity(Number(e.target.value))}
+                                  disabled={isExporting}
+                                  defaultValue={75}
+                                />
+                              </div>
+                              <WatermarkPreview
+                                imageAspectRatio={imageAspectRatio}
+                                watermarkImageAspectRatio={watermarkImageAspectRatio}
+                                watermarkPath={watermarkPath}
+                                anchor={watermarkAnchor as WatermarkAnchor}
+                                scale={watermarkScale}
+                                spacing={watermarkSpacing}
+                                opacity={watermarkOpacity}
+                              />
+                            </>
+                          )}
+                        </>
+                      )}
                     </div>
                   )}
                 </Section>
@@ -831,8 +1245,38 @@ export default function ExportPanel({
                           disabled={isExporting}
                           trackClassName="bg-surface"
                         />
+                        {!isAndroid && (
+                          <>
+                            <Switch
+                              label={t('export.advanced.syncExport')}
+                              checked={syncExport}
+                              onChange={setSyncExport}
+                              disabled={isExporting}
+                              trackClassName="bg-surface"
+                            />
+                            {syncExport && (
+                              <Switch
+                                label={t('export.advanced.deleteRemovedOutputs')}
+                                checked={deleteRemovedOutputs}
+                                onChange={setDeleteRemovedOutputs}
+                                disabled={isExporting}
+                                trackClassName="bg-surface"
+                              />
+                            )}
+                          </>
+                        )}
                         {fileFormat !== FileFormats.Cube && (
                           <>
+                            <div className="flex items-center justify-between gap-4">
+                              <Text variant={TextVariants.label}>{t('export.advanced.colorSpace')}</Text>
+                              <Dropdown
+                                options={colorSpaceOptions}
+                                value={outputColorSpace}
+                                onChange={setOutputColorSpace}
+                                disabled={isExporting}
+                                className="w-48"
+                              />
+                            </div>
                             <Switch
                               checked={preserveTimestamps}
                               disabled={isExporting}
@@ -911,6 +1355,16 @@ export default function ExportPanel({
                 {progress.total > 1
                   ? t('export.status.exportingProgress', { current: progress.current, total: progress.total })
                   : t('export.status.exporting')}
+                {progress.skipped && (
+                  <span className="ml-2 opacity-75">{t('export.status.skippedUnchanged')}</span>
+                )}
+                {progress.qualities?.some((quality) => quality != null) && (
+                  <span className="ml-2 opacity-75">
+                    {t('export.status.chosenQuality', {
+                      quality: progress.qualities.map((quality) => quality ?? '–').join(' / '),
+                    })}
+                  </span>
+                )}
               </span>
               <span className="hidden items-center group-hover:flex">
                 <Ban size={18} className="mr-2" />
@@ -942,6 +1396,16 @@ export default function ExportPanel({
             </>
           )}
         </Button>
+        {!isAndroid && (
+          <button
+            onClick={handleQueueExport}
+            disabled={!canExport}
+            className="w-full flex items-center justify-center gap-2 py-1.5 rounded-md text-sm text-text-secondary hover:text-text-primary hover:bg-surface transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+          >
+            <ListPlus size={16} />
+            {t('export.addToQueue')}
+          </button>
+        )}
       </div>
     </div>
   );
@@ -21,6 +21,7 @@ import {
   Folder as FolderIcon,
   FolderOpen,
   FolderPlus,
+  Images,
   Loader2,
   Plus,
   SortAsc,
@@ -44,6 +45,7 @@ import { TextColors, TextVariants, TextWeights } from '../../../types/typography
 import { Adjustments, INITIAL_ADJUSTMENTS, ADJUSTMENT_GROUPS } from '../../../utils/adjustments';
 import { Invokes, OPTION_SEPARATOR, Panel, Preset, SelectedImage } from '../../ui/AppProperties';
 import { useEditorStore } from '../../../store/useEditorStore';
+import { useLibraryStore } from '../../../store/useLibraryStore';
 import { useUIStore } from '../../../store/useUIStore';
 import { useEditorActions } from '../../../hooks/useEditorActions';
 
@@ -184,6 +186,24 @@ const evaluateCurveY = (curve: Array<{ x: number; y: number }>, targetX: number)
   return targetX;
 };
 
+const PRESET_STRENGTHS = [100, 75, 50, 25];
+
+const GEOMETRY_KEYS = ADJUSTMENT_GROUPS.geometry.flatMap((g) => g.keys);
+
+const presetIncludesMasks = (preset: Preset): boolean =>
+  preset.includeMasks ?? !!(preset.adjustments?.masks && preset.adjustments.masks.length > 0);
+
+const presetIncludesGeometry = (preset: Preset): boolean =>
+  preset.includeCropTransform ?? GEOMETRY_KEYS.some((key) => preset.adjustments?.[key] !== undefined);
+
+// Same as `Preset::applicable_adjustments` in the backend.
+const applicablePresetAdjustments = (preset: Preset): any => {
+  const adjustments: any = { ...preset.adjustments };
+  if (!presetIncludesMasks(preset)) delete adjustments.masks;
+  if (!presetIncludesGeometry(preset)) GEOMETRY_KEYS.forEach((key) => delete adjustments[key]);
+  return adjustments;
+};
+
 const mixAdjustments = (presetObj: any, intensity: number, initialObj: any = INITIAL_ADJUSTMENTS): any => {
   const fraction = intensity / 100;
 
@@ -267,11 +287,8 @@ function PresetItemDisplay({
   onDragStateChange,
 }: PresetItemDisplayProps) {
   const { t } = useTranslation();
-  const geometryKeys = ADJUSTMENT_GROUPS.geometry.flatMap((g) => g.keys);
-
-  const supportsMasks = preset.includeMasks ?? (preset.adjustments?.masks && preset.adjustments.masks.length > 0);
-  const supportsGeometry =
-    preset.includeCropTransform ?? geometryKeys.some((key) => preset.adjustments?.[key] !== undefined);
+  const supportsMasks = presetIncludesMasks(preset);
+  const supportsGeometry = presetIncludesGeometry(preset);
   const isTool = preset.presetType === 'tool';
   const tooltipContent = useMemo(() => {
     const features = [];
@@ -551,7 +568,7 @@ export default function PresetsPanel({ onNavigateToCommunity }: PresetsPanelProp
   const selectedImage = useEditorStore((s) => s.selectedImage);
   const adjustments = useEditorStore((s) => s.adjustments);
   const setEditor = useEditorStore((s) => s.setEditor);
-  const { setAdjustments } = useEditorActions();
+  const { setAdjustments, applyRelativeAdjustments } = useEditorActions();
 
   const {
     addFolder,
@@ -1135,7 +1152,29 @@ export default function PresetsPanel({ onNavigateToCommunity }: PresetsPanelProp
         },
       ];
     } else {
+      const { multiSelectedPaths } = useLibraryStore.getState();
+      const presetAdjustments = (data as Preset)?.adjustments;
+      const applyToSelection = (strength: number) =>
+        applyRelativeAdjustments(multiSelectedPaths, {
+          blend: applicablePresetAdjustments(data as Preset),
+          strength: strength / 100,
+          defaults: INITIAL_ADJUSTMENTS,
+        }).catch((error) => console.error('Failed to apply preset to selection:', error));
+
       options = [
+        ...(multiSelectedPaths.length > 0 && presetAdjustments
+          ? [
+              {
+                icon: Images,
+                label: t('editor.presets.menu.applyToSelection', { count: multiSelectedPaths.length }),
+                submenu: PRESET_STRENGTHS.map((strength) => ({
+                  label: t('editor.presets.menu.applyStrength', { strength }),
+                  onClick: () => applyToSelection(strength),
+                })),
+              },
+              { type: OPTION_SEPARATOR },
+            ]
+          : []),
         {
           icon: Save,
           label: t('editor.presets.menu.overwrite'),
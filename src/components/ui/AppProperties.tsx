@@ -1,4 +1,4 @@
-import { ExportPreset } from './ExportImportProperties';
+import { ExportPreset, ExportRecipe, ExportSettings, ProcessingRecipe } from './ExportImportProperties';
 import { Adjustments, CopyPasteSettings } from '../../utils/adjustments';
 import { ToolType } from '../panel/right/Masks';
 
@@ -33,23 +33,31 @@ export enum Invokes {
   AddTagForPaths = 'add_tag_for_paths',
   ApplyAdjustments = 'apply_adjustments',
   ApplyAdjustmentsToPaths = 'apply_adjustments_to_paths',
+  ApplyRelativeAdjustmentsToPaths = 'apply_relative_adjustments_to_paths',
   ApplyAutoAdjustmentsToPaths = 'apply_auto_adjustments_to_paths',
   ApplyDenoising = 'apply_denoising',
   CalculateAutoAdjustments = 'calculate_auto_adjustments',
   CancelExport = 'cancel_export',
+  CancelJob = 'cancel_job',
   CheckAIConnectorStatus = 'check_ai_connector_status',
   ClearAllSidecars = 'clear_all_sidecars',
   ClearAiTags = 'clear_ai_tags',
   ClearAllTags = 'clear_all_tags',
   ClearThumbnailCache = 'clear_thumbnail_cache',
+  ClearJobHistory = 'clear_job_history',
+  CombineRelativeAdjustments = 'combine_relative_adjustments',
   CopyFiles = 'copy_files',
   CreateFolder = 'create_folder',
   CreateVirtualCopy = 'create_virtual_copy',
   CullImages = 'cull_images',
   DeleteFolder = 'delete_folder',
   DuplicateFile = 'duplicate_file',
+  EditInExternalEditor = 'edit_in_external_editor',
   EstimateExportSizes = 'estimate_export_sizes',
+  EnqueueJob = 'enqueue_job',
   ExportImages = 'export_images',
+  ExportImagesWithRecipe = 'export_images_with_recipe',
+  ExportWebGallery = 'export_web_gallery',
   FrontendLog = 'frontend_log',
   GenerateAiForegroundMask = 'generate_ai_foreground_mask',
   GenerateAiSkyMask = 'generate_ai_sky_mask',
@@ -62,6 +70,7 @@ export enum Invokes {
   GenerateUncroppedPreview = 'generate_uncropped_preview',
   GetFolderTree = 'get_folder_tree',
   GetFolderChildren = 'get_folder_children',
+  GetJobQueue = 'get_job_queue',
   GetLogFilePath = 'get_log_file_path',
   GetOrCreateInternalLibraryRoot = 'get_or_create_internal_library_root',
   GetPinnedFolderTrees = 'get_pinned_folder_trees',
@@ -79,13 +88,18 @@ export enum Invokes {
   LoadImage = 'load_image',
   LoadMetadata = 'load_metadata',
   LoadPresets = 'load_presets',
+  LoadProcessingRecipeFile = 'load_processing_recipe_file',
   LoadSettings = 'load_settings',
   MoveFiles = 'move_files',
+  PauseJob = 'pause_job',
   ReadExifForPaths = 'read_exif_for_paths',
   RemoveTagForPaths = 'remove_tag_for_paths',
   RenameFiles = 'rename_files',
   RenameFolder = 'rename_folder',
   ResetAdjustmentsForPaths = 'reset_adjustments_for_paths',
+  ResumeJob = 'resume_job',
+  RunBatchScript = 'run_batch_script',
+  RunProcessingRecipe = 'run_processing_recipe',
   SaveMetadataAndUpdateThumbnail = 'save_metadata_and_update_thumbnail',
   SaveCollage = 'save_collage',
   SaveDenoisedImage = 'save_denoised_image',
@@ -94,9 +108,9 @@ export enum Invokes {
   SavePresets = 'save_presets',
   SaveSettings = 'save_settings',
   SetColorLabelForPaths = 'set_color_label_for_paths',
+  SetJobPriority = 'set_job_priority',
   SetRatingForPaths = 'set_rating_for_paths',
   ShowInFinder = 'show_in_finder',
-  StartBackgroundIndexing = 'start_background_indexing',
   StitchPanorama = 'stitch_panorama',
   StitchFocusStack = 'stitch_focus_stack',
   SaveFocusStack = 'save_focus_stack',
@@ -187,6 +201,58 @@ export interface WorkspaceState {
 export type GroupPreference = 'jpeg' | 'raw';
 export type GroupingMode = 'off' | GroupPreference;
 
+export type JobKind =
+  | {
+      type: 'export';
+      outputFolder: string;
+      baseOriginFolders: string[];
+      exportSettings: ExportSettings;
+      outputFormat: string;
+    }
+  | { type: 'denoise'; intensity: number; method: string }
+  | { type: 'convertNegatives'; params: any }
+  | { type: 'tagging' };
+
+export type JobStatus = 'queued' | 'running' | 'paused' | 'completed' | 'failed' | 'cancelled';
+
+export interface JobItem {
+  path: string;
+  status: 'pending' | 'done' | 'failed';
+  attempts: number;
+  error: string | null;
+  output: string | null;
+}
+
+export interface Job {
+  id: string;
+  name: string;
+  kind: JobKind;
+  priority: number;
+  status: JobStatus;
+  items: JobItem[];
+  maxRetries: number;
+  error: string | null;
+  createdAt: string;
+  finishedAt: string | null;
+}
+
+export interface HotFolderSettings {
+  enabled: boolean;
+  inputFolder: string;
+  outputFolder: string;
+  preset: string | null;
+  autoAdjust: boolean;
+  exportPreset: string;
+  archiveFolder: string | null;
+}
+
+export interface ExternalEditor {
+  id: string;
+  name: string;
+  command: string;
+  args: Array<string>;
+}
+
 export interface AppSettings {
   aiConnectorAddress?: string;
   aiProvider?: string;
@@ -213,9 +279,13 @@ export interface AppSettings {
   uiVisibility?: UiVisibility;
   adjustmentVisibility?: { [key: string]: boolean };
   rawHighlightCompression?: number;
+  rawHighlightMode?: string;
+  rawDefectDetection?: boolean;
   processingBackend?: string;
   linuxGpuOptimization?: boolean;
   exportPresets?: ExportPreset[];
+  exportRecipes?: ExportRecipe[];
+  processingRecipes?: ProcessingRecipe[];
   myLenses?: any;
   enableFolderImageCounts?: boolean;
   displayEditIcon?: boolean;
@@ -247,6 +317,13 @@ export interface AppSettings {
   groupEditedFiles?: boolean;
   groupPreferredType?: GroupPreference; // legacy
   alwaysDecodeRawThumbnails?: boolean;
+  sceneReferredMerges?: boolean;
+  mergeExrPrecision?: 'half' | 'float';
+  mergeDngOutput?: boolean;
+  automationServerEnabled?: boolean;
+  automationServerPort?: number;
+  hotFolder?: HotFolderSettings | null;
+  externalEditors?: Array<ExternalEditor>;
   workspace?: WorkspaceState;
 }
 
@@ -327,6 +404,8 @@ export interface Progress {
   completed?: number;
   current?: number;
   total: number;
+  qualities?: Array<number | null>;
+  skipped?: boolean;
 }
 
 export interface SelectedImage {
@@ -0,0 +1,92 @@
+import { useState } from 'react';
+import { open } from '@tauri-apps/plugin-dialog';
+import { invoke } from '@tauri-apps/api/core';
+import { FileInput, FolderOpen, Loader2, X } from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import { toast } from 'react-toastify';
+import Text from './Text';
+import { TextColors, TextVariants } from '../../types/typography';
+
+interface MasterFrameInfo {
+  path: string;
+  kind: 'dark' | 'flat';
+  width: number;
+  height: number;
+  frameCount: number;
+}
+
+interface CalibrationFrameControlProps {
+  kind: 'dark' | 'flat';
+  label: string;
+  path: string | null;
+  onChange(path: string | null): void;
+}
+
+export default function CalibrationFrameControl({ kind, label, path, onChange }: CalibrationFrameControlProps) {
+  const { t } = useTranslation();
+  const [isBuilding, setIsBuilding] = useState(false);
+  const fileName = path ? path.split(/[\\/]/).pop() : null;
+
+  const handleBuild = async () => {
+    const folder = await open({ directory: true, title: t('adjustments.details.calibrationSelectFolder') });
+    if (typeof folder !== 'string') return;
+    setIsBuilding(true);
+    try {
+      const info = await invoke<MasterFrameInfo>('build_master_calibration_frame', { folder, kind });
+      onChange(info.path);
+      toast.success(t('adjustments.details.calibrationBuilt', { count: info.frameCount }));
+    } catch (err) {
+      console.error('Failed to build master calibration frame:', err);
+      toast.error(String(err));
+    } finally {
+      setIsBuilding(false);
+    }
+  };
+
+  const handleSelect = async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: t('adjustments.details.calibrationFilter'), extensions: ['rrcal'] }],
+    });
+    if (typeof selected === 'string') {
+      onChange(selected);
+    }
+  };
+
+  return (
+    <div className="flex items-center justify-between gap-2">
+      <div className="min-w-0">
+        <Text variant={TextVariants.small}>{label}</Text>
+        <Text variant={TextVariants.small} color={TextColors.secondary} className="truncate" data-tooltip={path ?? ''}>
+          {fileName ?? t('adjustments.details.calibrationNone')}
+        </Text>
+      </div>
+      <div className="flex items-center gap-1 shrink-0">
+        {path && (
+          <button
+            onClick={() => onChange(null)}
+            className="p-1.5 rounded-md transition-colors hover:bg-bg-secondary text-text-secondary"
+            data-tooltip={t('adjustments.details.calibrationClear')}
+          >
+            <X size={16} />
+          </button>
+        )}
+        <button
+          onClick={handleSelect}
+          className="p-1.5 rounded-md transition-colors hover:bg-bg-secondary text-text-secondary"
+          data-tooltip={t('adjustments.details.calibrationSelectMaster')}
+        >
+          <FileInput size={16} />
+        </button>
+        <button
+          onClick={handleBuild}
+          disabled={isBuilding}
+          className="p-1.5 rounded-md transition-colors hover:bg-bg-secondary text-text-secondary disabled:opacity-50"
+          data-tooltip={t('adjustments.details.calibrationBuildMaster')}
+        >
+          {isBuilding ? <Loader2 size={16} className="animate-spin" /> : <FolderOpen size={16} />}
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,140 @@
+import { open } from '@tauri-apps/plugin-dialog';
+import { invoke } from '@tauri-apps/api/core';
+import { useCallback, useEffect, useMemo, useState } from 'react';
+import { Trash2, Upload } from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import { toast } from 'react-toastify';
+import Dropdown from './Dropdown';
+import Switch from './Switch';
+import Text from './Text';
+import { TextColors, TextVariants } from '../../types/typography';
+import { useEditorStore } from '../../store/useEditorStore';
+
+interface CameraProfileEntry {
+  name: string;
+  path: string;
+  cameraModel: string;
+}
+
+interface CameraProfileControlProps {
+  profilePath: string | null;
+  useToneCurve: boolean;
+  onProfileChange(path: string | null): void;
+  onToneCurveChange(enabled: boolean): void;
+}
+
+const exifText = (value: unknown) => (typeof value === 'string' ? value.replace(/"/g, '').trim() : '');
+
+export default function CameraProfileControl({
+  profilePath,
+  useToneCurve,
+  onProfileChange,
+  onToneCurveChange,
+}: CameraProfileControlProps) {
+  const { t } = useTranslation();
+  const exif = useEditorStore((state) => state.selectedImage?.exif);
+  const make = exifText(exif?.Make);
+  const model = exifText(exif?.Model);
+  const [entries, setEntries] = useState<CameraProfileEntry[]>([]);
+
+  const refreshList = useCallback(async () => {
+    try {
+      const list = await invoke<CameraProfileEntry[]>('list_camera_profiles', { make, model: model || null });
+      setEntries(list);
+    } catch (err) {
+      console.error('Failed to list camera profiles:', err);
+    }
+  }, [make, model]);
+
+  useEffect(() => {
+    refreshList();
+  }, [refreshList]);
+
+  const options = useMemo(
+    () => [
+      { label: t('adjustments.color.cameraProfileBuiltIn'), value: '' },
+      ...entries.map((entry) => ({ label: entry.name, value: entry.path })),
+    ],
+    [entries, t],
+  );
+
+  const handleImport = async () => {
+    try {
+      const selected = await open({
+        multiple: true,
+        filters: [{ name: t('adjustments.color.cameraProfileFilter'), extensions: ['dcp', 'DCP'] }],
+      });
+      const sourcePaths = Array.isArray(selected) ? selected : selected ? [selected] : [];
+      if (sourcePaths.length === 0) return;
+      const list = await invoke<CameraProfileEntry[]>('import_camera_profiles', {
+        sourcePaths,
+        make,
+        model: model || null,
+      });
+      setEntries(list);
+    } catch (err) {
+      console.error('Failed to import camera profiles:', err);
+      toast.error(t('adjustments.color.cameraProfileImportFailed'));
+    }
+  };
+
+  const handleRemove = async () => {
+    if (!profilePath) return;
+    try {
+      const list = await invoke<CameraProfileEntry[]>('remove_camera_profile', {
+        path: profilePath,
+        make,
+        model: model || null,
+      });
+      setEntries(list);
+      onProfileChange(null);
+    } catch (err) {
+      console.error('Failed to remove camera profile:', err);
+      toast.error(String(err));
+    }
+  };
+
+  return (
+    <>
+      <div className="flex justify-between items-center mb-2">
+        <Text variant={TextVariants.heading}>{t('adjustments.color.cameraProfile')}</Text>
+        <div className="flex items-center gap-1">
+          {profilePath && (
+            <button
+              onClick={handleRemove}
+              className="p-1.5 rounded-md transition-colors hover:bg-bg-secondary text-text-secondary"
+              data-tooltip={t('adjustments.color.cameraProfileRemove')}
+            >
+              <Trash2 size={16} />
+            </button>
+          )}
+          <button
+            onClick={handleImport}
+            className="p-1.5 rounded-md transition-colors hover:bg-bg-secondary text-text-secondary"
+            data-tooltip={t('adjustments.color.cameraProfileImport')}
+          >
+            <Upload size={16} />
+          </button>
+        </div>
+      </div>
+      <Dropdown
+        options={options}
+        value={profilePath ?? ''}
+        onChange={(value: string) => onProfileChange(value || null)}
+      />
+      {profilePath && (
+        <Switch
+          className="mt-3"
+          checked={useToneCurve}
+          label={t('adjustments.color.cameraProfileToneCurve')}
+          onChange={onToneCurveChange}
+        />
+      )}
+      <Text variant={TextVariants.small} color={TextColors.secondary} className="mt-2">
+        {entries.length === 0
+          ? t('adjustments.color.cameraProfileEmpty', { camera: model || t('adjustments.color.cameraProfileUnknown') })
+          : t('adjustments.color.cameraProfileDesc')}
+      </Text>
+    </>
+  );
+}
@@ -0,0 +1,98 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Loader2, ScanSearch, Trash2 } from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import { toast } from 'react-toastify';
+import Text from './Text';
+import { TextColors, TextVariants } from '../../types/typography';
+import { useEditorStore } from '../../store/useEditorStore';
+
+interface DefectMapInfo {
+  cameraKey: string;
+  pixelCount: number;
+}
+
+export default function DefectMapControl() {
+  const { t } = useTranslation();
+  const path = useEditorStore((state) => state.selectedImage?.path);
+  const [info, setInfo] = useState<DefectMapInfo | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [isScanning, setIsScanning] = useState(false);
+
+  const refreshInfo = useCallback(async () => {
+    if (!path) return;
+    try {
+      setInfo(await invoke<DefectMapInfo>('get_defect_map_info', { path }));
+      setError(null);
+    } catch (err) {
+      setInfo(null);
+      setError(String(err));
+    }
+  }, [path]);
+
+  useEffect(() => {
+    refreshInfo();
+  }, [refreshInfo]);
+
+  const handleScan = async () => {
+    if (!path) return;
+    setIsScanning(true);
+    try {
+      const updated = await invoke<DefectMapInfo>('add_defects_to_map', { path });
+      setInfo(updated);
+      toast.success(t('adjustments.details.defectMapUpdated', { count: updated.pixelCount }));
+    } catch (err) {
+      console.error('Failed to update defect map:', err);
+      toast.error(String(err));
+    } finally {
+      setIsScanning(false);
+    }
+  };
+
+  const handleClear = async () => {
+    if (!path) return;
+    try {
+      setInfo(await invoke<DefectMapInfo>('clear_defect_map', { path }));
+    } catch (err) {
+      console.error('Failed to clear defect map:', err);
+      toast.error(String(err));
+    }
+  };
+
+  return (
+    <>
+      <div className="flex justify-between items-center mb-2">
+        <Text variant={TextVariants.heading}>{t('adjustments.details.defectPixels')}</Text>
+        {info && (
+          <div className="flex items-center gap-1">
+            {info.pixelCount > 0 && (
+              <button
+                onClick={handleClear}
+                className="p-1.5 rounded-md transition-colors hover:bg-bg-secondary text-text-secondary"
+                data-tooltip={t('adjustments.details.defectMapClear')}
+              >
+                <Trash2 size={16} />
+              </button>
+            )}
+            <button
+              onClick={handleScan}
+              disabled={isScanning}
+              className="p-1.5 rounded-md transition-colors hover:bg-bg-secondary text-text-secondary disabled:opacity-50"
+              data-tooltip={t('adjustments.details.defectMapScan')}
+            >
+              {isScanning ? <Loader2 size={16} className="animate-spin" /> : <ScanSearch size={16} />}
+            </button>
+          </div>
+        )}
+      </div>
+      <Text variant={TextVariants.small} color={TextColors.secondary} className="truncate" data-tooltip={error ?? ''}>
+        {info
+          ? t('adjustments.details.defectMapCount', { count: info.pixelCount, camera: info.cameraKey })
+          : t('adjustments.details.defectMapUnavailable')}
+      </Text>
+      <Text variant={TextVariants.small} color={TextColors.secondary} className="mt-2">
+        {t('adjustments.details.defectPixelsDesc')}
+      </Text>
+    </>
+  );
+}
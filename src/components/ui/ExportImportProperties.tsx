@@ -10,6 +10,7 @@ export enum FileFormats {
   Webp = 'webp',
   Jxl = 'jxl',
   Avif = 'avif',
+  Dng = 'dng',
   Cube = 'cube',
 }
 
@@ -20,6 +21,7 @@ export const FILE_FORMATS: Array<FileFormat> = [
   { id: FileFormats.Webp, name: 'WebP', extensions: ['webp'] },
   { id: FileFormats.Jxl, name: 'JPEG XL', extensions: ['jxl'] },
   { id: FileFormats.Avif, name: 'AVIF', extensions: ['avif'] },
+  { id: FileFormats.Dng, name: 'DNG (Linear)', extensions: ['dng'] },
   { id: FileFormats.Cube, name: 'CUBE LUT', extensions: ['cube'] },
 ];
 
@@ -47,6 +49,33 @@ export interface ExportSettings {
   watermark: WatermarkSettings | null;
   exportMasks?: boolean;
   preserveFolders?: boolean;
+  outputColorSpace?: OutputColorSpace;
+  outputSharpening?: OutputSharpening | null;
+  maxFileSizeBytes?: number | null;
+  hdrGainMap?: HdrGainMapSettings | null;
+}
+
+export interface HdrGainMapSettings {
+  headroomStops: number;
+}
+
+export enum OutputColorSpace {
+  Srgb = 'srgb',
+  DisplayP3 = 'displayP3',
+  AdobeRgb = 'adobeRgb',
+  Rec2020 = 'rec2020',
+  ProPhoto = 'proPhoto',
+}
+
+export enum SharpeningTarget {
+  Screen = 'screen',
+  MattePaper = 'mattePaper',
+  GlossyPaper = 'glossyPaper',
+}
+
+export interface OutputSharpening {
+  target: SharpeningTarget;
+  amount: number;
 }
 
 export enum WatermarkAnchor {
@@ -61,12 +90,37 @@ export enum WatermarkAnchor {
   BottomRight = 'bottomRight',
 }
 
+export enum WatermarkType {
+  Image = 'image',
+  Text = 'text',
+}
+
+export const WATERMARK_TEXT_VARIABLES: Array<string> = [
+  '{artist}',
+  '{copyright}',
+  '{camera}',
+  '{lens}',
+  '{focal}',
+  '{aperture}',
+  '{shutter}',
+  '{iso}',
+  '{YYYY}',
+];
+
+export interface TextWatermarkSettings {
+  text: string;
+  fontFamily: string | null;
+  fontSize: number;
+  color: string;
+}
+
 export interface WatermarkSettings {
   path: string;
   anchor: WatermarkAnchor;
   scale: number;
   spacing: number;
   opacity: number;
+  text?: TextWatermarkSettings | null;
 }
 
 export interface ExportState {
@@ -112,6 +166,14 @@ export interface ExportPreset {
   stripGps: boolean;
   exportMasks?: boolean;
   preserveFolders?: boolean;
+  outputColorSpace?: OutputColorSpace;
+  enableOutputSharpening?: boolean;
+  outputSharpeningTarget?: string;
+  outputSharpeningAmount?: number;
+  enableMaxFileSize?: boolean;
+  maxFileSizeKb?: number;
+  enableHdrGainMap?: boolean;
+  hdrHeadroomStops?: number;
   filenameTemplate: string;
   enableWatermark: boolean;
   watermarkPath: string | null;
@@ -119,5 +181,46 @@ export interface ExportPreset {
   watermarkScale: number;
   watermarkSpacing: number;
   watermarkOpacity: number;
+  watermarkType?: string;
+  watermarkText?: string;
+  watermarkFontFamily?: string | null;
+  watermarkFontSize?: number;
+  watermarkColor?: string;
   lastExportPath?: string;
 }
+
+export interface ExportRecipeVariant {
+  preset: ExportPreset;
+  subFolder: string | null;
+}
+
+export interface ExportRecipe {
+  id: string;
+  name: string;
+  variants: Array<ExportRecipeVariant>;
+}
+
+export interface ProcessingRecipeNode {
+  id: string;
+  type: string;
+  inputs?: Array<string>;
+  [key: string]: unknown;
+}
+
+export interface ProcessingRecipe {
+  id: string;
+  name: string;
+  nodes: Array<ProcessingRecipeNode>;
+}
+
+export interface WebGalleryOptions {
+  title: string;
+  imageFormat: string;
+  imageSize: number;
+  thumbnailSize: number;
+}
+
+export interface WebGallerySettings extends WebGalleryOptions {
+  quality: number;
+  watermark: WatermarkSettings | null;
+}
@@ -0,0 +1,239 @@
+import { useState } from 'react';
+import { v4 as uuidv4 } from 'uuid';
+import { useTranslation } from 'react-i18next';
+import { toast } from 'react-toastify';
+import { Plus, Trash2, Save, X, Layers } from 'lucide-react';
+import { ExportPreset, ExportRecipe, FILE_FORMATS } from './ExportImportProperties';
+import { AppSettings } from './AppProperties';
+import Dropdown from './Dropdown';
+import Text from './Text';
+import { TextColors, TextVariants } from '../../types/typography';
+
+interface ExportRecipesListProps {
+  appSettings: AppSettings | null;
+  currentSettings: Omit<ExportPreset, 'id' | 'name'>;
+  disabled?: boolean;
+  onExportRecipe: (recipe: ExportRecipe) => void;
+  onSettingsChange: (settings: AppSettings) => void;
+}
+
+export default function ExportRecipesList({
+  appSettings,
+  currentSettings,
+  disabled = false,
+  onExportRecipe,
+  onSettingsChange,
+}: ExportRecipesListProps) {
+  const { t } = useTranslation();
+  const [isCreating, setIsCreating] = useState(false);
+  const [newRecipeName, setNewRecipeName] = useState('');
+  const [selectedRecipeId, setSelectedRecipeId] = useState<string>('');
+  const [variantName, setVariantName] = useState('');
+  const [subFolder, setSubFolder] = useState('');
+  const recipes = appSettings?.exportRecipes || [];
+  const selectedRecipe = recipes.find((r) => r.id === selectedRecipeId) ?? null;
+
+  const updateRecipes = (updatedRecipes: Array<ExportRecipe>) => {
+    if (!appSettings) return;
+    onSettingsChange({ ...appSettings, exportRecipes: updatedRecipes });
+  };
+
+  const handleSaveRecipe = () => {
+    if (!newRecipeName.trim() || !appSettings) return;
+
+    const newRecipe: ExportRecipe = {
+      id: uuidv4(),
+      name: newRecipeName.trim(),
+      variants: [],
+    };
+
+    updateRecipes([...recipes, newRecipe]);
+    setSelectedRecipeId(newRecipe.id);
+    setIsCreating(false);
+    setNewRecipeName('');
+  };
+
+  const handleDeleteRecipe = () => {
+    if (!selectedRecipe) return;
+    updateRecipes(recipes.filter((r) => r.id !== selectedRecipe.id));
+    setSelectedRecipeId('');
+  };
+
+  const handleAddVariant = () => {
+    if (!selectedRecipe || !variantName.trim()) return;
+
+    const sameDestination = selectedRecipe.variants.some(
+      (v) =>
+        v.preset.fileFormat === currentSettings.fileFormat &&
+        (v.subFolder ?? '') === subFolder.trim() &&
+        v.preset.filenameTemplate === currentSettings.filenameTemplate &&
+        (v.preset.preserveFolders ?? false) === (currentSettings.preserveFolders ?? false),
+    );
+    if (sameDestination) {
+      toast.error(t('ui.exportRecipes.duplicateVariant'));
+      return;
+    }
+
+    const variant = {
+      preset: { id: uuidv4(), name: variantName.trim(), ...currentSettings, lastExportPath: undefined },
+      subFolder: subFolder.trim() || null,
+    };
+
+    updateRecipes(
+      recipes.map((r) => (r.id === selectedRecipe.id ? { ...r, variants: [...r.variants, variant] } : r)),
+    );
+    setVariantName('');
+    setSubFolder('');
+  };
+
+  const handleRemoveVariant = (variantId: string) => {
+    if (!selectedRecipe) return;
+    updateRecipes(
+      recipes.map((r) =>
+        r.id === selectedRecipe.id ? { ...r, variants: r.variants.filter((v) => v.preset.id !== variantId) } : r,
+      ),
+    );
+  };
+
+  const formatName = (formatId: string) => FILE_FORMATS.find((f) => f.id === formatId)?.name ?? formatId;
+
+  const dropdownOptions = recipes.map((recipe) => ({
+    label: recipe.name,
+    value: recipe.id,
+  }));
+
+  return (
+    <div className="mb-8">
+      <Text variant={TextVariants.heading} className="mb-1">
+        {t('ui.exportRecipes.heading')}
+      </Text>
+      <Text variant={TextVariants.small} color={TextColors.secondary} className="mb-2">
+        {t('ui.exportRecipes.description')}
+      </Text>
+
+      {!isCreating ? (
+        <div className="flex gap-2">
+          <Dropdown
+            value={selectedRecipeId}
+            onChange={setSelectedRecipeId}
+            options={dropdownOptions}
+            placeholder={t('ui.exportRecipes.placeholder')}
+            className="w-full"
+          />
+
+          <button
+            onClick={() => setIsCreating(true)}
+            className="p-2 bg-surface hover:bg-card-active rounded-md text-text-primary transition-colors"
+            data-tooltip={t('ui.exportRecipes.newRecipeTooltip')}
+          >
+            <Plus size={18} />
+          </button>
+
+          {selectedRecipe && (
+            <button
+              onClick={handleDeleteRecipe}
+              className="p-2 bg-surface hover:bg-red-500/20 hover:text-red-500 rounded-md text-text-secondary transition-colors"
+              data-tooltip={t('ui.exportRecipes.deleteTooltip')}
+            >
+              <Trash2 size={18} />
+            </button>
+          )}
+        </div>
+      ) : (
+        <div className="flex gap-2 items-center animate-in fade-in slide-in-from-top-1 duration-200">
+          <input
+            autoFocus
+            type="text"
+            placeholder={t('ui.exportRecipes.recipeNamePlaceholder')}
+            value={newRecipeName}
+            onChange={(e) => setNewRecipeName(e.target.value)}
+            className="grow bg-bg-primary border border-surface rounded-md p-2 text-sm text-text-primary focus:ring-accent focus:border-accent"
+            onKeyDown={(e) => e.key === 'Enter' && handleSaveRecipe()}
+          />
+          <button
+            onClick={handleSaveRecipe}
+            disabled={!newRecipeName.trim()}
+            className="p-2 bg-accent text-button-text rounded-md hover:bg-accent/90 disabled:opacity-50 disabled:cursor-not-allowed"
+          >
+            <Save size={18} />
+          </button>
+          <button
+            onClick={() => setIsCreating(false)}
+            className="p-2 bg-surface text-text-secondary rounded-md hover:bg-card-active"
+          >
+            <X size={18} />
+          </button>
+        </div>
+      )}
+
+      {selectedRecipe && !isCreating && (
+        <div className="mt-3 space-y-2">
+          {selectedRecipe.variants.length === 0 ? (
+            <Text variant={TextVariants.small} color={TextColors.secondary}>
+              {t('ui.exportRecipes.emptyVariants')}
+            </Text>
+          ) : (
+            selectedRecipe.variants.map((variant) => (
+              <div
+                key={variant.preset.id}
+                className="flex items-center justify-between gap-2 bg-surface rounded-md px-3 py-2"
+              >
+                <div className="min-w-0">
+                  <Text className="truncate">{variant.preset.name}</Text>
+                  <Text variant={TextVariants.small} color={TextColors.secondary} className="truncate">
+                    {formatName(variant.preset.fileFormat)}
+                    {variant.preset.enableResize ? ` · ${variant.preset.resizeValue}px` : ''}
+                    {variant.subFolder ? ` · ${variant.subFolder}/` : ''}
+                  </Text>
+                </div>
+                <button
+                  onClick={() => handleRemoveVariant(variant.preset.id)}
+                  className="p-1 rounded-md text-text-secondary hover:bg-red-500/20 hover:text-red-500 transition-colors"
+                  data-tooltip={t('ui.exportRecipes.removeVariantTooltip')}
+                >
+                  <X size={16} />
+                </button>
+              </div>
+            ))
+          )}
+
+          <div className="flex gap-2">
+            <input
+              type="text"
+              placeholder={t('ui.exportRecipes.variantNamePlaceholder')}
+              value={variantName}
+              onChange={(e) => setVariantName(e.target.value)}
+              className="w-1/2 bg-bg-primary border border-surface rounded-md p-2 text-sm text-text-primary focus:ring-accent focus:border-accent"
+              onKeyDown={(e) => e.key === 'Enter' && handleAddVariant()}
+            />
+            <input
+              type="text"
+              placeholder={t('ui.exportRecipes.subFolderPlaceholder')}
+              value={subFolder}
+              onChange={(e) => setSubFolder(e.target.value)}
+              className="w-1/2 bg-bg-primary border border-surface rounded-md p-2 text-sm text-text-primary focus:ring-accent focus:border-accent"
+              onKeyDown={(e) => e.key === 'Enter' && handleAddVariant()}
+            />
+            <button
+              onClick={handleAddVariant}
+              disabled={!variantName.trim()}
+              className="p-2 bg-surface hover:bg-card-active rounded-md text-text-primary transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+              data-tooltip={t('ui.exportRecipes.addVariantTooltip')}
+            >
+              <Plus size={18} />
+            </button>
+          </div>
+
+          <button
+            onClick={() => onExportRecipe(selectedRecipe)}
+            disabled={disabled || selectedRecipe.variants.length === 0}
+            className="w-full flex items-center justify-center gap-2 p-2 bg-surface hover:bg-card-active rounded-md text-text-primary transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+          >
+            <Layers size={16} />
+            {t('ui.exportRecipes.export')}
+          </button>
+        </div>
+      )}
+    </div>
+  );
+}
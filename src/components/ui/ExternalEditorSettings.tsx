@@ -0,0 +1,87 @@
+import { useTranslation } from 'react-i18next';
+import { v4 as uuidv4 } from 'uuid';
+import { Plus, Trash2 } from 'lucide-react';
+import { AppSettings, ExternalEditor } from './AppProperties';
+import Input from './Input';
+import Text from './Text';
+import { TextVariants } from '../../types/typography';
+
+interface ExternalEditorSettingsProps {
+  appSettings: AppSettings | null;
+  onSettingsChange(settings: AppSettings): void;
+}
+
+export default function ExternalEditorSettings({ appSettings, onSettingsChange }: ExternalEditorSettingsProps) {
+  const { t } = useTranslation();
+  const editors = appSettings?.externalEditors ?? [];
+
+  const updateEditors = (updatedEditors: Array<ExternalEditor>) => {
+    onSettingsChange({ ...appSettings, externalEditors: updatedEditors } as AppSettings);
+  };
+
+  const updateEditor = (id: string, patch: Partial<ExternalEditor>) => {
+    updateEditors(editors.map((editor) => (editor.id === id ? { ...editor, ...patch } : editor)));
+  };
+
+  const handleAddEditor = () => {
+    updateEditors([
+      ...editors,
+      { id: uuidv4(), name: t('settings.processing.externalEditorsNewName'), command: '', args: ['{file}'] },
+    ]);
+  };
+
+  return (
+    <div className="space-y-4">
+      <div>
+        <Text variant={TextVariants.heading} className="block mb-2">
+          {t('settings.processing.externalEditors')}
+        </Text>
+        <Text variant={TextVariants.small}>{t('settings.processing.externalEditorsDesc')}</Text>
+      </div>
+
+      {editors.length > 0 && (
+        <div className="pl-4 border-l-2 border-border-color ml-1 space-y-4">
+          {editors.map((editor) => (
+            <div key={editor.id} className="space-y-2">
+              <div className="flex items-center gap-2">
+                <Input
+                  value={editor.name}
+                  onChange={(e) => updateEditor(editor.id, { name: e.target.value })}
+                  placeholder={t('settings.processing.externalEditorsName')}
+                  bgClassName="bg-bg-primary"
+                />
+                <button
+                  onClick={() => updateEditors(editors.filter((e) => e.id !== editor.id))}
+                  className="p-2 text-text-secondary hover:bg-red-500/20 hover:text-red-500 rounded-md transition-colors"
+                  data-tooltip={t('settings.processing.externalEditorsRemove')}
+                >
+                  <Trash2 size={18} />
+                </button>
+              </div>
+              <Input
+                value={editor.command}
+                onChange={(e) => updateEditor(editor.id, { command: e.target.value })}
+                placeholder={t('settings.processing.externalEditorsCommand')}
+                bgClassName="bg-bg-primary"
+              />
+              <Input
+                value={editor.args.join(' ')}
+                onChange={(e) => updateEditor(editor.id, { args: e.target.value.split(' ') })}
+                placeholder={t('settings.processing.externalEditorsArgs')}
+                bgClassName="bg-bg-primary"
+              />
+            </div>
+          ))}
+        </div>
+      )}
+
+      <button
+        onClick={handleAddEditor}
+        className="flex items-center gap-2 px-3 py-1.5 bg-bg-primary hover:bg-card-active rounded-md text-sm text-text-primary transition-colors"
+      >
+        <Plus size={16} />
+        {t('settings.processing.externalEditorsAdd')}
+      </button>
+    </div>
+  );
+}
@@ -0,0 +1,121 @@
+import { useTranslation } from 'react-i18next';
+import { open } from '@tauri-apps/plugin-dialog';
+import { FolderOpen } from 'lucide-react';
+import { AppSettings, HotFolderSettings as HotFolderConfig } from './AppProperties';
+import Dropdown from './Dropdown';
+import Input from './Input';
+import Switch from './Switch';
+import Text from './Text';
+import { TextVariants } from '../../types/typography';
+
+interface HotFolderSettingsProps {
+  appSettings: AppSettings | null;
+  onSettingsChange(settings: AppSettings): void;
+}
+
+const EMPTY_HOT_FOLDER: HotFolderConfig = {
+  enabled: false,
+  inputFolder: '',
+  outputFolder: '',
+  preset: null,
+  autoAdjust: false,
+  exportPreset: '',
+  archiveFolder: null,
+};
+
+export default function HotFolderSettings({ appSettings, onSettingsChange }: HotFolderSettingsProps) {
+  const { t } = useTranslation();
+  const hotFolder = { ...EMPTY_HOT_FOLDER, ...appSettings?.hotFolder };
+  const exportPresetOptions = (appSettings?.exportPresets ?? [])
+    .filter((preset) => !preset.id.startsWith('__'))
+    .map((preset) => ({ label: preset.name, value: preset.id }));
+
+  const update = (patch: Partial<HotFolderConfig>) => {
+    onSettingsChange({ ...appSettings, hotFolder: { ...hotFolder, ...patch } } as AppSettings);
+  };
+
+  const pickFolder = async (key: 'inputFolder' | 'outputFolder' | 'archiveFolder') => {
+    const selected = await open({ directory: true, defaultPath: hotFolder[key] || undefined });
+    if (typeof selected === 'string') {
+      update({ [key]: selected });
+    }
+  };
+
+  const folderField = (key: 'inputFolder' | 'outputFolder' | 'archiveFolder', label: string) => (
+    <div>
+      <Text variant={TextVariants.small} className="mb-1">
+        {label}
+      </Text>
+      <div className="flex items-center gap-2">
+        <Input
+          value={hotFolder[key] ?? ''}
+          onChange={(e) => update({ [key]: e.target.value || (key === 'archiveFolder' ? null : '') })}
+          placeholder={t('settings.processing.hotFolderNotSet')}
+          bgClassName="bg-bg-primary"
+        />
+        <button
+          onClick={() => pickFolder(key)}
+          className="p-2 text-text-secondary hover:text-text-primary hover:bg-surface rounded-md transition-colors"
+          data-tooltip={t('settings.processing.hotFolderBrowse')}
+        >
+          <FolderOpen size={18} />
+        </button>
+      </div>
+    </div>
+  );
+
+  return (
+    <div className="space-y-4">
+      <div>
+        <Text variant={TextVariants.heading} className="block mb-2">
+          {t('settings.processing.hotFolder')}
+        </Text>
+        <Switch
+          checked={hotFolder.enabled}
+          id="hot-folder-toggle"
+          label={t('settings.processing.hotFolderLabel')}
+          onChange={(checked) => update({ enabled: checked })}
+        />
+        <Text variant={TextVariants.small} className="mt-2">
+          {t('settings.processing.hotFolderDesc')}
+        </Text>
+      </div>
+
+      {hotFolder.enabled && (
+        <div className="pl-4 border-l-2 border-border-color ml-1 space-y-4">
+          {folderField('inputFolder', t('settings.processing.hotFolderInput'))}
+          {folderField('outputFolder', t('settings.processing.hotFolderOutput'))}
+          <div>
+            <Text variant={TextVariants.small} className="mb-1">
+              {t('settings.processing.hotFolderExportPreset')}
+            </Text>
+            <Dropdown
+              onChange={(value: string) => update({ exportPreset: value })}
+              options={exportPresetOptions}
+              value={hotFolder.exportPreset}
+              triggerClassName="bg-bg-primary"
+            />
+          </div>
+          <div>
+            <Text variant={TextVariants.small} className="mb-1">
+              {t('settings.processing.hotFolderPreset')}
+            </Text>
+            <Input
+              value={hotFolder.preset ?? ''}
+              onChange={(e) => update({ preset: e.target.value || null })}
+              placeholder={t('settings.processing.hotFolderPresetPlaceholder')}
+              bgClassName="bg-bg-primary"
+            />
+          </div>
+          <Switch
+            checked={hotFolder.autoAdjust}
+            id="hot-folder-auto-adjust-toggle"
+            label={t('settings.processing.hotFolderAutoAdjust')}
+            onChange={(checked) => update({ autoAdjust: checked })}
+          />
+          {folderField('archiveFolder', t('settings.processing.hotFolderArchive'))}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,143 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { useTranslation } from 'react-i18next';
+import { ArrowDown, ArrowUp, Pause, Play, RotateCcw, Trash2, X } from 'lucide-react';
+import { Invokes, Job, JobStatus } from './AppProperties';
+import Text from './Text';
+import { TextColors, TextVariants } from '../../types/typography';
+
+const FINISHED_STATUSES: JobStatus[] = ['completed', 'failed', 'cancelled'];
+
+export default function JobQueue() {
+  const { t } = useTranslation();
+  const [jobs, setJobs] = useState<Array<Job>>([]);
+
+  const refresh = useCallback(() => {
+    invoke<Array<Job>>(Invokes.GetJobQueue)
+      .then(setJobs)
+      .catch((err) => console.error('Failed to load job queue:', err));
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const unlistenUpdated = listen('job-queue-updated', refresh);
+    const unlistenProgress = listen('job-progress', refresh);
+    return () => {
+      unlistenUpdated.then((f) => f());
+      unlistenProgress.then((f) => f());
+    };
+  }, [refresh]);
+
+  const run = (command: Invokes, args: Record<string, unknown> = {}) => {
+    invoke(command, args).catch((err) => console.error(`Failed to run ${command}:`, err));
+  };
+
+  if (jobs.length === 0) {
+    return null;
+  }
+
+  const hasHistory = jobs.some((job) => FINISHED_STATUSES.includes(job.status));
+  const sortedJobs = [...jobs].sort((a, b) => {
+    const aFinished = FINISHED_STATUSES.includes(a.status);
+    const bFinished = FINISHED_STATUSES.includes(b.status);
+    if (aFinished !== bFinished) return aFinished ? 1 : -1;
+    if (aFinished) return (b.finishedAt ?? '').localeCompare(a.finishedAt ?? '');
+    return b.priority - a.priority;
+  });
+
+  const iconButton = 'p-1 rounded-md text-text-secondary hover:text-text-primary hover:bg-card-active transition-colors';
+
+  return (
+    <div className="mb-8">
+      <div className="flex items-center justify-between mb-2">
+        <Text variant={TextVariants.heading}>{t('ui.jobQueue.heading')}</Text>
+        {hasHistory && (
+          <button
+            onClick={() => run(Invokes.ClearJobHistory)}
+            className={iconButton}
+            data-tooltip={t('ui.jobQueue.clearHistory')}
+          >
+            <Trash2 size={16} />
+          </button>
+        )}
+      </div>
+      <div className="space-y-2">
+        {sortedJobs.map((job) => {
+          const finished = job.items.filter((item) => item.status !== 'pending').length;
+          const failed = job.items.filter((item) => item.status === 'failed').length;
+          const isFinished = FINISHED_STATUSES.includes(job.status);
+
+          return (
+            <div key={job.id} className="bg-surface rounded-md p-2">
+              <div className="flex items-center gap-2">
+                <Text className="grow truncate" data-tooltip={job.error ?? undefined}>
+                  {job.name}
+                </Text>
+                {!isFinished && (
+                  <>
+                    <button
+                      onClick={() => run(Invokes.SetJobPriority, { id: job.id, priority: job.priority + 1 })}
+                      className={iconButton}
+                      data-tooltip={t('ui.jobQueue.raisePriority')}
+                    >
+                      <ArrowUp size={14} />
+                    </button>
+                    <button
+                      onClick={() => run(Invokes.SetJobPriority, { id: job.id, priority: job.priority - 1 })}
+                      className={iconButton}
+                      data-tooltip={t('ui.jobQueue.lowerPriority')}
+                    >
+                      <ArrowDown size={14} />
+                    </button>
+                  </>
+                )}
+                {(job.status === 'queued' || job.status === 'running') && (
+                  <button
+                    onClick={() => run(Invokes.PauseJob, { id: job.id })}
+                    className={iconButton}
+                    data-tooltip={t('ui.jobQueue.pause')}
+                  >
+                    <Pause size={14} />
+                  </button>
+                )}
+                {job.status === 'paused' && (
+                  <button
+                    onClick={() => run(Invokes.ResumeJob, { id: job.id })}
+                    className={iconButton}
+                    data-tooltip={t('ui.jobQueue.resume')}
+                  >
+                    <Play size={14} />
+                  </button>
+                )}
+                {job.status === 'failed' && failed > 0 && (
+                  <button
+                    onClick={() => run(Invokes.ResumeJob, { id: job.id })}
+                    className={iconButton}
+                    data-tooltip={t('ui.jobQueue.retryFailed')}
+                  >
+                    <RotateCcw size={14} />
+                  </button>
+                )}
+                {!isFinished && (
+                  <button
+                    onClick={() => run(Invokes.CancelJob, { id: job.id })}
+                    className={iconButton}
+                    data-tooltip={t('ui.jobQueue.cancel')}
+                  >
+                    <X size={14} />
+                  </button>
+                )}
+              </div>
+              <Text variant={TextVariants.small} color={TextColors.secondary}>
+                {t(`ui.jobQueue.status.${job.status}`)} ·{' '}
+                {t('ui.jobQueue.progress', { current: finished, total: job.items.length })}
+                {failed > 0 && ` · ${t('ui.jobQueue.failedCount', { count: failed })}`}
+              </Text>
+            </div>
+          );
+        })}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,170 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { open } from '@tauri-apps/plugin-dialog';
+import { useTranslation } from 'react-i18next';
+import { FileUp, Play, Trash2 } from 'lucide-react';
+import { ProcessingRecipe } from './ExportImportProperties';
+import { AppSettings, Invokes } from './AppProperties';
+import Dropdown from './Dropdown';
+import Text from './Text';
+import { TextColors, TextVariants } from '../../types/typography';
+
+interface RecipeProgress {
+  node: string;
+  current: number;
+  total: number;
+}
+
+interface ProcessingRecipesListProps {
+  appSettings: AppSettings | null;
+  disabled?: boolean;
+  onSettingsChange: (settings: AppSettings) => void;
+  paths: Array<string>;
+}
+
+export default function ProcessingRecipesList({
+  appSettings,
+  disabled = false,
+  onSettingsChange,
+  paths,
+}: ProcessingRecipesListProps) {
+  const { t } = useTranslation();
+  const [selectedRecipeId, setSelectedRecipeId] = useState<string>('');
+  const [isRunning, setIsRunning] = useState(false);
+  const [progress, setProgress] = useState<RecipeProgress | null>(null);
+  const [message, setMessage] = useState('');
+  const recipes = appSettings?.processingRecipes || [];
+  const selectedRecipe = recipes.find((r) => r.id === selectedRecipeId) ?? null;
+
+  useEffect(() => {
+    const unlisten = listen<RecipeProgress>('recipe-progress', (event) => setProgress(event.payload));
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, []);
+
+  const updateRecipes = (updatedRecipes: Array<ProcessingRecipe>) => {
+    if (!appSettings) return;
+    onSettingsChange({ ...appSettings, processingRecipes: updatedRecipes });
+  };
+
+  const handleImportRecipe = async () => {
+    try {
+      const path = await open({
+        title: t('ui.processingRecipes.importTitle'),
+        filters: [{ name: 'Recipe', extensions: ['json'] }],
+        multiple: false,
+      });
+      if (typeof path !== 'string') return;
+
+      const recipe: ProcessingRecipe = await invoke(Invokes.LoadProcessingRecipeFile, { path });
+      updateRecipes([...recipes.filter((r) => r.id !== recipe.id), recipe]);
+      setSelectedRecipeId(recipe.id);
+      setMessage('');
+    } catch (error) {
+      setMessage(typeof error === 'string' ? error : t('ui.processingRecipes.importFailed'));
+    }
+  };
+
+  const handleDeleteRecipe = () => {
+    if (!selectedRecipe) return;
+    updateRecipes(recipes.filter((r) => r.id !== selectedRecipe.id));
+    setSelectedRecipeId('');
+  };
+
+  const handleRunRecipe = async () => {
+    if (!selectedRecipe || paths.length === 0 || isRunning) return;
+
+    const outputFolder = (await open({
+      title: t('ui.processingRecipes.selectFolderTitle'),
+      directory: true,
+    })) as string;
+    if (!outputFolder) return;
+
+    setIsRunning(true);
+    setProgress(null);
+    setMessage('');
+    try {
+      await invoke(Invokes.RunProcessingRecipe, { recipe: selectedRecipe, paths, outputFolder });
+      setMessage(t('ui.processingRecipes.completed'));
+    } catch (error) {
+      setMessage(typeof error === 'string' ? error : t('ui.processingRecipes.failed'));
+    } finally {
+      setIsRunning(false);
+      setProgress(null);
+    }
+  };
+
+  const dropdownOptions = recipes.map((recipe) => ({
+    label: recipe.name,
+    value: recipe.id,
+  }));
+
+  return (
+    <div className="mb-8">
+      <Text variant={TextVariants.heading} className="mb-1">
+        {t('ui.processingRecipes.heading')}
+      </Text>
+      <Text variant={TextVariants.small} color={TextColors.secondary} className="mb-2">
+        {t('ui.processingRecipes.description')}
+      </Text>
+
+      <div className="flex gap-2">
+        <Dropdown
+          value={selectedRecipeId}
+          onChange={setSelectedRecipeId}
+          options={dropdownOptions}
+          placeholder={t('ui.processingRecipes.placeholder')}
+          className="w-full"
+        />
+
+        <button
+          onClick={handleImportRecipe}
+          className="p-2 bg-surface hover:bg-card-active rounded-md text-text-primary transition-colors"
+          data-tooltip={t('ui.processingRecipes.importTooltip')}
+        >
+          <FileUp size={18} />
+        </button>
+
+        {selectedRecipe && (
+          <button
+            onClick={handleDeleteRecipe}
+            className="p-2 bg-surface hover:bg-red-500/20 hover:text-red-500 rounded-md text-text-secondary transition-colors"
+            data-tooltip={t('ui.processingRecipes.deleteTooltip')}
+          >
+            <Trash2 size={18} />
+          </button>
+        )}
+      </div>
+
+      {selectedRecipe && (
+        <div className="mt-3 space-y-2">
+          <Text variant={TextVariants.small} color={TextColors.secondary} className="truncate">
+            {selectedRecipe.nodes.map((node) => node.type).join(' → ')}
+          </Text>
+          <button
+            onClick={handleRunRecipe}
+            disabled={disabled || isRunning || paths.length === 0}
+            className="w-full flex items-center justify-center gap-2 p-2 bg-surface hover:bg-card-active rounded-md text-text-primary transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+          >
+            <Play size={16} />
+            {isRunning && progress
+              ? t('ui.processingRecipes.running', {
+                  node: progress.node,
+                  current: progress.current,
+                  total: progress.total,
+                })
+              : t('ui.processingRecipes.run')}
+          </button>
+        </div>
+      )}
+
+      {message && (
+        <Text variant={TextVariants.small} color={TextColors.secondary} className="mt-2">
+          {message}
+        </Text>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,102 @@
+import { useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Globe } from 'lucide-react';
+import { WebGalleryOptions } from './ExportImportProperties';
+import Text from './Text';
+import { TextColors, TextVariants } from '../../types/typography';
+
+interface WebGalleryExportProps {
+  disabled?: boolean;
+  onExportGallery: (options: WebGalleryOptions) => void;
+}
+
+const GALLERY_FORMATS = [
+  { id: 'jpg', name: 'JPEG' },
+  { id: 'webp', name: 'WebP' },
+];
+
+export default function WebGalleryExport({ disabled = false, onExportGallery }: WebGalleryExportProps) {
+  const { t } = useTranslation();
+  const [title, setTitle] = useState('');
+  const [imageFormat, setImageFormat] = useState('jpg');
+  const [imageSize, setImageSize] = useState(2048);
+  const [thumbnailSize, setThumbnailSize] = useState(480);
+
+  const handleExport = () => {
+    onExportGallery({
+      title: title.trim() || t('ui.webGallery.defaultTitle'),
+      imageFormat,
+      imageSize,
+      thumbnailSize,
+    });
+  };
+
+  return (
+    <div className="mb-8">
+      <Text variant={TextVariants.heading} className="mb-1">
+        {t('ui.webGallery.heading')}
+      </Text>
+      <Text variant={TextVariants.small} color={TextColors.secondary} className="mb-2">
+        {t('ui.webGallery.description')}
+      </Text>
+
+      <div className="space-y-2">
+        <input
+          type="text"
+          placeholder={t('ui.webGallery.titlePlaceholder')}
+          value={title}
+          onChange={(e) => setTitle(e.target.value)}
+          className="w-full bg-bg-primary border border-surface rounded-md p-2 text-sm text-text-primary focus:ring-accent focus:border-accent"
+        />
+
+        <div className="grid grid-cols-2 gap-2">
+          {GALLERY_FORMATS.map((format) => (
+            <button
+              key={format.id}
+              onClick={() => setImageFormat(format.id)}
+              className={`px-2 py-1.5 rounded-md transition-colors ${imageFormat === format.id ? 'bg-accent' : 'bg-surface hover:bg-card-active'}`}
+            >
+              <Text color={imageFormat === format.id ? TextColors.button : TextColors.secondary}>{format.name}</Text>
+            </button>
+          ))}
+        </div>
+
+        <div className="flex gap-2">
+          <label className="w-1/2">
+            <Text variant={TextVariants.small} color={TextColors.secondary}>
+              {t('ui.webGallery.imageSize')}
+            </Text>
+            <input
+              type="number"
+              min="256"
+              value={imageSize}
+              onChange={(e) => setImageSize(parseInt(e.target.value) || 0)}
+              className="w-full bg-surface text-center rounded-md p-2 border border-surface focus:border-accent focus:ring-accent text-text-secondary focus:text-text-primary"
+            />
+          </label>
+          <label className="w-1/2">
+            <Text variant={TextVariants.small} color={TextColors.secondary}>
+              {t('ui.webGallery.thumbnailSize')}
+            </Text>
+            <input
+              type="number"
+              min="64"
+              value={thumbnailSize}
+              onChange={(e) => setThumbnailSize(parseInt(e.target.value) || 0)}
+              className="w-full bg-surface text-center rounded-md p-2 border border-surface focus:border-accent focus:ring-accent text-text-secondary focus:text-text-primary"
+            />
+          </label>
+        </div>
+
+        <button
+          onClick={handleExport}
+          disabled={disabled || imageSize <= 0 || thumbnailSize <= 0}
+          className="w-full flex items-center justify-center gap-2 p-2 bg-surface hover:bg-card-active rounded-md text-text-primary transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+        >
+          <Globe size={16} />
+          {t('ui.webGallery.export')}
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -90,6 +90,10 @@ export function useAndroidBackHandler() {
         ui.setUI({ collageModalState: { isOpen: false, sourceImages: [] } });
         return;
       }
+      if (ui.scriptModalState.isOpen) {
+        ui.setUI({ scriptModalState: { isOpen: false, targetPaths: [] } });
+        return;
+      }
 
       window.dispatchEvent(new KeyboardEvent('keydown', { key: 'Escape', code: 'Escape', bubbles: true, cancelable: true }));
     };
@@ -31,6 +31,8 @@ import {
   Layers,
   Grip,
   Film,
+  ScrollText,
+  SquarePen,
   Home,
   Plane,
   Mountain,
@@ -51,7 +53,16 @@ import { useLibraryStore } from '../store/useLibraryStore';
 import { useProcessStore } from '../store/useProcessStore';
 import { useUIStore } from '../store/useUIStore';
 import { useSettingsStore } from '../store/useSettingsStore';
-import { Invokes, Option, OPTION_SEPARATOR, Panel, AlbumItem, Album, AlbumGroup } from '../components/ui/AppProperties';
+import {
+  Invokes,
+  Option,
+  OPTION_SEPARATOR,
+  Panel,
+  AlbumItem,
+  Album,
+  AlbumGroup,
+  ExternalEditor,
+} from '../components/ui/AppProperties';
 import { Color, COLOR_LABELS, INITIAL_ADJUSTMENTS, normalizeLoadedAdjustments } from '../utils/adjustments';
 import TaggingSubMenu from '../context/TaggingSubMenu';
 import { useEditorActions } from './useEditorActions';
@@ -157,6 +168,34 @@ export function useAppContextMenus(props: UseAppContextMenusProps) {
     [albumIcons, t],
   );
 
+  const buildEditInMenu = useCallback(
+    (path: string, getAdjustments: () => Promise<any>, disabled = false): Option => {
+      const editors = useSettingsStore.getState().appSettings?.externalEditors ?? [];
+
+      const openInEditor = async (editor: ExternalEditor) => {
+        toast.info(t('contextMenus.toasts.openingInEditor', { name: editor.name }));
+        try {
+          await invoke(Invokes.EditInExternalEditor, {
+            path,
+            editorId: editor.id,
+            adjustments: await getAdjustments(),
+          });
+          await props.refreshImageList();
+        } catch (err) {
+          toast.error(t('contextMenus.toasts.failedExternalEdit', { err }));
+        }
+      };
+
+      return {
+        label: t('contextMenus.editor.editIn'),
+        icon: SquarePen,
+        disabled: disabled || editors.length === 0,
+        submenu: editors.map((editor) => ({ label: editor.name, onClick: () => openInEditor(editor) })),
+      };
+    },
+    [props, t],
+  );
+
   const handleEditorContextMenu = useCallback(
     (event: any) => {
       event.preventDefault();
@@ -179,6 +218,7 @@ export function useAppContextMenus(props: UseAppContextMenusProps) {
           icon: FileInput,
           onClick: () => setPanel(Panel.Export),
         },
+        buildEditInMenu(selectedImage.path, async () => useEditorStore.getState().adjustments),
         { type: OPTION_SEPARATOR },
         { label: t('contextMenus.editor.undo'), icon: Undo, onClick: undo, disabled: !canUndo },
         { label: t('contextMenus.editor.redo'), icon: Redo, onClick: redo, disabled: !canRedo },
@@ -316,6 +356,7 @@ export function useAppContextMenus(props: UseAppContextMenusProps) {
     },
     [
       getCommonTags,
+      buildEditInMenu,
       handleCopyAdjustments,
       handlePasteAdjustments,
       handleAutoAdjustments,
@@ -428,6 +469,7 @@ export function useAppContextMenus(props: UseAppContextMenusProps) {
       const renameLabel = t('contextMenus.thumbnail.renameImage', { count: selectionCount });
       const cullLabel = t('contextMenus.thumbnail.cullImage', { count: selectionCount });
       const collageLabel = t('contextMenus.thumbnail.collage', { count: selectionCount });
+      const scriptLabel = t('contextMenus.thumbnail.runScript', { count: selectionCount });
       const stitchLabel = t('contextMenus.editor.stitchPanorama');
       const conversionLabel = t('contextMenus.thumbnail.convertNegative', { count: selectionCount });
       const denoiseLabel = t('contextMenus.thumbnail.denoise', { count: selectionCount });
@@ -527,6 +569,16 @@ export function useAppContextMenus(props: UseAppContextMenusProps) {
         }
       };
 
+      const editInMenu = buildEditInMenu(
+        finalSelection[0],
+        async () => {
+          if (isEditingThisImage) return useEditorStore.getState().adjustments;
+          const metadata: any = await invoke(Invokes.LoadMetadata, { path: finalSelection[0] });
+          return normalizeLoadedAdjustments(metadata.adjustments);
+        },
+        !isSingleSelection,
+      );
+
       const options = [
         ...(!isEditingThisImage
           ? [
@@ -537,9 +589,14 @@ export function useAppContextMenus(props: UseAppContextMenusProps) {
                 onClick: () => props.handleImageSelect(finalSelection[0]),
               },
               { icon: FileInput, label: exportLabel, onClick: onExportClick },
+              editInMenu,
               { type: OPTION_SEPARATOR },
             ]
-          : [{ icon: FileInput, label: exportLabel, onClick: onExportClick }, { type: OPTION_SEPARATOR }]),
+          : [
+              { icon: FileInput, label: exportLabel, onClick: onExportClick },
+              editInMenu,
+              { type: OPTION_SEPARATOR },
+            ]),
         {
           disabled: !isSingleSelection,
           icon: Copy,
@@ -607,6 +664,12 @@ export function useAppContextMenus(props: UseAppContextMenusProps) {
                 }),
               disabled: selectionCount < 2,
             },
+            {
+              label: scriptLabel,
+              icon: ScrollText,
+              disabled: selectionCount === 0,
+              onClick: () => setUI({ scriptModalState: { isOpen: true, targetPaths: finalSelection } }),
+            },
           ],
         },
         {
@@ -803,6 +866,7 @@ export function useAppContextMenus(props: UseAppContextMenusProps) {
     [
       getCommonTags,
       buildAddToAlbumMenu,
+      buildEditInMenu,
       handleCopyAdjustments,
       handlePasteAdjustments,
       handleRate,
@@ -979,6 +1043,18 @@ export function useAppContextMenus(props: UseAppContextMenusProps) {
           label: t('contextMenus.folders.importImages'),
           onClick: () => props.handleImportClick(targetPath),
         },
+        {
+          icon: ScrollText,
+          label: t('contextMenus.folders.runScript'),
+          onClick: async () => {
+            try {
+              const images: Array<{ path: string }> = await invoke(Invokes.ListImagesInDir, { path: targetPath });
+              setUI({ scriptModalState: { isOpen: true, targetPaths: images.map((image) => image.path) } });
+            } catch (err) {
+              toast.error(t('contextMenus.toasts.failedListImages', { err }));
+            }
+          },
+        },
         { type: OPTION_SEPARATOR },
         {
           icon: Folder,
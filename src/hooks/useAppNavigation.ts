@@ -388,10 +388,19 @@ export function useAppNavigation({ clearThumbnailQueue, refs }: AppNavigationPro
           setLibrary({ imageList: files });
         }
 
-        if (!preserveEditor) {
-          invoke(Invokes.StartBackgroundIndexing, { folderPath: path }).catch((err) => {
-            console.error('Failed to start background indexing:', err);
-          });
+        if (!preserveEditor && appSettings?.enableAiTagging) {
+          const untaggedPaths = files
+            .filter((f) => !f.tags?.some((tag) => !tag.startsWith('color:') && !tag.startsWith('user:')))
+            .map((f) => f.path);
+          if (untaggedPaths.length > 0) {
+            invoke(Invokes.EnqueueJob, {
+              kind: { type: 'tagging' },
+              paths: untaggedPaths,
+              priority: -1,
+            }).catch((err) => {
+              console.error('Failed to queue background indexing:', err);
+            });
+          }
         }
       } catch (err) {
         console.error('Failed to load folder contents:', err);
@@ -12,6 +12,8 @@ import {
   COPYABLE_ADJUSTMENT_KEYS,
   PasteMode,
   LensAdjustment,
+  RelativeAdjustments,
+  buildRelativePaste,
   normalizeLoadedAdjustments,
 } from '../utils/adjustments';
 import { calculateCenteredCrop } from '../utils/cropUtils';
@@ -194,6 +196,23 @@ export function useEditorActions() {
     useProcessStore.getState().setProcess({ isCopied: true });
   }, []);
 
+  const applyRelativeAdjustments = useCallback(
+    async (paths: string[], relative: RelativeAdjustments) => {
+      const { selectedImage, adjustments } = useEditorStore.getState();
+      paths.forEach((p) => globalImageCache.delete(p));
+
+      const otherPaths = paths.filter((p) => p !== selectedImage?.path);
+      if (otherPaths.length < paths.length) {
+        const combined: any = await invoke(Invokes.CombineRelativeAdjustments, { adjustments, relative });
+        setAdjustments(normalizeLoadedAdjustments(combined));
+      }
+      if (otherPaths.length > 0) {
+        await invoke(Invokes.ApplyRelativeAdjustmentsToPaths, { paths: otherPaths, relative });
+      }
+    },
+    [setAdjustments],
+  );
+
   const handlePasteAdjustments = useCallback(
     (paths?: string[]) => {
       const { copiedAdjustments, selectedImage, adjustments } = useEditorStore.getState();
@@ -203,7 +222,24 @@ export function useEditorActions() {
 
       if (!copiedAdjustments || !appSettings) return;
 
-      const { mode, includedAdjustments } = appSettings.copyPasteSettings;
+      const { mode, includedAdjustments, relativeMasks } = appSettings.copyPasteSettings;
+      const pathsToUpdate =
+        paths || (multiSelectedPaths.length > 0 ? multiSelectedPaths : selectedImage ? [selectedImage.path] : []);
+
+      if (mode === PasteMode.Relative) {
+        if (pathsToUpdate.length > 0) {
+          const relative = {
+            ...buildRelativePaste(copiedAdjustments, includedAdjustments),
+            includeMasks: !!relativeMasks,
+          };
+          applyRelativeAdjustments(pathsToUpdate, relative).catch((err) =>
+            toast.error(`Failed to paste adjustments: ${err}`),
+          );
+        }
+        setProcess({ isPasted: true });
+        return;
+      }
+
       const adjustmentsToApply: Partial<Adjustments> = {};
 
       for (const key of includedAdjustments) {
@@ -230,8 +266,6 @@ export function useEditorActions() {
         return;
       }
 
-      const pathsToUpdate =
-        paths || (multiSelectedPaths.length > 0 ? multiSelectedPaths : selectedImage ? [selectedImage.path] : []);
       if (pathsToUpdate.length === 0) return;
 
       pathsToUpdate.forEach((p) => globalImageCache.delete(p));
@@ -259,7 +293,7 @@ export function useEditorActions() {
 
       setProcess({ isPasted: true });
     },
-    [setAdjustments],
+    [applyRelativeAdjustments, setAdjustments],
   );
 
   const handleZoomChange = useCallback((zoomValue: number, fitToWindow: boolean = false) => {
@@ -321,6 +355,7 @@ export function useEditorActions() {
     handleResetAdjustments,
     handleCopyAdjustments,
     handlePasteAdjustments,
+    applyRelativeAdjustments,
     handleZoomChange,
   };
 }
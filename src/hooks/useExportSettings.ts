@@ -1,5 +1,11 @@
 import { useState, useMemo, useCallback } from 'react';
-import { ExportPreset, WatermarkAnchor } from '../components/ui/ExportImportProperties';
+import {
+  ExportPreset,
+  OutputColorSpace,
+  SharpeningTarget,
+  WatermarkAnchor,
+  WatermarkType,
+} from '../components/ui/ExportImportProperties';
 
 export function useExportSettings() {
   const [fileFormat, setFileFormat] = useState('jpeg');
@@ -13,6 +19,14 @@ export function useExportSettings() {
   const [stripGps, setStripGps] = useState(true);
   const [exportMasks, setExportMasks] = useState(false);
   const [preserveFolders, setPreserveFolders] = useState(false);
+  const [outputColorSpace, setOutputColorSpace] = useState<OutputColorSpace>(OutputColorSpace.Srgb);
+  const [enableOutputSharpening, setEnableOutputSharpening] = useState(false);
+  const [outputSharpeningTarget, setOutputSharpeningTarget] = useState<SharpeningTarget>(SharpeningTarget.Screen);
+  const [outputSharpeningAmount, setOutputSharpeningAmount] = useState(50);
+  const [enableMaxFileSize, setEnableMaxFileSize] = useState(false);
+  const [maxFileSizeKb, setMaxFileSizeKb] = useState(2048);
+  const [enableHdrGainMap, setEnableHdrGainMap] = useState(false);
+  const [hdrHeadroomStops, setHdrHeadroomStops] = useState(3);
   const [filenameTemplate, setFilenameTemplate] = useState('{original_filename}_edited');
   const [enableWatermark, setEnableWatermark] = useState(false);
   const [watermarkPath, setWatermarkPath] = useState<string | null>(null);
@@ -20,6 +34,11 @@ export function useExportSettings() {
   const [watermarkScale, setWatermarkScale] = useState(10);
   const [watermarkSpacing, setWatermarkSpacing] = useState(5);
   const [watermarkOpacity, setWatermarkOpacity] = useState(75);
+  const [watermarkType, setWatermarkType] = useState<WatermarkType>(WatermarkType.Image);
+  const [watermarkText, setWatermarkText] = useState('© {artist} {YYYY}');
+  const [watermarkFontFamily, setWatermarkFontFamily] = useState<string | null>(null);
+  const [watermarkFontSize, setWatermarkFontSize] = useState(3);
+  const [watermarkColor, setWatermarkColor] = useState('#ffffff');
 
   const handleApplyPreset = useCallback((preset: ExportPreset) => {
     setFileFormat(preset.fileFormat);
@@ -33,6 +52,14 @@ export function useExportSettings() {
     setStripGps(preset.stripGps);
     setExportMasks(preset.exportMasks ?? false);
     setPreserveFolders(preset.preserveFolders ?? false);
+    setOutputColorSpace(preset.outputColorSpace ?? OutputColorSpace.Srgb);
+    setEnableOutputSharpening(preset.enableOutputSharpening ?? false);
+    setOutputSharpeningTarget((preset.outputSharpeningTarget as SharpeningTarget) ?? SharpeningTarget.Screen);
+    setOutputSharpeningAmount(preset.outputSharpeningAmount ?? 50);
+    setEnableMaxFileSize(preset.enableMaxFileSize ?? false);
+    setMaxFileSizeKb(preset.maxFileSizeKb ?? 2048);
+    setEnableHdrGainMap(preset.enableHdrGainMap ?? false);
+    setHdrHeadroomStops(preset.hdrHeadroomStops ?? 3);
     setFilenameTemplate(preset.filenameTemplate);
     setEnableWatermark(preset.enableWatermark);
     setWatermarkPath(preset.watermarkPath);
@@ -40,6 +67,11 @@ export function useExportSettings() {
     setWatermarkScale(preset.watermarkScale);
     setWatermarkSpacing(preset.watermarkSpacing);
     setWatermarkOpacity(preset.watermarkOpacity);
+    setWatermarkType((preset.watermarkType as WatermarkType) ?? WatermarkType.Image);
+    setWatermarkText(preset.watermarkText ?? '© {artist} {YYYY}');
+    setWatermarkFontFamily(preset.watermarkFontFamily ?? null);
+    setWatermarkFontSize(preset.watermarkFontSize ?? 3);
+    setWatermarkColor(preset.watermarkColor ?? '#ffffff');
   }, []);
 
   const currentSettingsObject = useMemo(
@@ -55,6 +87,14 @@ export function useExportSettings() {
       stripGps,
       exportMasks,
       preserveFolders,
+      outputColorSpace,
+      enableOutputSharpening,
+      outputSharpeningTarget,
+      outputSharpeningAmount,
+      enableMaxFileSize,
+      maxFileSizeKb,
+      enableHdrGainMap,
+      hdrHeadroomStops,
       filenameTemplate,
       enableWatermark,
       watermarkPath,
@@ -62,6 +102,11 @@ export function useExportSettings() {
       watermarkScale,
       watermarkSpacing,
       watermarkOpacity,
+      watermarkType,
+      watermarkText,
+      watermarkFontFamily,
+      watermarkFontSize,
+      watermarkColor,
     }),
     [
       fileFormat,
@@ -75,6 +120,14 @@ export function useExportSettings() {
       stripGps,
       exportMasks,
       preserveFolders,
+      outputColorSpace,
+      enableOutputSharpening,
+      outputSharpeningTarget,
+      outputSharpeningAmount,
+      enableMaxFileSize,
+      maxFileSizeKb,
+      enableHdrGainMap,
+      hdrHeadroomStops,
       filenameTemplate,
       enableWatermark,
       watermarkPath,
@@ -82,6 +135,11 @@ export function useExportSettings() {
       watermarkScale,
       watermarkSpacing,
       watermarkOpacity,
+      watermarkType,
+      watermarkText,
+      watermarkFontFamily,
+      watermarkFontSize,
+      watermarkColor,
     ]
   );
 
@@ -108,6 +166,22 @@ export function useExportSettings() {
     setExportMasks,
     preserveFolders,
     setPreserveFolders,
+    outputColorSpace,
+    setOutputColorSpace,
+    enableOutputSharpening,
+    setEnableOutputSharpening,
+    outputSharpeningTarget,
+    setOutputSharpeningTarget,
+    outputSharpeningAmount,
+    setOutputSharpeningAmount,
+    enableMaxFileSize,
+    setEnableMaxFileSize,
+    maxFileSizeKb,
+    setMaxFileSizeKb,
+    enableHdrGainMap,
+    setEnableHdrGainMap,
+    hdrHeadroomStops,
+    setHdrHeadroomStops,
     filenameTemplate,
     setFilenameTemplate,
     enableWatermark,
@@ -122,6 +196,16 @@ export function useExportSettings() {
     setWatermarkSpacing,
     watermarkOpacity,
     setWatermarkOpacity,
+    watermarkType,
+    setWatermarkType,
+    watermarkText,
+    setWatermarkText,
+    watermarkFontFamily,
+    setWatermarkFontFamily,
+    watermarkFontSize,
+    setWatermarkFontSize,
+    watermarkColor,
+    setWatermarkColor,
     handleApplyPreset,
     currentSettingsObject,
   };
@@ -630,6 +630,7 @@ export const useKeyboardShortcuts = ({
         state.ui.panoramaModalState.isOpen ||
         state.ui.cullingModalState.isOpen ||
         state.ui.collageModalState.isOpen ||
+        state.ui.scriptModalState.isOpen ||
         state.ui.denoiseModalState.isOpen ||
         state.ui.negativeModalState.isOpen;
 
@@ -155,15 +155,13 @@ export function useProductivityActions(refreshImageList: () => Promise<void>) {
   const handleBatchDenoise = useCallback(
     async (intensity: number, method: 'ai' | 'bm3d', paths: string[]) => {
       try {
-        const savedPaths: string[] = await invoke('batch_denoise_images', { paths, intensity, method });
-        await refreshImageList();
-        return savedPaths;
+        await invoke(Invokes.EnqueueJob, { kind: { type: 'denoise', intensity, method }, paths });
       } catch (err) {
         setUI((state) => ({ denoiseModalState: { ...state.denoiseModalState, error: String(err) } }));
         throw err;
       }
     },
-    [refreshImageList, setUI],
+    [setUI],
   );
 
   const handleSaveDenoisedImage = useCallback(async (): Promise<string> => {
@@ -6,6 +6,7 @@ import { useProcessStore } from '../store/useProcessStore';
 import { useEditorStore } from '../store/useEditorStore';
 import { useUIStore } from '../store/useUIStore';
 import { useLibraryStore } from '../store/useLibraryStore';
+import { globalImageCache } from '../utils/ImageLRUCache';
 
 interface TauriListenerProps {
   refreshAllFolderTrees: () => void;
@@ -88,6 +89,11 @@ export function useTauriListeners({
       listen('external-edit-session', (event: any) => {
         if (isEffectActive) useProcessStore.getState().setProcess({ externalEditSession: event.payload });
       }),
+      listen('external-edit-updated', (event: any) => {
+        if (!isEffectActive) return;
+        globalImageCache.delete(event.payload.path);
+        refs.current.refreshImageList();
+      }),
       listen('thumbnail-progress', (event: any) => {
         if (isEffectActive)
           useProcessStore
@@ -151,6 +157,13 @@ export function useTauriListeners({
           }
         }
       }),
+      listen('job-finished', (event: any) => {
+        // Exports, denoise and negative conversion jobs may add files to the open folder.
+        const currentFolderPath = useLibraryStore.getState().currentFolderPath;
+        if (isEffectActive && currentFolderPath && event.payload.folders.includes(currentFolderPath)) {
+          refs.current.refreshImageList();
+        }
+      }),
       listen('batch-export-progress', (event: any) => {
         if (isEffectActive) useProcessStore.getState().setExportState({ progress: event.payload });
       }),
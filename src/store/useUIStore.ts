@@ -76,6 +76,11 @@ export interface NegativeConversionModalState {
   targetPaths: Array<string>;
 }
 
+export interface ScriptModalState {
+  isOpen: boolean;
+  targetPaths: Array<string>;
+}
+
 export interface CullingModalState {
   isOpen: boolean;
   suggestions: CullingSuggestions | null;
@@ -259,6 +264,7 @@ interface UIState {
   denoiseModalState: DenoiseModalState;
   cullingModalState: CullingModalState;
   collageModalState: CollageModalState;
+  scriptModalState: ScriptModalState;
 
   setUI: (updater: Partial<UIState> | ((state: UIState) => Partial<UIState>)) => void;
   setPanel: (panel: Panel | null) => void;
@@ -368,6 +374,7 @@ export const useUIStore = create<UIState>((set, get) => ({
   },
   cullingModalState: { isOpen: false, suggestions: null, progress: null, error: null, pathsToCull: [] },
   collageModalState: { isOpen: false, sourceImages: [] },
+  scriptModalState: { isOpen: false, targetPaths: [] },
 
   setUI: (updater) => set((state) => (typeof updater === 'function' ? updater(state) : updater)),
 
@@ -19,6 +19,7 @@ export enum DisplayMode {
 
 export enum PasteMode {
   Merge = 'merge',
+  Relative = 'relative',
   Replace = 'replace',
 }
 
@@ -27,6 +28,17 @@ export interface CopyPasteSettings {
   includedAdjustments: Array<string>;
   knownAdjustments: Array<string>;
   autoSync: boolean;
+  relativeMasks?: boolean;
+}
+
+export interface RelativeAdjustments {
+  set?: Record<string, any>;
+  add?: Record<string, any>;
+  multiply?: Record<string, any>;
+  blend?: Record<string, any>;
+  strength?: number;
+  defaults?: Record<string, any>;
+  includeMasks?: boolean;
 }
 
 export enum BasicAdjustment {
@@ -40,6 +52,8 @@ export enum BasicAdjustment {
 }
 
 export enum ColorAdjustment {
+  CameraProfile = 'cameraProfile',
+  CameraProfileToneCurve = 'cameraProfileToneCurve',
   ColorGrading = 'colorGrading',
   Hsl = 'hsl',
   Hue = 'hue',
@@ -70,6 +84,16 @@ export enum DetailsAdjustment {
   SharpnessThreshold = 'sharpnessThreshold',
   ChromaticAberrationRedCyan = 'chromaticAberrationRedCyan',
   ChromaticAberrationBlueYellow = 'chromaticAberrationBlueYellow',
+  Demosaic = 'demosaic',
+  DarkFrame = 'darkFrame',
+  FlatField = 'flatField',
+}
+
+export enum DemosaicMethod {
+  Default = 'default',
+  Rcd = 'rcd',
+  Markesteijn = 'markesteijn',
+  Bilinear = 'bilinear',
 }
 
 export enum Effect {
@@ -163,6 +187,8 @@ export interface Adjustments {
   aspectRatio: number | null;
   blacks: number;
   brightness: number;
+  cameraProfile?: string | null;
+  cameraProfileToneCurve?: boolean;
   centré: number;
   clarity: number;
   chromaticAberrationBlueYellow: number;
@@ -176,8 +202,11 @@ export interface Adjustments {
   parametricCurve?: ParametricCurve;
   curveMode?: 'point' | 'parametric';
   crop: Crop | null;
+  darkFrame?: string | null;
   dehaze: number;
+  demosaic?: DemosaicMethod;
   exposure: number;
+  flatField?: string | null;
   flipHorizontal: boolean;
   flipVertical: boolean;
   flareAmount: number;
@@ -769,6 +798,10 @@ export const ADJUSTMENT_GROUPS: Record<string, AdjustmentGroup[]> = {
     { label: 'modals.copyPaste.groups.colorGrading', keys: [ColorAdjustment.ColorGrading] },
     { label: 'modals.copyPaste.groups.colorMixer', keys: [ColorAdjustment.Hsl] },
     { label: 'modals.copyPaste.groups.colorCalibration', keys: ['colorCalibration'] },
+    {
+      label: 'modals.copyPaste.groups.cameraProfile',
+      keys: [ColorAdjustment.CameraProfile, ColorAdjustment.CameraProfileToneCurve],
+    },
   ],
   details: [
     {
@@ -792,6 +825,11 @@ export const ADJUSTMENT_GROUPS: Record<string, AdjustmentGroup[]> = {
       label: 'modals.copyPaste.groups.chromaticAberration',
       keys: [DetailsAdjustment.ChromaticAberrationRedCyan, DetailsAdjustment.ChromaticAberrationBlueYellow],
     },
+    { label: 'modals.copyPaste.groups.demosaic', keys: [DetailsAdjustment.Demosaic] },
+    {
+      label: 'modals.copyPaste.groups.calibrationFrames',
+      keys: [DetailsAdjustment.DarkFrame, DetailsAdjustment.FlatField],
+    },
   ],
   effects: [
     {
@@ -856,6 +894,46 @@ export const COPYABLE_ADJUSTMENT_KEYS: string[] = Object.values(ADJUSTMENT_GROUP
   .flat()
   .flatMap((group) => group.keys);
 
+const isPlainObject = (value: any) => value !== null && typeof value === 'object' && !Array.isArray(value);
+
+const isNumericTree = (value: any): boolean =>
+  typeof value === 'number' || (isPlainObject(value) && Object.values(value).every(isNumericTree));
+
+const diffFromDefault = (value: any, defaultValue: any): any => {
+  if (typeof value === 'number') {
+    const delta = value - (typeof defaultValue === 'number' ? defaultValue : 0);
+    return delta !== 0 ? delta : undefined;
+  }
+  const nested: any = {};
+  for (const [key, nestedValue] of Object.entries(value)) {
+    const delta = diffFromDefault(nestedValue, defaultValue?.[key]);
+    if (delta !== undefined) nested[key] = delta;
+  }
+  return Object.keys(nested).length > 0 ? nested : undefined;
+};
+
+// Turns copied adjustments into offsets from the defaults, so pasting shifts each image's own
+// values. Values that cannot be offset, such as curves or LUTs, are pasted as-is.
+export const buildRelativePaste = (copiedAdjustments: any, keys: Array<string>): RelativeAdjustments => {
+  const set: Record<string, any> = {};
+  const add: Record<string, any> = {};
+
+  for (const key of keys) {
+    if (!Object.prototype.hasOwnProperty.call(copiedAdjustments, key)) continue;
+    const value = copiedAdjustments[key];
+    const defaultValue = (INITIAL_ADJUSTMENTS as any)[key];
+
+    if (isNumericTree(value)) {
+      const delta = diffFromDefault(value, defaultValue);
+      if (delta !== undefined) add[key] = delta;
+    } else if (JSON.stringify(value) !== JSON.stringify(defaultValue)) {
+      set[key] = value;
+    }
+  }
+
+  return { set, add, defaults: INITIAL_ADJUSTMENTS };
+};
+
 export const ADJUSTMENT_SECTIONS: Sections = {
   basic: [
     BasicAdjustment.Brightness,